@@ -141,6 +141,11 @@ pub mod testing {
                 stop_voting_time: 0,
                 epoch_height: 0,
                 epoch_start_block: 0,
+                allow_leader_vid_dispersal: true,
+                max_future_view_skew: None,
+                views_per_decide_alert_threshold: None,
+                max_proposal_storage_attempts: 3,
+                high_qc_wait_fraction: (1, 2),
             };
 
             Self {
@@ -5,7 +5,7 @@ use async_broadcast::broadcast;
 use async_lock::RwLock;
 use espresso_types::{
     eth_signature_key::EthKeyPair, v0_1::NoStorage, v0_99::ChainConfig, EpochCommittees, FeeAmount,
-    NodeState, Payload, SeqTypes, ValidatedState,
+    NodeState, Payload, RewardConfig, SeqTypes, ValidatedState,
 };
 use ethers_conv::ToAlloy;
 use hotshot::traits::BlockPayload;
@@ -41,6 +41,7 @@ pub struct BuilderConfig {
 
 pub fn build_instance_state<V: Versions>(
     chain_config: ChainConfig,
+    reward_config: RewardConfig,
     l1_params: L1Params,
     state_peers: Vec<Url>,
 ) -> NodeState {
@@ -75,6 +76,7 @@ pub fn build_instance_state<V: Versions>(
         V::Base::version(),
         coordinator,
     )
+    .with_reward_config(reward_config)
 }
 
 impl BuilderConfig {
@@ -366,18 +366,23 @@ impl Versions for EpochUpgradeTestVersions {
 
 #[cfg(test)]
 mod tests {
+    use std::marker::PhantomData;
+
     use committable::{Commitment, Committable};
     use hotshot_types::{
         impl_has_epoch,
-        message::UpgradeLock,
-        simple_vote::{HasEpoch, VersionedVoteData},
-        traits::node_implementation::ConsensusTime,
+        message::{EpochsUpgradeView, UpgradeLock},
+        simple_certificate::{QuorumCertificate2, UpgradeCertificate},
+        simple_vote::{HasEpoch, QuorumData2, UpgradeProposalData, VersionedVoteData},
+        traits::node_implementation::{ConsensusTime, Versions},
         utils::{genesis_epoch_from_version, option_epoch_from_block_number},
     };
     use serde::{Deserialize, Serialize};
+    use vbs::version::StaticVersionType;
 
     use crate::node_types::{
-        EpochsTestVersions, MarketplaceTestVersions, NodeType, TestTypes, TestVersions,
+        EpochUpgradeTestVersions, EpochsTestVersions, MarketplaceTestVersions, NodeType, TestTypes,
+        TestVersions,
     };
     #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Hash, Eq)]
     /// Dummy data used for test
@@ -464,6 +469,95 @@ mod tests {
         assert_eq!(None, epoch);
     }
 
+    /// Build a QC for the given block number, the rest of its contents are irrelevant
+    fn qc_for_block(block_number: Option<u64>) -> QuorumCertificate2<TestTypes> {
+        let data = QuorumData2 {
+            leaf_commit: Commitment::from_raw([0; 32]),
+            epoch: None,
+            block_number,
+        };
+        let commit = data.commit();
+        QuorumCertificate2::new(
+            data,
+            commit,
+            <TestTypes as NodeType>::View::new(0),
+            None,
+            PhantomData,
+        )
+    }
+
+    #[test]
+    fn test_quorum_certificate2_is_epoch_root() {
+        let epoch_height = 10;
+        for (block_number, expected) in [
+            (None, false),
+            (Some(0), false),
+            (Some(4), false),
+            (Some(5), true),
+            (Some(6), false),
+            (Some(10), false),
+            (Some(15), true),
+        ] {
+            assert_eq!(
+                qc_for_block(block_number).is_epoch_root(epoch_height),
+                expected,
+                "block number {block_number:?}"
+            );
+        }
+
+        // Epochs are disabled
+        assert!(!qc_for_block(Some(5)).is_epoch_root(0));
+    }
+
+    #[test]
+    fn test_quorum_certificate2_is_transition() {
+        let epoch_height = 10;
+        for (block_number, expected) in [
+            (None, false),
+            (Some(0), false),
+            (Some(6), false),
+            (Some(7), true),
+            (Some(8), true),
+            (Some(9), true),
+            (Some(10), true),
+            (Some(11), false),
+            (Some(17), true),
+            (Some(20), true),
+        ] {
+            assert_eq!(
+                qc_for_block(block_number).is_transition(epoch_height),
+                expected,
+                "block number {block_number:?}"
+            );
+        }
+
+        // Epochs are disabled
+        assert!(!qc_for_block(Some(10)).is_transition(0));
+    }
+
+    #[test]
+    fn test_quorum_certificate2_is_last_block() {
+        let epoch_height = 10;
+        for (block_number, expected) in [
+            (None, false),
+            (Some(0), false),
+            (Some(9), false),
+            (Some(10), true),
+            (Some(11), false),
+            (Some(19), false),
+            (Some(20), true),
+        ] {
+            assert_eq!(
+                qc_for_block(block_number).is_last_block(epoch_height),
+                expected,
+                "block number {block_number:?}"
+            );
+        }
+
+        // Epochs are disabled
+        assert!(!qc_for_block(Some(10)).is_last_block(0));
+    }
+
     #[test]
     fn test_genesis_epoch_from_version() {
         let epoch = genesis_epoch_from_version::<TestVersions, TestTypes>();
@@ -472,4 +566,62 @@ mod tests {
         let epoch = genesis_epoch_from_version::<EpochsTestVersions, TestTypes>();
         assert_eq!(Some(<TestTypes as NodeType>::Epoch::new(1)), epoch);
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_epochs_upgrade_view() {
+        let view = <TestTypes as NodeType>::View::new;
+        let data = UpgradeProposalData {
+            old_version: <EpochUpgradeTestVersions as Versions>::Base::VERSION,
+            new_version: <EpochUpgradeTestVersions as Versions>::Epochs::VERSION,
+            decide_by: view(10),
+            new_version_hash: EpochUpgradeTestVersions::UPGRADE_HASH.to_vec(),
+            old_version_last_view: view(14),
+            new_version_first_view: view(15),
+        };
+        let commit = data.commit();
+        let cert = UpgradeCertificate::new(data, commit, view(5), None, PhantomData);
+
+        let upgrade_lock = UpgradeLock::<TestTypes, EpochUpgradeTestVersions>::new();
+        assert_eq!(
+            upgrade_lock.epochs_upgrade_view().await,
+            EpochsUpgradeView {
+                decided: None,
+                staged: None
+            }
+        );
+
+        // The upgrade is decided, but staged until the epoch upgrade block height
+        *upgrade_lock.staged_epoch_upgrade_certificate.write().await = Some(cert.clone());
+        assert_eq!(
+            upgrade_lock.epochs_upgrade_view().await,
+            EpochsUpgradeView {
+                decided: None,
+                staged: Some(view(15))
+            }
+        );
+        assert!(!upgrade_lock.epochs_enabled(view(15)).await);
+
+        // The epoch upgrade block height is reached
+        *upgrade_lock.decided_upgrade_certificate.write().await = Some(cert);
+        *upgrade_lock.staged_epoch_upgrade_certificate.write().await = None;
+        assert_eq!(
+            upgrade_lock.epochs_upgrade_view().await,
+            EpochsUpgradeView {
+                decided: Some(view(15)),
+                staged: None
+            }
+        );
+        assert!(upgrade_lock.epochs_enabled(view(15)).await);
+
+        // Epochs are enabled from genesis
+        assert_eq!(
+            UpgradeLock::<TestTypes, EpochsTestVersions>::new()
+                .epochs_upgrade_view()
+                .await,
+            EpochsUpgradeView {
+                decided: Some(view(0)),
+                staged: None
+            }
+        );
+    }
 }
@@ -6,7 +6,10 @@
 
 use std::{
     collections::{BTreeMap, HashMap},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
 };
 
 use anyhow::{bail, Result};
@@ -90,6 +93,8 @@ pub struct TestStorage<TYPES: NodeType> {
     inner: Arc<RwLock<TestStorageState<TYPES>>>,
     /// `should_return_err` is a testing utility to validate negative cases.
     pub should_return_err: bool,
+    /// Number of upcoming calls to `append_proposal_wrapper` that fail, to validate retries.
+    pub append_proposal_wrapper_failures: Arc<AtomicUsize>,
     pub delay_config: DelayConfig,
     pub decided_upgrade_certificate: Arc<RwLock<Option<UpgradeCertificate<TYPES>>>>,
 }
@@ -99,6 +104,7 @@ impl<TYPES: NodeType> Default for TestStorage<TYPES> {
         Self {
             inner: Arc::new(RwLock::new(TestStorageState::default())),
             should_return_err: false,
+            append_proposal_wrapper_failures: Arc::new(AtomicUsize::new(0)),
             delay_config: DelayConfig::default(),
             decided_upgrade_certificate: Arc::new(RwLock::new(None)),
         }
@@ -252,6 +258,15 @@ impl<TYPES: NodeType> Storage<TYPES> for TestStorage<TYPES> {
         if self.should_return_err {
             bail!("Failed to append Quorum proposal (wrapped) to storage");
         }
+        if self
+            .append_proposal_wrapper_failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |failures| {
+                failures.checked_sub(1)
+            })
+            .is_ok()
+        {
+            bail!("Transient failure appending Quorum proposal (wrapped) to storage");
+        }
         Self::run_delay_settings_from_config(&self.delay_config).await;
         let mut inner = self.inner.write().await;
         inner
@@ -12,7 +12,7 @@ use hotshot_types::{
     event::{Event, EventType},
     simple_vote::{HasEpoch, QuorumVote2, TimeoutData2, TimeoutVote2},
     traits::node_implementation::{ConsensusTime, NodeImplementation, NodeType},
-    utils::{is_epoch_transition, is_last_block, EpochTransitionIndicator},
+    utils::{is_epoch_transition, EpochTransitionIndicator},
     vote::{HasViewNumber, Vote},
 };
 use hotshot_utils::anytrace::*;
@@ -177,10 +177,7 @@ pub async fn send_high_qc<TYPES: NodeType, V: Versions, I: NodeImplementation<TY
 
     let consensus_reader = task_state.consensus.read().await;
     let high_qc = consensus_reader.high_qc().clone();
-    let is_eqc = high_qc
-        .data
-        .block_number
-        .is_some_and(|b| is_last_block(b, task_state.epoch_height));
+    let is_eqc = high_qc.is_last_block(task_state.epoch_height);
     drop(consensus_reader);
 
     if is_eqc {
@@ -218,11 +215,7 @@ pub async fn send_high_qc<TYPES: NodeType, V: Versions, I: NodeImplementation<TY
             .leader(new_view_number)
             .await?;
 
-        let (high_qc, maybe_next_epoch_qc) = if high_qc
-            .data
-            .block_number
-            .is_some_and(|b| is_epoch_transition(b, task_state.epoch_height))
-        {
+        let (high_qc, maybe_next_epoch_qc) = if high_qc.is_transition(task_state.epoch_height) {
             let Some((qc, next_epoch_qc)) =
                 task_state.consensus.read().await.transition_qc().cloned()
             else {
@@ -22,7 +22,7 @@ use hotshot_types::{
         signature_key::SignatureKey,
         storage::Storage,
     },
-    utils::{epoch_from_block_number, is_last_block},
+    utils::epoch_from_block_number,
     vote::HasViewNumber,
 };
 use hotshot_utils::anytrace::*;
@@ -170,11 +170,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> ConsensusTaskSt
                 .await;
             },
             HotShotEvent::ExtendedQcRecv(high_qc, next_epoch_high_qc, _) => {
-                if !high_qc
-                    .data
-                    .block_number
-                    .is_some_and(|bn| is_last_block(bn, self.epoch_height))
-                {
+                if !high_qc.is_last_block(self.epoch_height) {
                     tracing::warn!("Received extended QC but we can't verify the leaf is extended");
                     return Ok(());
                 }
@@ -66,6 +66,11 @@ pub struct HotShotTaskCompleted;
 
 /// All of the possible events that can be passed between Sequencing `HotShot` tasks
 #[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(
+    feature = "event-replay",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "TYPES: NodeType")
+)]
 #[allow(clippy::large_enum_variant)]
 pub enum HotShotEvent<TYPES: NodeType> {
     /// Shutdown the task
@@ -180,6 +185,7 @@ pub enum HotShotEvent<TYPES: NodeType> {
     /// Send transactions to the network
     TransactionSend(TYPES::Transaction, TYPES::SignatureKey),
     /// Event to send block payload commitment and metadata from DA leader to the quorum; internal event only
+    #[cfg_attr(feature = "event-replay", serde(skip))]
     SendPayloadCommitmentAndMetadata(
         VidCommitment,
         BuilderCommitment,
@@ -189,6 +195,7 @@ pub enum HotShotEvent<TYPES: NodeType> {
         Option<TYPES::AuctionResult>,
     ),
     /// Event when the transactions task has sequenced transactions. Contains the encoded transactions, the metadata, and the view number
+    #[cfg_attr(feature = "event-replay", serde(skip))]
     BlockRecv(PackedBundle<TYPES>),
     /// Send VID shares to VID storage nodes; emitted by the DA leader
     ///
@@ -16,7 +16,7 @@ use committable::{Commitment, Committable};
 use either::Either;
 use hotshot_task::dependency::{Dependency, EventDependency};
 use hotshot_types::{
-    consensus::OuterConsensus,
+    consensus::{ConsensusMetricsValue, OuterConsensus},
     data::{Leaf2, QuorumProposalWrapper, ViewChangeEvidence2},
     drb::{DrbResult, DrbSeedInput},
     epoch_membership::EpochMembershipCoordinator,
@@ -24,7 +24,7 @@ use hotshot_types::{
     message::{Proposal, UpgradeLock},
     request_response::ProposalRequestPayload,
     simple_certificate::{NextEpochQuorumCertificate2, QuorumCertificate2, UpgradeCertificate},
-    simple_vote::HasEpoch,
+    simple_vote::{HasEpoch, QuorumVote2},
     traits::{
         block_contents::BlockHeader,
         election::Membership,
@@ -34,8 +34,8 @@ use hotshot_types::{
         BlockPayload, ValidatedState,
     },
     utils::{
-        epoch_from_block_number, is_epoch_root, is_epoch_transition, is_transition_block,
-        option_epoch_from_block_number, Terminator, View, ViewInner,
+        epoch_from_block_number, is_epoch_root, is_epoch_transition, is_last_block,
+        is_transition_block, option_epoch_from_block_number, Terminator, View, ViewInner,
     },
     vote::{Certificate, HasViewNumber},
     StakeTableEntries,
@@ -47,6 +47,46 @@ use vbs::version::StaticVersionType;
 
 use crate::{events::HotShotEvent, quorum_proposal_recv::ValidationInfo, request::REQUEST_TIMEOUT};
 
+/// The event to send our quorum vote for a leaf at `leaf_height` with.
+///
+/// A node sends exactly one vote per leaf, even if it is a member of both the current and the next
+/// epoch committees. For the last block of an epoch that vote is an extended vote, which the
+/// receivers count towards both stake tables, so a second vote would count the node's stake twice.
+#[must_use]
+pub fn quorum_vote_event<TYPES: NodeType>(
+    vote: QuorumVote2<TYPES>,
+    leaf_height: u64,
+    epoch_height: u64,
+    epochs_enabled: bool,
+) -> HotShotEvent<TYPES> {
+    if epochs_enabled && is_last_block(leaf_height, epoch_height) {
+        HotShotEvent::ExtendedQuorumVoteSend(vote)
+    } else {
+        HotShotEvent::QuorumVoteSend(vote)
+    }
+}
+
+/// Record the number of views it took to reach a decide.
+///
+/// If it exceeds `alert_threshold`, finality is degrading, so we also log a warning and count the
+/// decide as slow.
+#[allow(clippy::cast_precision_loss)]
+pub fn record_views_per_decide(
+    metrics: &ConsensusMetricsValue,
+    views_per_decide: u64,
+    alert_threshold: Option<u64>,
+) {
+    metrics
+        .number_of_views_per_decide_event
+        .add_point(views_per_decide as f64);
+    if let Some(threshold) = alert_threshold.filter(|threshold| views_per_decide > *threshold) {
+        tracing::warn!(
+            "Decide took {views_per_decide} views, exceeding the alert threshold of {threshold}"
+        );
+        metrics.number_of_slow_decides.add(1);
+    }
+}
+
 /// Trigger a request to the network for a proposal for a view and wait for the response or timeout.
 #[instrument(skip_all)]
 #[allow(clippy::too_many_arguments)]
@@ -1093,6 +1133,59 @@ pub(crate) async fn validate_proposal_view_and_certs<
     Ok(())
 }
 
+/// Ensure that the epoch a proposal declares is the epoch its block number belongs to.
+///
+/// A proposal whose declared epoch disagrees with its block number is invalid, so this
+/// should be checked before the proposal is sent.
+///
+/// # Errors
+/// If the declared epoch differs from the epoch derived from the proposal's block number.
+pub fn validate_proposal_epoch<TYPES: NodeType>(
+    proposal: &QuorumProposalWrapper<TYPES>,
+    epochs_enabled: bool,
+    epoch_height: u64,
+) -> Result<()> {
+    let block_number = proposal.block_header().block_number();
+    let expected_epoch =
+        option_epoch_from_block_number::<TYPES>(epochs_enabled, block_number, epoch_height);
+    ensure!(
+        proposal.epoch() == expected_epoch,
+        error!(
+            "Proposal declares epoch {:?}, but block {} belongs to epoch {:?}",
+            proposal.epoch(),
+            block_number,
+            expected_epoch
+        )
+    );
+
+    Ok(())
+}
+
+/// Ensure that a proposal only carries a `next_drb_result` if its block is an epoch transition
+/// block.
+///
+/// The leader only attaches the DRB result of the next epoch to the proposals of the epoch
+/// transition, so a proposal carrying one for any other block is malformed.
+///
+/// # Errors
+/// If the proposal carries a `next_drb_result`, but its block is not an epoch transition block.
+pub fn validate_next_drb_result<TYPES: NodeType>(
+    proposal: &QuorumProposalWrapper<TYPES>,
+    epoch_height: u64,
+) -> Result<()> {
+    let block_number = proposal.block_header().block_number();
+    ensure!(
+        proposal.next_drb_result().is_none() || is_epoch_transition(block_number, epoch_height),
+        error!(
+            "Proposal for block {} carries a next_drb_result, but the block is not an epoch \
+             transition block",
+            block_number
+        )
+    );
+
+    Ok(())
+}
+
 /// Helper function to send events and log errors
 pub async fn broadcast_event<E: Clone + std::fmt::Debug>(event: E, sender: &Sender<E>) {
     match sender.broadcast_direct(event).await {
@@ -1112,6 +1205,63 @@ pub async fn broadcast_event<E: Clone + std::fmt::Debug>(event: E, sender: &Send
     }
 }
 
+/// Number of times [`broadcast_proposal`] retries sending a proposal into a full channel.
+pub const PROPOSAL_SEND_RETRIES: u32 = 3;
+
+/// Delay before the first retry of [`broadcast_proposal`], doubled after every further retry.
+const PROPOSAL_SEND_RETRY_DELAY: Duration = Duration::from_millis(10);
+
+/// Broadcast a proposal and confirm that the channel accepted it.
+///
+/// A full channel either evicts its oldest queued event to make room for the proposal, or holds
+/// the proposal back until there is room. Since the consumers usually catch up quickly, the send is
+/// first retried up to [`PROPOSAL_SEND_RETRIES`] times with exponential backoff while the channel
+/// is full. After that the proposal is sent like [`broadcast_event`] would. Since a lost proposal
+/// stalls the whole view, the retries and the cases where events are lost are counted in the
+/// consensus metrics: an overflowing channel accepts the event but evicts the oldest queued one,
+/// and a closed channel drops the event altogether.
+///
+/// # Errors
+/// If the channel is closed.
+pub async fn broadcast_proposal<E: Clone + std::fmt::Debug>(
+    event: E,
+    sender: &Sender<E>,
+    metrics: &ConsensusMetricsValue,
+) -> Result<()> {
+    let mut delay = PROPOSAL_SEND_RETRY_DELAY;
+    for retry in 1..=PROPOSAL_SEND_RETRIES {
+        if !sender.is_full() || sender.is_closed() {
+            break;
+        }
+        tracing::warn!(
+            "Event queue is full, retrying proposal send in {delay:?} (retry {retry} of \
+             {PROPOSAL_SEND_RETRIES})"
+        );
+        metrics.number_of_proposal_send_retries.add(1);
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+    }
+
+    match sender.broadcast_direct(event).await {
+        Ok(None) => Ok(()),
+        Ok(Some(overflowed)) => {
+            metrics.number_of_event_queue_overflows.add(1);
+            tracing::error!(
+                "Event sender queue overflow, Oldest event removed form queue: {:?}",
+                overflowed
+            );
+            Ok(())
+        },
+        Err(SendError(e)) => {
+            metrics.number_of_failed_proposal_sends.add(1);
+            bail!(
+                "Event: {:?}\n Sending failed, event stream probably shutdown",
+                e
+            );
+        },
+    }
+}
+
 /// Gets the next epoch QC corresponding to this epoch QC from the shared consensus state;
 /// if it's not yet available, waits for it with a given timeout.
 pub async fn wait_for_next_epoch_qc<TYPES: NodeType>(
@@ -1206,11 +1356,7 @@ pub async fn validate_qc_and_next_epoch_qc<TYPES: NodeType, V: Versions>(
         );
     }
 
-    if qc
-        .data
-        .block_number
-        .is_some_and(|b| is_epoch_transition(b, epoch_height))
-    {
+    if qc.is_transition(epoch_height) {
         ensure!(
             maybe_next_epoch_qc.is_some(),
             error!("Received High QC for the transition block but not the next epoch QC")
@@ -1238,3 +1384,136 @@ pub async fn validate_qc_and_next_epoch_qc<TYPES: NodeType, V: Versions>(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use hotshot_types::{consensus::ConsensusMetricsValue, traits::metrics::Counter};
+
+    use super::{broadcast_proposal, record_views_per_decide, PROPOSAL_SEND_RETRIES};
+
+    /// A counter whose value can be read back.
+    #[derive(Clone, Debug, Default)]
+    struct TestCounter(Arc<AtomicUsize>);
+
+    impl Counter for TestCounter {
+        fn add(&self, amount: usize) {
+            self.0.fetch_add(amount, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn test_record_views_per_decide_alerts_on_large_view_gap() {
+        let slow_decides = TestCounter::default();
+        let metrics = ConsensusMetricsValue {
+            number_of_slow_decides: Box::new(slow_decides.clone()),
+            ..Default::default()
+        };
+
+        // Regular decides do not trigger the alert.
+        record_views_per_decide(&metrics, 3, Some(10));
+        record_views_per_decide(&metrics, 10, Some(10));
+        assert_eq!(slow_decides.0.load(Ordering::Relaxed), 0);
+
+        // A decide after an abnormally large view gap does.
+        record_views_per_decide(&metrics, 50, Some(10));
+        assert_eq!(slow_decides.0.load(Ordering::Relaxed), 1);
+
+        // Without a threshold, the alert is disabled.
+        record_views_per_decide(&metrics, 50, None);
+        assert_eq!(slow_decides.0.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_broadcast_proposal_waits_for_room() {
+        let overflows = TestCounter::default();
+        let metrics = ConsensusMetricsValue {
+            number_of_event_queue_overflows: Box::new(overflows.clone()),
+            ..Default::default()
+        };
+        let (sender, mut receiver) = async_broadcast::broadcast::<u64>(1);
+
+        // Fill the channel so that the send has to wait for the reader
+        sender.try_broadcast(0).unwrap();
+
+        let reader = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let first = receiver.recv().await.unwrap();
+            let second = receiver.recv().await.unwrap();
+            (first, second)
+        });
+
+        broadcast_proposal(1, &sender, &metrics).await.unwrap();
+        assert_eq!(reader.await.unwrap(), (0, 1));
+        assert_eq!(overflows.0.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_broadcast_proposal_retries_full_channel() {
+        let retries = TestCounter::default();
+        let overflows = TestCounter::default();
+        let metrics = ConsensusMetricsValue {
+            number_of_proposal_send_retries: Box::new(retries.clone()),
+            number_of_event_queue_overflows: Box::new(overflows.clone()),
+            ..Default::default()
+        };
+
+        // An overflowing channel that is full when we first try to send
+        let (mut sender, mut receiver) = async_broadcast::broadcast::<u64>(1);
+        sender.set_overflow(true);
+        sender.try_broadcast(0).unwrap();
+
+        let reader = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(15)).await;
+            let first = receiver.recv().await.unwrap();
+            let second = receiver.recv().await.unwrap();
+            (first, second)
+        });
+
+        // The send is retried once the reader makes room, instead of evicting the queued event
+        broadcast_proposal(1, &sender, &metrics).await.unwrap();
+        assert_eq!(reader.await.unwrap(), (0, 1));
+        assert!(retries.0.load(Ordering::Relaxed) >= 1);
+        assert_eq!(overflows.0.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_broadcast_proposal_counts_lost_events() {
+        let retries = TestCounter::default();
+        let overflows = TestCounter::default();
+        let failed_sends = TestCounter::default();
+        let metrics = ConsensusMetricsValue {
+            number_of_proposal_send_retries: Box::new(retries.clone()),
+            number_of_event_queue_overflows: Box::new(overflows.clone()),
+            number_of_failed_proposal_sends: Box::new(failed_sends.clone()),
+            ..Default::default()
+        };
+
+        // An overflowing channel that stays full accepts the event once the retries run out, but
+        // evicts the oldest one
+        let (mut sender, mut receiver) = async_broadcast::broadcast::<u64>(1);
+        sender.set_overflow(true);
+        sender.try_broadcast(0).unwrap();
+        broadcast_proposal(1, &sender, &metrics).await.unwrap();
+        assert_eq!(receiver.recv().await.unwrap(), 1);
+        assert_eq!(
+            retries.0.load(Ordering::Relaxed),
+            PROPOSAL_SEND_RETRIES as usize
+        );
+        assert_eq!(overflows.0.load(Ordering::Relaxed), 1);
+        assert_eq!(failed_sends.0.load(Ordering::Relaxed), 0);
+
+        // A closed channel drops the event
+        let (sender, receiver) = async_broadcast::broadcast::<u64>(1);
+        drop(receiver);
+        assert!(broadcast_proposal(1, &sender, &metrics).await.is_err());
+        assert_eq!(failed_sends.0.load(Ordering::Relaxed), 1);
+    }
+}
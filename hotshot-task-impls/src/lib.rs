@@ -47,6 +47,9 @@ pub mod builder;
 /// Helper functions used by any task
 pub mod helpers;
 
+/// Runtime adjustable log levels for individual tasks
+pub mod log_level;
+
 /// Task which responses to requests from the network
 pub mod response;
 
@@ -61,3 +64,7 @@ pub mod quorum_proposal_recv;
 
 /// Task for storing and replaying all received tasks by a node
 pub mod rewind;
+
+/// Recording and offline replay of the events received by a single task
+#[cfg(feature = "event-replay")]
+pub mod replay;
@@ -0,0 +1,172 @@
+// Copyright (c) 2021-2024 Espresso Systems (espressosys.com)
+// This file is part of the HotShot repository.
+
+// You should have received a copy of the MIT License
+// along with the HotShot repository. If not, see <https://mit-license.org/>.
+
+//! Runtime adjustable log levels for individual tasks.
+//!
+//! Each task state carries a [`TaskLogLevel`], through which the verbosity of
+//! the task can be raised or lowered while the node is running, independently
+//! of the rest of the logs. The handles only take effect once they are
+//! registered with the subscriber, which `hotshot` does for the tasks of every
+//! node it starts.
+
+use std::sync::{
+    atomic::{AtomicU8, Ordering},
+    Arc,
+};
+
+use tracing::{level_filters::LevelFilter, Level, Metadata};
+
+/// Targets of the logs and spans emitted by the quorum proposal task.
+pub const QUORUM_PROPOSAL_LOG_TARGETS: &[&str] = &[
+    "hotshot_task_impls::quorum_proposal",
+    "QuorumProposalTaskState",
+];
+
+/// Targets of the logs and spans emitted by the quorum vote task.
+pub const QUORUM_VOTE_LOG_TARGETS: &[&str] = &[
+    "hotshot_task_impls::quorum_vote",
+    "QuorumVoteTaskState",
+    "VoteDependencyHandle",
+];
+
+/// Targets of the logs and spans of every task with a [`TaskLogLevel`].
+pub const TASK_LOG_TARGETS: &[&[&str]] = &[QUORUM_PROPOSAL_LOG_TARGETS, QUORUM_VOTE_LOG_TARGETS];
+
+/// The log level handles of the tasks of a single node.
+#[derive(Clone, Debug)]
+pub struct TaskLogLevels {
+    /// The log level of the quorum proposal task.
+    pub quorum_proposal: TaskLogLevel,
+
+    /// The log level of the quorum vote task.
+    pub quorum_vote: TaskLogLevel,
+}
+
+impl Default for TaskLogLevels {
+    fn default() -> Self {
+        Self {
+            quorum_proposal: TaskLogLevel::new(QUORUM_PROPOSAL_LOG_TARGETS),
+            quorum_vote: TaskLogLevel::new(QUORUM_VOTE_LOG_TARGETS),
+        }
+    }
+}
+
+impl TaskLogLevels {
+    /// The log level handle of the task with the given name, if it has one.
+    ///
+    /// The tasks are named `quorum-proposal` and `quorum-vote`.
+    #[must_use]
+    pub fn task(&self, task: &str) -> Option<&TaskLogLevel> {
+        match task {
+            "quorum-proposal" => Some(&self.quorum_proposal),
+            "quorum-vote" => Some(&self.quorum_vote),
+            _ => None,
+        }
+    }
+
+    /// All the handles, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &TaskLogLevel> {
+        [&self.quorum_proposal, &self.quorum_vote].into_iter()
+    }
+}
+
+/// Marker for a handle without a level of its own.
+const UNSET: u8 = u8::MAX;
+
+/// Encode a level filter so that it can be stored atomically.
+fn encode(level: LevelFilter) -> u8 {
+    match level.into_level() {
+        None => 0,
+        Some(Level::ERROR) => 1,
+        Some(Level::WARN) => 2,
+        Some(Level::INFO) => 3,
+        Some(Level::DEBUG) => 4,
+        Some(_) => 5,
+    }
+}
+
+/// Decode a level filter stored with [`encode`].
+fn decode(level: u8) -> Option<LevelFilter> {
+    match level {
+        0 => Some(LevelFilter::OFF),
+        1 => Some(LevelFilter::ERROR),
+        2 => Some(LevelFilter::WARN),
+        3 => Some(LevelFilter::INFO),
+        4 => Some(LevelFilter::DEBUG),
+        5 => Some(LevelFilter::TRACE),
+        _ => None,
+    }
+}
+
+/// A handle to the log level of a single task, which can be changed at runtime.
+///
+/// Clones of a handle share the same level. While no level is set, the logs of
+/// the task are filtered like any other logs.
+#[derive(Clone, Debug)]
+pub struct TaskLogLevel {
+    /// Targets of the logs and spans that belong to the task.
+    targets: &'static [&'static str],
+
+    /// The encoded level, or [`UNSET`].
+    level: Arc<AtomicU8>,
+}
+
+impl TaskLogLevel {
+    /// Create a handle for the task emitting logs with the given targets.
+    ///
+    /// A target also covers all of its submodules.
+    #[must_use]
+    pub fn new(targets: &'static [&'static str]) -> Self {
+        Self {
+            targets,
+            level: Arc::new(AtomicU8::new(UNSET)),
+        }
+    }
+
+    /// The level set for the task, if any.
+    #[must_use]
+    pub fn level(&self) -> Option<LevelFilter> {
+        decode(self.level.load(Ordering::Relaxed))
+    }
+
+    /// Override the level of the logs of the task.
+    pub fn set_level(&self, level: LevelFilter) {
+        self.level.store(encode(level), Ordering::Relaxed);
+        // The most verbose level enabled by the subscriber may have changed.
+        tracing::callsite::rebuild_interest_cache();
+    }
+
+    /// Remove the override, so that the logs of the task are filtered like any
+    /// other logs again.
+    pub fn reset(&self) {
+        self.level.store(UNSET, Ordering::Relaxed);
+        tracing::callsite::rebuild_interest_cache();
+    }
+
+    /// Whether another handle still shares the level of this one.
+    #[must_use]
+    pub fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.level) > 1
+    }
+
+    /// Whether the given log or span belongs to the task.
+    #[must_use]
+    pub fn matches(&self, metadata: &Metadata<'_>) -> bool {
+        matches_targets(self.targets, metadata)
+    }
+}
+
+/// Whether the given log or span has one of the targets, or belongs to a
+/// submodule of one of them.
+#[must_use]
+pub fn matches_targets(targets: &[&str], metadata: &Metadata<'_>) -> bool {
+    targets.iter().any(|target| {
+        metadata
+            .target()
+            .strip_prefix(target)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+    })
+}
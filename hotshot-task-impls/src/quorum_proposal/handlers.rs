@@ -19,8 +19,10 @@ use async_lock::RwLock;
 use committable::{Commitment, Committable};
 use hotshot_task::dependency_task::HandleDepOutput;
 use hotshot_types::{
-    consensus::{CommitmentAndMetadata, OuterConsensus},
-    data::{Leaf2, QuorumProposal2, QuorumProposalWrapper, VidDisperse, ViewChangeEvidence2},
+    consensus::{CommitmentAndMetadata, ConsensusMetricsValue, OuterConsensus},
+    data::{
+        null_block, Leaf2, QuorumProposal2, QuorumProposalWrapper, VidDisperse, ViewChangeEvidence2,
+    },
     epoch_membership::EpochMembership,
     message::Proposal,
     simple_certificate::{NextEpochQuorumCertificate2, QuorumCertificate2, UpgradeCertificate},
@@ -28,7 +30,6 @@ use hotshot_types::{
         block_contents::BlockHeader,
         node_implementation::{ConsensusTime, NodeImplementation, NodeType},
         signature_key::SignatureKey,
-        BlockPayload,
     },
     utils::{
         epoch_from_block_number, is_epoch_transition, is_last_block, is_transition_block,
@@ -43,8 +44,8 @@ use vbs::version::StaticVersionType;
 use crate::{
     events::HotShotEvent,
     helpers::{
-        broadcast_event, parent_leaf_and_state, validate_qc_and_next_epoch_qc,
-        wait_for_next_epoch_qc,
+        broadcast_event, broadcast_proposal, parent_leaf_and_state, validate_proposal_epoch,
+        validate_qc_and_next_epoch_qc, wait_for_next_epoch_qc,
     },
     quorum_proposal::{QuorumProposalTaskState, UpgradeLock, Versions},
 };
@@ -71,6 +72,40 @@ pub(crate) enum ProposalDependency {
     VidShare,
 }
 
+/// The time left to wait for QCs, out of the `wait_duration` that started at `view_start_time`.
+///
+/// Running out of time is a normal outcome, for which `Ok(None)` is returned, so that the caller
+/// can continue with the best QC it has received so far.
+///
+/// # Errors
+/// If `now` lies before `view_start_time`, which should never happen.
+fn wait_time_left(
+    view_start_time: Instant,
+    now: Instant,
+    wait_duration: Duration,
+) -> Result<Option<Duration>> {
+    let time_spent = now.checked_duration_since(view_start_time).ok_or(error!(
+        "Time elapsed since the start of the task is negative. This should never happen."
+    ))?;
+
+    Ok(wait_duration
+        .checked_sub(time_spent)
+        .filter(|time_left| !time_left.is_zero()))
+}
+
+/// How long to wait for the highest QC and the transition QC from the start of the view, given the
+/// view `timeout` in milliseconds and the `(numerator, denominator)` fraction of it to wait.
+///
+/// A zero denominator means we do not wait at all.
+fn high_qc_wait_duration(timeout: u64, (numerator, denominator): (u64, u64)) -> Duration {
+    Duration::from_millis(
+        timeout
+            .saturating_mul(numerator)
+            .checked_div(denominator)
+            .unwrap_or_default(),
+    )
+}
+
 /// Handler for the proposal dependency
 pub struct ProposalDependencyHandle<TYPES: NodeType, V: Versions> {
     /// Latest view number that has been proposed for (proxy for cur_view).
@@ -100,9 +135,16 @@ pub struct ProposalDependencyHandle<TYPES: NodeType, V: Versions> {
     /// Shared consensus task state
     pub consensus: OuterConsensus<TYPES>,
 
+    /// The consensus metrics
+    pub consensus_metrics: Arc<ConsensusMetricsValue>,
+
     /// View timeout from config.
     pub timeout: u64,
 
+    /// Fraction of the view timeout, as a numerator and a denominator, to wait for the highest QC
+    /// and the transition QC before proposing.
+    pub high_qc_wait_fraction: (u64, u64),
+
     /// The most recent upgrade certificate this node formed.
     /// Note: this is ONLY for certificates that have been formed internally,
     /// so that we can propose with them.
@@ -165,7 +207,7 @@ impl<TYPES: NodeType, V: Versions> ProposalDependencyHandle<TYPES, V> {
 
         let mut transition_qc = self.consensus.read().await.transition_qc().cloned();
 
-        let wait_duration = Duration::from_millis(self.timeout / 2);
+        let wait_duration = high_qc_wait_duration(self.timeout, self.high_qc_wait_fraction);
 
         let mut rx = self.receiver.clone();
 
@@ -200,16 +242,20 @@ impl<TYPES: NodeType, V: Versions> ProposalDependencyHandle<TYPES, V> {
                 }
             }
         }
-        // TODO configure timeout
+        // Whether we actually waited, and whether a newer transition QC arrived while we did.
+        let mut waited = false;
+        let mut received_newer_qc = false;
         while self.view_start_time.elapsed() < wait_duration {
-            let time_spent = Instant::now()
-            .checked_duration_since(self.view_start_time)
-            .ok_or(error!("Time elapsed since the start of the task is negative. This should never happen."))?;
-            let time_left = wait_duration
-                .checked_sub(time_spent)
-                .ok_or(info!("No time left"))?;
-            let Ok(Ok(event)) = tokio::time::timeout(time_left, rx.recv_direct()).await else {
-                return Ok(transition_qc);
+            let Some(time_left) =
+                wait_time_left(self.view_start_time, Instant::now(), wait_duration)?
+            else {
+                break;
+            };
+            waited = true;
+            let event = match tokio::time::timeout(time_left, rx.recv_direct()).await {
+                Ok(Ok(event)) => event,
+                Ok(Err(_)) => return Ok(transition_qc),
+                Err(_) => break,
             };
             if let HotShotEvent::HighQcRecv(qc, maybe_next_epoch_qc, _sender) = event.as_ref() {
                 if let Some(block_number) = qc.data.block_number {
@@ -237,9 +283,19 @@ impl<TYPES: NodeType, V: Versions> ProposalDependencyHandle<TYPES, V> {
                         .is_none_or(|tqc| qc.view_number() > tqc.0.view_number())
                 {
                     transition_qc = Some((qc.clone(), next_epoch_qc.clone()));
+                    received_newer_qc = true;
                 }
             }
         }
+        if waited {
+            tracing::info!("No time left to wait for the transition QC. Continuing with the transition QC that we received: {transition_qc:?}");
+            // Counted as in `wait_for_highest_qc`.
+            if !received_newer_qc {
+                self.consensus_metrics
+                    .number_of_high_qc_wait_timeouts
+                    .add(1);
+            }
+        }
         Ok(transition_qc)
     }
     /// Waits for the configured timeout for nodes to send HighQc messages to us.  We'll
@@ -254,7 +310,7 @@ impl<TYPES: NodeType, V: Versions> ProposalDependencyHandle<TYPES, V> {
 
         let mut highest_qc = self.consensus.read().await.high_qc().clone();
 
-        let wait_duration = Duration::from_millis(self.timeout / 2);
+        let wait_duration = high_qc_wait_duration(self.timeout, self.high_qc_wait_fraction);
 
         let mut rx = self.receiver.clone();
 
@@ -278,25 +334,38 @@ impl<TYPES: NodeType, V: Versions> ProposalDependencyHandle<TYPES, V> {
             }
         }
 
-        // TODO configure timeout
+        // Whether we actually waited, and whether a higher QC arrived while we did.
+        let mut waited = false;
+        let mut received_newer_qc = false;
         while self.view_start_time.elapsed() < wait_duration {
-            let time_spent = Instant::now()
-                .checked_duration_since(self.view_start_time)
-                .ok_or(error!("Time elapsed since the start of the task is negative. This should never happen."))?;
-            let time_left = wait_duration
-                .checked_sub(time_spent)
-                .ok_or(info!("No time left"))?;
+            let Some(time_left) =
+                wait_time_left(self.view_start_time, Instant::now(), wait_duration)?
+            else {
+                break;
+            };
+            waited = true;
             let Ok(maybe_qc) =
                 tokio::time::timeout(time_left, self.wait_for_qc_event(rx.clone())).await
             else {
-                tracing::info!("Some nodes did not respond with their HighQc in time. Continuing with the highest QC that we received: {highest_qc:?}");
-                return Ok(highest_qc);
+                break;
             };
             let Some(qc) = maybe_qc else {
                 continue;
             };
             if qc.view_number() > highest_qc.view_number() {
                 highest_qc = qc;
+                received_newer_qc = true;
+            }
+        }
+        if waited {
+            tracing::info!("Some nodes did not respond with their HighQc in time. Continuing with the highest QC that we received: {highest_qc:?}");
+            // Running out of time only counts as a timeout if nobody sent us a higher QC in the
+            // meantime. The transition QC wait shares the deadline of this one, and a zero-length
+            // wait is not counted, so a proposal that waits for both QCs is only counted once.
+            if !received_newer_qc {
+                self.consensus_metrics
+                    .number_of_high_qc_wait_timeouts
+                    .add(1);
             }
         }
         Ok(highest_qc.clone())
@@ -387,11 +456,11 @@ impl<TYPES: NodeType, V: Versions> ProposalDependencyHandle<TYPES, V> {
             if is_epoch_transition(parent_block_number, self.epoch_height)
                 && !is_last_block(parent_block_number, self.epoch_height)
             {
-                let (empty_payload, empty_metadata) = <TYPES as NodeType>::BlockPayload::empty();
+                let (empty_builder_commitment, empty_metadata) =
+                    null_block::empty_block_commitment::<TYPES>();
                 tracing::info!("Reached end of epoch.");
                 ensure!(
-                    builder_commitment == empty_payload.builder_commitment(&metadata)
-                        && metadata == empty_metadata,
+                    builder_commitment == empty_builder_commitment && metadata == empty_metadata,
                     "We're trying to propose non empty block in the epoch transition. Do not propose. View number: {}. Parent Block number: {}",
                     self.view_number,
                     parent_block_number,
@@ -449,10 +518,7 @@ impl<TYPES: NodeType, V: Versions> ProposalDependencyHandle<TYPES, V> {
             );
             return Ok(());
         }
-        let is_high_qc_for_last_block = parent_qc
-            .data
-            .block_number
-            .is_some_and(|block_number| is_epoch_transition(block_number, self.epoch_height));
+        let is_high_qc_for_last_block = parent_qc.is_transition(self.epoch_height);
         let next_epoch_qc = if self.upgrade_lock.epochs_enabled(self.view_number).await
             && is_high_qc_for_last_block
         {
@@ -499,6 +565,7 @@ impl<TYPES: NodeType, V: Versions> ProposalDependencyHandle<TYPES, V> {
                 next_drb_result,
             },
         };
+        validate_proposal_epoch(&proposal, version >= V::Epochs::VERSION, self.epoch_height)?;
 
         let proposed_leaf = Leaf2::from_quorum_proposal(&proposal);
         ensure!(
@@ -523,14 +590,23 @@ impl<TYPES: NodeType, V: Versions> ProposalDependencyHandle<TYPES, V> {
             proposed_leaf.justify_qc().view_number()
         );
 
-        broadcast_event(
+        // Measured before broadcasting, so that the time spent sending is not included.
+        self.consensus_metrics
+            .proposal_latency
+            .add_point(self.view_start_time.elapsed().as_secs_f64());
+        broadcast_proposal(
             Arc::new(HotShotEvent::QuorumProposalSend(
                 message.clone(),
                 self.public_key.clone(),
             )),
             &self.sender,
+            &self.consensus_metrics,
         )
-        .await;
+        .await
+        .context(warn!(
+            "Failed to send quorum proposal for view {:?}",
+            self.view_number
+        ))?;
 
         Ok(())
     }
@@ -646,10 +722,7 @@ impl<TYPES: NodeType, V: Versions> HandleDepOutput for ProposalDependencyHandle<
                     tracing::error!("Error while waiting for highest QC");
                     return;
                 };
-                if qc.data.block_number.is_some_and(|bn| {
-                    is_epoch_transition(bn, self.epoch_height)
-                        && !is_last_block(bn, self.epoch_height)
-                }) {
+                if qc.is_transition(self.epoch_height) && !qc.is_last_block(self.epoch_height) {
                     tracing::error!("High is in transition but we need to propose with transition QC, do nothing");
                     return;
                 }
@@ -756,3 +829,68 @@ pub(super) async fn handle_eqc_formed<
     )
     .await;
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_high_qc_wait_duration() {
+        // The default waits for half the view timeout.
+        assert_eq!(
+            high_qc_wait_duration(10_000, (1, 2)),
+            Duration::from_millis(5_000)
+        );
+        assert_eq!(
+            high_qc_wait_duration(10_000, (3, 4)),
+            Duration::from_millis(7_500)
+        );
+        assert_eq!(high_qc_wait_duration(10_000, (0, 2)), Duration::ZERO);
+        assert_eq!(high_qc_wait_duration(10_000, (1, 0)), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_wait_time_left_at_boundary() {
+        let view_start_time = Instant::now();
+        let wait_duration = Duration::from_millis(100);
+
+        assert_eq!(
+            wait_time_left(
+                view_start_time,
+                view_start_time + Duration::from_millis(40),
+                wait_duration
+            )
+            .unwrap(),
+            Some(Duration::from_millis(60))
+        );
+
+        // Running out of time exactly at the boundary, or past it, lets the caller continue with
+        // the best QC so far rather than failing.
+        assert_eq!(
+            wait_time_left(
+                view_start_time,
+                view_start_time + wait_duration,
+                wait_duration
+            )
+            .unwrap(),
+            None
+        );
+        assert_eq!(
+            wait_time_left(
+                view_start_time,
+                view_start_time + 2 * wait_duration,
+                wait_duration
+            )
+            .unwrap(),
+            None
+        );
+
+        // Time going backwards is still an error.
+        assert!(wait_time_left(
+            view_start_time + wait_duration,
+            view_start_time,
+            wait_duration
+        )
+        .is_err());
+    }
+}
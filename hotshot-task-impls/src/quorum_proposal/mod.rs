@@ -16,7 +16,7 @@ use hotshot_task::{
     task::TaskState,
 };
 use hotshot_types::{
-    consensus::OuterConsensus,
+    consensus::{ConsensusMetricsValue, OuterConsensus},
     epoch_membership::EpochMembershipCoordinator,
     message::UpgradeLock,
     simple_certificate::{NextEpochQuorumCertificate2, QuorumCertificate2, UpgradeCertificate},
@@ -24,7 +24,7 @@ use hotshot_types::{
         node_implementation::{ConsensusTime, NodeImplementation, NodeType, Versions},
         signature_key::SignatureKey,
     },
-    utils::{is_epoch_transition, EpochTransitionIndicator},
+    utils::EpochTransitionIndicator,
     vote::{Certificate, HasViewNumber},
     StakeTableEntries,
 };
@@ -33,7 +33,9 @@ use tokio::task::JoinHandle;
 use tracing::instrument;
 
 use self::handlers::{ProposalDependency, ProposalDependencyHandle};
-use crate::{events::HotShotEvent, quorum_proposal::handlers::handle_eqc_formed};
+use crate::{
+    events::HotShotEvent, log_level::TaskLogLevel, quorum_proposal::handlers::handle_eqc_formed,
+};
 
 mod handlers;
 
@@ -70,12 +72,19 @@ pub struct QuorumProposalTaskState<TYPES: NodeType, I: NodeImplementation<TYPES>
     /// View timeout from config.
     pub timeout: u64,
 
+    /// Fraction of the view timeout, as a numerator and a denominator, to wait for the highest QC
+    /// and the transition QC before proposing.
+    pub high_qc_wait_fraction: (u64, u64),
+
     /// This node's storage ref
     pub storage: Arc<RwLock<I::Storage>>,
 
     /// Shared consensus task state
     pub consensus: OuterConsensus<TYPES>,
 
+    /// The consensus metrics
+    pub consensus_metrics: Arc<ConsensusMetricsValue>,
+
     /// The node's id
     pub id: u64,
 
@@ -92,6 +101,9 @@ pub struct QuorumProposalTaskState<TYPES: NodeType, I: NodeImplementation<TYPES>
 
     /// Number of blocks in an epoch, zero means there are no epochs
     pub epoch_height: u64,
+
+    /// Handle to the log level of this task, which can be changed while it is running
+    pub log_level: TaskLogLevel,
 }
 
 impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions>
@@ -232,10 +244,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions>
                 }
                 if let HotShotEvent::Qc2Formed(Either::Left(qc)) = event.as_ref() {
                     if qc.view_number() + 1 == view_number {
-                        return qc
-                            .data
-                            .block_number
-                            .is_none_or(|bn| !is_epoch_transition(bn, epoch_height));
+                        return !qc.is_transition(epoch_height);
                     }
                 }
                 false
@@ -254,11 +263,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions>
                     timeout_dependency.mark_as_completed(event);
                 },
                 Either::Left(qc) => {
-                    if qc
-                        .data
-                        .block_number
-                        .is_none_or(|bn| !is_epoch_transition(bn, epoch_height))
-                    {
+                    if !qc.is_transition(epoch_height) {
                         next_epoch_qc_dependency.mark_as_completed(event.clone());
                     }
                     qc_dependency.mark_as_completed(event);
@@ -380,7 +385,9 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions>
                 private_key: self.private_key.clone(),
                 instance_state: Arc::clone(&self.instance_state),
                 consensus: OuterConsensus::new(Arc::clone(&self.consensus.inner_consensus)),
+                consensus_metrics: Arc::clone(&self.consensus_metrics),
                 timeout: self.timeout,
+                high_qc_wait_fraction: self.high_qc_wait_fraction,
                 formed_upgrade_certificate: self.formed_upgrade_certificate.clone(),
                 upgrade_lock: self.upgrade_lock.clone(),
                 id: self.id,
@@ -643,6 +650,20 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions>
         }
         self.proposal_dependencies = keep;
     }
+
+    /// The leader this task expects to propose next, i.e. the leader of the view after
+    /// `latest_proposed_view` in the current epoch.
+    ///
+    /// # Errors
+    /// If the membership of the current epoch or its leader cannot be determined
+    pub async fn current_leader(&self) -> Result<TYPES::SignatureKey> {
+        let epoch_membership = self
+            .membership_coordinator
+            .membership_for_epoch(self.cur_epoch)
+            .await?;
+
+        epoch_membership.leader(self.latest_proposed_view + 1).await
+    }
 }
 
 #[async_trait]
@@ -13,13 +13,13 @@ use async_lock::{RwLock, RwLockUpgradableReadGuard};
 use committable::Committable;
 use hotshot_types::{
     consensus::OuterConsensus,
-    data::{Leaf2, QuorumProposal, QuorumProposalWrapper},
+    data::{null_block, Leaf2, QuorumProposal, QuorumProposalWrapper},
     epoch_membership::EpochMembershipCoordinator,
     message::Proposal,
     simple_certificate::{QuorumCertificate, QuorumCertificate2},
     simple_vote::HasEpoch,
     traits::{
-        block_contents::{BlockHeader, BlockPayload},
+        block_contents::BlockHeader,
         election::Membership,
         node_implementation::{ConsensusTime, NodeImplementation, NodeType},
         signature_key::SignatureKey,
@@ -33,7 +33,7 @@ use hotshot_types::{
     vote::{Certificate, HasViewNumber},
 };
 use hotshot_utils::anytrace::*;
-use tokio::spawn;
+use tokio::{spawn, task::JoinHandle};
 use tracing::instrument;
 use vbs::version::StaticVersionType;
 
@@ -42,15 +42,15 @@ use crate::{
     events::HotShotEvent,
     helpers::{
         broadcast_event, fetch_proposal, update_high_qc, validate_epoch_transition_qc,
-        validate_proposal_safety_and_liveness, validate_proposal_view_and_certs,
-        validate_qc_and_next_epoch_qc,
+        validate_next_drb_result, validate_proposal_safety_and_liveness,
+        validate_proposal_view_and_certs, validate_qc_and_next_epoch_qc,
     },
     quorum_proposal_recv::{UpgradeLock, Versions},
 };
 
 /// Spawn a task which will fire a request to get a proposal, and store it.
 #[allow(clippy::too_many_arguments)]
-fn spawn_fetch_proposal<TYPES: NodeType, V: Versions>(
+pub(super) fn spawn_fetch_proposal<TYPES: NodeType, V: Versions>(
     view: TYPES::View,
     event_sender: Sender<Arc<HotShotEvent<TYPES>>>,
     event_receiver: Receiver<Arc<HotShotEvent<TYPES>>>,
@@ -79,6 +79,84 @@ fn spawn_fetch_proposal<TYPES: NodeType, V: Versions>(
     });
 }
 
+/// Spawn a task which fetches the parent of a proposal that is too far ahead of our view, and
+/// then handles the proposal so that we move into its view once the chain has been caught up.
+pub(super) fn spawn_far_future_catchup<
+    TYPES: NodeType,
+    I: NodeImplementation<TYPES>,
+    V: Versions,
+>(
+    proposal: Proposal<TYPES, QuorumProposalWrapper<TYPES>>,
+    quorum_proposal_sender_key: TYPES::SignatureKey,
+    event_sender: Sender<Arc<HotShotEvent<TYPES>>>,
+    event_receiver: Receiver<Arc<HotShotEvent<TYPES>>>,
+    task_state: &QuorumProposalRecvTaskState<TYPES, I, V>,
+) -> JoinHandle<()> {
+    let id = task_state.id;
+    let public_key = task_state.public_key.clone();
+    let private_key = task_state.private_key.clone();
+    let consensus = task_state.consensus.clone();
+    let membership = task_state.membership.clone();
+    let output_event_stream = task_state.output_event_stream.clone();
+    let storage = Arc::clone(&task_state.storage);
+    let upgrade_lock = task_state.upgrade_lock.clone();
+    let epoch_height = task_state.epoch_height;
+
+    spawn(async move {
+        let parent_view = proposal.data.justify_qc().view_number();
+        if let Err(e) = fetch_proposal(
+            parent_view,
+            event_sender.clone(),
+            event_receiver.clone(),
+            membership.clone(),
+            OuterConsensus::new(Arc::clone(&consensus.inner_consensus)),
+            public_key.clone(),
+            private_key.clone(),
+            &upgrade_lock,
+            epoch_height,
+        )
+        .await
+        {
+            tracing::warn!(
+                "Failed to fetch the parent of far future proposal for view {:?}: {e:?}",
+                proposal.data.view_number()
+            );
+        }
+
+        let proposal_epoch = option_epoch_from_block_number::<TYPES>(
+            proposal.data.proposal.epoch().is_some(),
+            proposal.data.block_header().block_number(),
+            epoch_height,
+        );
+        let Ok(epoch_membership) = membership.membership_for_epoch(proposal_epoch).await else {
+            tracing::warn!("No Stake table for epoch = {:?}", proposal_epoch);
+            return;
+        };
+        let validation_info = ValidationInfo::<TYPES, I, V> {
+            id,
+            public_key,
+            private_key,
+            consensus,
+            membership: epoch_membership,
+            output_event_stream,
+            storage,
+            upgrade_lock,
+            epoch_height,
+        };
+        if let Err(e) = handle_quorum_proposal_recv(
+            &proposal,
+            &quorum_proposal_sender_key,
+            &event_sender,
+            &event_receiver,
+            validation_info,
+        )
+        .await
+        {
+            tracing::error!(?e, "Failed to catch up to the far future proposal");
+        }
+    })
+}
+
 /// Update states in the event that the parent state is not found for a given `proposal`.
 #[instrument(skip_all)]
 pub async fn validate_proposal_liveness<
@@ -152,6 +230,7 @@ async fn validate_epoch_transition_block<
     {
         return Ok(());
     }
+    validate_next_drb_result(&proposal.data, validation_info.epoch_height)?;
     if !is_epoch_transition(
         proposal.data.block_header().block_number(),
         validation_info.epoch_height,
@@ -165,12 +244,10 @@ async fn validate_epoch_transition_block<
     ) {
         return Ok(());
     }
-    // TODO: Is this the best way to do this?
-    let (empty_payload, metadata) = <TYPES as NodeType>::BlockPayload::empty();
+    let (builder_commitment, metadata) = null_block::empty_block_commitment::<TYPES>();
     let header = proposal.data.block_header();
     ensure!(
-        empty_payload.builder_commitment(&metadata) == header.builder_commitment()
-            && &metadata == header.metadata(),
+        builder_commitment == header.builder_commitment() && &metadata == header.metadata(),
         "Block is not empty"
     );
     Ok(())
@@ -389,9 +466,6 @@ pub(crate) async fn handle_quorum_proposal_recv<
         .write()
         .await
         .update_highest_block(proposal_block_number);
-    {
-        validation_info.consensus.write().await.highest_block = proposal_block_number;
-    }
     broadcast_event(
         Arc::new(HotShotEvent::ViewChange(view_number, proposal_epoch)),
         event_sender,
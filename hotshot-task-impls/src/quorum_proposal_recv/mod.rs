@@ -35,7 +35,7 @@ use tokio::task::JoinHandle;
 use tracing::{debug, error, info, instrument, warn};
 use vbs::version::Version;
 
-use self::handlers::handle_quorum_proposal_recv;
+use self::handlers::{handle_quorum_proposal_recv, spawn_far_future_catchup};
 use crate::{
     events::{HotShotEvent, ProposalMissing},
     helpers::{broadcast_event, fetch_proposal, parent_leaf_and_state},
@@ -85,6 +85,10 @@ pub struct QuorumProposalRecvTaskState<TYPES: NodeType, I: NodeImplementation<TY
 
     /// Number of blocks in an epoch, zero means there are no epochs
     pub epoch_height: u64,
+
+    /// Maximum number of views a proposal may be ahead of our current view before we fetch its
+    /// parent first and only then process it, `None` means there is no limit
+    pub max_future_view_skew: Option<u64>,
 }
 
 /// all the info we need to validate a proposal.  This makes it easy to spawn an effemeral task to
@@ -156,6 +160,36 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions>
                     );
                     return;
                 }
+                if let Some(max_future_view_skew) = self.max_future_view_skew {
+                    if *proposal.data.view_number()
+                        > (*self.cur_view).saturating_add(max_future_view_skew)
+                    {
+                        tracing::warn!(
+                            "Proposal for view {:?} is more than {} views ahead of our view {:?}, catching up before processing it",
+                            proposal.data.view_number(),
+                            max_future_view_skew,
+                            self.cur_view
+                        );
+                        self.consensus
+                            .read()
+                            .await
+                            .metrics
+                            .number_of_far_future_proposals
+                            .add(1);
+                        let handle = spawn_far_future_catchup(
+                            proposal.clone(),
+                            sender.clone(),
+                            event_sender,
+                            event_receiver,
+                            self,
+                        );
+                        self.spawned_tasks
+                            .entry(proposal.data.view_number())
+                            .or_default()
+                            .push(handle);
+                        return;
+                    }
+                }
                 let proposal_epoch = option_epoch_from_block_number::<TYPES>(
                     proposal.data.proposal.epoch().is_some(),
                     proposal.data.block_header().block_number(),
@@ -4,14 +4,14 @@
 // You should have received a copy of the MIT License
 // along with the HotShot repository. If not, see <https://mit-license.org/>.
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use async_broadcast::{InactiveReceiver, Sender};
 use async_lock::RwLock;
 use chrono::Utc;
 use committable::Committable;
 use hotshot_types::{
-    consensus::OuterConsensus,
+    consensus::{ConsensusMetricsValue, OuterConsensus},
     data::{Leaf2, QuorumProposalWrapper, VidDisperseShare},
     drb::{DrbResult, INITIAL_DRB_RESULT},
     epoch_membership::{EpochMembership, EpochMembershipCoordinator},
@@ -41,11 +41,14 @@ use crate::{
     events::HotShotEvent,
     helpers::{
         broadcast_event, decide_from_proposal, decide_from_proposal_2, fetch_proposal,
-        handle_drb_result, LeafChainTraversalOutcome,
+        handle_drb_result, quorum_vote_event, record_views_per_decide, LeafChainTraversalOutcome,
     },
     quorum_vote::Versions,
 };
 
+/// Delay before retrying to store a proposal, doubled after every further failed attempt.
+const PROPOSAL_STORAGE_RETRY_DELAY: Duration = Duration::from_millis(10);
+
 /// Store the DRB result from the computation task to the shared `results` table.
 ///
 /// Returns the result if it exists.
@@ -219,7 +222,13 @@ pub(crate) async fn handle_quorum_proposal_validated<
         .await
     };
 
-    if let Some(cert) = &task_state.staged_epoch_upgrade_certificate {
+    let staged_epoch_upgrade_certificate = task_state
+        .upgrade_lock
+        .staged_epoch_upgrade_certificate
+        .read()
+        .await
+        .clone();
+    if let Some(cert) = staged_epoch_upgrade_certificate {
         if leaf_views.last().unwrap().leaf.height() >= task_state.epoch_upgrade_block_height {
             let mut decided_certificate_lock = task_state
                 .upgrade_lock
@@ -236,13 +245,21 @@ pub(crate) async fn handle_quorum_proposal_validated<
                 .update_decided_upgrade_certificate(Some(cert.clone()))
                 .await;
 
-            task_state.staged_epoch_upgrade_certificate = None;
+            *task_state
+                .upgrade_lock
+                .staged_epoch_upgrade_certificate
+                .write()
+                .await = None;
         }
     };
 
     if let Some(cert) = decided_upgrade_cert.clone() {
         if cert.data.new_version == V::Epochs::VERSION {
-            task_state.staged_epoch_upgrade_certificate = Some(cert);
+            *task_state
+                .upgrade_lock
+                .staged_epoch_upgrade_certificate
+                .write()
+                .await = Some(cert);
 
             let epoch_height = task_state.consensus.read().await.epoch_height;
             let first_epoch_number = TYPES::Epoch::new(epoch_from_block_number(
@@ -279,7 +296,6 @@ pub(crate) async fn handle_quorum_proposal_validated<
         consensus_writer.update_locked_view(locked_view_number)?;
     }
 
-    #[allow(clippy::cast_precision_loss)]
     if let Some(decided_view_number) = new_decided_view_number {
         // Bring in the cleanup crew. When a new decide is indeed valid, we need to clear out old memory.
 
@@ -300,10 +316,11 @@ pub(crate) async fn handle_quorum_proposal_validated<
             .set(usize::try_from(consensus_writer.last_decided_view().u64()).unwrap());
         let cur_number_of_views_per_decide_event =
             *proposal.view_number() - consensus_writer.last_decided_view().u64();
-        consensus_writer
-            .metrics
-            .number_of_views_per_decide_event
-            .add_point(cur_number_of_views_per_decide_event as f64);
+        record_views_per_decide(
+            &consensus_writer.metrics,
+            cur_number_of_views_per_decide_event,
+            task_state.views_per_decide_alert_threshold,
+        );
 
         tracing::debug!(
             "Sending Decide for view {:?}",
@@ -460,6 +477,58 @@ pub(crate) async fn update_shared_state<
     Ok(())
 }
 
+/// Store a proposal before voting on it, making up to `max_attempts` attempts.
+///
+/// A failure that a later attempt recovers from is a transient storage hiccup, and is only
+/// counted as a retry. If every attempt fails, the failure is deemed permanent: it is counted
+/// separately and returned, and we must not vote for the proposal.
+pub(crate) async fn store_proposal<TYPES: NodeType, I: NodeImplementation<TYPES>>(
+    storage: &Arc<RwLock<I::Storage>>,
+    proposal: &Proposal<TYPES, QuorumProposalWrapper<TYPES>>,
+    max_attempts: u64,
+    metrics: &ConsensusMetricsValue,
+) -> Result<()> {
+    let view_number = proposal.data.view_number();
+    let mut delay = PROPOSAL_STORAGE_RETRY_DELAY;
+    let mut attempt = 1;
+    loop {
+        let result = storage
+            .write()
+            .await
+            .append_proposal_wrapper(proposal)
+            .await;
+        match result {
+            Ok(()) => {
+                if attempt > 1 {
+                    tracing::warn!(
+                        "Stored proposal for view {view_number:?} after {attempt} attempts, the \
+                         storage failure was transient"
+                    );
+                }
+                return Ok(());
+            },
+            Err(e) if attempt < max_attempts => {
+                tracing::warn!(
+                    "Failed to store proposal for view {view_number:?} (attempt {attempt} of \
+                     {max_attempts}), retrying; error = {e:#}"
+                );
+                metrics.number_of_proposal_storage_retries.add(1);
+            },
+            Err(e) => {
+                metrics.number_of_proposal_storage_failures.add(1);
+                bail!(
+                    "Permanent storage failure, could not store proposal for view \
+                     {view_number:?} in {attempt} attempts; error = {e:#}"
+                );
+            },
+        }
+
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+        attempt += 1;
+    }
+}
+
 /// Submits the `QuorumVoteSend` event if all the dependencies are met.
 #[instrument(skip_all, fields(name = "Submit quorum vote", level = "error"))]
 #[allow(clippy::too_many_arguments)]
@@ -473,7 +542,6 @@ pub(crate) async fn submit_vote<TYPES: NodeType, I: NodeImplementation<TYPES>, V
     storage: Arc<RwLock<I::Storage>>,
     leaf: Leaf2<TYPES>,
     vid_share: Proposal<TYPES, VidDisperseShare<TYPES>>,
-    extended_vote: bool,
     epoch_height: u64,
     _state_private_key: &<TYPES::StateSignatureKey as StateSignatureKey>::StatePrivateKey,
 ) -> Result<()> {
@@ -526,20 +594,20 @@ pub(crate) async fn submit_vote<TYPES: NodeType, I: NodeImplementation<TYPES>, V
         .wrap()
         .context(error!("Failed to store VID share"))?;
 
-    if extended_vote && upgrade_lock.epochs_enabled(view_number).await {
-        tracing::debug!("sending extended vote to everybody",);
-        broadcast_event(
-            Arc::new(HotShotEvent::ExtendedQuorumVoteSend(vote)),
-            &sender,
-        )
-        .await;
+    // We only ever send a single vote, which is counted towards both stake tables if it is
+    // an extended vote, regardless of which committees we are a member of.
+    let event = quorum_vote_event(
+        vote,
+        leaf.height(),
+        epoch_height,
+        upgrade_lock.epochs_enabled(view_number).await,
+    );
+    if matches!(event, HotShotEvent::ExtendedQuorumVoteSend(_)) {
+        tracing::debug!("sending extended vote to everybody");
     } else {
-        tracing::debug!(
-            "sending vote to next quorum leader {:?}",
-            vote.view_number() + 1
-        );
-        broadcast_event(Arc::new(HotShotEvent::QuorumVoteSend(vote)), &sender).await;
+        tracing::debug!("sending vote to next quorum leader {:?}", view_number + 1);
     }
+    broadcast_event(Arc::new(event), &sender).await;
 
     Ok(())
 }
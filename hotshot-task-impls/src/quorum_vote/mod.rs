@@ -21,7 +21,6 @@ use hotshot_types::{
     epoch_membership::EpochMembershipCoordinator,
     event::Event,
     message::UpgradeLock,
-    simple_certificate::UpgradeCertificate,
     simple_vote::HasEpoch,
     traits::{
         block_contents::BlockHeader,
@@ -40,7 +39,10 @@ use tracing::instrument;
 use crate::{
     events::HotShotEvent,
     helpers::broadcast_event,
-    quorum_vote::handlers::{handle_quorum_proposal_validated, submit_vote, update_shared_state},
+    log_level::TaskLogLevel,
+    quorum_vote::handlers::{
+        handle_quorum_proposal_validated, store_proposal, submit_vote, update_shared_state,
+    },
 };
 
 /// Event handlers for `QuorumProposalValidated`.
@@ -100,6 +102,9 @@ pub struct VoteDependencyHandle<TYPES: NodeType, I: NodeImplementation<TYPES>, V
 
     /// Signature key for light client state
     pub state_private_key: <TYPES::StateSignatureKey as StateSignatureKey>::StatePrivateKey,
+
+    /// Number of attempts at storing the proposal before giving up on voting
+    pub max_proposal_storage_attempts: u64,
 }
 
 impl<TYPES: NodeType, I: NodeImplementation<TYPES> + 'static, V: Versions> HandleDepOutput
@@ -136,14 +141,15 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + 'static, V: Versions> Handl
                         tracing::warn!("Proposed leaf parent commitment does not match parent leaf payload commitment. Aborting vote.");
                         return;
                     }
-                    // Update our persistent storage of the proposal. If we cannot store the proposal return
-                    // and error so we don't vote
-                    if let Err(e) = self
-                        .storage
-                        .write()
-                        .await
-                        .append_proposal_wrapper(proposal)
-                        .await
+                    // Update our persistent storage of the proposal. If we cannot store the proposal,
+                    // even after retrying, we don't vote
+                    if let Err(e) = store_proposal::<TYPES, I>(
+                        &self.storage,
+                        proposal,
+                        self.max_proposal_storage_attempts,
+                        &self.consensus_metrics,
+                    )
+                    .await
                     {
                         tracing::error!("failed to store proposal, not voting.  error = {e:#}");
                         return;
@@ -295,7 +301,6 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + 'static, V: Versions> Handl
             Arc::clone(&self.storage),
             leaf,
             vid_share,
-            is_vote_leaf_extended,
             self.epoch_height,
             &self.state_private_key,
         )
@@ -355,11 +360,20 @@ pub struct QuorumVoteTaskState<TYPES: NodeType, I: NodeImplementation<TYPES>, V:
     /// Signature key for light client state
     pub state_private_key: <TYPES::StateSignatureKey as StateSignatureKey>::StatePrivateKey,
 
-    /// Upgrade certificate to enable epochs, staged until we reach the specified block height
-    pub staged_epoch_upgrade_certificate: Option<UpgradeCertificate<TYPES>>,
-
     /// Block height at which to enable the epoch upgrade
     pub epoch_upgrade_block_height: u64,
+
+    /// Whether to accept VID shares from a view leader that is not a DA member
+    pub allow_leader_vid_dispersal: bool,
+
+    /// Number of views per decide above which we warn that finality is degrading
+    pub views_per_decide_alert_threshold: Option<u64>,
+
+    /// Number of attempts at storing a proposal before giving up on voting for it
+    pub max_proposal_storage_attempts: u64,
+
+    /// Handle to the log level of this task, which can be changed while it is running
+    pub log_level: TaskLogLevel,
 }
 
 impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> QuorumVoteTaskState<TYPES, I, V> {
@@ -466,6 +480,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> QuorumVoteTaskS
                 epoch_height: self.epoch_height,
                 consensus_metrics: Arc::clone(&self.consensus_metrics),
                 state_private_key: self.state_private_key.clone(),
+                max_proposal_storage_attempts: self.max_proposal_storage_attempts,
             },
         );
         self.vote_dependencies
@@ -607,14 +622,17 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> QuorumVoteTaskS
                 let vid_epoch = share.data.epoch();
                 let target_epoch = share.data.target_epoch();
                 let membership_reader = self.membership.membership_for_epoch(vid_epoch).await?;
-                // ensure that the VID share was sent by a DA member OR the view leader
+                // ensure that the VID share was sent by a DA member, OR the view leader if
+                // leader dispersal is allowed
+                let sent_by_da_member = membership_reader
+                    .da_committee_members(view)
+                    .await
+                    .contains(sender);
                 ensure!(
-                    membership_reader
-                        .da_committee_members(view)
-                        .await
-                        .contains(sender)
-                        || *sender == membership_reader.leader(view).await?,
-                    "VID share was not sent by a DA member or the view leader."
+                    sent_by_da_member
+                        || (self.allow_leader_vid_dispersal
+                            && *sender == membership_reader.leader(view).await?),
+                    "VID share was not sent by a DA member or an allowed view leader."
                 );
 
                 let total_weight = vid_total_weight::<TYPES>(
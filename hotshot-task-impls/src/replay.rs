@@ -0,0 +1,222 @@
+// Copyright (c) 2021-2024 Espresso Systems (espressosys.com)
+// This file is part of the HotShot repository.
+
+// You should have received a copy of the MIT License
+// along with the HotShot repository. If not, see <https://mit-license.org/>.
+
+//! Recording and offline replay of the events received by a single task.
+//!
+//! Wrapping a task state in a [`RecordingTaskState`] records the most recent events the task
+//! receives, and writes them to a file when the task shuts down. [`replay`] then feeds a recorded sequence into
+//! a fresh task state, so that a stall observed on a running node can be reproduced in a test.
+
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::Context;
+use async_broadcast::{broadcast, Receiver, Sender};
+use async_trait::async_trait;
+use hotshot_task::task::TaskState;
+use hotshot_types::traits::node_implementation::NodeType;
+use hotshot_utils::anytrace::Result;
+use tokio::time::timeout;
+
+use crate::events::HotShotEvent;
+
+/// Capacity of the channels used to replay events.
+const REPLAY_CHANNEL_SIZE: usize = 1024;
+
+/// Number of events a [`RecordingTaskState`] keeps unless configured otherwise.
+pub const DEFAULT_RECORDING_CAPACITY: usize = 10_000;
+
+/// Wraps a task state, recording the events it receives before handing them to the task.
+///
+/// Only the last `capacity` events are kept, so that a long running node does not grow its memory
+/// usage without bound.
+pub struct RecordingTaskState<TYPES: NodeType, S> {
+    /// The task state events are forwarded to
+    inner: S,
+
+    /// The most recent events received by the task, in the order they were received
+    events: VecDeque<Arc<HotShotEvent<TYPES>>>,
+
+    /// The maximum number of events kept
+    capacity: usize,
+
+    /// The file the events are written to when the task shuts down
+    path: Option<PathBuf>,
+}
+
+impl<TYPES: NodeType, S> RecordingTaskState<TYPES, S> {
+    /// Start recording the last [`DEFAULT_RECORDING_CAPACITY`] events received by `inner`.
+    #[must_use]
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            events: VecDeque::new(),
+            capacity: DEFAULT_RECORDING_CAPACITY,
+            path: None,
+        }
+    }
+
+    /// Keep only the last `capacity` events received by the task.
+    #[must_use]
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        let excess = self.events.len().saturating_sub(capacity);
+        self.events.drain(..excess);
+        self
+    }
+
+    /// Write the recorded events to `path` when the task shuts down.
+    #[must_use]
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// The events recorded so far, oldest first.
+    #[must_use]
+    pub fn events(&self) -> &VecDeque<Arc<HotShotEvent<TYPES>>> {
+        &self.events
+    }
+
+    /// The wrapped task state.
+    #[must_use]
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+}
+
+#[async_trait]
+impl<TYPES: NodeType, S: TaskState<Event = HotShotEvent<TYPES>>> TaskState
+    for RecordingTaskState<TYPES, S>
+{
+    type Event = HotShotEvent<TYPES>;
+
+    async fn handle_event(
+        &mut self,
+        event: Arc<Self::Event>,
+        sender: &Sender<Arc<Self::Event>>,
+        receiver: &Receiver<Arc<Self::Event>>,
+    ) -> Result<()> {
+        if self.capacity > 0 {
+            if self.events.len() == self.capacity {
+                self.events.pop_front();
+            }
+            self.events.push_back(Arc::clone(&event));
+        }
+        self.inner.handle_event(event, sender, receiver).await
+    }
+
+    fn cancel_subtasks(&mut self) {
+        self.inner.cancel_subtasks();
+
+        let Some(path) = &self.path else {
+            return;
+        };
+        tracing::info!(
+            "Recording {} events to {}",
+            self.events.len(),
+            path.display()
+        );
+        if let Err(e) = save_events(path, &self.events) {
+            tracing::error!(
+                "Failed to record events to {}; error = {e:#}",
+                path.display()
+            );
+        }
+    }
+}
+
+/// Write `events` to `path`, one JSON encoded event per line.
+///
+/// Events that cannot be serialized, such as the internal events carrying a block payload, are
+/// skipped with a warning, since a partial recording is still useful for debugging.
+///
+/// # Errors
+/// If the file cannot be written
+pub fn save_events<'a, TYPES: NodeType>(
+    path: impl AsRef<Path>,
+    events: impl IntoIterator<Item = &'a Arc<HotShotEvent<TYPES>>>,
+) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    let file =
+        File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+    let mut writer = BufWriter::new(file);
+
+    for (event_number, event) in events.into_iter().enumerate() {
+        match serde_json::to_string(&**event) {
+            Ok(line) => writeln!(writer, "{line}")?,
+            Err(e) => tracing::warn!("Skipping event number {event_number} {event}; error = {e}"),
+        }
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Read events written with [`save_events`] from `path`.
+///
+/// # Errors
+/// If the file cannot be read or contains an invalid event
+pub fn load_events<TYPES: NodeType>(
+    path: impl AsRef<Path>,
+) -> anyhow::Result<Vec<Arc<HotShotEvent<TYPES>>>> {
+    let path = path.as_ref();
+    let file = File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+
+    BufReader::new(file)
+        .lines()
+        .enumerate()
+        .map(|(line_number, line)| -> anyhow::Result<_> {
+            let event = serde_json::from_str(&line?)
+                .with_context(|| format!("invalid event on line {}", line_number + 1))?;
+            Ok(Arc::new(event))
+        })
+        .collect()
+}
+
+/// Feed `events` one at a time into `state`, and return the events it emits in response.
+///
+/// Like in a running node, each event is also broadcast on the channel the task receives from,
+/// so that dependency tasks spawned by the task observe it. After each event we collect outputs
+/// until none arrives for `output_timeout`.
+///
+/// # Panics
+/// If the event cannot be broadcast to the task
+pub async fn replay<TYPES: NodeType, S: TaskState<Event = HotShotEvent<TYPES>>>(
+    state: &mut S,
+    events: impl IntoIterator<Item = Arc<HotShotEvent<TYPES>>>,
+    output_timeout: Duration,
+) -> Vec<Arc<HotShotEvent<TYPES>>> {
+    let (to_task, mut from_replay) = broadcast(REPLAY_CHANNEL_SIZE);
+    let (to_replay, mut from_task) = broadcast(REPLAY_CHANNEL_SIZE);
+
+    let mut outputs = Vec::new();
+    for event in events {
+        to_task
+            .broadcast(Arc::clone(&event))
+            .await
+            .expect("Failed to broadcast replayed event");
+
+        let _ = state
+            .handle_event(event, &to_replay, &from_replay)
+            .await
+            .inspect_err(|e| tracing::debug!("{e}"));
+
+        while from_replay.try_recv().is_ok() {}
+
+        while let Ok(Ok(output)) = timeout(output_timeout, from_task.recv_direct()).await {
+            outputs.push(output);
+        }
+    }
+
+    outputs
+}
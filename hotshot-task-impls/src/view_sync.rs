@@ -45,6 +45,7 @@ use crate::{
     },
 };
 #[derive(PartialEq, PartialOrd, Clone, Debug, Eq, Hash)]
+#[cfg_attr(feature = "event-replay", derive(serde::Serialize, serde::Deserialize))]
 /// Phases of view sync
 pub enum ViewSyncPhase {
     /// No phase; before the protocol has begun
@@ -5,7 +5,13 @@
 // along with the HotShot repository. If not, see <https://mit-license.org/>.
 
 #![allow(clippy::panic)]
-use std::{collections::BTreeMap, fmt::Debug, hash::Hash, marker::PhantomData, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    hash::Hash,
+    marker::PhantomData,
+    sync::Arc,
+};
 
 use async_broadcast::{Receiver, Sender};
 use async_lock::RwLock;
@@ -23,9 +29,13 @@ use hotshot_example_types::{
     state_types::{TestInstanceState, TestValidatedState},
     storage_types::TestStorage,
 };
-use hotshot_task_impls::events::HotShotEvent;
+use hotshot_task_impls::{
+    events::HotShotEvent,
+    log_level::{TaskLogLevel, QUORUM_PROPOSAL_LOG_TARGETS},
+    quorum_proposal::QuorumProposalTaskState,
+};
 use hotshot_types::{
-    consensus::ConsensusMetricsValue,
+    consensus::{Consensus, ConsensusMetricsValue, OuterConsensus},
     data::{vid_commitment, Leaf2, VidCommitment, VidDisperse, VidDisperseShare},
     epoch_membership::{EpochMembership, EpochMembershipCoordinator},
     message::{Proposal, UpgradeLock},
@@ -456,3 +466,169 @@ pub async fn build_fake_view_with_leaf_and_state<V: Versions>(
         },
     }
 }
+
+/// Builder for a [`QuorumProposalTaskState`] that can be used to unit test the task's
+/// handlers without standing up a full [`SystemContext`].
+///
+/// By default the task state is backed by in-memory [`TestStorage`], a genesis consensus state
+/// and a membership coordinator for a single-node committee made up of the node itself.
+pub struct QuorumProposalTaskStateBuilder<
+    TYPES: NodeType,
+    I: NodeImplementation<TYPES>,
+    V: Versions,
+> {
+    /// The node's id, used to derive its keys
+    node_id: u64,
+
+    /// Number of blocks in an epoch, zero means there are no epochs
+    epoch_height: u64,
+
+    /// The epoch the task state starts in
+    cur_epoch: Option<TYPES::Epoch>,
+
+    /// View timeout, in milliseconds
+    timeout: u64,
+
+    /// Marker for the node implementation and versions
+    _pd: PhantomData<(I, V)>,
+}
+
+impl<TYPES, I, V> Default for QuorumProposalTaskStateBuilder<TYPES, I, V>
+where
+    TYPES: NodeType<InstanceState = TestInstanceState>,
+    I: NodeImplementation<TYPES, Storage = TestStorage<TYPES>>,
+    V: Versions,
+{
+    fn default() -> Self {
+        Self {
+            node_id: 0,
+            epoch_height: 0,
+            cur_epoch: None,
+            timeout: 10_000,
+            _pd: PhantomData,
+        }
+    }
+}
+
+impl<TYPES, I, V> QuorumProposalTaskStateBuilder<TYPES, I, V>
+where
+    TYPES: NodeType<InstanceState = TestInstanceState>,
+    I: NodeImplementation<TYPES, Storage = TestStorage<TYPES>>,
+    V: Versions,
+{
+    /// Create a builder with the default settings
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the node id, which determines the node's keys
+    #[must_use]
+    pub fn node_id(mut self, node_id: u64) -> Self {
+        self.node_id = node_id;
+        self
+    }
+
+    /// Set the number of blocks in an epoch
+    #[must_use]
+    pub fn epoch_height(mut self, epoch_height: u64) -> Self {
+        self.epoch_height = epoch_height;
+        self
+    }
+
+    /// Set the epoch the task state starts in
+    #[must_use]
+    pub fn cur_epoch(mut self, cur_epoch: Option<TYPES::Epoch>) -> Self {
+        self.cur_epoch = cur_epoch;
+        self
+    }
+
+    /// Set the view timeout, in milliseconds
+    #[must_use]
+    pub fn timeout(mut self, timeout: u64) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Build the [`QuorumProposalTaskState`]
+    /// # Panics
+    /// if cannot create a [`HotShotInitializer`]
+    pub async fn build(self) -> QuorumProposalTaskState<TYPES, I, V> {
+        let initializer = HotShotInitializer::<TYPES>::from_genesis::<V>(
+            TestInstanceState::default(),
+            self.epoch_height,
+            0,
+            vec![],
+        )
+        .await
+        .unwrap();
+
+        let validator_config: ValidatorConfig<TYPES> =
+            ValidatorConfig::generated_from_seed_indexed([0u8; 32], self.node_id, 1, true);
+        let peer_config = validator_config.public_config();
+
+        let membership = Arc::new(RwLock::new(TYPES::Membership::new(
+            vec![peer_config.clone()],
+            vec![peer_config],
+        )));
+        let membership_coordinator = EpochMembershipCoordinator::new(membership, self.epoch_height);
+
+        let anchor_leaf = initializer.anchor_leaf;
+        let mut validated_state_map = BTreeMap::new();
+        validated_state_map.insert(
+            anchor_leaf.view_number(),
+            View {
+                view_inner: ViewInner::Leaf {
+                    leaf: anchor_leaf.commit(),
+                    state: Arc::clone(&initializer.anchor_state),
+                    delta: initializer.anchor_state_delta,
+                    epoch: self.cur_epoch,
+                },
+            },
+        );
+        let mut saved_leaves = HashMap::new();
+        saved_leaves.insert(anchor_leaf.commit(), anchor_leaf.clone());
+
+        let consensus = Consensus::new(
+            validated_state_map,
+            Some(initializer.saved_vid_shares),
+            anchor_leaf.view_number(),
+            self.cur_epoch,
+            anchor_leaf.view_number(),
+            anchor_leaf.view_number(),
+            initializer.last_actioned_view,
+            initializer.saved_proposals,
+            saved_leaves,
+            BTreeMap::new(),
+            initializer.high_qc,
+            initializer.next_epoch_high_qc,
+            Arc::new(ConsensusMetricsValue::default()),
+            self.epoch_height,
+            initializer.state_cert,
+        );
+
+        let consensus_metrics = Arc::clone(&consensus.metrics);
+
+        QuorumProposalTaskState {
+            latest_proposed_view: anchor_leaf.view_number(),
+            cur_epoch: self.cur_epoch,
+            proposal_dependencies: BTreeMap::new(),
+            formed_quorum_certificates: BTreeMap::new(),
+            formed_next_epoch_quorum_certificates: BTreeMap::new(),
+            instance_state: Arc::new(initializer.instance_state),
+            membership_coordinator,
+            public_key: validator_config.public_key,
+            private_key: validator_config.private_key,
+            timeout: self.timeout,
+            high_qc_wait_fraction: (1, 2),
+            storage: Arc::new(RwLock::new(TestStorage::default())),
+            consensus: OuterConsensus::new(Arc::new(RwLock::new(consensus))),
+            consensus_metrics,
+            id: self.node_id,
+            formed_upgrade_certificate: None,
+            upgrade_lock: UpgradeLock::new(),
+            epoch_height: self.epoch_height,
+            log_level: TaskLogLevel::new(QUORUM_PROPOSAL_LOG_TARGETS),
+        }
+    }
+}
@@ -88,6 +88,11 @@ pub fn default_hotshot_config<TYPES: NodeType>(
         stop_voting_time: 0,
         epoch_height,
         epoch_start_block,
+        allow_leader_vid_dispersal: true,
+        max_future_view_skew: None,
+        views_per_decide_alert_threshold: None,
+        max_proposal_storage_attempts: 3,
+        high_qc_wait_fraction: (1, 2),
     }
 }
 
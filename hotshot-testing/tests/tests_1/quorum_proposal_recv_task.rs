@@ -31,6 +31,7 @@ use hotshot_types::{
     data::{EpochNumber, Leaf2, ViewNumber},
     request_response::ProposalRequestPayload,
     traits::{
+        block_contents::BlockHeader,
         consensus_api::ConsensusApi,
         election::Membership,
         node_implementation::{ConsensusTime, NodeType},
@@ -107,6 +108,12 @@ async fn test_quorum_proposal_recv_task() {
         expectations,
     };
     run_test![inputs, script].await;
+
+    // The fully validated proposal advances the highest block.
+    assert_eq!(
+        consensus.read().await.highest_block,
+        proposals[1].data.block_header().block_number()
+    );
 }
 
 #[cfg(test)]
@@ -200,4 +207,112 @@ async fn test_quorum_proposal_recv_task_liveness_check() {
         expectations,
     };
     run_test![inputs, script].await;
+
+    // The proposal that only passed the liveness check advances the highest block too.
+    assert_eq!(
+        consensus.read().await.highest_block,
+        proposals[2].data.block_header().block_number()
+    );
+}
+
+#[cfg(test)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_quorum_proposal_recv_task_far_future_proposal() {
+    use std::time::Duration;
+
+    use hotshot_testing::script::{Expectations, TaskScript};
+    use hotshot_types::vote::HasViewNumber;
+
+    hotshot::helpers::initialize_logging();
+
+    let (handle, _, _, node_key_map) =
+        build_system_handle::<TestTypes, MemoryImpl, TestVersions>(2).await;
+    let membership = handle.hotshot.membership_coordinator.clone();
+    let consensus = handle.hotshot.consensus();
+
+    let mut generator = TestViewGenerator::<TestVersions>::generate(membership, node_key_map);
+    let mut proposals = Vec::new();
+    let mut leaders = Vec::new();
+    let mut leaves = Vec::new();
+    for view in (&mut generator).take(6).collect::<Vec<_>>().await {
+        proposals.push(view.quorum_proposal.clone());
+        leaders.push(view.leader_public_key);
+        leaves.push(view.leaf.clone());
+    }
+
+    // The proposal for view 6 is well beyond the allowed skew from view 0, so we first request
+    // its parent, and only process the proposal once the parent has arrived.
+    let inputs = vec![serial![
+        QuorumProposalRecv(proposals[5].clone(), leaders[5]),
+        QuorumProposalResponseRecv(proposals[4].clone()),
+    ]];
+
+    let req = ProposalRequestPayload {
+        view_number: proposals[5].data.justify_qc().view_number(),
+        key: handle.public_key(),
+    };
+    let signature =
+        <TestTypes as NodeType>::SignatureKey::sign(handle.private_key(), req.commit().as_ref())
+            .unwrap();
+
+    let expectations = vec![Expectations::from_outputs(vec![
+        exact(QuorumProposalRequestSend(req, signature)),
+        exact(QuorumProposalPreliminarilyValidated(proposals[5].clone())),
+        exact(QuorumProposalValidated(
+            proposals[5].clone(),
+            leaves[4].clone(),
+        )),
+        exact(ViewChange(ViewNumber::new(6), None)),
+    ])];
+
+    let mut state =
+        QuorumProposalRecvTaskState::<TestTypes, MemoryImpl, TestVersions>::create_from(&handle)
+            .await;
+    state.max_future_view_skew = Some(2);
+    let mut script = TaskScript {
+        timeout: Duration::from_millis(35),
+        state,
+        expectations,
+    };
+    run_test![inputs, script].await;
+
+    // Once caught up, the far-future proposal advances the highest block.
+    assert_eq!(
+        consensus.read().await.highest_block,
+        proposals[5].data.block_header().block_number()
+    );
+}
+
+#[cfg(test)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_quorum_proposal_with_next_drb_result_outside_transition_is_rejected() {
+    use hotshot_task_impls::helpers::validate_next_drb_result;
+
+    hotshot::helpers::initialize_logging();
+
+    let epoch_height = 10;
+    let (handle, _, _, node_key_map) =
+        build_system_handle::<TestTypes, MemoryImpl, TestVersions>(1).await;
+    let membership = handle.hotshot.membership_coordinator.clone();
+
+    let mut generator = TestViewGenerator::<TestVersions>::generate(membership, node_key_map);
+    let view = generator.next().await.unwrap();
+    let mut proposal = view.quorum_proposal.data;
+
+    // Outside of the epoch transition, only a proposal without a DRB result is accepted.
+    proposal.proposal.block_header.block_number = 5;
+    proposal.proposal.next_drb_result = None;
+    assert!(validate_next_drb_result(&proposal, epoch_height).is_ok());
+
+    proposal.proposal.next_drb_result = Some([1; 32]);
+    let err = validate_next_drb_result(&proposal, epoch_height).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("carries a next_drb_result, but the block is not an epoch transition block"),
+        "unexpected error: {err}"
+    );
+
+    // During the epoch transition, the proposal may carry the DRB result of the next epoch.
+    proposal.proposal.block_header.block_number = 8;
+    assert!(validate_next_drb_result(&proposal, epoch_height).is_ok());
 }
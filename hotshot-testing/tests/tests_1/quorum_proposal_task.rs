@@ -730,3 +730,147 @@ async fn test_quorum_proposal_task_with_incomplete_events() {
     };
     run_test![inputs, script].await;
 }
+
+#[cfg(test)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_quorum_proposal_task_current_leader() {
+    use hotshot_testing::helpers::QuorumProposalTaskStateBuilder;
+    use hotshot_types::data::EpochNumber;
+
+    hotshot::helpers::initialize_logging();
+
+    let epoch = Some(EpochNumber::new(1));
+    let mut state = QuorumProposalTaskStateBuilder::<TestTypes, MemoryImpl, TestVersions>::new()
+        .node_id(1)
+        .epoch_height(10)
+        .cur_epoch(epoch)
+        .build()
+        .await;
+    let epoch_membership = state
+        .membership_coordinator
+        .membership_for_epoch(epoch)
+        .await
+        .unwrap();
+
+    // The expected leader follows the latest view the task proposed for.
+    for view in [0, 1, 5] {
+        state.latest_proposed_view = ViewNumber::new(view);
+        assert_eq!(
+            state.current_leader().await.unwrap(),
+            epoch_membership
+                .lookup_leader(ViewNumber::new(view + 1))
+                .await
+                .unwrap()
+        );
+    }
+}
+
+#[cfg(test)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_quorum_proposal_task_view_change_with_builder() {
+    use hotshot_testing::helpers::QuorumProposalTaskStateBuilder;
+    use hotshot_types::data::EpochNumber;
+
+    hotshot::helpers::initialize_logging();
+
+    let mut state = QuorumProposalTaskStateBuilder::<TestTypes, MemoryImpl, TestVersions>::new()
+        .node_id(1)
+        .epoch_height(10)
+        .cur_epoch(Some(EpochNumber::new(1)))
+        .build()
+        .await;
+
+    let (sender, receiver) = async_broadcast::broadcast(10);
+
+    // Spawn a pending dependency task for each of views 1 through 4.
+    let mut handles = Vec::new();
+    for view in 1..=4 {
+        let handle = tokio::spawn(futures::future::pending::<()>());
+        handles.push(handle.abort_handle());
+        state
+            .proposal_dependencies
+            .insert(ViewNumber::new(view), handle);
+    }
+
+    // A view change to view 3 in a newer epoch updates the epoch and cancels every dependency
+    // task for a view before view 2.
+    state
+        .handle(
+            Arc::new(ViewChange(ViewNumber::new(3), Some(EpochNumber::new(2)))),
+            receiver.clone(),
+            sender.clone(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(state.cur_epoch, Some(EpochNumber::new(2)));
+    assert_eq!(
+        state
+            .proposal_dependencies
+            .keys()
+            .copied()
+            .collect::<Vec<_>>(),
+        vec![ViewNumber::new(2), ViewNumber::new(3), ViewNumber::new(4)]
+    );
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert!(handles[0].is_finished());
+    assert!(!handles[1].is_finished());
+
+    // A view change carrying an older epoch does not move the epoch backwards.
+    state
+        .handle(
+            Arc::new(ViewChange(ViewNumber::new(4), Some(EpochNumber::new(1)))),
+            receiver,
+            sender,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(state.cur_epoch, Some(EpochNumber::new(2)));
+    assert_eq!(
+        state
+            .proposal_dependencies
+            .keys()
+            .copied()
+            .collect::<Vec<_>>(),
+        vec![ViewNumber::new(3), ViewNumber::new(4)]
+    );
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert!(handles[1].is_finished());
+    assert!(!handles[2].is_finished());
+}
+
+#[cfg(test)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_quorum_proposal_with_wrong_epoch_is_rejected() {
+    use hotshot_task_impls::helpers::validate_proposal_epoch;
+    use hotshot_types::{
+        traits::block_contents::BlockHeader, utils::option_epoch_from_block_number,
+    };
+
+    hotshot::helpers::initialize_logging();
+
+    let epoch_height = 10;
+    let (handle, _, _, node_key_map) =
+        build_system_handle::<TestTypes, MemoryImpl, TestVersions>(1).await;
+    let membership = handle.hotshot.membership_coordinator.clone();
+
+    let mut generator = TestViewGenerator::<TestVersions>::generate(membership, node_key_map);
+    let view = generator.next().await.unwrap();
+    let mut proposal = view.quorum_proposal.data;
+
+    let block_number = proposal.block_header().block_number();
+    let expected_epoch =
+        option_epoch_from_block_number::<TestTypes>(true, block_number, epoch_height).unwrap();
+
+    // A proposal declaring the epoch its block number belongs to is accepted.
+    proposal.proposal.epoch = Some(expected_epoch);
+    assert!(validate_proposal_epoch(&proposal, true, epoch_height).is_ok());
+
+    // A proposal declaring any other epoch is rejected before it is sent.
+    proposal.proposal.epoch = Some(expected_epoch + 1);
+    assert!(validate_proposal_epoch(&proposal, true, epoch_height).is_err());
+
+    proposal.proposal.epoch = None;
+    assert!(validate_proposal_epoch(&proposal, true, epoch_height).is_err());
+}
@@ -15,6 +15,7 @@ use hotshot_example_types::{
     state_types::TestValidatedState,
 };
 use hotshot_macros::{run_test, test_scripts};
+use hotshot_task_impls::events::HotShotEvent;
 use hotshot_testing::{
     all_predicates,
     helpers::vid_share,
@@ -23,7 +24,8 @@ use hotshot_testing::{
     script::{Expectations, InputOrder, TaskScript},
 };
 use hotshot_types::{
-    data::{Leaf2, ViewNumber},
+    data::{Leaf2, VidDisperseShare, ViewNumber},
+    message::Proposal,
     traits::node_implementation::ConsensusTime,
 };
 
@@ -97,6 +99,87 @@ async fn test_quorum_vote_task_success() {
     run_test![inputs, script].await;
 }
 
+#[cfg(test)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_quorum_vote_task_retries_proposal_storage() {
+    use std::sync::atomic::Ordering;
+
+    use hotshot_task_impls::{events::HotShotEvent::*, quorum_vote::QuorumVoteTaskState};
+    use hotshot_testing::{
+        helpers::build_system_handle,
+        predicates::event::{exact, quorum_vote_send},
+        view_generator::TestViewGenerator,
+    };
+
+    hotshot::helpers::initialize_logging();
+
+    let (handle, _, _, node_key_map) =
+        build_system_handle::<TestTypes, MemoryImpl, TestVersions>(2).await;
+
+    let membership = handle.hotshot.membership_coordinator.clone();
+
+    let mut generator = TestViewGenerator::<TestVersions>::generate(membership, node_key_map);
+
+    let mut proposals = Vec::new();
+    let mut leaves = Vec::new();
+    let mut dacs = Vec::new();
+    let mut vids = Vec::new();
+    let mut leaders = Vec::new();
+    let consensus = handle.hotshot.consensus().clone();
+    let mut consensus_writer = consensus.write().await;
+    for view in (&mut generator).take(2).collect::<Vec<_>>().await {
+        leaders.push(view.leader_public_key);
+        proposals.push(view.quorum_proposal.clone());
+        leaves.push(view.leaf.clone());
+        dacs.push(view.da_certificate.clone());
+        vids.push(view.vid_proposal.clone());
+        consensus_writer
+            .update_leaf(
+                Leaf2::from_quorum_proposal(&view.quorum_proposal.data),
+                Arc::new(TestValidatedState::default()),
+                None,
+            )
+            .unwrap();
+    }
+    drop(consensus_writer);
+
+    // The first attempt to store the proposal fails, but the retry succeeds, so we still vote.
+    let storage = handle.storage();
+    let failures = Arc::clone(&storage.read().await.append_proposal_wrapper_failures);
+    failures.store(1, Ordering::SeqCst);
+
+    let inputs = vec![random![
+        QuorumProposalValidated(proposals[1].clone(), leaves[0].clone()),
+        DaCertificateRecv(dacs[1].clone()),
+        VidShareRecv(leaders[1], vids[1].0[0].clone()),
+    ]];
+
+    let expectations = vec![Expectations::from_outputs(all_predicates![
+        exact(DaCertificateValidated(dacs[1].clone())),
+        exact(VidShareValidated(vids[1].0[0].clone())),
+        exact(ViewChange(ViewNumber::new(3), None)),
+        quorum_vote_send(),
+    ])];
+
+    let quorum_vote_state =
+        QuorumVoteTaskState::<TestTypes, MemoryImpl, TestVersions>::create_from(&handle).await;
+
+    let mut script = TaskScript {
+        timeout: Duration::from_millis(100),
+        state: quorum_vote_state,
+        expectations,
+    };
+    run_test![inputs, script].await;
+
+    assert_eq!(failures.load(Ordering::SeqCst), 0);
+    assert!(storage
+        .read()
+        .await
+        .proposals_cloned()
+        .await
+        .contains_key(&ViewNumber::new(2)));
+}
+
 #[cfg(test)]
 #[tokio::test(flavor = "multi_thread")]
 async fn test_quorum_vote_task_miss_dependency() {
@@ -232,3 +315,251 @@ async fn test_quorum_vote_task_incorrect_dependency() {
     };
     run_test![inputs, script].await;
 }
+
+/// Builds a handle for node 2 in a network where only node 0 is a DA member, so that the
+/// leader of view 2 is not a DA member, and returns the leader-sent VID share for view 2.
+#[cfg(test)]
+async fn leader_vid_share_setup(
+    allow_leader_vid_dispersal: bool,
+) -> (
+    hotshot::types::SystemContextHandle<TestTypes, MemoryImpl, TestVersions>,
+    HotShotEvent<TestTypes>,
+    Proposal<TestTypes, VidDisperseShare<TestTypes>>,
+) {
+    use hotshot_testing::{
+        helpers::build_system_handle_from_launcher, test_builder::TestDescription,
+        view_generator::TestViewGenerator,
+    };
+
+    hotshot::helpers::initialize_logging();
+
+    let launcher = TestDescription::<TestTypes, MemoryImpl, TestVersions>::default()
+        .set_num_nodes(7, 1)
+        .gen_launcher()
+        .map_hotshot_config(move |hotshot_config| {
+            hotshot_config.epoch_height = 0;
+            hotshot_config.allow_leader_vid_dispersal = allow_leader_vid_dispersal;
+        });
+    let (handle, _, _, node_key_map) = build_system_handle_from_launcher(2, &launcher).await;
+
+    let membership = handle.hotshot.membership_coordinator.clone();
+
+    let mut generator = TestViewGenerator::<TestVersions>::generate(membership, node_key_map);
+
+    let mut leaders = Vec::new();
+    let mut vids = Vec::new();
+    let consensus = handle.hotshot.consensus().clone();
+    let mut consensus_writer = consensus.write().await;
+    for view in (&mut generator).take(2).collect::<Vec<_>>().await {
+        leaders.push(view.leader_public_key);
+        vids.push(view.vid_proposal.clone());
+        consensus_writer
+            .update_leaf(
+                Leaf2::from_quorum_proposal(&view.quorum_proposal.data),
+                Arc::new(TestValidatedState::default()),
+                None,
+            )
+            .unwrap();
+    }
+    drop(consensus_writer);
+
+    let share = vids[1].0[0].clone();
+    (
+        handle,
+        HotShotEvent::VidShareRecv(leaders[1], share.clone()),
+        share,
+    )
+}
+
+#[cfg(test)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_quorum_vote_task_leader_vid_share_allowed() {
+    use hotshot_task_impls::{events::HotShotEvent::*, quorum_vote::QuorumVoteTaskState};
+    use hotshot_testing::predicates::event::exact;
+
+    let (handle, vid_share_recv, share) = leader_vid_share_setup(true).await;
+
+    let inputs = vec![random![vid_share_recv]];
+    let expectations = vec![Expectations::from_outputs(vec![exact(VidShareValidated(
+        share,
+    ))])];
+
+    let quorum_vote_state =
+        QuorumVoteTaskState::<TestTypes, MemoryImpl, TestVersions>::create_from(&handle).await;
+    assert!(quorum_vote_state.allow_leader_vid_dispersal);
+
+    let mut script = TaskScript {
+        timeout: TIMEOUT,
+        state: quorum_vote_state,
+        expectations,
+    };
+    run_test![inputs, script].await;
+}
+
+#[cfg(test)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_quorum_vote_task_leader_vid_share_rejected() {
+    use hotshot_task_impls::quorum_vote::QuorumVoteTaskState;
+
+    let (handle, vid_share_recv, _) = leader_vid_share_setup(false).await;
+
+    // The leader is not a DA member, so the share is rejected and never validated.
+    let inputs = vec![random![vid_share_recv]];
+    let expectations = vec![Expectations::from_outputs(vec![])];
+
+    let quorum_vote_state =
+        QuorumVoteTaskState::<TestTypes, MemoryImpl, TestVersions>::create_from(&handle).await;
+    assert!(!quorum_vote_state.allow_leader_vid_dispersal);
+
+    let mut script = TaskScript {
+        timeout: TIMEOUT,
+        state: quorum_vote_state,
+        expectations,
+    };
+    run_test![inputs, script].await;
+}
+
+#[cfg(test)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_quorum_vote_in_both_committees_sends_single_extended_vote() {
+    use committable::Committable;
+    use hotshot_example_types::node_types::EpochsTestVersions;
+    use hotshot_task_impls::helpers::quorum_vote_event;
+    use hotshot_testing::{helpers::build_system_handle, view_generator::TestViewGenerator};
+    use hotshot_types::{
+        data::EpochNumber,
+        simple_vote::{QuorumData2, QuorumVote2},
+        traits::consensus_api::ConsensusApi,
+    };
+
+    hotshot::helpers::initialize_logging();
+
+    let epoch_height = 10;
+    let (handle, _, _, node_key_map) =
+        build_system_handle::<TestTypes, MemoryImpl, EpochsTestVersions>(2).await;
+    let public_key = handle.public_key().clone();
+
+    // The test committee is the same in every epoch, so this node is a member of both the
+    // current and the next epoch committees.
+    let membership = handle.hotshot.membership_coordinator.clone();
+    let epoch_membership = membership
+        .membership_for_epoch(Some(EpochNumber::new(1)))
+        .await
+        .unwrap();
+    assert!(epoch_membership.has_stake(&public_key).await);
+    assert!(
+        epoch_membership
+            .next_epoch_stake_table()
+            .await
+            .unwrap()
+            .has_stake(&public_key)
+            .await
+    );
+
+    let mut generator = TestViewGenerator::<EpochsTestVersions>::generate(membership, node_key_map);
+    let view = generator.next().await.unwrap();
+    let leaf = Leaf2::from_quorum_proposal(&view.quorum_proposal.data);
+    let vote = QuorumVote2::<TestTypes>::create_signed_vote(
+        QuorumData2 {
+            leaf_commit: leaf.commit(),
+            epoch: Some(EpochNumber::new(1)),
+            block_number: Some(epoch_height),
+        },
+        ViewNumber::new(epoch_height),
+        &public_key,
+        handle.private_key(),
+        &handle.hotshot.upgrade_lock,
+    )
+    .await
+    .unwrap();
+
+    // For the last block of the epoch, the node sends a single extended vote that counts towards
+    // both committees.
+    let event = quorum_vote_event(vote.clone(), epoch_height, epoch_height, true);
+    assert_eq!(event, HotShotEvent::ExtendedQuorumVoteSend(vote.clone()));
+
+    // Any other block gets a regular vote, as does the last block before epochs are enabled.
+    assert_eq!(
+        quorum_vote_event(vote.clone(), epoch_height - 1, epoch_height, true),
+        HotShotEvent::QuorumVoteSend(vote.clone())
+    );
+    assert_eq!(
+        quorum_vote_event(vote.clone(), epoch_height, epoch_height, false),
+        HotShotEvent::QuorumVoteSend(vote)
+    );
+}
+
+#[cfg(feature = "event-replay")]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_quorum_vote_task_record_and_replay() {
+    use hotshot_task_impls::{
+        events::HotShotEvent::*,
+        quorum_vote::QuorumVoteTaskState,
+        replay::{load_events, replay, save_events, RecordingTaskState},
+    };
+    use hotshot_testing::{helpers::build_system_handle, view_generator::TestViewGenerator};
+
+    hotshot::helpers::initialize_logging();
+
+    let (handle, _, _, node_key_map) =
+        build_system_handle::<TestTypes, MemoryImpl, TestVersions>(2).await;
+
+    let membership = handle.hotshot.membership_coordinator.clone();
+
+    let mut generator = TestViewGenerator::<TestVersions>::generate(membership, node_key_map);
+
+    let mut proposals = Vec::new();
+    let mut leaves = Vec::new();
+    let mut dacs = Vec::new();
+    let mut vids = Vec::new();
+    let mut leaders = Vec::new();
+    let consensus = handle.hotshot.consensus().clone();
+    let mut consensus_writer = consensus.write().await;
+    for view in (&mut generator).take(2).collect::<Vec<_>>().await {
+        leaders.push(view.leader_public_key);
+        proposals.push(view.quorum_proposal.clone());
+        leaves.push(view.leaf.clone());
+        dacs.push(view.da_certificate.clone());
+        vids.push(view.vid_proposal.clone());
+        consensus_writer
+            .update_leaf(
+                Leaf2::from_quorum_proposal(&view.quorum_proposal.data),
+                Arc::new(TestValidatedState::default()),
+                None,
+            )
+            .unwrap();
+    }
+    drop(consensus_writer);
+
+    let inputs = vec![
+        Arc::new(QuorumProposalValidated(
+            proposals[1].clone(),
+            leaves[0].clone(),
+        )),
+        Arc::new(DaCertificateRecv(dacs[1].clone())),
+        Arc::new(VidShareRecv(leaders[1], vids[1].0[0].clone())),
+    ];
+
+    // Record the events received by the task while it votes.
+    let mut recording = RecordingTaskState::new(
+        QuorumVoteTaskState::<TestTypes, MemoryImpl, TestVersions>::create_from(&handle).await,
+    );
+    let recorded_outputs = replay(&mut recording, inputs.clone(), TIMEOUT).await;
+    assert_eq!(*recording.events(), inputs);
+    assert!(recorded_outputs
+        .iter()
+        .any(|event| matches!(**event, QuorumVoteSend(_))));
+
+    let path =
+        std::env::temp_dir().join(format!("quorum_vote_events_{}.jsonl", std::process::id()));
+    save_events(&path, recording.events()).unwrap();
+    let events = load_events::<TestTypes>(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(events, inputs);
+
+    // Replaying the recording into a fresh task emits the same events.
+    let mut quorum_vote_state =
+        QuorumVoteTaskState::<TestTypes, MemoryImpl, TestVersions>::create_from(&handle).await;
+    let replayed_outputs = replay(&mut quorum_vote_state, events, TIMEOUT).await;
+    assert_eq!(replayed_outputs, recorded_outputs);
+}
@@ -387,6 +387,25 @@ pub struct ConsensusMetricsValue {
     pub number_of_empty_blocks_proposed: Box<dyn Counter>,
     /// Number of events in the hotshot event queue
     pub internal_event_queue_len: Box<dyn Gauge>,
+    /// Number of proposals received for a view too far ahead of our current view
+    pub number_of_far_future_proposals: Box<dyn Counter>,
+    /// Number of decides that took more views than the configured alert threshold
+    pub number_of_slow_decides: Box<dyn Counter>,
+    /// Number of failed attempts at storing a proposal before voting that were retried
+    pub number_of_proposal_storage_retries: Box<dyn Counter>,
+    /// Number of proposals we did not vote for because every attempt at storing them failed
+    pub number_of_proposal_storage_failures: Box<dyn Counter>,
+    /// Number of times sending a proposal was retried because the event queue was full
+    pub number_of_proposal_send_retries: Box<dyn Counter>,
+    /// Number of events evicted from a full event queue to make room for a proposal we sent
+    pub number_of_event_queue_overflows: Box<dyn Counter>,
+    /// Number of proposals we could not send because the event queue was closed
+    pub number_of_failed_proposal_sends: Box<dyn Counter>,
+    /// Duration in seconds from the start of a view to sending our proposal for it as leader
+    pub proposal_latency: Box<dyn Histogram>,
+    /// Number of proposals for which the wait for the highest or transition QC ran out of time
+    /// without a newer QC arriving
+    pub number_of_high_qc_wait_timeouts: Box<dyn Counter>,
 }
 
 impl ConsensusMetricsValue {
@@ -418,6 +437,23 @@ impl ConsensusMetricsValue {
                 .create_counter(String::from("number_of_empty_blocks_proposed"), None),
             internal_event_queue_len: metrics
                 .create_gauge(String::from("internal_event_queue_len"), None),
+            number_of_far_future_proposals: metrics
+                .create_counter(String::from("number_of_far_future_proposals"), None),
+            number_of_slow_decides: metrics
+                .create_counter(String::from("number_of_slow_decides"), None),
+            number_of_proposal_storage_retries: metrics
+                .create_counter(String::from("number_of_proposal_storage_retries"), None),
+            number_of_proposal_storage_failures: metrics
+                .create_counter(String::from("number_of_proposal_storage_failures"), None),
+            number_of_proposal_send_retries: metrics
+                .create_counter(String::from("number_of_proposal_send_retries"), None),
+            number_of_event_queue_overflows: metrics
+                .create_counter(String::from("number_of_event_queue_overflows"), None),
+            number_of_failed_proposal_sends: metrics
+                .create_counter(String::from("number_of_failed_proposal_sends"), None),
+            proposal_latency: metrics.create_histogram(String::from("proposal_latency"), None),
+            number_of_high_qc_wait_timeouts: metrics
+                .create_counter(String::from("number_of_high_qc_wait_timeouts"), None),
         }
     }
 }
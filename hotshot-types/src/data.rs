@@ -1814,7 +1814,14 @@ impl<TYPES: NodeType> Leaf<TYPES> {
 pub mod null_block {
     #![allow(missing_docs)]
 
+    use std::{
+        any::{Any, TypeId},
+        collections::HashMap,
+        sync::RwLock,
+    };
+
     use jf_vid::VidScheme;
+    use lazy_static::lazy_static;
     use vbs::version::StaticVersionType;
 
     use crate::{
@@ -1825,6 +1832,7 @@ pub mod null_block {
             signature_key::BuilderSignatureKey,
             BlockPayload,
         },
+        utils::BuilderCommitment,
         vid::advz::advz_scheme,
     };
 
@@ -1846,6 +1854,46 @@ pub mod null_block {
         }
     }
 
+    lazy_static! {
+        /// Memoized results of [`empty_block_commitment`], keyed by the [`NodeType`].
+        static ref EMPTY_BLOCK_COMMITMENTS: RwLock<HashMap<TypeId, Box<dyn Any + Send + Sync>>> =
+            RwLock::new(HashMap::new());
+    }
+
+    /// The builder commitment and metadata of the canonical empty block payload.
+    ///
+    /// Every site that proposes or validates an empty block should use this, so that they all
+    /// agree on the commitment. The result is memoized per [`NodeType`].
+    #[must_use]
+    pub fn empty_block_commitment<TYPES: NodeType>() -> (
+        BuilderCommitment,
+        <TYPES::BlockPayload as BlockPayload<TYPES>>::Metadata,
+    ) {
+        type Entry<TYPES> = (
+            BuilderCommitment,
+            <<TYPES as NodeType>::BlockPayload as BlockPayload<TYPES>>::Metadata,
+        );
+
+        let type_id = TypeId::of::<TYPES>();
+        if let Some(entry) = EMPTY_BLOCK_COMMITMENTS
+            .read()
+            .unwrap()
+            .get(&type_id)
+            .and_then(|entry| entry.downcast_ref::<Entry<TYPES>>())
+        {
+            return entry.clone();
+        }
+
+        let (empty_payload, metadata) = <TYPES::BlockPayload as BlockPayload<TYPES>>::empty();
+        let entry: Entry<TYPES> = (empty_payload.builder_commitment(&metadata), metadata);
+        EMPTY_BLOCK_COMMITMENTS
+            .write()
+            .unwrap()
+            .insert(type_id, Box::new(entry.clone()));
+
+        entry
+    }
+
     /// Builder fee data for a null block payload
     #[must_use]
     pub fn builder_fee<TYPES: NodeType, V: Versions>(
@@ -28,7 +28,9 @@ use crate::{
     },
     vid::{
         advz::{advz_scheme, ADVZCommitment, ADVZCommon, ADVZScheme, ADVZShare},
-        avidm::{init_avidm_param, AvidMCommitment, AvidMCommon, AvidMScheme, AvidMShare},
+        avidm::{
+            init_avidm_param, AvidMCommitment, AvidMCommon, AvidMParam, AvidMScheme, AvidMShare,
+        },
     },
     vote::HasViewNumber,
     PeerConfig,
@@ -307,6 +309,41 @@ pub fn vid_total_weight<TYPES: NodeType>(
     }
 }
 
+/// The VID weight of each member of `stake_table`, in stake table order.
+///
+/// These are the weights AVID-M shares are dispersed with.
+pub fn vid_weights<TYPES: NodeType>(stake_table: Vec<PeerConfig<TYPES>>) -> Vec<u32> {
+    approximate_weights(stake_table).weights
+}
+
+/// Check that an AVID-M dispersal under `param` covers the full weight of `stake_table`.
+///
+/// The total weights of `param` must match the VID weight of the committee, and the ranges of
+/// `shares` must tile them exactly, so that no member was omitted or double counted.
+///
+/// # Errors
+/// If the dispersal does not cover the committee
+pub fn verify_vid_dispersal_coverage<TYPES: NodeType>(
+    stake_table: Vec<PeerConfig<TYPES>>,
+    param: &AvidMParam,
+    shares: &[AvidMShare],
+) -> Result<()> {
+    let total_weight = approximate_weights(stake_table).total_weight;
+    ensure!(
+        param.total_weights == total_weight,
+        error!(
+            "VID total weights {} do not match the committee weight {}",
+            param.total_weights, total_weight
+        )
+    );
+    AvidMScheme::verify_dispersal_coverage(param, shares).map_err(|err| {
+        error!(
+            "VID dispersal does not cover the committee: {}",
+            err.to_string()
+        )
+    })
+}
+
 fn approximate_weights<TYPES: NodeType>(stake_table: Vec<PeerConfig<TYPES>>) -> Weights {
     let total_stake = stake_table.iter().fold(U256::zero(), |acc, entry| {
         acc + entry.stake_table_entry.stake()
@@ -229,4 +229,24 @@ pub mod election {
         /// DRB result
         drb: [u8; 32],
     }
+
+    impl<Entry> RandomizedCommittee<Entry> {
+        /// The entries of the committee, in selection order, each paired with the cumulative stake
+        /// up to and including that entry
+        pub fn cdf(&self) -> &[(Entry, U256)] {
+            &self.cdf
+        }
+
+        /// The total stake of the committee
+        pub fn total_stake(&self) -> U256 {
+            self.cdf
+                .last()
+                .map_or(U256::zero(), |(_, cumulative_stake)| *cumulative_stake)
+        }
+
+        /// The DRB result the committee was randomized with
+        pub fn drb(&self) -> [u8; 32] {
+            self.drb
+        }
+    }
 }
@@ -17,7 +17,7 @@ use crate::{
         election::Membership,
         node_implementation::{ConsensusTime, NodeType},
     },
-    utils::{root_block_in_epoch, transition_block_for_epoch},
+    utils::{drb_leaf_height, root_block_in_epoch},
     PeerConfig,
 };
 
@@ -165,10 +165,8 @@ where
 
         // Get the epoch root headers and update our membership with them, finally sync them
         // Verification of the root is handled in get_epoch_root_and_drb
-        let Ok(header) = root_membership
-            .get_epoch_root(root_block_in_epoch(*root_epoch, self.epoch_height))
-            .await
-        else {
+        let root_height = root_block_in_epoch(*root_epoch, self.epoch_height);
+        let Ok(header) = root_membership.get_epoch_root(root_height).await else {
             anytrace::bail!("get epoch root failed for epoch {:?}", root_epoch);
         };
 
@@ -188,10 +186,7 @@ where
 
         // get the DRB from the last block of the epoch right before the one we're catching up to
         let Ok(drb) = drb_membership
-            .get_epoch_drb(transition_block_for_epoch(
-                *(root_epoch + 1),
-                self.epoch_height,
-            ))
+            .get_epoch_drb(drb_leaf_height(root_height, self.epoch_height))
             .await
         else {
             return Err(anytrace::warn!(
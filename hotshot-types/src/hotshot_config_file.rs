@@ -10,8 +10,9 @@ use url::Url;
 use vec1::Vec1;
 
 use crate::{
-    constants::REQUEST_DATA_DELAY, upgrade_config::UpgradeConfig, HotShotConfig, NodeType,
-    PeerConfig, ValidatorConfig,
+    constants::REQUEST_DATA_DELAY, default_allow_leader_vid_dispersal,
+    default_high_qc_wait_fraction, default_max_proposal_storage_attempts,
+    upgrade_config::UpgradeConfig, HotShotConfig, NodeType, PeerConfig, ValidatorConfig,
 };
 
 /// Default builder URL, used as placeholder
@@ -57,6 +58,25 @@ pub struct HotShotConfigFile<TYPES: NodeType> {
     pub epoch_height: u64,
     /// Epoch start block
     pub epoch_start_block: u64,
+    /// Whether the view leader may disperse VID shares without being a DA committee member
+    #[serde(default = "default_allow_leader_vid_dispersal")]
+    pub allow_leader_vid_dispersal: bool,
+    /// Maximum number of views a received proposal may be ahead of our current view before we
+    /// catch up to it rather than processing it immediately. `None` means there is no limit.
+    #[serde(default)]
+    pub max_future_view_skew: Option<u64>,
+    /// Number of views between a proposal and the view it decides above which we warn that
+    /// finality is degrading. `None` disables the alert.
+    #[serde(default)]
+    pub views_per_decide_alert_threshold: Option<u64>,
+    /// Number of attempts at storing a proposal before voting on it, after which we give up on
+    /// voting for that view
+    #[serde(default = "default_max_proposal_storage_attempts")]
+    pub max_proposal_storage_attempts: u64,
+    /// Fraction of the view timeout, as a numerator and a denominator, that the leader waits for
+    /// the highest QC and the transition QC from the other nodes before proposing
+    #[serde(default = "default_high_qc_wait_fraction")]
+    pub high_qc_wait_fraction: (u64, u64),
 }
 
 impl<TYPES: NodeType> From<HotShotConfigFile<TYPES>> for HotShotConfig<TYPES> {
@@ -86,6 +106,11 @@ impl<TYPES: NodeType> From<HotShotConfigFile<TYPES>> for HotShotConfig<TYPES> {
             stop_voting_time: val.upgrade.stop_voting_time,
             epoch_height: val.epoch_height,
             epoch_start_block: val.epoch_start_block,
+            allow_leader_vid_dispersal: val.allow_leader_vid_dispersal,
+            max_future_view_skew: val.max_future_view_skew,
+            views_per_decide_alert_threshold: val.views_per_decide_alert_threshold,
+            max_proposal_storage_attempts: val.max_proposal_storage_attempts,
+            high_qc_wait_fraction: val.high_qc_wait_fraction,
         }
     }
 }
@@ -132,6 +157,11 @@ impl<TYPES: NodeType> HotShotConfigFile<TYPES> {
             upgrade: UpgradeConfig::default(),
             epoch_height: 0,
             epoch_start_block: 0,
+            allow_leader_vid_dispersal: default_allow_leader_vid_dispersal(),
+            max_future_view_skew: None,
+            views_per_decide_alert_threshold: None,
+            max_proposal_storage_attempts: default_max_proposal_storage_attempts(),
+            high_qc_wait_fraction: default_high_qc_wait_fraction(),
         }
     }
 }
@@ -238,12 +238,49 @@ pub struct HotShotConfig<TYPES: NodeType> {
     /// Epoch start block   
     #[serde(default = "default_epoch_start_block")]
     pub epoch_start_block: u64,
+    /// Whether the view leader may disperse VID shares without being a DA committee member
+    #[serde(default = "default_allow_leader_vid_dispersal")]
+    pub allow_leader_vid_dispersal: bool,
+    /// Maximum number of views a received proposal may be ahead of our current view before we
+    /// catch up to it rather than processing it immediately. `None` means there is no limit.
+    #[serde(default)]
+    pub max_future_view_skew: Option<u64>,
+    /// Number of views between a proposal and the view it decides above which we warn that
+    /// finality is degrading. `None` disables the alert.
+    #[serde(default)]
+    pub views_per_decide_alert_threshold: Option<u64>,
+    /// Number of attempts at storing a proposal before voting on it, after which we give up on
+    /// voting for that view
+    #[serde(default = "default_max_proposal_storage_attempts")]
+    pub max_proposal_storage_attempts: u64,
+    /// Fraction of the view timeout, as a numerator and a denominator, that the leader waits for
+    /// the highest QC and the transition QC from the other nodes before proposing
+    #[serde(default = "default_high_qc_wait_fraction")]
+    pub high_qc_wait_fraction: (u64, u64),
 }
 
 fn default_epoch_start_block() -> u64 {
     1
 }
 
+/// The default for [`HotShotConfig::allow_leader_vid_dispersal`].
+#[must_use]
+pub fn default_allow_leader_vid_dispersal() -> bool {
+    true
+}
+
+/// The default for [`HotShotConfig::max_proposal_storage_attempts`].
+#[must_use]
+pub fn default_max_proposal_storage_attempts() -> u64 {
+    3
+}
+
+/// The default for [`HotShotConfig::high_qc_wait_fraction`].
+#[must_use]
+pub fn default_high_qc_wait_fraction() -> (u64, u64) {
+    (1, 2)
+}
+
 impl<TYPES: NodeType> HotShotConfig<TYPES> {
     /// Update a hotshot config to have a view-based upgrade.
     pub fn set_view_upgrade(&mut self, view: u64) {
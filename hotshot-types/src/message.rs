@@ -573,12 +573,28 @@ where
     }
 }
 
+/// The views at which epochs are enabled, as known to this node.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(deserialize = "", serialize = ""))]
+pub struct EpochsUpgradeView<TYPES: NodeType> {
+    /// The first view with epochs enabled, once the upgrade enabling them is in effect
+    pub decided: Option<TYPES::View>,
+
+    /// The first view of a decided upgrade to epochs, which is staged until the epoch upgrade
+    /// block height is reached
+    pub staged: Option<TYPES::View>,
+}
+
 #[derive(Clone, Debug)]
 /// A lock for an upgrade certificate decided by HotShot, which doubles as `PhantomData` for an instance of the `Versions` trait.
 pub struct UpgradeLock<TYPES: NodeType, V: Versions> {
     /// a shared lock to an upgrade certificate decided by consensus
     pub decided_upgrade_certificate: Arc<RwLock<Option<UpgradeCertificate<TYPES>>>>,
 
+    /// a shared lock to an upgrade certificate enabling epochs, decided by consensus but staged
+    /// until we reach the epoch upgrade block height
+    pub staged_epoch_upgrade_certificate: Arc<RwLock<Option<UpgradeCertificate<TYPES>>>>,
+
     /// phantom data for the `Versions` trait
     pub _pd: PhantomData<V>,
 }
@@ -589,6 +605,7 @@ impl<TYPES: NodeType, V: Versions> UpgradeLock<TYPES, V> {
     pub fn new() -> Self {
         Self {
             decided_upgrade_certificate: Arc::new(RwLock::new(None)),
+            staged_epoch_upgrade_certificate: Arc::new(RwLock::new(None)),
             _pd: PhantomData::<V>,
         }
     }
@@ -598,6 +615,7 @@ impl<TYPES: NodeType, V: Versions> UpgradeLock<TYPES, V> {
     pub fn from_certificate(certificate: &Option<UpgradeCertificate<TYPES>>) -> Self {
         Self {
             decided_upgrade_certificate: Arc::new(RwLock::new(certificate.clone())),
+            staged_epoch_upgrade_certificate: Arc::new(RwLock::new(None)),
             _pd: PhantomData::<V>,
         }
     }
@@ -657,6 +675,29 @@ impl<TYPES: NodeType, V: Versions> UpgradeLock<TYPES, V> {
         self.version_infallible(view).await >= V::Epochs::VERSION
     }
 
+    /// Return the views at which epochs are enabled, both from the decided upgrade and from the
+    /// upgrade to epochs that is still staged, if any.
+    pub async fn epochs_upgrade_view(&self) -> EpochsUpgradeView<TYPES> {
+        let decided = if V::Base::VERSION >= V::Epochs::VERSION {
+            Some(TYPES::View::genesis())
+        } else {
+            self.decided_upgrade_certificate
+                .read()
+                .await
+                .as_ref()
+                .filter(|cert| cert.data.new_version >= V::Epochs::VERSION)
+                .map(|cert| cert.data.new_version_first_view)
+        };
+        let staged = self
+            .staged_epoch_upgrade_certificate
+            .read()
+            .await
+            .as_ref()
+            .map(|cert| cert.data.new_version_first_view);
+
+        EpochsUpgradeView { decided, staged }
+    }
+
     /// Serialize a message with a version number, using `message.view_number()` and an optional decided upgrade certificate to determine the message's version.
     ///
     /// # Errors
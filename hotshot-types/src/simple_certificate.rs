@@ -35,6 +35,7 @@ use crate::{
         node_implementation::{ConsensusTime, NodeType, Versions},
         signature_key::{SignatureKey, StateSignatureKey},
     },
+    utils::{is_epoch_root, is_epoch_transition, is_last_block},
     vote::{Certificate, HasViewNumber},
     PeerConfig, StakeTableEntries,
 };
@@ -490,6 +491,33 @@ impl<TYPES: NodeType> QuorumCertificate2<TYPES> {
             _pd: PhantomData,
         }
     }
+
+    /// Returns true if this QC is for the epoch root block, the fifth from the last block in
+    /// its epoch. A QC without a block number is never for the epoch root.
+    #[must_use]
+    pub fn is_epoch_root(&self, epoch_height: u64) -> bool {
+        self.data
+            .block_number
+            .is_some_and(|bn| is_epoch_root(bn, epoch_height))
+    }
+
+    /// Returns true if this QC is for a block that is part of the epoch transition, including
+    /// the last block in the epoch. A QC without a block number is never part of the transition.
+    #[must_use]
+    pub fn is_transition(&self, epoch_height: u64) -> bool {
+        self.data
+            .block_number
+            .is_some_and(|bn| is_epoch_transition(bn, epoch_height))
+    }
+
+    /// Returns true if this QC is for the last block in the epoch, i.e. if it is an eQC.
+    /// A QC without a block number is never for the last block.
+    #[must_use]
+    pub fn is_last_block(&self, epoch_height: u64) -> bool {
+        self.data
+            .block_number
+            .is_some_and(|bn| is_last_block(bn, epoch_height))
+    }
 }
 
 impl<TYPES: NodeType> DaCertificate<TYPES> {
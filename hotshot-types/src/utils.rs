@@ -8,7 +8,7 @@
 
 use std::{
     hash::{Hash, Hasher},
-    ops::Deref,
+    ops::{Deref, RangeInclusive},
     sync::Arc,
 };
 
@@ -327,18 +327,103 @@ pub fn bincode_opts() -> WithOtherTrailing<
         .reject_trailing_bytes()
 }
 
+/// Classifies block numbers relative to the epoch boundaries for a fixed epoch height.
+///
+/// An epoch height of 0 means epochs are disabled: every block belongs to epoch 0 and
+/// no block is ever classified as part of an epoch transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EpochLayout {
+    /// Number of blocks in each epoch
+    epoch_height: u64,
+}
+
+impl EpochLayout {
+    /// Create a new layout for the given epoch height
+    #[must_use]
+    pub const fn new(epoch_height: u64) -> Self {
+        Self { epoch_height }
+    }
+
+    /// The epoch height this layout was built from
+    #[must_use]
+    pub const fn epoch_height(&self) -> u64 {
+        self.epoch_height
+    }
+
+    /// Returns the epoch the given block belongs to. Block 0 belongs to epoch 1.
+    #[must_use]
+    pub fn epoch_of(&self, block_number: u64) -> u64 {
+        if self.epoch_height == 0 {
+            0
+        } else if block_number == 0 {
+            1
+        } else {
+            block_number.div_ceil(self.epoch_height)
+        }
+    }
+
+    /// Returns the first and last block of the given epoch.
+    ///
+    /// The range is empty if the epoch contains no blocks, i.e. epoch 0 when epochs are
+    /// enabled, or any epoch other than 0 when they are not.
+    #[must_use]
+    pub fn boundaries(&self, epoch: u64) -> RangeInclusive<u64> {
+        if self.epoch_height == 0 {
+            return if epoch == 0 {
+                0..=u64::MAX
+            } else {
+                RangeInclusive::new(1, 0)
+            };
+        }
+
+        match epoch {
+            0 => RangeInclusive::new(1, 0),
+            // The genesis block is part of the first epoch
+            1 => 0..=self.epoch_height,
+            _ => {
+                let last = epoch.saturating_mul(self.epoch_height);
+                (last - self.epoch_height + 1)..=last
+            },
+        }
+    }
+
+    /// Returns true if the block is part of the epoch transition, i.e. it is the transition
+    /// block, one of the two blocks following it, or the last block in the epoch
+    #[must_use]
+    pub fn is_transition(&self, block_number: u64) -> bool {
+        if block_number == 0 || self.epoch_height == 0 {
+            false
+        } else {
+            block_number % self.epoch_height >= self.epoch_height - 3
+                || block_number % self.epoch_height == 0
+        }
+    }
+
+    /// Returns true if the block is the last block in its epoch
+    #[must_use]
+    pub fn is_last(&self, block_number: u64) -> bool {
+        if block_number == 0 || self.epoch_height == 0 {
+            false
+        } else {
+            block_number % self.epoch_height == 0
+        }
+    }
+
+    /// Returns true if the block is the epoch root, the fifth from the last block in its epoch
+    #[must_use]
+    pub fn is_root(&self, block_number: u64) -> bool {
+        if block_number == 0 || self.epoch_height == 0 {
+            false
+        } else {
+            (block_number + 5) % self.epoch_height == 0
+        }
+    }
+}
+
 /// Returns an epoch number given a block number and an epoch height
 #[must_use]
 pub fn epoch_from_block_number(block_number: u64, epoch_height: u64) -> u64 {
-    if epoch_height == 0 {
-        0
-    } else if block_number == 0 {
-        1
-    } else if block_number % epoch_height == 0 {
-        block_number / epoch_height
-    } else {
-        block_number / epoch_height + 1
-    }
+    EpochLayout::new(epoch_height).epoch_of(block_number)
 }
 
 /// Returns the block number of the epoch root in the given epoch
@@ -364,6 +449,22 @@ pub fn transition_block_for_epoch(epoch: u64, epoch_height: u64) -> u64 {
     }
 }
 
+/// Returns the block number of the leaf carrying the DRB result seeded by the epoch root at
+/// `epoch_root_height`.
+///
+/// The epoch root is the fifth from the last block of its epoch, and the DRB result computed
+/// from it is attached to the transition block of the following epoch, the third from the last
+/// block. The DRB leaf is therefore one full epoch after the root, plus the `+2` separating the
+/// root's distance from the end of an epoch (5) from the transition block's (3).
+#[must_use]
+pub fn drb_leaf_height(epoch_root_height: u64, epoch_height: u64) -> u64 {
+    if epoch_height == 0 {
+        0
+    } else {
+        epoch_root_height + epoch_height + 2
+    }
+}
+
 /// Returns an Option<Epoch> based on a boolean condition of whether or not epochs are enabled, a block number,
 /// and the epoch height. If epochs are disabled or the epoch height is zero, returns None.
 #[must_use]
@@ -431,21 +532,13 @@ pub fn is_first_transition_block(block_number: u64, epoch_height: u64) -> bool {
 /// Returns true if the block is part of the epoch transition (including the last non null block)  
 #[must_use]
 pub fn is_epoch_transition(block_number: u64, epoch_height: u64) -> bool {
-    if block_number == 0 || epoch_height == 0 {
-        false
-    } else {
-        block_number % epoch_height >= epoch_height - 3 || block_number % epoch_height == 0
-    }
+    EpochLayout::new(epoch_height).is_transition(block_number)
 }
 
 /// Returns true if the block is the last block in the epoch
 #[must_use]
 pub fn is_last_block(block_number: u64, epoch_height: u64) -> bool {
-    if block_number == 0 || epoch_height == 0 {
-        false
-    } else {
-        block_number % epoch_height == 0
-    }
+    EpochLayout::new(epoch_height).is_last(block_number)
 }
 
 /// Returns true if the block number is in trasntion but not the transition block
@@ -467,11 +560,7 @@ pub fn is_middle_transition_block(block_number: u64, epoch_height: u64) -> bool
 /// given epoch height.
 #[must_use]
 pub fn is_epoch_root(block_number: u64, epoch_height: u64) -> bool {
-    if block_number == 0 || epoch_height == 0 {
-        false
-    } else {
-        (block_number + 5) % epoch_height == 0
-    }
+    EpochLayout::new(epoch_height).is_root(block_number)
 }
 
 /// Returns true if the given block number is equal or greater than the epoch root block
@@ -555,4 +644,88 @@ mod test {
             epoch_from_block_number(epoch_root_block_number, epoch_height)
         );
     }
+
+    #[test]
+    fn test_drb_leaf_height() {
+        assert_eq!(drb_leaf_height(25, 10), 37);
+        assert_eq!(drb_leaf_height(95, 100), 197);
+        assert_eq!(drb_leaf_height(25, 0), 0);
+
+        // The DRB leaf is always the transition block of the epoch following the root's epoch
+        for epoch_height in [10, 17, 100] {
+            for epoch in 1..20 {
+                assert_eq!(
+                    drb_leaf_height(root_block_in_epoch(epoch, epoch_height), epoch_height),
+                    transition_block_for_epoch(epoch + 1, epoch_height)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_epoch_layout_boundaries() {
+        let layout = EpochLayout::new(10);
+        assert_eq!(layout.boundaries(1), 0..=10);
+        assert_eq!(layout.boundaries(2), 11..=20);
+        assert_eq!(layout.boundaries(3), 21..=30);
+        assert!(layout.boundaries(0).is_empty());
+
+        let layout = EpochLayout::new(0);
+        assert_eq!(layout.boundaries(0), 0..=u64::MAX);
+        assert!(layout.boundaries(1).is_empty());
+    }
+
+    #[test]
+    fn test_epoch_layout_classification() {
+        for epoch_height in [0, 6, 10, 17, 100] {
+            let layout = EpochLayout::new(epoch_height);
+
+            for block_number in 0..1000 {
+                let epoch = layout.epoch_of(block_number);
+                assert_eq!(epoch, epoch_from_block_number(block_number, epoch_height));
+                assert_eq!(
+                    layout.is_transition(block_number),
+                    is_epoch_transition(block_number, epoch_height)
+                );
+                assert_eq!(
+                    layout.is_last(block_number),
+                    is_last_block(block_number, epoch_height)
+                );
+                assert_eq!(
+                    layout.is_root(block_number),
+                    is_epoch_root(block_number, epoch_height)
+                );
+
+                let boundaries = layout.boundaries(epoch);
+                assert!(boundaries.contains(&block_number));
+
+                // Classify the block by its distance from the end of its epoch
+                let blocks_left = boundaries.end().checked_sub(block_number);
+                let enabled = epoch_height != 0 && block_number != 0;
+                assert_eq!(
+                    layout.is_last(block_number),
+                    enabled && blocks_left == Some(0)
+                );
+                assert_eq!(
+                    layout.is_root(block_number),
+                    enabled && blocks_left == Some(5)
+                );
+                assert_eq!(
+                    layout.is_transition(block_number),
+                    enabled && blocks_left.is_some_and(|left| left <= 3)
+                );
+
+                if enabled {
+                    assert_eq!(
+                        layout.is_root(block_number),
+                        block_number == root_block_in_epoch(epoch, epoch_height)
+                    );
+                    assert_eq!(
+                        is_transition_block(block_number, epoch_height),
+                        block_number == transition_block_for_epoch(epoch, epoch_height)
+                    );
+                }
+            }
+        }
+    }
 }
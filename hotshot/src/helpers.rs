@@ -1,6 +1,27 @@
-use tracing_subscriber::{fmt::format::FmtSpan, EnvFilter};
+use hotshot_task_impls::log_level::{matches_targets, TASK_LOG_TARGETS};
+pub use hotshot_task_impls::log_level::{TaskLogLevel, TaskLogLevels};
+use parking_lot::RwLock;
+use tracing::{level_filters::LevelFilter, span, subscriber::Interest, Metadata};
+use tracing_subscriber::{
+    fmt::format::FmtSpan,
+    layer::{Context, Filter, SubscriberExt},
+    util::SubscriberInitExt,
+    EnvFilter, Layer,
+};
+
+/// The log level handles of the tasks of every node in this process, which are
+/// applied by the filter that [`initialize_logging`] installs.
+static TASK_LOG_LEVELS: TaskLogRegistry = TaskLogRegistry::new();
+
+/// Make the log levels of the tasks of a node take effect in the logs.
+pub fn register_task_log_levels(levels: &TaskLogLevels) {
+    TASK_LOG_LEVELS.register(levels);
+}
 
 /// Initializes logging
+///
+/// Logs are filtered according to `RUST_LOG`, except for the tasks whose log
+/// level has been changed at runtime through their [`TaskLogLevel`].
 pub fn initialize_logging() {
     // Parse the `RUST_LOG_SPAN_EVENTS` environment variable
     let span_event_filter = match std::env::var("RUST_LOG_SPAN_EVENTS") {
@@ -19,17 +40,231 @@ pub fn initialize_logging() {
         Err(_) => FmtSpan::NONE,
     };
 
+    let filter = TaskLogFilter::new(EnvFilter::from_default_env(), &TASK_LOG_LEVELS);
+
     // Conditionally initialize in `json` mode
     if std::env::var("RUST_LOG_FORMAT") == Ok("json".to_string()) {
-        let _ = tracing_subscriber::fmt()
-            .with_env_filter(EnvFilter::from_default_env())
-            .with_span_events(span_event_filter)
-            .json()
+        let _ = tracing_subscriber::registry()
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .with_span_events(span_event_filter)
+                    .json()
+                    .with_filter(filter),
+            )
             .try_init();
     } else {
-        let _ = tracing_subscriber::fmt()
-            .with_env_filter(EnvFilter::from_default_env())
-            .with_span_events(span_event_filter)
+        let _ = tracing_subscriber::registry()
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .with_span_events(span_event_filter)
+                    .with_filter(filter),
+            )
             .try_init();
     };
 }
+
+/// The log level handles of the tasks a [`TaskLogFilter`] applies.
+///
+/// When several nodes run in the same process, the logs of a task are filtered
+/// with the most verbose level set for that task on any of them.
+#[derive(Debug, Default)]
+pub struct TaskLogRegistry {
+    /// The registered handles.
+    tasks: RwLock<Vec<TaskLogLevel>>,
+}
+
+impl TaskLogRegistry {
+    /// Create an empty registry.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            tasks: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Register the log level handles of the tasks of a node.
+    ///
+    /// Handles of nodes that have since been dropped are removed.
+    pub fn register(&self, levels: &TaskLogLevels) {
+        let mut tasks = self.tasks.write();
+        tasks.retain(TaskLogLevel::is_shared);
+        tasks.extend(levels.iter().cloned());
+        drop(tasks);
+
+        // A registered handle may already have a level set.
+        tracing::callsite::rebuild_interest_cache();
+    }
+
+    /// The level set for the task the given log or span belongs to, if any.
+    fn task_level(&self, metadata: &Metadata<'_>) -> Option<LevelFilter> {
+        self.tasks
+            .read()
+            .iter()
+            .filter(|task| task.matches(metadata))
+            .filter_map(TaskLogLevel::level)
+            .max()
+    }
+
+    /// The most verbose level set for any task, if any.
+    fn max_level(&self) -> Option<LevelFilter> {
+        self.tasks
+            .read()
+            .iter()
+            .filter_map(TaskLogLevel::level)
+            .max()
+    }
+}
+
+/// A [`Filter`] that applies the levels of the registered tasks to their logs,
+/// and defers to an inner filter for everything else.
+pub struct TaskLogFilter<F> {
+    /// Filter for the logs that do not belong to a task with a level set.
+    inner: F,
+
+    /// The tasks whose levels can be changed at runtime.
+    tasks: &'static TaskLogRegistry,
+}
+
+impl<F> TaskLogFilter<F> {
+    /// Create a filter applying the levels of the tasks in `tasks`, and
+    /// deferring to `inner` for the rest.
+    #[must_use]
+    pub fn new(inner: F, tasks: &'static TaskLogRegistry) -> Self {
+        Self { inner, tasks }
+    }
+}
+
+impl<S, F: Filter<S>> Filter<S> for TaskLogFilter<F> {
+    fn enabled(&self, metadata: &Metadata<'_>, cx: &Context<'_, S>) -> bool {
+        match self.tasks.task_level(metadata) {
+            Some(level) => metadata.level() <= &level,
+            None => self.inner.enabled(metadata, cx),
+        }
+    }
+
+    fn callsite_enabled(&self, metadata: &'static Metadata<'static>) -> Interest {
+        // The level of a task can change at any time, so its callsites must
+        // not be cached.
+        if TASK_LOG_TARGETS
+            .iter()
+            .any(|targets| matches_targets(targets, metadata))
+        {
+            Interest::sometimes()
+        } else {
+            self.inner.callsite_enabled(metadata)
+        }
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        // Changing the level of a task rebuilds the interest cache, which
+        // picks up the new hint.
+        let inner = self.inner.max_level_hint()?;
+        Some(
+            self.tasks
+                .max_level()
+                .map_or(inner, |level| level.max(inner)),
+        )
+    }
+
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        self.inner.on_new_span(attrs, id, ctx);
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        self.inner.on_record(id, values, ctx);
+    }
+
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        self.inner.on_enter(id, ctx);
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+        self.inner.on_exit(id, ctx);
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        self.inner.on_close(id, ctx);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use tracing::{Event, Subscriber};
+    use tracing_subscriber::Registry;
+
+    use super::*;
+
+    /// Records the target of every event it sees.
+    #[derive(Clone, Default)]
+    struct RecordingLayer {
+        targets: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl<S: Subscriber> Layer<S> for RecordingLayer {
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            self.targets
+                .lock()
+                .unwrap()
+                .push(event.metadata().target().to_string());
+        }
+    }
+
+    /// Emit a debug log for the proposal task, inside one of its spans, and for
+    /// the vote task.
+    fn emit_debug_logs() {
+        let span = tracing::debug_span!(target: "QuorumProposalTaskState", "handle method");
+        let _guard = span.enter();
+        tracing::debug!(target: "hotshot_task_impls::quorum_proposal::handlers", "proposal");
+        tracing::debug!(target: "hotshot_task_impls::quorum_vote", "vote");
+        tracing::debug!(target: "hotshot_task_impls::quorum_proposal_recv", "proposal recv");
+    }
+
+    #[test]
+    fn test_task_log_level_toggles_debug_logs() {
+        static TASKS: TaskLogRegistry = TaskLogRegistry::new();
+
+        let levels = TaskLogLevels::default();
+        TASKS.register(&levels);
+        let proposal_log_level = &levels.quorum_proposal;
+
+        let layer = RecordingLayer::default();
+        let subscriber = tracing_subscriber::registry().with(
+            layer
+                .clone()
+                .with_filter(TaskLogFilter::new(LevelFilter::INFO, &TASKS)),
+        );
+        let max_level_hint =
+            || Filter::<Registry>::max_level_hint(&TaskLogFilter::new(LevelFilter::INFO, &TASKS));
+
+        tracing::subscriber::with_default(subscriber, || {
+            // Without an override, the proposal task is filtered like the rest.
+            emit_debug_logs();
+            assert!(layer.targets.lock().unwrap().is_empty());
+            assert_eq!(max_level_hint(), Some(LevelFilter::INFO));
+
+            // Raising the level of the proposal task only enables its logs.
+            proposal_log_level.set_level(LevelFilter::DEBUG);
+            assert_eq!(max_level_hint(), Some(LevelFilter::DEBUG));
+            emit_debug_logs();
+            assert_eq!(
+                *layer.targets.lock().unwrap(),
+                vec!["hotshot_task_impls::quorum_proposal::handlers".to_string()]
+            );
+
+            // Lowering it again makes them disappear.
+            layer.targets.lock().unwrap().clear();
+            proposal_log_level.set_level(LevelFilter::INFO);
+            emit_debug_logs();
+            assert!(layer.targets.lock().unwrap().is_empty());
+
+            // As does removing the override.
+            proposal_log_level.set_level(LevelFilter::DEBUG);
+            proposal_log_level.reset();
+            emit_debug_logs();
+            assert!(layer.targets.lock().unwrap().is_empty());
+            assert_eq!(max_level_hint(), Some(LevelFilter::INFO));
+        });
+    }
+}
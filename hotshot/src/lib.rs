@@ -50,7 +50,9 @@ use async_lock::RwLock;
 use async_trait::async_trait;
 use futures::join;
 use hotshot_task::task::{ConsensusTaskRegistry, NetworkTaskRegistry};
-use hotshot_task_impls::{events::HotShotEvent, helpers::broadcast_event};
+use hotshot_task_impls::{
+    events::HotShotEvent, helpers::broadcast_event, log_level::TaskLogLevels,
+};
 // Internal
 /// Reexport error type
 pub use hotshot_types::error::HotShotError;
@@ -83,6 +85,7 @@ use tracing::{debug, instrument, trace};
 // -- Rexports
 // External
 use crate::{
+    helpers::register_task_log_levels,
     tasks::{add_consensus_tasks, add_network_tasks},
     traits::NodeImplementation,
     types::{Event, SystemContextHandle},
@@ -164,6 +167,9 @@ pub struct SystemContext<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versi
 
     /// Marketplace config for this instance of HotShot
     pub marketplace_config: MarketplaceConfig<TYPES, I>,
+
+    /// Runtime adjustable log levels of the consensus tasks
+    pub(crate) task_log_levels: TaskLogLevels,
 }
 impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> Clone
     for SystemContext<TYPES, I, V>
@@ -190,6 +196,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> Clone
             storage: Arc::clone(&self.storage),
             upgrade_lock: self.upgrade_lock.clone(),
             marketplace_config: self.marketplace_config.clone(),
+            task_log_levels: self.task_log_levels.clone(),
         }
     }
 }
@@ -374,6 +381,9 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> SystemContext<T
         // Our own copy of the receiver is inactive so it doesn't count.
         external_tx.set_await_active(false);
 
+        let task_log_levels = TaskLogLevels::default();
+        register_task_log_levels(&task_log_levels);
+
         let inner: Arc<SystemContext<TYPES, I, V>> = Arc::new(SystemContext {
             id: nonce,
             consensus: OuterConsensus::new(consensus),
@@ -394,6 +404,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> SystemContext<T
             storage: Arc::new(RwLock::new(storage)),
             upgrade_lock,
             marketplace_config,
+            task_log_levels,
         });
 
         inner
@@ -248,8 +248,32 @@ pub async fn add_consensus_tasks<TYPES: NodeType, I: NodeImplementation<TYPES>,
             quorum_proposal_recv::QuorumProposalRecvTaskState, quorum_vote::QuorumVoteTaskState,
         };
 
-        handle.add_task(QuorumProposalTaskState::<TYPES, I, V>::create_from(handle).await);
-        handle.add_task(QuorumVoteTaskState::<TYPES, I, V>::create_from(handle).await);
+        #[cfg(not(feature = "event-replay"))]
+        {
+            handle.add_task(QuorumProposalTaskState::<TYPES, I, V>::create_from(handle).await);
+            handle.add_task(QuorumVoteTaskState::<TYPES, I, V>::create_from(handle).await);
+        }
+        #[cfg(feature = "event-replay")]
+        {
+            use hotshot_task_impls::replay::RecordingTaskState;
+
+            // Recordings go to `HOTSHOT_EVENT_RECORDING_DIR`, or the temporary directory if unset.
+            let dir = std::env::var_os("HOTSHOT_EVENT_RECORDING_DIR")
+                .map_or_else(std::env::temp_dir, std::path::PathBuf::from);
+            let id = handle.hotshot.id;
+            handle.add_task(
+                RecordingTaskState::new(
+                    QuorumProposalTaskState::<TYPES, I, V>::create_from(handle).await,
+                )
+                .with_path(dir.join(format!("quorum_proposal_events_{id}.jsonl"))),
+            );
+            handle.add_task(
+                RecordingTaskState::new(
+                    QuorumVoteTaskState::<TYPES, I, V>::create_from(handle).await,
+                )
+                .with_path(dir.join(format!("quorum_vote_events_{id}.jsonl"))),
+            );
+        }
         handle.add_task(QuorumProposalRecvTaskState::<TYPES, I, V>::create_from(handle).await);
         handle.add_task(ConsensusTaskState::<TYPES, I, V>::create_from(handle).await);
     }
@@ -246,8 +246,14 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> CreateTaskState
             upgrade_lock: handle.hotshot.upgrade_lock.clone(),
             epoch_height: handle.hotshot.config.epoch_height,
             epoch_upgrade_block_height: handle.hotshot.config.epoch_start_block,
-            staged_epoch_upgrade_certificate: None,
+            allow_leader_vid_dispersal: handle.hotshot.config.allow_leader_vid_dispersal,
+            views_per_decide_alert_threshold: handle
+                .hotshot
+                .config
+                .views_per_decide_alert_threshold,
+            max_proposal_storage_attempts: handle.hotshot.config.max_proposal_storage_attempts,
             consensus_metrics,
+            log_level: handle.task_log_levels().quorum_vote.clone(),
         }
     }
 }
@@ -259,6 +265,9 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> CreateTaskState
     async fn create_from(handle: &SystemContextHandle<TYPES, I, V>) -> Self {
         let consensus = handle.hotshot.consensus();
 
+        // Clone the consensus metrics
+        let consensus_metrics = Arc::clone(&consensus.read().await.metrics);
+
         Self {
             latest_proposed_view: handle.cur_view().await,
             cur_epoch: handle.cur_epoch().await,
@@ -266,16 +275,19 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> CreateTaskState
             formed_quorum_certificates: BTreeMap::new(),
             formed_next_epoch_quorum_certificates: BTreeMap::new(),
             consensus: OuterConsensus::new(consensus),
+            consensus_metrics,
             instance_state: handle.hotshot.instance_state(),
             membership_coordinator: handle.hotshot.membership_coordinator.clone(),
             public_key: handle.public_key().clone(),
             private_key: handle.private_key().clone(),
             storage: Arc::clone(&handle.storage),
             timeout: handle.hotshot.config.next_view_timeout,
+            high_qc_wait_fraction: handle.hotshot.config.high_qc_wait_fraction,
             id: handle.hotshot.id,
             formed_upgrade_certificate: None,
             upgrade_lock: handle.hotshot.upgrade_lock.clone(),
             epoch_height: handle.hotshot.config.epoch_height,
+            log_level: handle.task_log_levels().quorum_proposal.clone(),
         }
     }
 }
@@ -301,6 +313,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> CreateTaskState
             id: handle.hotshot.id,
             upgrade_lock: handle.hotshot.upgrade_lock.clone(),
             epoch_height: handle.hotshot.config.epoch_height,
+            max_future_view_skew: handle.hotshot.config.max_future_view_skew,
         }
     }
 }
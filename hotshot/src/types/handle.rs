@@ -17,13 +17,15 @@ use hotshot_task::{
     dependency::{Dependency, EventDependency},
     task::{ConsensusTaskRegistry, NetworkTaskRegistry, Task, TaskState},
 };
-use hotshot_task_impls::{events::HotShotEvent, helpers::broadcast_event};
+use hotshot_task_impls::{
+    events::HotShotEvent, helpers::broadcast_event, log_level::TaskLogLevels,
+};
 use hotshot_types::{
     consensus::Consensus,
     data::{Leaf2, QuorumProposalWrapper},
     epoch_membership::EpochMembershipCoordinator,
     error::HotShotError,
-    message::{Message, MessageKind, Proposal, RecipientList},
+    message::{EpochsUpgradeView, Message, MessageKind, Proposal, RecipientList},
     request_response::ProposalRequestPayload,
     traits::{
         block_contents::BlockHeader,
@@ -383,10 +385,23 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + 'static, V: Versions>
         self.hotshot.consensus.read().await.cur_epoch()
     }
 
+    /// Wrapper to get the views at which epochs are enabled, decided or staged.
+    #[instrument(skip_all, target = "SystemContextHandle", fields(id = self.hotshot.id))]
+    pub async fn epochs_upgrade_view(&self) -> EpochsUpgradeView<TYPES> {
+        self.hotshot.upgrade_lock.epochs_upgrade_view().await
+    }
+
     /// Provides a reference to the underlying storage for this [`SystemContext`], allowing access to
     /// historical data
     #[must_use]
     pub fn storage(&self) -> Arc<RwLock<I::Storage>> {
         Arc::clone(&self.storage)
     }
+
+    /// Handles to the log levels of the consensus tasks of this node, which can be changed while
+    /// it is running
+    #[must_use]
+    pub fn task_log_levels(&self) -> &TaskLogLevels {
+        &self.hotshot.task_log_levels
+    }
 }
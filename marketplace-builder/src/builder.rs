@@ -10,7 +10,7 @@ use espresso_types::{
     v0_1::NoStorage,
     v0_99::{ChainConfig, RollupRegistration},
     EpochCommittees, FeeAmount, L1Client, MarketplaceVersion, MockSequencerVersions, NamespaceId,
-    NodeState, Payload, SeqTypes, SequencerVersions, ValidatedState, V0_1,
+    NodeState, Payload, RewardConfig, SeqTypes, SequencerVersions, ValidatedState, V0_1,
 };
 use ethers::{
     core::k256::ecdsa::SigningKey,
@@ -66,6 +66,7 @@ pub struct BuilderConfig {
 
 pub fn build_instance_state<V: Versions>(
     chain_config: ChainConfig,
+    reward_config: RewardConfig,
     l1_params: L1Params,
     state_peers: Vec<Url>,
 ) -> NodeState {
@@ -98,6 +99,7 @@ pub fn build_instance_state<V: Versions>(
             10,
         ),
     )
+    .with_reward_config(reward_config)
 }
 
 impl BuilderConfig {
@@ -1,4 +1,4 @@
-use std::{sync::Arc, time::Duration};
+use std::{future::Future, sync::Arc, time::Duration};
 
 use async_lock::RwLock;
 use espresso_types::SeqTypes;
@@ -6,42 +6,64 @@ use futures::{
     channel::mpsc::{self, Receiver, SendError, Sender},
     Sink, SinkExt,
 };
+use hotshot_stake_table::vec_based::StakeTable;
+use hotshot_types::{
+    light_client::{CircuitField, StateVerKey},
+    signature_key::BLSPubKey,
+    traits::metrics::Metrics,
+};
 use tokio::{spawn, task::JoinHandle};
 use url::Url;
 
-use super::{get_stake_table_from_sequencer, LeafAndBlock, ProcessNodeIdentityUrlStreamTask};
+use super::{get_stake_table_from_sequencer, LeafBlockPair, ProcessNodeIdentityUrlStreamTask};
 use crate::service::{
     client_id::ClientId,
     client_message::InternalClientMessage,
     client_state::{
-        ClientThreadState, InternalClientMessageProcessingTask,
-        ProcessDistributeBlockDetailHandlingTask, ProcessDistributeNodeIdentityHandlingTask,
+        BackpressureStrategy, ClientThreadState, InternalClientMessageProcessingTask,
+        ProcessDistributeBlockDetailHandlingTask,
+        ProcessDistributeBlockDetailWithPayloadHandlingTask,
+        ProcessDistributeDecidedBlockDetailHandlingTask, ProcessDistributeNodeIdentityHandlingTask,
         ProcessDistributeVotersHandlingTask,
     },
     data_state::{DataState, ProcessLeafAndBlockPairStreamTask, ProcessNodeIdentityStreamTask},
     server_message::ServerMessage,
+    storage::{Storage, StorageError},
 };
 
 pub struct NodeValidatorAPI<K> {
     pub process_internal_client_message_handle: Option<InternalClientMessageProcessingTask>,
     pub process_distribute_block_detail_handle: Option<ProcessDistributeBlockDetailHandlingTask>,
+    pub process_distribute_block_detail_with_payload_handle:
+        Option<ProcessDistributeBlockDetailWithPayloadHandlingTask>,
+    pub process_distribute_decided_block_detail_handle:
+        Option<ProcessDistributeDecidedBlockDetailHandlingTask>,
     pub process_distribute_node_identity_handle: Option<ProcessDistributeNodeIdentityHandlingTask>,
     pub process_distribute_voters_handle: Option<ProcessDistributeVotersHandlingTask>,
     pub process_leaf_stream_handle: Option<ProcessLeafAndBlockPairStreamTask>,
     pub process_node_identity_stream_handle: Option<ProcessNodeIdentityStreamTask>,
     pub process_url_stream_handle: Option<ProcessNodeIdentityUrlStreamTask>,
     pub submit_public_urls_handle: Option<SubmitPublicUrlsToScrapeTask>,
+    pub refresh_stake_table_handle: Option<RefreshStakeTableTask>,
     pub url_sender: K,
+    pub data_state: Arc<RwLock<DataState>>,
 }
 
 pub struct NodeValidatorConfig {
     pub stake_table_url_base: Url,
+    pub stake_table_refresh_interval: Duration,
     pub initial_node_public_base_urls: Vec<Url>,
+    pub backpressure_strategy: BackpressureStrategy,
+    pub include_block_payload: bool,
+    pub decide_history: usize,
+    pub storage: Arc<dyn Storage>,
+    pub metrics: Box<dyn Metrics>,
 }
 
 #[derive(Debug)]
 pub enum CreateNodeValidatorProcessingError {
     FailedToGetStakeTable(hotshot_query_service::Error),
+    FailedToRestoreFromStorage(StorageError),
 }
 
 /// [SubmitPublicUrlsToScrapeTask] is a task that is capable of submitting
@@ -91,6 +113,86 @@ impl SubmitPublicUrlsToScrapeTask {
     }
 }
 
+/// [RefreshStakeTableTask] is a task that periodically re-fetches the stake
+/// table, and replaces the stake table stored in the [DataState] with it.
+/// This keeps voter participation attributed to the current set of
+/// validators.
+///
+/// If a fetch fails, the last successfully retrieved stake table is kept,
+/// and the fetch is retried at the next interval.
+pub struct RefreshStakeTableTask {
+    pub task_handle: Option<JoinHandle<()>>,
+}
+
+impl RefreshStakeTableTask {
+    pub fn new<F, Fut>(
+        fetch_stake_table: F,
+        data_state: Arc<RwLock<DataState>>,
+        interval: Duration,
+    ) -> Self
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<
+                Output = Result<
+                    StakeTable<BLSPubKey, StateVerKey, CircuitField>,
+                    hotshot_query_service::Error,
+                >,
+            > + Send,
+    {
+        let task_handle = spawn(Self::refresh_stake_table(
+            fetch_stake_table,
+            data_state,
+            interval,
+        ));
+
+        Self {
+            task_handle: Some(task_handle),
+        }
+    }
+
+    /// [refresh_stake_table] is a function that fetches the stake table
+    /// every `interval`, and replaces the stake table in the [DataState] with
+    /// it whenever the fetch succeeds.
+    async fn refresh_stake_table<F, Fut>(
+        mut fetch_stake_table: F,
+        data_state: Arc<RwLock<DataState>>,
+        interval: Duration,
+    ) where
+        F: FnMut() -> Fut,
+        Fut: Future<
+            Output = Result<
+                StakeTable<BLSPubKey, StateVerKey, CircuitField>,
+                hotshot_query_service::Error,
+            >,
+        >,
+    {
+        loop {
+            tokio::time::sleep(interval).await;
+
+            match fetch_stake_table().await {
+                Ok(stake_table) => {
+                    tracing::debug!("refreshed stake table");
+                    data_state.write().await.replace_stake_table(stake_table);
+                },
+                Err(err) => {
+                    tracing::warn!(
+                        "failed to refresh stake table, keeping the last known stake table: {}",
+                        err
+                    );
+                },
+            }
+        }
+    }
+}
+
+impl Drop for RefreshStakeTableTask {
+    fn drop(&mut self) {
+        if let Some(task_handle) = self.task_handle.take() {
+            task_handle.abort();
+        }
+    }
+}
+
 /**
  * create_node_validator_processing is a function that creates a node validator
  * processing environment.  This function will create a number of tasks that
@@ -101,27 +203,42 @@ impl SubmitPublicUrlsToScrapeTask {
 pub async fn create_node_validator_processing(
     config: NodeValidatorConfig,
     internal_client_message_receiver: Receiver<InternalClientMessage<Sender<ServerMessage>>>,
-    leaf_and_block_pair_receiver: Receiver<LeafAndBlock<SeqTypes>>,
+    leaf_and_block_pair_receiver: Receiver<LeafBlockPair<SeqTypes>>,
 ) -> Result<NodeValidatorAPI<Sender<Url>>, CreateNodeValidatorProcessingError> {
     let client_thread_state = ClientThreadState::<Sender<ServerMessage>>::new(
         Default::default(),
         Default::default(),
         Default::default(),
         Default::default(),
+        Default::default(),
+        Default::default(),
         ClientId::from_count(1),
-    );
+    )
+    .with_backpressure_strategy(config.backpressure_strategy)
+    .with_include_block_payload(config.include_block_payload)
+    .with_metrics(config.metrics.as_ref());
 
     let client_stake_table = surf_disco::Client::new(config.stake_table_url_base.clone());
 
-    let stake_table = get_stake_table_from_sequencer(client_stake_table)
+    let stake_table = get_stake_table_from_sequencer(client_stake_table.clone())
         .await
         .map_err(CreateNodeValidatorProcessingError::FailedToGetStakeTable)?;
 
-    let data_state = DataState::new(Default::default(), Default::default(), stake_table);
+    let mut data_state = DataState::new(Default::default(), Default::default(), stake_table)
+        .with_decide_history(config.decide_history)
+        .with_storage(config.storage.clone());
+
+    // Any state persisted by a previous run is restored, so that it does not
+    // need to be scraped and streamed all over again.
+    data_state
+        .restore_from_storage()
+        .await
+        .map_err(CreateNodeValidatorProcessingError::FailedToRestoreFromStorage)?;
 
     let data_state = Arc::new(RwLock::new(data_state));
     let client_thread_state = Arc::new(RwLock::new(client_thread_state));
     let (block_detail_sender, block_detail_receiver) = mpsc::channel(32);
+    let (decided_block_detail_sender, decided_block_detail_receiver) = mpsc::channel(32);
     let (node_identity_sender_1, node_identity_receiver_1) = mpsc::channel(32);
     let (node_identity_sender_2, node_identity_receiver_2) = mpsc::channel(32);
     let (voters_sender, voters_receiver) = mpsc::channel(32);
@@ -138,6 +255,32 @@ pub async fn create_node_validator_processing(
         block_detail_receiver,
     );
 
+    // Block payloads are only encoded and distributed when the service has
+    // been configured to include them.
+    let (block_detail_with_payload_sender, process_distribute_block_detail_with_payload_handle) =
+        if config.include_block_payload {
+            let (block_detail_with_payload_sender, block_detail_with_payload_receiver) =
+                mpsc::channel(32);
+            let process_distribute_block_detail_with_payload_handle =
+                ProcessDistributeBlockDetailWithPayloadHandlingTask::new(
+                    client_thread_state.clone(),
+                    block_detail_with_payload_receiver,
+                );
+
+            (
+                Some(block_detail_with_payload_sender),
+                Some(process_distribute_block_detail_with_payload_handle),
+            )
+        } else {
+            (None, None)
+        };
+
+    let process_distribute_decided_block_detail_handle =
+        ProcessDistributeDecidedBlockDetailHandlingTask::new(
+            client_thread_state.clone(),
+            decided_block_detail_receiver,
+        );
+
     let process_distribute_node_identity_handle = ProcessDistributeNodeIdentityHandlingTask::new(
         client_thread_state.clone(),
         node_identity_receiver_2,
@@ -151,6 +294,8 @@ pub async fn create_node_validator_processing(
         data_state.clone(),
         block_detail_sender,
         voters_sender,
+        decided_block_detail_sender,
+        block_detail_with_payload_sender,
     );
 
     let process_node_identity_stream_handle = ProcessNodeIdentityStreamTask::new(
@@ -169,24 +314,128 @@ pub async fn create_node_validator_processing(
         config.initial_node_public_base_urls.clone(),
     );
 
+    let refresh_stake_table_handle = RefreshStakeTableTask::new(
+        move || get_stake_table_from_sequencer(client_stake_table.clone()),
+        data_state.clone(),
+        config.stake_table_refresh_interval,
+    );
+
     Ok(NodeValidatorAPI {
         process_internal_client_message_handle: Some(process_internal_client_message_handle),
         process_distribute_block_detail_handle: Some(process_distribute_block_detail_handle),
+        process_distribute_block_detail_with_payload_handle,
+        process_distribute_decided_block_detail_handle: Some(
+            process_distribute_decided_block_detail_handle,
+        ),
         process_distribute_node_identity_handle: Some(process_distribute_node_identity_handle),
         process_distribute_voters_handle: Some(process_distribute_voters_handle),
         process_leaf_stream_handle: Some(process_leaf_stream_handle),
         process_node_identity_stream_handle: Some(process_node_identity_stream_handle),
         process_url_stream_handle: Some(process_url_stream_handle),
         submit_public_urls_handle: Some(submit_public_urls_handle),
+        refresh_stake_table_handle: Some(refresh_stake_table_handle),
         url_sender,
+        data_state,
     })
 }
 
 #[cfg(test)]
 mod test {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use async_lock::RwLock;
+    use hotshot_stake_table::vec_based::StakeTable;
+    use hotshot_types::{
+        light_client::{CircuitField, StateKeyPair, StateVerKey},
+        signature_key::BLSPubKey,
+        traits::{
+            signature_key::{SignatureKey, StakeTableEntryType},
+            stake_table::{SnapshotVersion, StakeTableScheme},
+        },
+    };
+    use tokio::{net::TcpListener, spawn, time::timeout};
     use url::Url;
 
-    use crate::run_standalone_service;
+    use super::RefreshStakeTableTask;
+    use crate::{
+        run_standalone_service, service::data_state::DataState, RunStandaloneServiceError,
+    };
+
+    fn create_stake_table(
+        indices: impl IntoIterator<Item = u64>,
+    ) -> StakeTable<BLSPubKey, StateVerKey, CircuitField> {
+        let indices = indices.into_iter().collect::<Vec<_>>();
+        let mut stake_table = StakeTable::new(indices.len());
+        for index in indices {
+            let (pub_key, _) = BLSPubKey::generated_from_seed_indexed([0; 32], index);
+            let state_ver_key = StateKeyPair::generate_from_seed_indexed([0; 32], index).ver_key();
+            stake_table
+                .register(pub_key, pub_key.stake_table_entry(1).stake(), state_ver_key)
+                .unwrap();
+        }
+        stake_table.advance();
+        stake_table.advance();
+        stake_table
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_refresh_stake_table_picks_up_new_validators() {
+        let data_state = Arc::new(RwLock::new(DataState::new(
+            Default::default(),
+            Default::default(),
+            create_stake_table([0]),
+        )));
+
+        // The first refresh fails, after which the validator set grows with
+        // every fetch.
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let task_fetches = fetches.clone();
+        let _task = RefreshStakeTableTask::new(
+            move || {
+                let fetch = task_fetches.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if fetch == 0 {
+                        return Err(hotshot_query_service::Error::internal(
+                            "stake table source unavailable",
+                        ));
+                    }
+
+                    Ok(create_stake_table(0..=fetch as u64))
+                }
+            },
+            data_state.clone(),
+            Duration::from_millis(100),
+        );
+
+        // The failed refresh keeps the last known stake table.
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            data_state
+                .read()
+                .await
+                .stake_table()
+                .len(SnapshotVersion::Head)
+                .unwrap(),
+            1
+        );
+
+        // The next refresh picks up the new validator set.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+        let data_state = data_state.read().await;
+        assert_eq!(
+            data_state.stake_table().len(SnapshotVersion::Head).unwrap(),
+            2
+        );
+        assert_eq!(data_state.node_identity().count(), 2);
+    }
 
     #[tokio::test(flavor = "multi_thread")]
     #[ignore]
@@ -200,7 +449,10 @@ mod test {
 
         run_standalone_service(crate::Options {
             stake_table_source_base_url: base_url.clone(),
-            leaf_stream_base_url: base_url,
+            stake_table_refresh_secs: 300,
+            leaf_stream_base_urls: vec![base_url],
+            leaf_stream_stall_timeout: Duration::from_secs(60),
+            leaf_stream_health_check_interval: Duration::from_secs(30),
             initial_node_public_base_urls: vec![
                 "https://query-1.main.net.espresso.network/"
                     .parse()
@@ -216,7 +468,63 @@ mod test {
                     .unwrap(),
             ],
             port: 9000,
+            client_backpressure_strategy: Default::default(),
+            include_block_payload: false,
+            decide_history: crate::service::data_state::MAX_HISTORY,
+            stream_max_retry_interval: Duration::from_secs(10),
+            stream_max_retry_attempts: 100,
+            storage_path: None,
+            startup_timeout: Duration::from_secs(300),
         })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_startup_timeout_with_unresponsive_upstream() {
+        // This listener accepts connections, but never responds to any of
+        // the requests that are made over them.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url: Url = format!("http://{}/v0/", listener.local_addr().unwrap())
+            .parse()
+            .unwrap();
+        let listener_handle = spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((connection, _)) = listener.accept().await {
+                connections.push(connection);
+            }
+        });
+
+        let startup_timeout = Duration::from_secs(1);
+        let result = timeout(
+            Duration::from_secs(10),
+            run_standalone_service(crate::Options {
+                stake_table_source_base_url: base_url.clone(),
+                stake_table_refresh_secs: 300,
+                leaf_stream_base_urls: vec![base_url],
+                leaf_stream_stall_timeout: Duration::from_secs(60),
+                leaf_stream_health_check_interval: Duration::from_secs(30),
+                initial_node_public_base_urls: vec![],
+                port: 0,
+                client_backpressure_strategy: Default::default(),
+                include_block_payload: false,
+                decide_history: crate::service::data_state::MAX_HISTORY,
+                stream_max_retry_interval: Duration::from_secs(10),
+                stream_max_retry_attempts: 100,
+                storage_path: None,
+                startup_timeout,
+            }),
+        )
         .await;
+
+        match result {
+            Ok(Err(RunStandaloneServiceError::StartupTimeout(timeout))) => {
+                assert_eq!(timeout, startup_timeout);
+            },
+            Ok(result) => panic!("expected startup timeout, got {:?}", result),
+            Err(_) => panic!("startup did not give up within the startup timeout"),
+        }
+
+        listener_handle.abort();
     }
 }
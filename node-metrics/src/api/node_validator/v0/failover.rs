@@ -0,0 +1,398 @@
+use std::{
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use espresso_types::SeqTypes;
+use futures::{Future, Stream, StreamExt};
+use tokio::{spawn, task::JoinHandle, time::Sleep};
+use url::Url;
+
+use super::{LeafAndBlock, LeafAndBlockPairStream, Version01};
+
+/// [AvailabilityClient] is the client used to reach the availability API of
+/// a leaf stream source.
+type AvailabilityClient = surf_disco::Client<hotshot_query_service::Error, Version01>;
+
+/// [LeafStreamSource] is a single availability API endpoint that leaves and
+/// blocks can be streamed from, along with its observed health.
+struct LeafStreamSource {
+    url: Url,
+    client: AvailabilityClient,
+    healthy: bool,
+    consecutive_failures: usize,
+}
+
+/// [FailoverClient] manages a list of availability API endpoints that leaves
+/// and blocks can be streamed from, in order of preference.
+///
+/// The stream is always created against the most preferred source that is
+/// considered healthy.  A source is considered unhealthy once its stream has
+/// failed, and healthy again once it answers a health check.  Should no
+/// source be considered healthy, the one that has failed the fewest times in
+/// a row is used, so that the sources are rotated through.
+///
+/// Clones of a [FailoverClient] share the health of the sources.
+#[derive(Clone)]
+pub struct FailoverClient {
+    sources: Arc<Mutex<Vec<LeafStreamSource>>>,
+}
+
+impl FailoverClient {
+    /// [new] creates a [FailoverClient] for the given URLs, the first of
+    /// which is the most preferred.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no URL is given.
+    pub fn new(urls: impl IntoIterator<Item = Url>) -> Self {
+        let sources: Vec<_> = urls
+            .into_iter()
+            .map(|url| LeafStreamSource {
+                client: surf_disco::Client::new(url.clone()),
+                url,
+                healthy: true,
+                consecutive_failures: 0,
+            })
+            .collect();
+        assert!(
+            !sources.is_empty(),
+            "at least one leaf stream source is required"
+        );
+
+        Self {
+            sources: Arc::new(Mutex::new(sources)),
+        }
+    }
+
+    /// [select] returns the index and the client of the source that the
+    /// stream should be created against.
+    pub fn select(&self) -> (usize, AvailabilityClient) {
+        let sources = self.sources.lock().unwrap();
+        let index = sources
+            .iter()
+            .position(|source| source.healthy)
+            .unwrap_or_else(|| {
+                (0..sources.len())
+                    .min_by_key(|index| sources[*index].consecutive_failures)
+                    .expect("there is at least one source")
+            });
+
+        tracing::debug!("streaming leaves and blocks from {}", sources[index].url);
+        (index, sources[index].client.clone())
+    }
+
+    /// [has_preferred_source_over] returns true if a source that is preferred
+    /// over the source with the given index is considered healthy.
+    pub fn has_preferred_source_over(&self, index: usize) -> bool {
+        let sources = self.sources.lock().unwrap();
+        sources[..index.min(sources.len())]
+            .iter()
+            .any(|source| source.healthy)
+    }
+
+    /// [is_healthy] returns true if the source with the given index is
+    /// considered healthy.
+    pub fn is_healthy(&self, index: usize) -> bool {
+        self.sources.lock().unwrap()[index].healthy
+    }
+
+    /// [report_success] records that the source with the given index has
+    /// delivered a leaf and block.
+    pub fn report_success(&self, index: usize) {
+        let mut sources = self.sources.lock().unwrap();
+        let source = &mut sources[index];
+        source.healthy = true;
+        source.consecutive_failures = 0;
+    }
+
+    /// [report_failure] records that the stream from the source with the
+    /// given index has failed, so that the next stream is created against
+    /// another source.
+    pub fn report_failure(&self, index: usize) {
+        let mut sources = self.sources.lock().unwrap();
+        let source = &mut sources[index];
+        source.healthy = false;
+        source.consecutive_failures += 1;
+        tracing::warn!(
+            "leaf stream source {} failed ({} times in a row), failing over",
+            source.url,
+            source.consecutive_failures
+        );
+    }
+
+    /// [report_recovered] records that the source with the given index has
+    /// answered a health check, so that it can be preferred again.
+    pub fn report_recovered(&self, index: usize) {
+        let mut sources = self.sources.lock().unwrap();
+        let source = &mut sources[index];
+        if !source.healthy {
+            tracing::info!("leaf stream source {} recovered", source.url);
+        }
+        source.healthy = true;
+    }
+
+    /// [block_height] retrieves the current block height from the sources,
+    /// in order of preference, and returns the first one that is available.
+    pub async fn block_height(&self) -> Option<u64> {
+        for (url, client) in self.clients() {
+            match client.get::<u64>("status/block-height").send().await {
+                Ok(block_height) => return Some(block_height),
+                Err(err) => {
+                    tracing::warn!("retrieve block height request to {} failed: {}", url, err);
+                },
+            }
+        }
+
+        None
+    }
+
+    /// [check_health] asks every source that is considered unhealthy for its
+    /// block height, and considers those that answer healthy again.
+    pub async fn check_health(&self) {
+        for (index, (url, client)) in self.clients().into_iter().enumerate() {
+            if self.is_healthy(index) {
+                continue;
+            }
+
+            match client.get::<u64>("status/block-height").send().await {
+                Ok(_) => self.report_recovered(index),
+                Err(err) => {
+                    tracing::debug!("leaf stream source {} is still unhealthy: {}", url, err);
+                },
+            }
+        }
+    }
+
+    /// [clients] returns the URL and client of every source, in order of
+    /// preference, so that they can be used without holding the lock.
+    fn clients(&self) -> Vec<(Url, AvailabilityClient)> {
+        self.sources
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|source| (source.url.clone(), source.client.clone()))
+            .collect()
+    }
+}
+
+/// [FailoverLeafAndBlockStream] wraps the stream of leaves and blocks from a
+/// single source of a [FailoverClient].
+///
+/// The stream ends when the underlying stream ends, when it does not produce
+/// a leaf and block within the stall timeout, or when a source that is
+/// preferred over its own is considered healthy again.  In the first two
+/// cases, the source is reported as failed.  Either way, it is left to the
+/// consumer to create the next stream, from the last processed block height,
+/// against the source selected by the [FailoverClient].
+pub struct FailoverLeafAndBlockStream<S> {
+    stream: S,
+    source_index: usize,
+    failover_client: FailoverClient,
+    stall_timeout: Duration,
+    stall: Pin<Box<Sleep>>,
+    received_any: bool,
+}
+
+impl<S> FailoverLeafAndBlockStream<S>
+where
+    S: LeafAndBlockPairStream + Unpin,
+{
+    pub fn new(
+        stream: S,
+        source_index: usize,
+        failover_client: FailoverClient,
+        stall_timeout: Duration,
+    ) -> Self {
+        Self {
+            stream,
+            source_index,
+            failover_client,
+            stall_timeout,
+            stall: Box::pin(tokio::time::sleep(stall_timeout)),
+            received_any: false,
+        }
+    }
+}
+
+impl<S> Stream for FailoverLeafAndBlockStream<S>
+where
+    S: LeafAndBlockPairStream + Unpin,
+{
+    type Item = LeafAndBlock<SeqTypes>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let self_mut = self.get_mut();
+
+        if self_mut
+            .failover_client
+            .has_preferred_source_over(self_mut.source_index)
+        {
+            tracing::info!("a preferred leaf stream source is available again, switching back");
+            return std::task::Poll::Ready(None);
+        }
+
+        match self_mut.stream.poll_next_unpin(cx) {
+            std::task::Poll::Ready(Some(leaf_and_block)) => {
+                if !self_mut.received_any {
+                    self_mut.received_any = true;
+                    self_mut
+                        .failover_client
+                        .report_success(self_mut.source_index);
+                }
+                let deadline = tokio::time::Instant::now() + self_mut.stall_timeout;
+                self_mut.stall.as_mut().reset(deadline);
+                std::task::Poll::Ready(Some(leaf_and_block))
+            },
+
+            std::task::Poll::Ready(None) => {
+                self_mut
+                    .failover_client
+                    .report_failure(self_mut.source_index);
+                std::task::Poll::Ready(None)
+            },
+
+            std::task::Poll::Pending => match self_mut.stall.as_mut().poll(cx) {
+                std::task::Poll::Ready(()) => {
+                    tracing::warn!(
+                        "leaf stream stalled for {:?}, failing over",
+                        self_mut.stall_timeout
+                    );
+                    self_mut
+                        .failover_client
+                        .report_failure(self_mut.source_index);
+                    std::task::Poll::Ready(None)
+                },
+                std::task::Poll::Pending => std::task::Poll::Pending,
+            },
+        }
+    }
+}
+
+/// [LeafStreamSourceHealthCheckTask] is a task that periodically checks the
+/// health of the sources of a [FailoverClient] that are considered
+/// unhealthy, so that a recovered source can be preferred again.
+pub struct LeafStreamSourceHealthCheckTask {
+    pub task_handle: Option<JoinHandle<()>>,
+}
+
+impl LeafStreamSourceHealthCheckTask {
+    /// [new] creates a new [LeafStreamSourceHealthCheckTask] that checks the
+    /// sources of the given [FailoverClient] every `interval`.
+    ///
+    /// Calling this function will create an async task that will start
+    /// processing immediately.  The task's handle will be stored in the
+    /// returned state.
+    pub fn new(failover_client: FailoverClient, interval: Duration) -> Self {
+        let task_handle = spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                failover_client.check_health().await;
+            }
+        });
+
+        Self {
+            task_handle: Some(task_handle),
+        }
+    }
+}
+
+/// [Drop] implementation for [LeafStreamSourceHealthCheckTask] that will
+/// cancel the task if it hasn't already been completed.
+impl Drop for LeafStreamSourceHealthCheckTask {
+    fn drop(&mut self) {
+        if let Some(task_handle) = self.task_handle.take() {
+            task_handle.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use espresso_types::SeqTypes;
+    use futures::StreamExt;
+    use tokio::time::timeout;
+
+    use super::{FailoverClient, FailoverLeafAndBlockStream};
+    use crate::api::node_validator::v0::LeafAndBlock;
+
+    fn failover_client() -> FailoverClient {
+        FailoverClient::new([
+            "http://primary.example.com/v0/".parse().unwrap(),
+            "http://secondary.example.com/v0/".parse().unwrap(),
+        ])
+    }
+
+    #[test]
+    fn test_failover_client_prefers_recovered_source() {
+        let failover_client = failover_client();
+        assert_eq!(failover_client.select().0, 0);
+
+        // The primary fails, so we fail over to the secondary.
+        failover_client.report_failure(0);
+        assert_eq!(failover_client.select().0, 1);
+        assert!(!failover_client.has_preferred_source_over(1));
+
+        // Once both have failed, they are rotated through.
+        failover_client.report_failure(1);
+        failover_client.report_failure(0);
+        assert_eq!(failover_client.select().0, 1);
+        failover_client.report_success(1);
+        assert_eq!(failover_client.select().0, 1);
+
+        // Once the primary recovers, it is preferred again.
+        failover_client.report_recovered(0);
+        assert!(failover_client.has_preferred_source_over(1));
+        assert_eq!(failover_client.select().0, 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_failover_stream_ends_when_stalled() {
+        let failover_client = failover_client();
+        let (index, _) = failover_client.select();
+
+        let mut stream = FailoverLeafAndBlockStream::new(
+            futures::stream::pending::<LeafAndBlock<SeqTypes>>(),
+            index,
+            failover_client.clone(),
+            Duration::from_millis(50),
+        );
+
+        let next = timeout(Duration::from_secs(1), stream.next())
+            .await
+            .unwrap();
+        assert!(next.is_none());
+        assert!(!failover_client.is_healthy(0));
+        assert_eq!(failover_client.select().0, 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_failover_stream_switches_back_to_recovered_source() {
+        let failover_client = failover_client();
+        failover_client.report_failure(0);
+        let (index, _) = failover_client.select();
+        assert_eq!(index, 1);
+
+        let mut stream = FailoverLeafAndBlockStream::new(
+            futures::stream::pending::<LeafAndBlock<SeqTypes>>(),
+            index,
+            failover_client.clone(),
+            Duration::from_secs(60),
+        );
+
+        // The secondary is not failed for giving way to the primary.
+        failover_client.report_recovered(0);
+        let next = timeout(Duration::from_secs(1), stream.next())
+            .await
+            .unwrap();
+        assert!(next.is_none());
+        assert!(failover_client.is_healthy(1));
+        assert_eq!(failover_client.select().0, 0);
+    }
+}
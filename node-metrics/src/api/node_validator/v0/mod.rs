@@ -1,34 +1,46 @@
 pub mod create_node_validator_api;
+pub mod failover;
 
-use std::{fmt, future::Future, io::BufRead, pin::Pin, str::FromStr, time::Duration};
+use std::{
+    borrow::Cow, fmt, future::Future, io::BufRead, pin::Pin, str::FromStr, sync::Arc,
+    time::Duration,
+};
 
-use espresso_types::{BackoffParams, SeqTypes};
+use async_lock::RwLock;
+use espresso_types::SeqTypes;
 use futures::{
-    channel::mpsc::{self, SendError, Sender},
+    channel::{
+        mpsc::{self, SendError, Sender},
+        oneshot,
+    },
     future::{BoxFuture, Either},
     pin_mut, FutureExt, Sink, SinkExt, Stream, StreamExt,
 };
 use hotshot_query_service::{
     availability::{BlockQueryData, Leaf1QueryData},
+    metrics::PrometheusMetrics,
     types::HeightIndexed,
 };
 use hotshot_stake_table::vec_based::StakeTable;
 use hotshot_types::{
     light_client::{CircuitField, StateVerKey},
     signature_key::BLSPubKey,
-    traits::{signature_key::StakeTableEntryType, stake_table::StakeTableScheme},
+    traits::{
+        node_implementation::NodeType, signature_key::StakeTableEntryType,
+        stake_table::StakeTableScheme,
+    },
     PeerConfig,
 };
 use prometheus_parse::{Sample, Scrape};
 use serde::{Deserialize, Serialize};
-use tide_disco::{api::ApiError, socket::Connection, Api};
+use tide_disco::{api::ApiError, method::ReadState, socket::Connection, Api, Error as _};
 use tokio::{spawn, task::JoinHandle};
 use url::Url;
 use vbs::version::{StaticVersion, StaticVersionType, Version};
 
 use crate::service::{
     client_message::{ClientMessage, InternalClientMessage},
-    data_state::{LocationDetails, NodeIdentity},
+    data_state::{DataState, LocationDetails, NodeIdentity},
     server_message::ServerMessage,
 };
 
@@ -165,12 +177,29 @@ pub trait StateClientMessageSender<K> {
     fn sender(&self) -> Sender<InternalClientMessage<K>>;
 }
 
+/// [StatePrometheusMetrics] allows for the retrieval of the [PrometheusMetrics]
+/// registry that is exposed via the metrics endpoint.
+pub trait StatePrometheusMetrics {
+    fn metrics(&self) -> &PrometheusMetrics;
+}
+
+/// [StateDataState] allows for the retrieval of the [DataState] maintained
+/// by the service, so that it can be queried directly.
+pub trait StateDataState {
+    fn data_state(&self) -> &Arc<RwLock<DataState>>;
+}
+
 #[derive(Debug)]
 pub enum EndpointError {}
 
 pub fn define_api<State>() -> Result<Api<State, Error, Version01>, DefineApiError>
 where
-    State: StateClientMessageSender<Sender<ServerMessage>> + Send + Sync + 'static,
+    State: StateClientMessageSender<Sender<ServerMessage>> + ReadState + Send + Sync + 'static,
+    <State as ReadState>::State: StateClientMessageSender<Sender<ServerMessage>>
+        + StatePrometheusMetrics
+        + StateDataState
+        + Send
+        + Sync,
 {
     let mut api = load_api::<State, Version01>(include_str!("./node_validator.toml"))?;
 
@@ -292,6 +321,48 @@ where
             .boxed()
         },
     )?;
+
+    api.metrics("metrics", |_req, state| {
+        async move { Ok(Cow::Borrowed(state.metrics())) }.boxed()
+    })?;
+
+    api.get("decides", |req, state| {
+        async move {
+            let window = req
+                .opt_integer_param("window")
+                .map_err(Error::from_request_error)?;
+            Ok(state.data_state().read().await.recent_decides(window))
+        }
+        .boxed()
+    })?;
+
+    api.get("snapshot", |_req, state| {
+        async move {
+            // The snapshot is requested from the same processing task that
+            // serves the stream, so that both reflect the same state.
+            let (network_snapshot_sender, network_snapshot_receiver) = oneshot::channel();
+            if let Err(err) = state
+                .sender()
+                .send(InternalClientMessage::RequestNetworkSnapshot(
+                    network_snapshot_sender,
+                ))
+                .await
+            {
+                return Err(Error::catch_all(
+                    tide_disco::StatusCode::SERVICE_UNAVAILABLE,
+                    format!("client message sender is closed: {}", err),
+                ));
+            }
+
+            network_snapshot_receiver.await.map_err(|_| {
+                Error::catch_all(
+                    tide_disco::StatusCode::SERVICE_UNAVAILABLE,
+                    "network snapshot request was dropped".to_string(),
+                )
+            })
+        }
+        .boxed()
+    })?;
     Ok(api)
 }
 
@@ -440,10 +511,55 @@ pub struct SurfDiscoAvailabilityAPIStream<'a, T> {
 
     last_received_block: u64,
 
-    backoff_params: BackoffParams,
+    retry_params: StreamRetryParams,
+}
+
+/// [DEFAULT_MAX_STREAM_RETRY_ATTEMPTS] is the default number of consecutive
+/// attempts to (re-)establish a stream before the failure is considered
+/// permanent.
+pub const DEFAULT_MAX_STREAM_RETRY_ATTEMPTS: usize = 100;
+
+/// [DEFAULT_MAX_STREAM_RETRY_INTERVAL] is the default longest time to wait
+/// between two attempts to (re-)establish a stream.
+pub const DEFAULT_MAX_STREAM_RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// [StreamRetryParams] controls how the streams of the Availability API are
+/// re-established after they fail.
+///
+/// Attempts are spaced out with a capped exponential backoff: the first
+/// retry waits 10ms, and every further retry waits ten times as long, up to
+/// `max_retry_interval`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StreamRetryParams {
+    /// The longest time to wait between two attempts.
+    pub max_retry_interval: Duration,
+
+    /// The number of consecutive failed attempts after which a failure is
+    /// considered permanent.
+    pub max_attempts: usize,
+}
+
+impl Default for StreamRetryParams {
+    fn default() -> Self {
+        Self {
+            max_retry_interval: DEFAULT_MAX_STREAM_RETRY_INTERVAL,
+            max_attempts: DEFAULT_MAX_STREAM_RETRY_ATTEMPTS,
+        }
+    }
 }
 
-const MAX_STREAM_RECONNECT_ATTEMPTS: usize = 100;
+impl StreamRetryParams {
+    /// [retry_delay] returns the time to wait after the given number of
+    /// consecutive failed attempts.
+    pub fn retry_delay(&self, failed_attempts: usize) -> Duration {
+        let exponent = u32::try_from(failed_attempts).unwrap_or(u32::MAX);
+        10u64
+            .checked_pow(exponent)
+            .map_or(self.max_retry_interval, |delay| {
+                Duration::from_millis(delay).min(self.max_retry_interval)
+            })
+    }
+}
 
 /// [SurfDiscoAvailabilityAPIPathResolver] is a trait that allows for the
 /// specification of a sub path to the base URL that will resolve in a
@@ -523,7 +639,7 @@ impl SurfDiscoAvailabilityAPIStream<'_, Leaf1QueryData<SeqTypes>> {
             client,
             connection: None,
             last_received_block: starting_block,
-            backoff_params: BackoffParams::default(),
+            retry_params: StreamRetryParams::default(),
             connection_future: None,
         }
     }
@@ -538,7 +654,7 @@ impl SurfDiscoAvailabilityAPIStream<'_, BlockQueryData<SeqTypes>> {
             client,
             connection: None,
             last_received_block: starting_block,
-            backoff_params: BackoffParams::default(),
+            retry_params: StreamRetryParams::default(),
             connection_future: None,
         }
     }
@@ -549,6 +665,13 @@ where
     T: serde::de::DeserializeOwned,
     Self: SurfDiscoAvailabilityAPIPathResolver + UpdateBlockHeightForEntry<T>,
 {
+    /// [with_retry_params] sets how the connection is re-established after
+    /// it fails.  Once `max_attempts` consecutive attempts have failed, the
+    /// stream ends.
+    pub fn with_retry_params(mut self, retry_params: StreamRetryParams) -> Self {
+        self.retry_params = retry_params;
+        self
+    }
 }
 
 impl<T> Stream for SurfDiscoAvailabilityAPIStream<'_, T>
@@ -633,11 +756,12 @@ where
                 },
 
                 std::task::Poll::Ready(Err(err)) => {
-                    tracing::debug!("encountered error retrieving connection: {}", err);
+                    // The connection future has exhausted its attempts, so
+                    // we end the stream, and leave it to the consumer to
+                    // decide whether to start over.
+                    tracing::error!("unable to retrieve connection, ending stream: {}", err);
                     self_mut.connection_future = None;
-                    // We need to reschedule ourselves in order to make progress
-                    cx.waker().wake_by_ref();
-                    return std::task::Poll::Pending;
+                    return std::task::Poll::Ready(None);
                 },
             }
         }
@@ -646,15 +770,14 @@ where
         // We're not connected yet. So let's try to connect.
         let path = self_mut.resolve_path_for_height(self_mut.last_received_block);
         let client = self_mut.client.clone();
-        let backoff_params = self_mut.backoff_params;
+        let retry_params = self_mut.retry_params;
         self_mut.connection_future.replace(
             async move {
                 let path = path;
                 let client = client;
-                let backoff_params = backoff_params;
-                let mut delay = Duration::from_millis(100);
+                let retry_params = retry_params;
 
-                for attempt in 0..MAX_STREAM_RECONNECT_ATTEMPTS {
+                for attempt in 0..retry_params.max_attempts {
                     match client.socket(&path).subscribe().await {
                         Ok(connection) => {
                             tracing::debug!(
@@ -670,21 +793,16 @@ where
                                 attempt,
                                 err
                             );
-                            delay = backoff_params.backoff(delay);
-                            tokio::time::sleep(delay).await;
+                            tokio::time::sleep(retry_params.retry_delay(attempt + 1)).await;
                             continue;
                         },
                     }
                 }
 
-                tracing::warn!(
-                    "unable to retrieve connection after {} attempts",
-                    MAX_STREAM_RECONNECT_ATTEMPTS
-                );
-                panic!(
+                Err(hotshot_query_service::Error::internal(format!(
                     "unable to retrieve connection after {} attempts",
-                    MAX_STREAM_RECONNECT_ATTEMPTS
-                );
+                    retry_params.max_attempts
+                )))
             }
             .boxed(),
         );
@@ -717,6 +835,68 @@ impl<S> AvailabilityAPIBlockStream for S where S: Stream<Item = BlockQueryData<S
 /// This acts as a simple type declaration for quick reference.
 pub type LeafAndBlock<T> = (Leaf1QueryData<T>, BlockQueryData<T>);
 
+/// [LeafBlockPair] is a [Leaf1QueryData] and the [BlockQueryData] for the
+/// same block height.  Unlike [LeafAndBlock], a [LeafBlockPair] can only be
+/// created from a leaf and a block whose heights agree.
+#[derive(Clone, Debug)]
+pub struct LeafBlockPair<T: NodeType> {
+    pub height: u64,
+    pub leaf: Leaf1QueryData<T>,
+    pub block: BlockQueryData<T>,
+}
+
+/// [LeafBlockHeightMismatch] is returned when attempting to pair a leaf and a
+/// block that are for different block heights.
+#[derive(Debug, PartialEq, Eq)]
+pub struct LeafBlockHeightMismatch {
+    pub leaf_height: u64,
+    pub block_height: u64,
+}
+
+impl fmt::Display for LeafBlockHeightMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "leaf height {} does not match block height {}",
+            self.leaf_height, self.block_height
+        )
+    }
+}
+
+impl std::error::Error for LeafBlockHeightMismatch {}
+
+impl<T: NodeType> LeafBlockPair<T> {
+    /// [try_new] pairs the given leaf and block, provided that they are for
+    /// the same block height.
+    pub fn try_new(
+        leaf: Leaf1QueryData<T>,
+        block: BlockQueryData<T>,
+    ) -> Result<Self, LeafBlockHeightMismatch> {
+        let leaf_height = leaf.leaf().height();
+        let block_height = block.height();
+        if leaf_height != block_height {
+            return Err(LeafBlockHeightMismatch {
+                leaf_height,
+                block_height,
+            });
+        }
+
+        Ok(Self {
+            height: leaf_height,
+            leaf,
+            block,
+        })
+    }
+}
+
+impl<T: NodeType> TryFrom<LeafAndBlock<T>> for LeafBlockPair<T> {
+    type Error = LeafBlockHeightMismatch;
+
+    fn try_from((leaf, block): LeafAndBlock<T>) -> Result<Self, Self::Error> {
+        Self::try_new(leaf, block)
+    }
+}
+
 /// [LeafAndBlockPairStream] is a trait that represents a stream of [Leaf1QueryData]s
 /// and [BlockQueryData]s.  This acts as a simple type declaration for quick
 /// reference.
@@ -726,10 +906,190 @@ pub trait LeafAndBlockPairStream: Stream<Item = LeafAndBlock<SeqTypes>> {}
 /// Stream that matches the produced a pair of [Leaf1QueryData], and [BlockQueryData].
 impl<S> LeafAndBlockPairStream for S where S: Stream<Item = LeafAndBlock<SeqTypes>> {}
 
+/// [DEFAULT_MAX_CONSECUTIVE_STREAM_RESTARTS] is the default number of times
+/// in a row that [RestartingLeafAndBlockStream] will attempt to restart one
+/// of its underlying streams without receiving an entry from it.
+pub const DEFAULT_MAX_CONSECUTIVE_STREAM_RESTARTS: usize = 10;
+
+/// [RestartingLeafAndBlockStream] pairs a stream of [Leaf1QueryData]s with a
+/// stream of [BlockQueryData]s, similar to [StreamExt::zip].
+///
+/// Unlike [StreamExt::zip], the end of one of the underlying streams does not
+/// end the combined stream.  Instead, the stream that ended is replaced with
+/// a new one, created by the corresponding restart function, while the other
+/// stream is left untouched.  The restart functions are given the height of
+/// the last entry received from the stream being replaced, or the starting
+/// height if no entry has been received yet.
+///
+/// The combined stream only ends once a single underlying stream has been
+/// restarted more than the configured number of times in a row without
+/// producing an entry.
+pub struct RestartingLeafAndBlockStream<L, B, RL, RB> {
+    leaf_stream: L,
+    block_stream: B,
+    restart_leaf_stream: RL,
+    restart_block_stream: RB,
+    pending_leaf: Option<Leaf1QueryData<SeqTypes>>,
+    pending_block: Option<BlockQueryData<SeqTypes>>,
+    last_leaf_height: u64,
+    last_block_height: u64,
+    leaf_restarts: usize,
+    block_restarts: usize,
+    max_consecutive_restarts: usize,
+}
+
+impl<L, B, RL, RB> RestartingLeafAndBlockStream<L, B, RL, RB>
+where
+    L: AvailabilityAPILeafStream + Unpin,
+    B: AvailabilityAPIBlockStream + Unpin,
+    RL: FnMut(u64) -> L + Unpin,
+    RB: FnMut(u64) -> B + Unpin,
+{
+    pub fn new(
+        leaf_stream: L,
+        block_stream: B,
+        starting_block_height: u64,
+        restart_leaf_stream: RL,
+        restart_block_stream: RB,
+    ) -> Self {
+        Self {
+            leaf_stream,
+            block_stream,
+            restart_leaf_stream,
+            restart_block_stream,
+            pending_leaf: None,
+            pending_block: None,
+            last_leaf_height: starting_block_height,
+            last_block_height: starting_block_height,
+            leaf_restarts: 0,
+            block_restarts: 0,
+            max_consecutive_restarts: DEFAULT_MAX_CONSECUTIVE_STREAM_RESTARTS,
+        }
+    }
+
+    /// [with_max_consecutive_restarts] sets the number of times in a row
+    /// that either underlying stream may be restarted without producing an
+    /// entry before the combined stream gives up and ends.
+    pub fn with_max_consecutive_restarts(mut self, max_consecutive_restarts: usize) -> Self {
+        self.max_consecutive_restarts = max_consecutive_restarts;
+        self
+    }
+
+    /// [restart_leaf_stream] replaces the ended leaf stream with a new one.
+    /// It returns false if the leaf stream has already been restarted too
+    /// many times in a row.
+    fn restart_leaf_stream(&mut self) -> bool {
+        if self.leaf_restarts >= self.max_consecutive_restarts {
+            tracing::error!(
+                "leaf stream ended after {} consecutive restarts, giving up",
+                self.leaf_restarts
+            );
+            return false;
+        }
+
+        self.leaf_restarts += 1;
+        tracing::warn!(
+            "leaf stream ended prematurely, restarting from block height {} (attempt {})",
+            self.last_leaf_height,
+            self.leaf_restarts
+        );
+        self.leaf_stream = (self.restart_leaf_stream)(self.last_leaf_height);
+        true
+    }
+
+    /// [restart_block_stream] replaces the ended block stream with a new one.
+    /// It returns false if the block stream has already been restarted too
+    /// many times in a row.
+    fn restart_block_stream(&mut self) -> bool {
+        if self.block_restarts >= self.max_consecutive_restarts {
+            tracing::error!(
+                "block stream ended after {} consecutive restarts, giving up",
+                self.block_restarts
+            );
+            return false;
+        }
+
+        self.block_restarts += 1;
+        tracing::warn!(
+            "block stream ended prematurely, restarting from block height {} (attempt {})",
+            self.last_block_height,
+            self.block_restarts
+        );
+        self.block_stream = (self.restart_block_stream)(self.last_block_height);
+        true
+    }
+}
+
+impl<L, B, RL, RB> Stream for RestartingLeafAndBlockStream<L, B, RL, RB>
+where
+    L: AvailabilityAPILeafStream + Unpin,
+    B: AvailabilityAPIBlockStream + Unpin,
+    RL: FnMut(u64) -> L + Unpin,
+    RB: FnMut(u64) -> B + Unpin,
+{
+    type Item = LeafAndBlock<SeqTypes>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let self_mut = self.get_mut();
+
+        loop {
+            if self_mut.pending_leaf.is_none() {
+                match self_mut.leaf_stream.poll_next_unpin(cx) {
+                    std::task::Poll::Ready(Some(leaf)) => {
+                        self_mut.last_leaf_height = leaf.leaf().height();
+                        self_mut.leaf_restarts = 0;
+                        self_mut.pending_leaf = Some(leaf);
+                    },
+                    std::task::Poll::Ready(None) => {
+                        if !self_mut.restart_leaf_stream() {
+                            return std::task::Poll::Ready(None);
+                        }
+                        continue;
+                    },
+                    std::task::Poll::Pending => {},
+                }
+            }
+
+            if self_mut.pending_block.is_none() {
+                match self_mut.block_stream.poll_next_unpin(cx) {
+                    std::task::Poll::Ready(Some(block)) => {
+                        self_mut.last_block_height = block.height();
+                        self_mut.block_restarts = 0;
+                        self_mut.pending_block = Some(block);
+                    },
+                    std::task::Poll::Ready(None) => {
+                        if !self_mut.restart_block_stream() {
+                            return std::task::Poll::Ready(None);
+                        }
+                        continue;
+                    },
+                    std::task::Poll::Pending => {},
+                }
+            }
+
+            // Any stream that has not produced an entry yet has registered
+            // our waker, so we can wait to be polled again.
+            return match (self_mut.pending_leaf.take(), self_mut.pending_block.take()) {
+                (Some(leaf), Some(block)) => std::task::Poll::Ready(Some((leaf, block))),
+                (leaf, block) => {
+                    self_mut.pending_leaf = leaf;
+                    self_mut.pending_block = block;
+                    std::task::Poll::Pending
+                },
+            };
+        }
+    }
+}
+
 /// [BridgeLeafAndBlockStreamToSenderTask] is a task that produce a stream of
 /// pairs of [Leaf1QueryData]s and [BlockQueryData]s from the Hotshot Query Service. It
 /// will attempt to retrieve the [Leaf1QueryData]s and [BlockQueryData]s from the Hotshot
-/// Query Service and then send them to the [Sink] provided.
+/// Query Service and then send them to the [Sink] provided as
+/// [LeafBlockPair]s.  Any leaf and block that are not for the same block
+/// height are logged and skipped, rather than being forwarded.
 pub struct BridgeLeafAndBlockStreamToSenderTask {
     pub task_handle: Option<JoinHandle<()>>,
 }
@@ -744,7 +1104,7 @@ impl BridgeLeafAndBlockStreamToSenderTask {
     pub fn new<R, K>(item_stream: R, item_sender: K) -> Self
     where
         R: LeafAndBlockPairStream + Send + Unpin + 'static,
-        K: Sink<LeafAndBlock<SeqTypes>, Error = SendError> + Clone + Send + Unpin + 'static,
+        K: Sink<LeafBlockPair<SeqTypes>, Error = SendError> + Clone + Send + Unpin + 'static,
     {
         // let future = Self::process_consume_leaf_stream(leaf_stream_retriever, leaf_sender);
         let task_handle = spawn(Self::bridge_stream(item_stream, item_sender));
@@ -754,10 +1114,95 @@ impl BridgeLeafAndBlockStreamToSenderTask {
         }
     }
 
+    /// [new_reconnecting] creates a new [BridgeLeafAndBlockStreamToSenderTask]
+    /// that re-establishes its stream whenever it ends.
+    ///
+    /// The stream is created by `create_stream`, which is given the height
+    /// of the last block that has been forwarded, or the starting height if
+    /// none has been forwarded yet.  Attempts to re-establish the stream are
+    /// spaced out according to `retry_params`.  Once `max_attempts`
+    /// consecutive attempts have failed to forward anything, the failure is
+    /// logged as an error, but the task keeps retrying, so that the service
+    /// can keep serving the last known state.  The task only ends once the
+    /// [Sink] is closed.
+    pub fn new_reconnecting<F, R, K>(
+        starting_block_height: u64,
+        create_stream: F,
+        item_sender: K,
+        retry_params: StreamRetryParams,
+    ) -> Self
+    where
+        F: FnMut(u64) -> R + Send + 'static,
+        R: LeafAndBlockPairStream + Send + Unpin + 'static,
+        K: Sink<LeafBlockPair<SeqTypes>, Error = SendError> + Clone + Send + Unpin + 'static,
+    {
+        let task_handle = spawn(Self::bridge_reconnecting_stream(
+            starting_block_height,
+            create_stream,
+            item_sender,
+            retry_params,
+        ));
+
+        Self {
+            task_handle: Some(task_handle),
+        }
+    }
+
+    async fn bridge_reconnecting_stream<F, R, K>(
+        starting_block_height: u64,
+        mut create_stream: F,
+        item_sender: K,
+        retry_params: StreamRetryParams,
+    ) where
+        F: FnMut(u64) -> R,
+        R: LeafAndBlockPairStream + Unpin,
+        K: Sink<LeafBlockPair<SeqTypes>, Error = SendError> + Clone + Unpin + 'static,
+    {
+        let mut last_block_height = starting_block_height;
+        let mut failed_attempts = 0;
+
+        loop {
+            let stream = create_stream(last_block_height);
+            let previous_block_height = last_block_height;
+            let sender_closed = Self::process_consume_leaf_stream::<R, K>(
+                stream,
+                item_sender.clone(),
+                &mut last_block_height,
+            )
+            .await;
+            if sender_closed {
+                return;
+            }
+
+            if last_block_height != previous_block_height {
+                // The stream made progress before it ended, so this is a
+                // fresh failure.
+                failed_attempts = 0;
+            }
+            failed_attempts += 1;
+
+            if failed_attempts >= retry_params.max_attempts {
+                tracing::error!(
+                    "leaf and block stream ended {} times in a row without progress, will keep retrying from block height {}",
+                    failed_attempts,
+                    last_block_height
+                );
+            } else {
+                tracing::warn!(
+                    "leaf and block stream ended, re-establishing from block height {} (attempt {})",
+                    last_block_height,
+                    failed_attempts
+                );
+            }
+
+            tokio::time::sleep(retry_params.retry_delay(failed_attempts)).await;
+        }
+    }
+
     async fn bridge_stream<R, K>(item_stream: R, item_sender: K)
     where
         R: LeafAndBlockPairStream + Unpin,
-        K: Sink<LeafAndBlock<SeqTypes>, Error = SendError> + Clone + Unpin + 'static,
+        K: Sink<LeafBlockPair<SeqTypes>, Error = SendError> + Clone + Unpin + 'static,
     {
         // We want to try and ensure that we are connected to the HotShot Query
         // Service, and are consuming leaves.
@@ -772,7 +1217,13 @@ impl BridgeLeafAndBlockStreamToSenderTask {
         //   the leave again.
 
         // Consume the leaves of a stream
-        Self::process_consume_leaf_stream::<R, K>(item_stream, item_sender.clone()).await;
+        let mut last_block_height = 0;
+        Self::process_consume_leaf_stream::<R, K>(
+            item_stream,
+            item_sender.clone(),
+            &mut last_block_height,
+        )
+        .await;
         tracing::warn!("leaf stream ended, will attempt to re-acquire leaf stream");
     }
 
@@ -781,10 +1232,17 @@ impl BridgeLeafAndBlockStreamToSenderTask {
     /// Hotshot Query Service and then send them to the [Sink] provided.  If the
     /// [Sink] is closed, or if the Stream ends prematurely, then the function
     /// will return.
-    async fn process_consume_leaf_stream<R, K>(item_stream: R, item_sender: K)
+    ///
+    /// The height of every block that is forwarded is recorded in
+    /// `last_block_height`.  Returns true if the [Sink] has been closed.
+    async fn process_consume_leaf_stream<R, K>(
+        item_stream: R,
+        item_sender: K,
+        last_block_height: &mut u64,
+    ) -> bool
     where
         R: LeafAndBlockPairStream + Unpin,
-        K: Sink<LeafAndBlock<SeqTypes>, Error = SendError> + Clone + Unpin + 'static,
+        K: Sink<LeafBlockPair<SeqTypes>, Error = SendError> + Clone + Unpin + 'static,
     {
         let mut leaf_sender = item_sender;
         let mut leaves_stream = item_stream;
@@ -798,12 +1256,24 @@ impl BridgeLeafAndBlockStreamToSenderTask {
                 break;
             };
 
+            let leaf = match LeafBlockPair::try_from(leaf) {
+                Ok(leaf) => leaf,
+                Err(err) => {
+                    tracing::error!("skipping misaligned leaf and block: {}", err);
+                    continue;
+                },
+            };
+
+            let height = leaf.height;
             let leaf_send_result = leaf_sender.send(leaf).await;
             if let Err(err) = leaf_send_result {
                 tracing::info!("leaf sender closed: {}", err);
-                break;
+                return true;
             }
+            *last_block_height = height;
         }
+
+        false
     }
 }
 
@@ -1127,7 +1597,33 @@ impl Drop for ProcessNodeIdentityUrlStreamTask {
 
 #[cfg(test)]
 mod tests {
-    use std::io::{BufRead, BufReader};
+    use std::{
+        io::{BufRead, BufReader},
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use espresso_types::{
+        v0_1::RewardMerkleTree, v0_99::ChainConfig, BlockMerkleTree, FeeMerkleTree, NodeState,
+        ValidatedState,
+    };
+    use futures::{channel::mpsc, StreamExt};
+    use hotshot_example_types::node_types::TestVersions;
+    use hotshot_query_service::{
+        availability::{BlockQueryData, Leaf1QueryData},
+        testing::mocks::MockVersions,
+        types::HeightIndexed,
+    };
+    use hotshot_types::{
+        data::{Leaf, Leaf2, QuorumProposal, ViewNumber},
+        traits::node_implementation::ConsensusTime,
+    };
+    use tokio::time::timeout;
+
+    use super::{
+        BridgeLeafAndBlockStreamToSenderTask, LeafBlockHeightMismatch, LeafBlockPair,
+        RestartingLeafAndBlockStream, StreamRetryParams,
+    };
 
     fn example_prometheus_output() -> &'static str {
         include_str!("example_prometheus_metrics_output.txt")
@@ -1256,4 +1752,261 @@ mod tests {
         assert_eq!(node_identity_location.country(), &Some("US".to_string()));
         assert_eq!(node_identity_location.coords, Some((-40.7128, -74.0060)));
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bridge_skips_misaligned_leaf_and_block() {
+        let validated_state = ValidatedState {
+            block_merkle_tree: BlockMerkleTree::new(32),
+            fee_merkle_tree: FeeMerkleTree::new(32),
+            reward_merkle_tree: RewardMerkleTree::new(32),
+            chain_config: ChainConfig::default().into(),
+        };
+        let instance_state = NodeState::mock();
+
+        let genesis_leaf = Leaf2::genesis::<TestVersions>(&validated_state, &instance_state).await;
+        let genesis_block =
+            BlockQueryData::genesis::<MockVersions>(&validated_state, &instance_state).await;
+
+        let make_leaf = |height: u64| {
+            let justify_qc = genesis_leaf.justify_qc().to_qc();
+            let mut header = genesis_block.header().clone();
+            *header.height_mut() = height;
+
+            let leaf = Leaf::from_quorum_proposal(&QuorumProposal {
+                block_header: header,
+                view_number: ViewNumber::new(height),
+                justify_qc: justify_qc.clone(),
+                upgrade_certificate: None,
+                proposal_certificate: None,
+            });
+
+            Leaf1QueryData::new(leaf, justify_qc)
+        };
+        let make_block = |height: u64| {
+            let mut header = genesis_block.header().clone();
+            *header.height_mut() = height;
+            BlockQueryData::new(header, genesis_block.payload().clone())
+        };
+
+        assert_eq!(
+            LeafBlockPair::try_new(make_leaf(1), make_block(2)).unwrap_err(),
+            LeafBlockHeightMismatch {
+                leaf_height: 1,
+                block_height: 2,
+            }
+        );
+
+        // The pairs at heights 2 and 4 are misaligned and should be dropped
+        // by the bridge.
+        let items = vec![
+            (make_leaf(1), make_block(1)),
+            (make_leaf(2), make_block(3)),
+            (make_leaf(3), make_block(3)),
+            (make_leaf(4), make_block(5)),
+            (make_leaf(5), make_block(5)),
+        ];
+
+        let (sender, receiver) = mpsc::channel(10);
+        let mut bridge =
+            BridgeLeafAndBlockStreamToSenderTask::new(futures::stream::iter(items), sender);
+
+        let received = timeout(Duration::from_secs(1), receiver.collect::<Vec<_>>())
+            .await
+            .unwrap();
+        let heights: Vec<_> = received
+            .iter()
+            .map(|pair| (pair.height, pair.block.height()))
+            .collect();
+        assert_eq!(heights, vec![(1, 1), (3, 3), (5, 5)]);
+
+        if let Some(task_handle) = bridge.task_handle.take() {
+            assert_eq!(task_handle.await.ok(), Some(()));
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_restarting_leaf_and_block_stream_restarts_ended_stream() {
+        let validated_state = ValidatedState {
+            block_merkle_tree: BlockMerkleTree::new(32),
+            fee_merkle_tree: FeeMerkleTree::new(32),
+            reward_merkle_tree: RewardMerkleTree::new(32),
+            chain_config: ChainConfig::default().into(),
+        };
+        let instance_state = NodeState::mock();
+
+        let genesis_leaf = Leaf2::genesis::<TestVersions>(&validated_state, &instance_state).await;
+        let genesis_block =
+            BlockQueryData::genesis::<MockVersions>(&validated_state, &instance_state).await;
+
+        let make_leaf = |height: u64| {
+            let justify_qc = genesis_leaf.justify_qc().to_qc();
+            let mut header = genesis_block.header().clone();
+            *header.height_mut() = height;
+
+            let leaf = Leaf::from_quorum_proposal(&QuorumProposal {
+                block_header: header,
+                view_number: ViewNumber::new(height),
+                justify_qc: justify_qc.clone(),
+                upgrade_certificate: None,
+                proposal_certificate: None,
+            });
+
+            Leaf1QueryData::new(leaf, justify_qc)
+        };
+        let make_block = |height: u64| {
+            let mut header = genesis_block.header().clone();
+            *header.height_mut() = height;
+            BlockQueryData::new(header, genesis_block.payload().clone())
+        };
+
+        // The leaf stream is healthy, but the block stream ends after the
+        // second block.
+        let leaves: Vec<_> = (1..=5).map(make_leaf).collect();
+        let blocks: Vec<_> = (1..=2).map(make_block).collect();
+        let remaining_blocks: Vec<_> = (3..=5).map(make_block).collect();
+
+        let leaf_restarts = Arc::new(Mutex::new(Vec::new()));
+        let block_restarts = Arc::new(Mutex::new(Vec::new()));
+
+        let stream = RestartingLeafAndBlockStream::new(
+            futures::stream::iter(leaves),
+            futures::stream::iter(blocks),
+            0,
+            {
+                let leaf_restarts = leaf_restarts.clone();
+                move |height| {
+                    leaf_restarts.lock().unwrap().push(height);
+                    futures::stream::iter(Vec::new())
+                }
+            },
+            {
+                let block_restarts = block_restarts.clone();
+                move |height| {
+                    block_restarts.lock().unwrap().push(height);
+                    futures::stream::iter(remaining_blocks.clone())
+                }
+            },
+        )
+        .with_max_consecutive_restarts(1);
+
+        let (sender, receiver) = mpsc::channel(10);
+        let mut bridge = BridgeLeafAndBlockStreamToSenderTask::new(stream, sender);
+
+        let received = timeout(Duration::from_secs(1), receiver.collect::<Vec<_>>())
+            .await
+            .unwrap();
+        let heights: Vec<_> = received
+            .iter()
+            .map(|pair| (pair.height, pair.block.height()))
+            .collect();
+        assert_eq!(heights, vec![(1, 1), (2, 2), (3, 3), (4, 4), (5, 5)]);
+
+        // Only the block stream ended prematurely, and it was restarted from
+        // the last block it had produced.  The leaf stream was only restarted
+        // once it ran out of leaves, at which point the stream gave up.
+        assert_eq!(*block_restarts.lock().unwrap(), vec![2]);
+        assert_eq!(*leaf_restarts.lock().unwrap(), vec![5]);
+
+        if let Some(task_handle) = bridge.task_handle.take() {
+            assert_eq!(task_handle.await.ok(), Some(()));
+        }
+    }
+
+    #[test]
+    fn test_stream_retry_delay_is_capped() {
+        let retry_params = StreamRetryParams {
+            max_retry_interval: Duration::from_secs(1),
+            max_attempts: 3,
+        };
+        assert_eq!(retry_params.retry_delay(1), Duration::from_millis(10));
+        assert_eq!(retry_params.retry_delay(2), Duration::from_millis(100));
+        assert_eq!(retry_params.retry_delay(3), Duration::from_secs(1));
+        assert_eq!(retry_params.retry_delay(4), Duration::from_secs(1));
+        assert_eq!(retry_params.retry_delay(usize::MAX), Duration::from_secs(1));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bridge_re_establishes_ended_stream() {
+        let validated_state = ValidatedState {
+            block_merkle_tree: BlockMerkleTree::new(32),
+            fee_merkle_tree: FeeMerkleTree::new(32),
+            reward_merkle_tree: RewardMerkleTree::new(32),
+            chain_config: ChainConfig::default().into(),
+        };
+        let instance_state = NodeState::mock();
+
+        let genesis_leaf = Leaf2::genesis::<TestVersions>(&validated_state, &instance_state).await;
+        let genesis_block =
+            BlockQueryData::genesis::<MockVersions>(&validated_state, &instance_state).await;
+
+        let make_pair = |height: u64| {
+            let justify_qc = genesis_leaf.justify_qc().to_qc();
+            let mut header = genesis_block.header().clone();
+            *header.height_mut() = height;
+
+            let leaf = Leaf::from_quorum_proposal(&QuorumProposal {
+                block_header: header.clone(),
+                view_number: ViewNumber::new(height),
+                justify_qc: justify_qc.clone(),
+                upgrade_certificate: None,
+                proposal_certificate: None,
+            });
+
+            (
+                Leaf1QueryData::new(leaf, justify_qc),
+                BlockQueryData::new(header, genesis_block.payload().clone()),
+            )
+        };
+
+        // The first connection ends after the second block, the second one
+        // ends without producing anything, and the third one delivers the
+        // rest.  Every later connection ends immediately.
+        let first_items: Vec<_> = (1..=2).map(make_pair).collect();
+        let remaining_items: Vec<_> = (3..=5).map(make_pair).collect();
+
+        let starting_heights = Arc::new(Mutex::new(Vec::new()));
+        let create_stream = {
+            let starting_heights = starting_heights.clone();
+            move |height| {
+                let mut starting_heights = starting_heights.lock().unwrap();
+                let items = match starting_heights.len() {
+                    0 => first_items.clone(),
+                    2 => remaining_items.clone(),
+                    _ => Vec::new(),
+                };
+                starting_heights.push(height);
+                futures::stream::iter(items)
+            }
+        };
+
+        let (sender, receiver) = mpsc::channel(10);
+        let mut bridge = BridgeLeafAndBlockStreamToSenderTask::new_reconnecting(
+            0,
+            create_stream,
+            sender,
+            StreamRetryParams {
+                max_retry_interval: Duration::from_millis(10),
+                max_attempts: 2,
+            },
+        );
+
+        let received = timeout(Duration::from_secs(1), receiver.take(5).collect::<Vec<_>>())
+            .await
+            .unwrap();
+        let heights: Vec<_> = received.iter().map(|pair| pair.height).collect();
+        assert_eq!(heights, vec![1, 2, 3, 4, 5]);
+
+        // Every connection was re-established from the last block that was
+        // forwarded, and the bridge keeps retrying once the connections stop
+        // making progress.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let starting_heights = starting_heights.lock().unwrap().clone();
+        assert_eq!(starting_heights[..4], [0, 2, 2, 5]);
+        assert!(starting_heights.len() > 4);
+        assert!(starting_heights[4..].iter().all(|height| *height == 5));
+
+        let task_handle = bridge.task_handle.take().unwrap();
+        assert!(!task_handle.is_finished());
+        task_handle.abort();
+    }
 }
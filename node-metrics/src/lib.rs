@@ -54,6 +54,11 @@
 //!        - Block Time
 //!        - Block Space Used
 //!
+//! The node identity information and the most recent N blocks can be
+//! persisted through the [service::storage::Storage] trait, for instance in
+//! a SQLite database, so that they survive a restart of the service.  The
+//! remaining state is derived from these as the service runs.
+//!
 //! ## Data Streams
 //!
 //! In order for clients to be able to receive the information from the node
@@ -99,23 +104,45 @@
 pub mod api;
 pub mod service;
 
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
 use api::node_validator::v0::SurfDiscoAvailabilityAPIStream;
+use async_lock::RwLock;
+use async_trait::async_trait;
 use clap::Parser;
+use espresso_types::parse_duration;
 use futures::{
     channel::mpsc::{self, Sender},
-    StreamExt,
+    future::BoxFuture,
 };
-use service::data_state::MAX_VOTERS_HISTORY;
-use tide_disco::App;
-use tokio::spawn;
+use hotshot_query_service::metrics::PrometheusMetrics;
+use service::data_state::{DataState, MAX_HISTORY, MAX_VOTERS_HISTORY};
+use tide_disco::{app::AppError, method::ReadState, App};
+use tokio::{spawn, task::JoinError, time::timeout};
 use url::Url;
 
 use crate::{
     api::node_validator::v0::{
-        create_node_validator_api::{create_node_validator_processing, NodeValidatorConfig},
-        BridgeLeafAndBlockStreamToSenderTask, StateClientMessageSender, STATIC_VER_0_1,
+        create_node_validator_api::{
+            create_node_validator_processing, CreateNodeValidatorProcessingError, NodeValidatorAPI,
+            NodeValidatorConfig,
+        },
+        failover::{FailoverClient, FailoverLeafAndBlockStream, LeafStreamSourceHealthCheckTask},
+        BridgeLeafAndBlockStreamToSenderTask, DefineApiError, LeafAndBlockPairStream,
+        RestartingLeafAndBlockStream, StateClientMessageSender, StateDataState,
+        StatePrometheusMetrics, StreamRetryParams, Version01, DEFAULT_MAX_STREAM_RETRY_ATTEMPTS,
+        STATIC_VER_0_1,
+    },
+    service::{
+        client_message::InternalClientMessage,
+        client_state::BackpressureStrategy,
+        server_message::ServerMessage,
+        storage::{InMemoryStorage, SqliteStorage, Storage, StorageError},
     },
-    service::{client_message::InternalClientMessage, server_message::ServerMessage},
 };
 
 /// Options represents the configuration options that are available for running
@@ -134,16 +161,57 @@ pub struct Options {
     #[clap(long, env = "ESPRESSO_NODE_VALIDATOR_STAKE_TABLE_SOURCE_BASE_URL")]
     stake_table_source_base_url: Url,
 
-    /// leaf_stream_base_url is the base URL for the availability API endpoint
-    /// that is capable of providing a stream of leaf data.
+    /// stake_table_refresh_secs is the number of seconds to wait between
+    /// re-fetching the stake table from the stake table source.  If a
+    /// re-fetch fails, the last known stake table remains in use.
+    #[clap(
+        long,
+        env = "ESPRESSO_NODE_VALIDATOR_STAKE_TABLE_REFRESH_SECS",
+        default_value = "300"
+    )]
+    stake_table_refresh_secs: u64,
+
+    /// leaf_stream_base_urls is a list of base URLs for the availability API
+    /// endpoints that are capable of providing a stream of leaf data, in
+    /// order of preference.  Should the stream from one of them fail, or
+    /// stall, the service fails over to the next one, and switches back once
+    /// a preferred endpoint has recovered.
     ///
-    /// This endpoint is expected to point to the version root path of the
+    /// These endpoints are expected to point to the version root path of the
     /// URL.
     /// Example:
     ///   - https://query.cappuccino.testnet.espresso.network/v0/
     ///
-    #[clap(long, env = "ESPRESSO_NODE_VALIDATOR_LEAF_STREAM_SOURCE_BASE_URL")]
-    leaf_stream_base_url: Url,
+    #[clap(
+        long,
+        alias = "leaf-stream-base-url",
+        env = "ESPRESSO_NODE_VALIDATOR_LEAF_STREAM_SOURCE_BASE_URL",
+        value_delimiter = ',',
+        required = true
+    )]
+    leaf_stream_base_urls: Vec<Url>,
+
+    /// leaf_stream_stall_timeout is the longest time to wait for the next
+    /// leaf and block from the current leaf stream source before failing
+    /// over to the next one.
+    #[clap(
+        long,
+        env = "ESPRESSO_NODE_VALIDATOR_LEAF_STREAM_STALL_TIMEOUT",
+        default_value = "1m",
+        value_parser = parse_duration
+    )]
+    leaf_stream_stall_timeout: Duration,
+
+    /// leaf_stream_health_check_interval is the time between two checks of
+    /// the leaf stream sources that have failed, so that a recovered source
+    /// can be preferred again.
+    #[clap(
+        long,
+        env = "ESPRESSO_NODE_VALIDATOR_LEAF_STREAM_HEALTH_CHECK_INTERVAL",
+        default_value = "30s",
+        value_parser = parse_duration
+    )]
+    leaf_stream_health_check_interval: Duration,
 
     /// initial_node_public_base_urls is a list of URLs that are the initial
     /// public base URLs of the nodes that are in the network.  These can be
@@ -172,6 +240,82 @@ pub struct Options {
         default_value = "9000"
     )]
     port: u16,
+
+    /// client_backpressure_strategy determines how the service handles a
+    /// connected client that is unable to keep up with the updates being
+    /// broadcast to it.
+    ///
+    /// Possible values:
+    ///   - drop-slow: skip updates for the lagging client
+    ///   - disconnect: disconnect the lagging client
+    ///   - block: wait for the lagging client before continuing
+    #[clap(
+        long,
+        value_enum,
+        env = "ESPRESSO_NODE_VALIDATOR_CLIENT_BACKPRESSURE_STRATEGY",
+        default_value = "drop-slow"
+    )]
+    client_backpressure_strategy: BackpressureStrategy,
+
+    /// include_block_payload determines whether clients are able to opt into
+    /// receiving the encoded payload bytes of each block alongside the
+    /// latest block updates.  By default, only the block details are sent to
+    /// clients in order to conserve bandwidth.
+    #[clap(long, env = "ESPRESSO_NODE_VALIDATOR_INCLUDE_BLOCK_PAYLOAD", action)]
+    include_block_payload: bool,
+
+    /// decide_history is the number of recent decide events that are
+    /// retained, and that can be queried via the decides endpoint.  This also
+    /// bounds the window that can be requested from that endpoint.
+    #[clap(
+        long,
+        env = "ESPRESSO_NODE_VALIDATOR_DECIDE_HISTORY",
+        default_value_t = MAX_HISTORY
+    )]
+    decide_history: usize,
+
+    /// stream_max_retry_interval is the longest time to wait between two
+    /// attempts to re-establish the leaf and block streams from the
+    /// availability API.  Attempts back off exponentially up to this
+    /// interval.
+    #[clap(
+        long,
+        env = "ESPRESSO_NODE_VALIDATOR_STREAM_MAX_RETRY_INTERVAL",
+        default_value = "10s",
+        value_parser = parse_duration
+    )]
+    stream_max_retry_interval: Duration,
+
+    /// stream_max_retry_attempts is the number of consecutive failed
+    /// attempts to re-establish the leaf and block streams after which the
+    /// failure is reported as an error.  The service keeps retrying even
+    /// after this, so that it can keep serving the last known state.
+    #[clap(
+        long,
+        env = "ESPRESSO_NODE_VALIDATOR_STREAM_MAX_RETRY_ATTEMPTS",
+        default_value_t = DEFAULT_MAX_STREAM_RETRY_ATTEMPTS
+    )]
+    stream_max_retry_attempts: usize,
+
+    /// storage_path is the path of the SQLite database that the node
+    /// identities and the most recent blocks are persisted in.  When it is
+    /// provided, the service restores that state on startup, and only
+    /// replays the leaves it needs to rebuild the voters.  Otherwise, the
+    /// state is only kept in memory, and is lost on restart.
+    #[clap(long, env = "ESPRESSO_NODE_VALIDATOR_STORAGE_PATH")]
+    storage_path: Option<PathBuf>,
+
+    /// startup_timeout is the maximum amount of time that the service will
+    /// wait for its startup sequence to complete.  If the upstream sources
+    /// are unresponsive, the service will exit with an error once this
+    /// timeout has elapsed, rather than hanging indefinitely.
+    #[clap(
+        long,
+        env = "ESPRESSO_NODE_VALIDATOR_STARTUP_TIMEOUT",
+        default_value = "5m",
+        value_parser = parse_duration
+    )]
+    startup_timeout: Duration,
 }
 
 impl Options {
@@ -179,8 +323,20 @@ impl Options {
         &self.stake_table_source_base_url
     }
 
-    fn leaf_stream_base_url(&self) -> &Url {
-        &self.leaf_stream_base_url
+    fn stake_table_refresh_interval(&self) -> Duration {
+        Duration::from_secs(self.stake_table_refresh_secs)
+    }
+
+    fn leaf_stream_base_urls(&self) -> &[Url] {
+        &self.leaf_stream_base_urls
+    }
+
+    fn leaf_stream_stall_timeout(&self) -> Duration {
+        self.leaf_stream_stall_timeout
+    }
+
+    fn leaf_stream_health_check_interval(&self) -> Duration {
+        self.leaf_stream_health_check_interval
     }
 
     fn initial_node_public_base_urls(&self) -> &[Url] {
@@ -190,12 +346,41 @@ impl Options {
     fn port(&self) -> u16 {
         self.port
     }
+
+    fn client_backpressure_strategy(&self) -> BackpressureStrategy {
+        self.client_backpressure_strategy
+    }
+
+    fn include_block_payload(&self) -> bool {
+        self.include_block_payload
+    }
+
+    fn decide_history(&self) -> usize {
+        self.decide_history
+    }
+
+    fn stream_retry_params(&self) -> StreamRetryParams {
+        StreamRetryParams {
+            max_retry_interval: self.stream_max_retry_interval,
+            max_attempts: self.stream_max_retry_attempts,
+        }
+    }
+
+    fn storage_path(&self) -> Option<&Path> {
+        self.storage_path.as_deref()
+    }
+
+    fn startup_timeout(&self) -> Duration {
+        self.startup_timeout
+    }
 }
 
 /// MainState represents the State of the application this is available to
 /// tide_disco.
 struct MainState {
     internal_client_message_sender: Sender<InternalClientMessage<Sender<ServerMessage>>>,
+    metrics: PrometheusMetrics,
+    data_state: Arc<RwLock<DataState>>,
 }
 
 impl StateClientMessageSender<Sender<ServerMessage>> for MainState {
@@ -204,44 +389,259 @@ impl StateClientMessageSender<Sender<ServerMessage>> for MainState {
     }
 }
 
-/// Run the service by itself.
+impl StatePrometheusMetrics for MainState {
+    fn metrics(&self) -> &PrometheusMetrics {
+        &self.metrics
+    }
+}
+
+impl StateDataState for MainState {
+    fn data_state(&self) -> &Arc<RwLock<DataState>> {
+        &self.data_state
+    }
+}
+
+#[async_trait]
+impl ReadState for MainState {
+    type State = MainState;
+
+    async fn read<T>(
+        &self,
+        op: impl Send + for<'a> FnOnce(&'a Self::State) -> BoxFuture<'a, T> + 'async_trait,
+    ) -> T {
+        op(self).await
+    }
+}
+
+/// [RunStandaloneServiceError] represents the scope of errors that can be
+/// returned from the [run_standalone_service] function.
+#[derive(Debug)]
+pub enum RunStandaloneServiceError {
+    /// The startup sequence did not complete within the configured
+    /// startup timeout.
+    StartupTimeout(Duration),
+
+    /// The current block height could not be retrieved from the leaf stream
+    /// source.
+    UnableToRetrieveBlockHeight,
+
+    /// The storage could not be opened, or read from.
+    Storage(StorageError),
+
+    /// The node validator processing tasks could not be created.
+    CreateNodeValidatorProcessing(CreateNodeValidatorProcessingError),
+
+    /// The node validator API could not be defined.
+    DefineApi(DefineApiError),
+
+    /// The node validator API could not be registered with the app.
+    RegisterModule(AppError),
+
+    /// The app stopped serving requests.
+    Serve(std::io::Error),
+
+    /// The task serving the app panicked, or was cancelled.
+    ServeTask(JoinError),
+}
+
+impl std::fmt::Display for RunStandaloneServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunStandaloneServiceError::StartupTimeout(timeout) => {
+                write!(f, "startup did not complete within {:?}", timeout)
+            },
+            RunStandaloneServiceError::UnableToRetrieveBlockHeight => {
+                write!(f, "unable to retrieve block height")
+            },
+            RunStandaloneServiceError::Storage(err) => {
+                write!(f, "error accessing storage: {}", err)
+            },
+            RunStandaloneServiceError::CreateNodeValidatorProcessing(err) => {
+                write!(f, "error creating node validator processing: {:?}", err)
+            },
+            RunStandaloneServiceError::DefineApi(err) => {
+                write!(f, "error defining node validator api: {:?}", err)
+            },
+            RunStandaloneServiceError::RegisterModule(err) => {
+                write!(f, "error registering node validator api: {:?}", err)
+            },
+            RunStandaloneServiceError::Serve(err) => {
+                write!(f, "error serving node validator api: {}", err)
+            },
+            RunStandaloneServiceError::ServeTask(err) => {
+                write!(f, "node validator api task failed: {}", err)
+            },
+        }
+    }
+}
+
+impl std::error::Error for RunStandaloneServiceError {}
+
+impl From<StorageError> for RunStandaloneServiceError {
+    fn from(err: StorageError) -> Self {
+        RunStandaloneServiceError::Storage(err)
+    }
+}
+
+impl From<CreateNodeValidatorProcessingError> for RunStandaloneServiceError {
+    fn from(err: CreateNodeValidatorProcessingError) -> Self {
+        RunStandaloneServiceError::CreateNodeValidatorProcessing(err)
+    }
+}
+
+impl From<DefineApiError> for RunStandaloneServiceError {
+    fn from(err: DefineApiError) -> Self {
+        RunStandaloneServiceError::DefineApi(err)
+    }
+}
+
+impl From<AppError> for RunStandaloneServiceError {
+    fn from(err: AppError) -> Self {
+        RunStandaloneServiceError::RegisterModule(err)
+    }
+}
+
+/// [SHUTDOWN_DRAIN_TIMEOUT] is how long the service waits, on shutdown, for
+/// the node validator processing to handle the client messages that are
+/// still queued.
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// [shutdown_signal] resolves once the process has been asked to shut down,
+/// either via ctrl-c, or via SIGTERM on unix.
 ///
-/// This function will run the node validator as its own service.  It has some
-/// options that allow it to be configured in order for it to operate
-/// effectively.
-pub async fn run_standalone_service(options: Options) {
-    let (internal_client_message_sender, internal_client_message_receiver) = mpsc::channel(32);
-    let state = MainState {
-        internal_client_message_sender,
+/// Should a signal handler fail to install, the service keeps running, as it
+/// is still able to be killed.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            tracing::error!("unable to listen for ctrl-c: {}", err);
+            futures::future::pending::<()>().await;
+        }
     };
 
-    let mut app: App<_, api::node_validator::v0::Error> = App::with_state(state);
-    let node_validator_api =
-        api::node_validator::v0::define_api().expect("error defining node validator api");
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            },
+            Err(err) => {
+                tracing::error!("unable to listen for SIGTERM: {}", err);
+                futures::future::pending::<()>().await;
+            },
+        }
+    };
 
-    match app.register_module("node-validator", node_validator_api) {
-        Ok(_) => {},
-        Err(err) => {
-            panic!("error registering node validator api: {:?}", err);
-        },
+    #[cfg(not(unix))]
+    let terminate = futures::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
     }
+}
 
+/// [shutdown_node_validator_processing] stops the node validator processing
+/// once the service has been asked to shut down.
+///
+/// The internal client message channel is closed so that no new requests are
+/// accepted, and the leaf and block stream is stopped.  The requests that are
+/// still queued are then given [SHUTDOWN_DRAIN_TIMEOUT] to be processed,
+/// before the remaining tasks are cancelled.
+async fn shutdown_node_validator_processing(
+    mut internal_client_message_sender: Sender<InternalClientMessage<Sender<ServerMessage>>>,
+    process_consume_leaves: BridgeLeafAndBlockStreamToSenderTask,
+    mut node_validator_task_state: NodeValidatorAPI<Sender<Url>>,
+) {
+    internal_client_message_sender.close_channel();
+    drop(process_consume_leaves);
+
+    let Some(mut task_handle) = node_validator_task_state
+        .process_internal_client_message_handle
+        .as_mut()
+        .and_then(|task| task.task_handle.take())
+    else {
+        return;
+    };
+
+    if timeout(SHUTDOWN_DRAIN_TIMEOUT, &mut task_handle)
+        .await
+        .is_err()
+    {
+        tracing::warn!(
+            "node validator processing did not drain within {:?}",
+            SHUTDOWN_DRAIN_TIMEOUT
+        );
+        task_handle.abort();
+    }
+}
+
+/// [create_leaf_and_block_stream] creates the stream of leaves and blocks
+/// from the availability API, starting after the given block height.
+fn create_leaf_and_block_stream(
+    client: surf_disco::Client<hotshot_query_service::Error, Version01>,
+    block_height: u64,
+    retry_params: StreamRetryParams,
+) -> impl LeafAndBlockPairStream + Send + Unpin + 'static {
+    let leaf_stream = SurfDiscoAvailabilityAPIStream::new_leaf_stream(client.clone(), block_height)
+        .with_retry_params(retry_params);
+    let block_stream =
+        SurfDiscoAvailabilityAPIStream::new_block_stream(client.clone(), block_height)
+            .with_retry_params(retry_params);
+
+    // If either stream ends, only that stream is restarted, so that a single
+    // unhealthy stream does not stop the whole pipeline.
+    RestartingLeafAndBlockStream::new(
+        leaf_stream,
+        block_stream,
+        block_height,
+        {
+            let client = client.clone();
+            move |height| {
+                SurfDiscoAvailabilityAPIStream::new_leaf_stream(client.clone(), height)
+                    .with_retry_params(retry_params)
+            }
+        },
+        move |height| {
+            SurfDiscoAvailabilityAPIStream::new_block_stream(client.clone(), height)
+                .with_retry_params(retry_params)
+        },
+    )
+}
+
+/// [start_node_validator_processing] performs the startup sequence of the
+/// standalone service.  It opens the storage, retrieves the current block
+/// height, creates the leaf and block streams, and creates the node validator
+/// processing tasks.
+async fn start_node_validator_processing(
+    options: &Options,
+    internal_client_message_receiver: mpsc::Receiver<InternalClientMessage<Sender<ServerMessage>>>,
+    metrics: PrometheusMetrics,
+) -> Result<
+    (
+        BridgeLeafAndBlockStreamToSenderTask,
+        LeafStreamSourceHealthCheckTask,
+        NodeValidatorAPI<Sender<Url>>,
+    ),
+    RunStandaloneServiceError,
+> {
     let (leaf_and_block_pair_sender, leaf_and_block_pair_receiver) = mpsc::channel(10);
 
-    let client = surf_disco::Client::new(options.leaf_stream_base_url().clone());
+    let storage: Arc<dyn Storage> = match options.storage_path() {
+        Some(storage_path) => Arc::new(SqliteStorage::connect(storage_path).await?),
+        None => Arc::new(InMemoryStorage),
+    };
+
+    let failover_client = FailoverClient::new(options.leaf_stream_base_urls().iter().cloned());
 
     // Let's get the current starting block height.
     let block_height = {
         // Retry up to 4 times to get the block height
         let mut i = 0;
         let block_height: Option<u64> = loop {
-            let block_height_result = client.get("status/block-height").send().await;
-            match block_height_result {
-                Ok(block_height) => break Some(block_height),
-                Err(err) => {
-                    tracing::warn!("retrieve block height request failed: {}", err);
-                },
-            };
+            if let Some(block_height) = failover_client.block_height().await {
+                break Some(block_height);
+            }
 
             // Sleep so we're not spamming too much with back to back requests.
             // The sleep time delay will be 10ms, then 100ms, then 1s, then 10s.
@@ -253,48 +653,136 @@ pub async fn run_standalone_service(options: Options) {
             }
         };
 
-        if let Some(block_height) = block_height {
-            // We want to make sure that we have at least MAX_VOTERS_HISTORY blocks of
-            // history that we are pulling
-            block_height.saturating_sub(MAX_VOTERS_HISTORY as u64 + 1)
-        } else {
-            panic!("unable to retrieve block height");
-        }
+        let Some(block_height) = block_height else {
+            return Err(RunStandaloneServiceError::UnableToRetrieveBlockHeight);
+        };
+
+        // We want to make sure that we have at least MAX_VOTERS_HISTORY blocks of
+        // history that we are pulling.  This holds even when blocks have been
+        // persisted by a previous run, as the voters are not persisted along
+        // with them.  The blocks that have already been processed are skipped
+        // when they are replayed, and only contribute their voters.
+        block_height.saturating_sub(MAX_VOTERS_HISTORY as u64 + 1)
     };
 
     tracing::debug!("creating stream starting at block height: {}", block_height);
 
-    let leaf_stream = SurfDiscoAvailabilityAPIStream::new_leaf_stream(client.clone(), block_height);
-    let block_stream = SurfDiscoAvailabilityAPIStream::new_block_stream(client, block_height);
-
-    let zipped_stream = leaf_stream.zip(block_stream);
-
-    let _process_consume_leaves =
-        BridgeLeafAndBlockStreamToSenderTask::new(zipped_stream, leaf_and_block_pair_sender);
+    // Should the leaf and block streams give up, or stall, both are
+    // re-established from the last block that has been forwarded, against
+    // the preferred leaf stream source that is healthy.
+    let retry_params = options.stream_retry_params();
+    let stall_timeout = options.leaf_stream_stall_timeout();
+    let process_consume_leaves = BridgeLeafAndBlockStreamToSenderTask::new_reconnecting(
+        block_height,
+        {
+            let failover_client = failover_client.clone();
+            move |height| {
+                let (source_index, client) = failover_client.select();
+                FailoverLeafAndBlockStream::new(
+                    create_leaf_and_block_stream(client, height, retry_params),
+                    source_index,
+                    failover_client.clone(),
+                    stall_timeout,
+                )
+            }
+        },
+        leaf_and_block_pair_sender,
+        retry_params,
+    );
+    let check_leaf_stream_sources = LeafStreamSourceHealthCheckTask::new(
+        failover_client,
+        options.leaf_stream_health_check_interval(),
+    );
 
-    let _node_validator_task_state = match create_node_validator_processing(
+    let node_validator_task_state = create_node_validator_processing(
         NodeValidatorConfig {
             stake_table_url_base: options.stake_table_source_base_url().clone(),
+            stake_table_refresh_interval: options.stake_table_refresh_interval(),
             initial_node_public_base_urls: options.initial_node_public_base_urls().to_vec(),
+            backpressure_strategy: options.client_backpressure_strategy(),
+            include_block_payload: options.include_block_payload(),
+            decide_history: options.decide_history(),
+            storage,
+            metrics: Box::new(metrics),
         },
         internal_client_message_receiver,
         leaf_and_block_pair_receiver,
     )
+    .await?;
+
+    Ok((
+        process_consume_leaves,
+        check_leaf_stream_sources,
+        node_validator_task_state,
+    ))
+}
+
+/// Run the service by itself.
+///
+/// This function will run the node validator as its own service.  It has some
+/// options that allow it to be configured in order for it to operate
+/// effectively.
+///
+/// If the startup sequence does not complete within the configured startup
+/// timeout, this function will return a
+/// [RunStandaloneServiceError::StartupTimeout] error.
+///
+/// The service runs until it receives ctrl-c, or SIGTERM on unix, at which
+/// point it shuts down gracefully and returns.
+pub async fn run_standalone_service(options: Options) -> Result<(), RunStandaloneServiceError> {
+    let (internal_client_message_sender, internal_client_message_receiver) = mpsc::channel(32);
+    let metrics = PrometheusMetrics::default();
+
+    let startup_timeout = options.startup_timeout();
+    let (process_consume_leaves, _check_leaf_stream_sources, node_validator_task_state) = timeout(
+        startup_timeout,
+        start_node_validator_processing(
+            &options,
+            internal_client_message_receiver,
+            metrics.clone(),
+        ),
+    )
     .await
-    {
-        Ok(node_validator_task_state) => node_validator_task_state,
+    .map_err(|_| RunStandaloneServiceError::StartupTimeout(startup_timeout))??;
 
-        Err(err) => {
-            panic!("error defining node validator api: {:?}", err);
-        },
+    // The data state is only available once the startup sequence has
+    // completed, so the app is created afterwards.
+    let state = MainState {
+        internal_client_message_sender: internal_client_message_sender.clone(),
+        metrics,
+        data_state: node_validator_task_state.data_state.clone(),
     };
 
+    let mut app: App<_, api::node_validator::v0::Error> = App::with_state(state);
+    let node_validator_api = api::node_validator::v0::define_api()?;
+    app.register_module("node-validator", node_validator_api)?;
+
     let port = options.port();
+    let mut app_serve_handle =
+        spawn(async move { app.serve(format!("0.0.0.0:{}", port), STATIC_VER_0_1).await });
+
     // We would like to wait until being signaled
-    let app_serve_handle = spawn(async move {
-        let app_serve_result = app.serve(format!("0.0.0.0:{}", port), STATIC_VER_0_1).await;
-        tracing::info!("app serve result: {:?}", app_serve_result);
-    });
+    tokio::select! {
+        app_serve_result = &mut app_serve_handle => {
+            tracing::info!("app serve result: {:?}", app_serve_result);
+            return match app_serve_result {
+                Ok(Ok(())) => Ok(()),
+                Ok(Err(err)) => Err(RunStandaloneServiceError::Serve(err)),
+                Err(err) => Err(RunStandaloneServiceError::ServeTask(err)),
+            };
+        },
+        _ = shutdown_signal() => {
+            tracing::info!("shutting down node validator service");
+        },
+    }
+
+    shutdown_node_validator_processing(
+        internal_client_message_sender,
+        process_consume_leaves,
+        node_validator_task_state,
+    )
+    .await;
+    app_serve_handle.abort();
 
-    let _ = app_serve_handle.await;
+    Ok(())
 }
@@ -6,5 +6,8 @@ use node_metrics::{run_standalone_service, Options};
 async fn main() {
     initialize_logging();
 
-    run_standalone_service(Options::parse()).await;
+    if let Err(err) = run_standalone_service(Options::parse()).await {
+        tracing::error!("node validator service failed: {}", err);
+        std::process::exit(1);
+    }
 }
@@ -1,6 +1,58 @@
+use futures::channel::oneshot;
+use hotshot_types::signature_key::BLSPubKey;
 use serde::{Deserialize, Serialize};
 
-use super::client_id::ClientId;
+use super::{client_id::ClientId, server_message::NetworkSnapshot};
+
+/// [NodeIdentitySubscription] determines which node identity updates a
+/// client receives.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum NodeIdentitySubscription {
+    /// Updates for every node in the network.
+    All,
+    /// Updates for the node with the given public key only.
+    Node(BLSPubKey),
+}
+
+/// [SubscriptionSpec] describes the complete set of streams that a client
+/// wishes to receive.  Unlike the individual Subscribe messages, a
+/// [SubscriptionSpec] replaces the subscriptions of the client, so it can be
+/// used to narrow or change them in the middle of a session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SubscriptionSpec {
+    pub latest_block: bool,
+    pub node_identity: Option<NodeIdentitySubscription>,
+    pub voters: bool,
+    pub decided: bool,
+}
+
+impl SubscriptionSpec {
+    /// [blocks_only] subscribes to the latest block stream, and nothing else.
+    pub fn blocks_only() -> Self {
+        Self {
+            latest_block: true,
+            ..Default::default()
+        }
+    }
+
+    /// [node_identity_only] subscribes to the node identity updates of every
+    /// node, and nothing else.
+    pub fn node_identity_only() -> Self {
+        Self {
+            node_identity: Some(NodeIdentitySubscription::All),
+            ..Default::default()
+        }
+    }
+
+    /// [node_only] subscribes to the node identity updates of the node with
+    /// the given public key, and nothing else.
+    pub fn node_only(public_key: BLSPubKey) -> Self {
+        Self {
+            node_identity: Some(NodeIdentitySubscription::Node(public_key)),
+            ..Default::default()
+        }
+    }
+}
 
 /// [ClientMessage] represents the messages that the client can send to the
 /// server for a request.
@@ -10,11 +62,15 @@ pub enum ClientMessage {
     SubscribeLatestBlock,
     SubscribeNodeIdentity,
     SubscribeVoters,
+    SubscribeDecided,
+    SubscribeLatestBlockWithPayload,
 
     RequestBlocksSnapshot,
     RequestNodeIdentitySnapshot,
     RequestHistogramSnapshot,
     RequestVotersSnapshot,
+
+    UpdateSubscription(SubscriptionSpec),
 }
 
 /// InternalClientMessage represents the message requests that the client can
@@ -27,13 +83,24 @@ pub enum InternalClientMessage<K> {
     Disconnected(ClientId),
 
     Request(ClientId, ClientMessage),
+    UpdateSubscription(ClientId, SubscriptionSpec),
+
+    /// RequestNetworkSnapshot is a one-shot request for a [NetworkSnapshot]
+    /// that does not belong to a connected client.  The snapshot is sent
+    /// back on the given channel.
+    RequestNetworkSnapshot(oneshot::Sender<NetworkSnapshot>),
 }
 
 impl ClientMessage {
     /// [to_internal_with_client_id] converts the [ClientMessage] into an
     /// [InternalClientMessage] with the given [ClientId].
     pub fn to_internal_with_client_id<K>(&self, client_id: ClientId) -> InternalClientMessage<K> {
-        InternalClientMessage::Request(client_id, *self)
+        match self {
+            ClientMessage::UpdateSubscription(subscription) => {
+                InternalClientMessage::UpdateSubscription(client_id, *subscription)
+            },
+            _ => InternalClientMessage::Request(client_id, *self),
+        }
     }
 }
 
@@ -42,6 +109,7 @@ mod tests {
     use std::iter::zip;
 
     use futures::channel::mpsc::Sender;
+    use hotshot_types::traits::signature_key::SignatureKey;
 
     use super::{InternalClientMessage, *};
     use crate::service::server_message::ServerMessage;
@@ -57,6 +125,11 @@ mod tests {
                     Self::Request(lhs_client_id, lhs_message),
                     Self::Request(rhs_client_id, rhs_message),
                 ) => lhs_client_id == rhs_client_id && lhs_message == rhs_message,
+                (
+                    Self::UpdateSubscription(lhs_client_id, lhs_subscription),
+                    Self::UpdateSubscription(rhs_client_id, rhs_subscription),
+                ) => lhs_client_id == rhs_client_id && lhs_subscription == rhs_subscription,
+                (Self::RequestNetworkSnapshot(_), Self::RequestNetworkSnapshot(_)) => true,
                 _ => false,
             }
         }
@@ -68,9 +141,13 @@ mod tests {
             ClientMessage::SubscribeLatestBlock,
             ClientMessage::SubscribeNodeIdentity,
             ClientMessage::SubscribeVoters,
+            ClientMessage::SubscribeDecided,
+            ClientMessage::SubscribeLatestBlockWithPayload,
             ClientMessage::RequestBlocksSnapshot,
             ClientMessage::RequestNodeIdentitySnapshot,
             ClientMessage::RequestHistogramSnapshot,
+            ClientMessage::UpdateSubscription(SubscriptionSpec::blocks_only()),
+            ClientMessage::UpdateSubscription(SubscriptionSpec::node_identity_only()),
         ];
 
         for (l, r) in zip(messages.iter(), messages.iter()) {
@@ -93,9 +170,13 @@ mod tests {
             ClientMessage::SubscribeLatestBlock,
             ClientMessage::SubscribeNodeIdentity,
             ClientMessage::SubscribeVoters,
+            ClientMessage::SubscribeDecided,
+            ClientMessage::SubscribeLatestBlockWithPayload,
             ClientMessage::RequestBlocksSnapshot,
             ClientMessage::RequestNodeIdentitySnapshot,
             ClientMessage::RequestHistogramSnapshot,
+            ClientMessage::UpdateSubscription(SubscriptionSpec::blocks_only()),
+            ClientMessage::UpdateSubscription(SubscriptionSpec::node_identity_only()),
         ];
 
         for message in messages.iter() {
@@ -112,9 +193,13 @@ mod tests {
             ClientMessage::SubscribeLatestBlock,
             ClientMessage::SubscribeNodeIdentity,
             ClientMessage::SubscribeVoters,
+            ClientMessage::SubscribeDecided,
+            ClientMessage::SubscribeLatestBlockWithPayload,
             ClientMessage::RequestBlocksSnapshot,
             ClientMessage::RequestNodeIdentitySnapshot,
             ClientMessage::RequestHistogramSnapshot,
+            ClientMessage::UpdateSubscription(SubscriptionSpec::blocks_only()),
+            ClientMessage::UpdateSubscription(SubscriptionSpec::node_identity_only()),
         ];
 
         for message in messages.iter() {
@@ -130,6 +215,8 @@ mod tests {
             ClientMessage::SubscribeLatestBlock,
             ClientMessage::SubscribeNodeIdentity,
             ClientMessage::SubscribeVoters,
+            ClientMessage::SubscribeDecided,
+            ClientMessage::SubscribeLatestBlockWithPayload,
             ClientMessage::RequestBlocksSnapshot,
             ClientMessage::RequestNodeIdentitySnapshot,
             ClientMessage::RequestHistogramSnapshot,
@@ -150,6 +237,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_client_message_update_subscription_to_internal_with_client_id() {
+        let public_key = BLSPubKey::generated_from_seed_indexed([0; 32], 0).0;
+        let subscriptions = [
+            SubscriptionSpec::default(),
+            SubscriptionSpec::blocks_only(),
+            SubscriptionSpec::node_identity_only(),
+            SubscriptionSpec::node_only(public_key),
+        ];
+
+        for subscription in subscriptions {
+            let client_id = ClientId::from_count(1);
+            let internal_client_message = ClientMessage::UpdateSubscription(subscription)
+                .to_internal_with_client_id::<Sender<ServerMessage>>(client_id);
+            assert_eq!(
+                internal_client_message,
+                InternalClientMessage::UpdateSubscription(client_id, subscription)
+            );
+        }
+    }
+
     #[test]
     fn test_internal_client_message_partial_eq() {
         let (sender, _) = futures::channel::mpsc::channel::<ServerMessage>(1);
@@ -177,6 +285,10 @@ mod tests {
                 ClientId::from_count(1),
                 ClientMessage::RequestHistogramSnapshot,
             ),
+            InternalClientMessage::UpdateSubscription(
+                ClientId::from_count(1),
+                SubscriptionSpec::blocks_only(),
+            ),
         ];
 
         for (l, r) in zip(messages.iter(), messages.iter()) {
@@ -219,6 +331,10 @@ mod tests {
                     ClientId::from_count(j),
                     ClientMessage::RequestHistogramSnapshot,
                 ),
+                InternalClientMessage::UpdateSubscription(
+                    ClientId::from_count(j),
+                    SubscriptionSpec::blocks_only(),
+                ),
             ];
 
             // We skip the first message, as we don't want to include the
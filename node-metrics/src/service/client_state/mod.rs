@@ -3,44 +3,109 @@ use std::{
     sync::Arc,
 };
 
-use async_lock::{RwLock, RwLockWriteGuard};
+use async_lock::{Mutex, RwLock, RwLockWriteGuard};
 use bitvec::vec::BitVec;
+use clap::ValueEnum;
 use espresso_types::SeqTypes;
-use futures::{channel::mpsc::SendError, Sink, SinkExt, Stream, StreamExt};
+use futures::{
+    channel::{mpsc::SendError, oneshot},
+    FutureExt, Sink, SinkExt, Stream, StreamExt,
+};
 use hotshot_query_service::explorer::{BlockDetail, ExplorerHistograms};
+use hotshot_types::{
+    signature_key::BLSPubKey,
+    traits::metrics::{Gauge, Metrics, NoMetrics},
+};
 use tokio::{spawn, task::JoinHandle};
 
 use super::{
     client_id::ClientId,
-    client_message::{ClientMessage, InternalClientMessage},
-    data_state::{DataState, NodeIdentity},
-    server_message::ServerMessage,
+    client_message::{
+        ClientMessage, InternalClientMessage, NodeIdentitySubscription, SubscriptionSpec,
+    },
+    data_state::{BlockDetailWithPayload, DataState, NodeIdentity},
+    server_message::{BlockProducer, NetworkSnapshot, ServerMessage},
 };
 
+/// [MAX_TOP_PRODUCERS] is the maximum number of block producers that are
+/// included in a [NetworkSnapshot].
+pub const MAX_TOP_PRODUCERS: usize = 10;
+
 /// ClientState represents the service state of the connected clients.
 /// It maintains and represents the connected clients, and their subscriptions.
 // This state is meant to be managed in a separate thread that assists with
 // processing and updating of individual client states.
 pub struct ClientState<K> {
     client_id: ClientId,
-    sender: K,
+    // The sender is kept behind a lock so that broadcasts can check whether
+    // this particular sender is ready, see [send_broadcast_message].
+    sender: Mutex<K>,
 }
 
 impl<K> ClientState<K> {
     /// Create a new ClientState with the given client_id and receiver.
     pub fn new(client_id: ClientId, sender: K) -> Self {
-        Self { client_id, sender }
+        Self {
+            client_id,
+            sender: Mutex::new(sender),
+        }
     }
 
     pub fn client_id(&self) -> ClientId {
         self.client_id
     }
 
-    pub fn sender(&self) -> &K {
+    pub fn sender(&self) -> &Mutex<K> {
         &self.sender
     }
 }
 
+/// [BackpressureStrategy] determines how the service reacts to a subscribed
+/// client that is unable to keep up with the messages that are being
+/// broadcast to it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum BackpressureStrategy {
+    /// Skip the message for any client that is not ready to receive it, but
+    /// keep the client connected.
+    #[default]
+    DropSlow,
+
+    /// Disconnect any client that is not ready to receive the message.
+    Disconnect,
+
+    /// Wait for every subscribed client to accept the message before
+    /// moving on.  A single slow client will stall the broadcast for all
+    /// other clients.
+    Block,
+}
+
+/// [ClientThreadStateMetrics] contains the gauges that reflect the number of
+/// connected clients, and the number of active stream subscriptions held by
+/// those clients.
+pub struct ClientThreadStateMetrics {
+    connected_clients: Box<dyn Gauge>,
+    active_subscriptions: Box<dyn Gauge>,
+}
+
+impl ClientThreadStateMetrics {
+    /// [new] creates the gauges for the [ClientThreadState] within the given
+    /// [Metrics] registry.
+    pub fn new(metrics: &dyn Metrics) -> Self {
+        Self {
+            connected_clients: metrics
+                .create_gauge(String::from("node_validator_connected_clients"), None),
+            active_subscriptions: metrics
+                .create_gauge(String::from("node_validator_active_subscriptions"), None),
+        }
+    }
+}
+
+impl Default for ClientThreadStateMetrics {
+    fn default() -> Self {
+        Self::new(&NoMetrics)
+    }
+}
+
 /// [ClientThreadState] represents the state of all of the active client
 /// connections connected to the service. This state governs which clients
 /// are connected, and what subscriptions they have setup.
@@ -49,7 +114,13 @@ pub struct ClientThreadState<K> {
     subscribed_latest_block: HashSet<ClientId>,
     subscribed_node_identity: HashSet<ClientId>,
     subscribed_voters: HashSet<ClientId>,
+    subscribed_decided: HashSet<ClientId>,
+    subscribed_latest_block_with_payload: HashSet<ClientId>,
+    node_identity_filters: HashMap<ClientId, BLSPubKey>,
     connection_id_counter: ClientId,
+    backpressure_strategy: BackpressureStrategy,
+    include_block_payload: bool,
+    metrics: ClientThreadStateMetrics,
 }
 
 impl<K> ClientThreadState<K> {
@@ -58,6 +129,8 @@ impl<K> ClientThreadState<K> {
         subscribed_latest_block: HashSet<ClientId>,
         subscribed_node_identity: HashSet<ClientId>,
         subscribed_voters: HashSet<ClientId>,
+        subscribed_decided: HashSet<ClientId>,
+        subscribed_latest_block_with_payload: HashSet<ClientId>,
         connection_id_counter: ClientId,
     ) -> Self {
         Self {
@@ -65,9 +138,63 @@ impl<K> ClientThreadState<K> {
             subscribed_latest_block,
             subscribed_node_identity,
             subscribed_voters,
+            subscribed_decided,
+            subscribed_latest_block_with_payload,
+            node_identity_filters: Default::default(),
             connection_id_counter,
+            backpressure_strategy: Default::default(),
+            include_block_payload: false,
+            metrics: Default::default(),
         }
     }
+
+    /// [with_backpressure_strategy] replaces the [BackpressureStrategy] that
+    /// is used when broadcasting messages to subscribed clients.
+    pub fn with_backpressure_strategy(
+        mut self,
+        backpressure_strategy: BackpressureStrategy,
+    ) -> Self {
+        self.backpressure_strategy = backpressure_strategy;
+        self
+    }
+
+    pub fn backpressure_strategy(&self) -> BackpressureStrategy {
+        self.backpressure_strategy
+    }
+
+    /// [with_include_block_payload] determines whether clients are able to
+    /// opt into receiving the payload bytes of the latest blocks.  When this
+    /// is disabled, clients that ask for block payloads will only receive
+    /// the latest block details.
+    pub fn with_include_block_payload(mut self, include_block_payload: bool) -> Self {
+        self.include_block_payload = include_block_payload;
+        self
+    }
+
+    pub fn include_block_payload(&self) -> bool {
+        self.include_block_payload
+    }
+
+    /// [with_metrics] registers the connected client and active subscription
+    /// gauges with the given [Metrics] registry.
+    pub fn with_metrics(mut self, metrics: &dyn Metrics) -> Self {
+        self.metrics = ClientThreadStateMetrics::new(metrics);
+        self.update_metrics();
+        self
+    }
+
+    /// [update_metrics] brings the gauges in line with the current set of
+    /// connected clients and their subscriptions.
+    fn update_metrics(&self) {
+        let active_subscriptions = self.subscribed_latest_block.len()
+            + self.subscribed_node_identity.len()
+            + self.subscribed_voters.len()
+            + self.subscribed_decided.len()
+            + self.subscribed_latest_block_with_payload.len();
+
+        self.metrics.connected_clients.set(self.clients.len());
+        self.metrics.active_subscriptions.set(active_subscriptions);
+    }
 }
 
 /// [drop_client_client_thread_state_write_guard] is a utility function for
@@ -83,6 +210,19 @@ fn drop_client_client_thread_state_write_guard<K>(
     client_thread_state_write_guard
         .subscribed_node_identity
         .remove(client_id);
+    client_thread_state_write_guard
+        .subscribed_voters
+        .remove(client_id);
+    client_thread_state_write_guard
+        .subscribed_decided
+        .remove(client_id);
+    client_thread_state_write_guard
+        .subscribed_latest_block_with_payload
+        .remove(client_id);
+    client_thread_state_write_guard
+        .node_identity_filters
+        .remove(client_id);
+    client_thread_state_write_guard.update_metrics();
 
     client
 }
@@ -140,13 +280,10 @@ where
     client_thread_state_write_lock_guard.connection_id_counter += 1;
     let client_id = client_thread_state_write_lock_guard.connection_id_counter;
 
-    client_thread_state_write_lock_guard.clients.insert(
-        client_id,
-        ClientState {
-            client_id,
-            sender: sender.clone(),
-        },
-    );
+    client_thread_state_write_lock_guard
+        .clients
+        .insert(client_id, ClientState::new(client_id, sender.clone()));
+    client_thread_state_write_lock_guard.update_metrics();
 
     // Explicitly unlock
     drop(client_thread_state_write_lock_guard);
@@ -183,6 +320,7 @@ pub async fn handle_client_message_subscribe_latest_block<K>(
     client_thread_state_write_lock_guard
         .subscribed_latest_block
         .insert(client_id);
+    client_thread_state_write_lock_guard.update_metrics();
 
     // Explicitly unlock
     drop(client_thread_state_write_lock_guard);
@@ -199,6 +337,10 @@ pub async fn handle_client_message_subscribe_node_identity<K>(
     client_thread_state_write_lock_guard
         .subscribed_node_identity
         .insert(client_id);
+    client_thread_state_write_lock_guard
+        .node_identity_filters
+        .remove(&client_id);
+    client_thread_state_write_lock_guard.update_metrics();
 
     // Explicitly unlock
     drop(client_thread_state_write_lock_guard);
@@ -215,9 +357,168 @@ pub async fn handle_client_message_subscribe_voters<K>(
     client_thread_state_write_lock_guard
         .subscribed_voters
         .insert(client_id);
+    client_thread_state_write_lock_guard.update_metrics();
+
+    // Explicitly unlock
+    drop(client_thread_state_write_lock_guard);
+}
+
+/// [handle_client_message_subscribe_decided] is a function that processes
+/// the client message to subscribe to the decided block stream.
+pub async fn handle_client_message_subscribe_decided<K>(
+    client_id: ClientId,
+    client_thread_state: Arc<RwLock<ClientThreadState<K>>>,
+) {
+    let mut client_thread_state_write_lock_guard = client_thread_state.write().await;
+
+    client_thread_state_write_lock_guard
+        .subscribed_decided
+        .insert(client_id);
+    client_thread_state_write_lock_guard.update_metrics();
+
+    // Explicitly unlock
+    drop(client_thread_state_write_lock_guard);
+}
+
+/// [handle_client_message_subscribe_latest_block_with_payload] is a function
+/// that processes the client message to subscribe to the latest block stream
+/// along with the payload bytes of each block.
+///
+/// If the service has not been configured to include block payloads, the
+/// client is subscribed to the latest block stream without payloads instead.
+pub async fn handle_client_message_subscribe_latest_block_with_payload<K>(
+    client_id: ClientId,
+    client_thread_state: Arc<RwLock<ClientThreadState<K>>>,
+) {
+    let mut client_thread_state_write_lock_guard = client_thread_state.write().await;
+
+    if client_thread_state_write_lock_guard.include_block_payload {
+        client_thread_state_write_lock_guard
+            .subscribed_latest_block_with_payload
+            .insert(client_id);
+    } else {
+        client_thread_state_write_lock_guard
+            .subscribed_latest_block
+            .insert(client_id);
+    }
+    client_thread_state_write_lock_guard.update_metrics();
+
+    // Explicitly unlock
+    drop(client_thread_state_write_lock_guard);
+}
+
+/// [handle_client_message_update_subscription] is a function that processes
+/// the client message to replace the subscriptions of the client with the
+/// given [SubscriptionSpec].
+///
+/// Every stream that the client was not already subscribed to is started
+/// with a fresh snapshot, so that the client does not have to separately
+/// request one.  The same holds when the client switches the node it
+/// follows.  The decided block stream has no snapshot to start from.
+pub async fn handle_client_message_update_subscription<K>(
+    client_id: ClientId,
+    subscription: SubscriptionSpec,
+    data_state: Arc<RwLock<DataState>>,
+    client_thread_state: Arc<RwLock<ClientThreadState<K>>>,
+) -> Result<(), ProcessClientMessageError>
+where
+    K: Sink<ServerMessage, Error = SendError> + Clone + Unpin,
+{
+    let mut client_thread_state_write_lock_guard = client_thread_state.write().await;
+    if !client_thread_state_write_lock_guard
+        .clients
+        .contains_key(&client_id)
+    {
+        return Ok(());
+    }
+
+    let previous_node_identity = client_thread_state_write_lock_guard
+        .subscribed_node_identity
+        .contains(&client_id)
+        .then(|| {
+            match client_thread_state_write_lock_guard
+                .node_identity_filters
+                .get(&client_id)
+            {
+                Some(public_key) => NodeIdentitySubscription::Node(*public_key),
+                None => NodeIdentitySubscription::All,
+            }
+        });
+    let send_blocks_snapshot = subscription.latest_block
+        && !client_thread_state_write_lock_guard
+            .subscribed_latest_block
+            .contains(&client_id)
+        && !client_thread_state_write_lock_guard
+            .subscribed_latest_block_with_payload
+            .contains(&client_id);
+    let send_node_identity_snapshot = subscription.node_identity.is_some()
+        && subscription.node_identity != previous_node_identity;
+    let send_voters_snapshot = subscription.voters
+        && !client_thread_state_write_lock_guard
+            .subscribed_voters
+            .contains(&client_id);
+
+    let state = &mut *client_thread_state_write_lock_guard;
+    for (subscribed, is_subscribed) in [
+        (
+            &mut state.subscribed_latest_block,
+            subscription.latest_block,
+        ),
+        (
+            &mut state.subscribed_node_identity,
+            subscription.node_identity.is_some(),
+        ),
+        (&mut state.subscribed_voters, subscription.voters),
+        (&mut state.subscribed_decided, subscription.decided),
+    ] {
+        if is_subscribed {
+            subscribed.insert(client_id);
+        } else {
+            subscribed.remove(&client_id);
+        }
+    }
+    // A subscription to the latest blocks replaces one that includes the
+    // block payloads.
+    state
+        .subscribed_latest_block_with_payload
+        .remove(&client_id);
+    match subscription.node_identity {
+        Some(NodeIdentitySubscription::Node(public_key)) => {
+            state.node_identity_filters.insert(client_id, public_key);
+        },
+        _ => {
+            state.node_identity_filters.remove(&client_id);
+        },
+    }
+    state.update_metrics();
 
     // Explicitly unlock
     drop(client_thread_state_write_lock_guard);
+
+    if send_blocks_snapshot {
+        handle_client_message_request_blocks_snapshot(
+            client_id,
+            data_state.clone(),
+            client_thread_state.clone(),
+        )
+        .await?;
+    }
+
+    if send_node_identity_snapshot {
+        handle_client_message_request_node_identity_snapshot(
+            client_id,
+            data_state.clone(),
+            client_thread_state.clone(),
+        )
+        .await?;
+    }
+
+    if send_voters_snapshot {
+        handle_client_message_request_voters_snapshot(client_id, data_state, client_thread_state)
+            .await?;
+    }
+
+    Ok(())
 }
 
 /// [HandleRequestBlocksSnapshotsError] represents the scope of errors that can
@@ -249,6 +550,21 @@ impl std::error::Error for HandleRequestBlocksSnapshotsError {
     }
 }
 
+/// [copy_block_detail] creates a copy of the given [BlockDetail], as it does
+/// not implement [Clone] itself.
+fn copy_block_detail(block: &BlockDetail<SeqTypes>) -> BlockDetail<SeqTypes> {
+    BlockDetail {
+        hash: block.hash,
+        proposer_id: block.proposer_id.clone(),
+        height: block.height,
+        size: block.size,
+        time: block.time,
+        num_transactions: block.num_transactions,
+        fee_recipient: block.fee_recipient.clone(),
+        block_reward: block.block_reward.clone(),
+    }
+}
+
 /// [handle_client_message_request_blocks_snapshot] is a function that processes
 /// the client message request for a blocks snapshot.
 pub async fn handle_client_message_request_blocks_snapshot<K>(
@@ -264,20 +580,11 @@ where
 
     let latest_blocks = data_state_read_lock_guard
         .latest_blocks()
-        .map(|block| BlockDetail {
-            hash: block.hash,
-            proposer_id: block.proposer_id.clone(),
-            height: block.height,
-            size: block.size,
-            time: block.time,
-            num_transactions: block.num_transactions,
-            fee_recipient: block.fee_recipient.clone(),
-            block_reward: block.block_reward.clone(),
-        })
+        .map(copy_block_detail)
         .collect::<Vec<BlockDetail<SeqTypes>>>();
 
     if let Some(client) = client_thread_state_read_lock_guard.clients.get(&client_id) {
-        let mut sender = client.sender.clone();
+        let mut sender = client.sender.lock().await.clone();
         if let Err(err) = sender
             .send(ServerMessage::BlocksSnapshot(Arc::new(latest_blocks)))
             .await
@@ -322,6 +629,9 @@ impl std::error::Error for HandleRequestNodeIdentitySnapshotError {
 
 /// [handle_client_message_request_node_identity_snapshot] is a function that
 /// processes the client message request for a node identity snapshot.
+///
+/// If the client has narrowed its node identity subscription down to a
+/// single node, the snapshot only contains that node.
 pub async fn handle_client_message_request_node_identity_snapshot<K>(
     client_id: ClientId,
     data_state: Arc<RwLock<DataState>>,
@@ -335,11 +645,18 @@ where
         futures::join!(client_thread_state.read(), data_state.read());
     let client_result = client_thread_state_read_lock_guard.clients.get(&client_id);
     if let Some(client) = client_result {
-        let mut sender = client.sender.clone();
+        let mut sender = client.sender.lock().await.clone();
 
         // Let's copy the current node identity snapshot and send them
+        let node_identity_filter = client_thread_state_read_lock_guard
+            .node_identity_filters
+            .get(&client_id);
         let nodes = data_state_read_lock_guard
             .node_identity()
+            .filter(|node_identity| {
+                node_identity_filter
+                    .is_none_or(|public_key| node_identity.public_key() == public_key)
+            })
             .cloned()
             .collect::<Vec<_>>();
 
@@ -388,50 +705,56 @@ impl std::error::Error for HandleRequestHistogramSnapshotError {
     }
 }
 
-/// [handle_client_message_request_histogram_snapshot] is a function that
-/// processes the client message request for a histogram snapshot.
-pub async fn handle_client_message_request_histogram_snapshot<K>(
-    client_id: ClientId,
-    data_state: Arc<RwLock<DataState>>,
-    client_thread_state: Arc<RwLock<ClientThreadState<K>>>,
-) -> Result<(), HandleRequestHistogramSnapshotError>
-where
-    K: Sink<ServerMessage, Error = SendError> + Clone + Unpin,
-{
-    // Let's send the current histogram data snapshot to the client
-    let (client_thread_state_read_lock_guard, data_state_read_lock_guard) =
-        futures::join!(client_thread_state.read(), data_state.read());
-
-    let histogram_data = ExplorerHistograms {
-        block_size: data_state_read_lock_guard
+/// [create_explorer_histograms] derives the [ExplorerHistograms] from the
+/// latest blocks held in the given [DataState].
+fn create_explorer_histograms(data_state: &DataState) -> ExplorerHistograms {
+    ExplorerHistograms {
+        block_size: data_state
             .latest_blocks()
             .skip(1)
             .map(|block| Some(block.size))
             .collect(),
-        block_time: data_state_read_lock_guard
+        block_time: data_state
             .latest_blocks()
             .skip(1)
-            .zip(data_state_read_lock_guard.latest_blocks())
+            .zip(data_state.latest_blocks())
             .map(|(block_i, block_i_sub_1)| {
                 Some((block_i.time.0 - block_i_sub_1.time.0).whole_seconds() as u64)
             })
             .collect(),
-        block_transactions: data_state_read_lock_guard
+        block_transactions: data_state
             .latest_blocks()
             .skip(1)
             .map(|block| block.num_transactions)
             .collect(),
-        block_heights: data_state_read_lock_guard
+        block_heights: data_state
             .latest_blocks()
             .skip(1)
             .map(|block| block.height)
             .collect(),
-    };
+    }
+}
+
+/// [handle_client_message_request_histogram_snapshot] is a function that
+/// processes the client message request for a histogram snapshot.
+pub async fn handle_client_message_request_histogram_snapshot<K>(
+    client_id: ClientId,
+    data_state: Arc<RwLock<DataState>>,
+    client_thread_state: Arc<RwLock<ClientThreadState<K>>>,
+) -> Result<(), HandleRequestHistogramSnapshotError>
+where
+    K: Sink<ServerMessage, Error = SendError> + Clone + Unpin,
+{
+    // Let's send the current histogram data snapshot to the client
+    let (client_thread_state_read_lock_guard, data_state_read_lock_guard) =
+        futures::join!(client_thread_state.read(), data_state.read());
+
+    let histogram_data = create_explorer_histograms(&data_state_read_lock_guard);
     let arc_histogram_data = Arc::new(histogram_data);
     drop(data_state_read_lock_guard);
 
     if let Some(client) = client_thread_state_read_lock_guard.clients.get(&client_id) {
-        let mut sender = client.sender.clone();
+        let mut sender = client.sender.lock().await.clone();
         drop(client_thread_state_read_lock_guard);
 
         if let Err(err) = sender
@@ -496,7 +819,7 @@ where
     let voters_data = Arc::new(voters_data);
 
     if let Some(client) = client_thread_state_read_lock_guard.clients.get(&client_id) {
-        let mut sender = client.sender.clone();
+        let mut sender = client.sender.lock().await.clone();
         drop(client_thread_state_read_lock_guard);
 
         if let Err(err) = sender
@@ -512,6 +835,68 @@ where
     Ok(())
 }
 
+/// [create_top_producers] counts the blocks produced by each proposer within
+/// the latest blocks held in the given [DataState], and returns the
+/// [MAX_TOP_PRODUCERS] most productive ones.  Ties are broken in favor of
+/// the proposer that produced a block most recently.
+fn create_top_producers(data_state: &DataState) -> Vec<BlockProducer> {
+    let latest_blocks = data_state.latest_blocks().collect::<Vec<_>>();
+    let mut producers: Vec<BlockProducer> = Vec::new();
+    for block in latest_blocks.into_iter().rev() {
+        match producers
+            .iter_mut()
+            .find(|producer| producer.proposer_id == block.proposer_id)
+        {
+            Some(producer) => producer.num_blocks += 1,
+            None => producers.push(BlockProducer {
+                proposer_id: block.proposer_id.clone(),
+                num_blocks: 1,
+            }),
+        }
+    }
+
+    // The sort is stable, so the order of equal producers is retained.
+    producers.sort_by(|lhs, rhs| rhs.num_blocks.cmp(&lhs.num_blocks));
+    producers.truncate(MAX_TOP_PRODUCERS);
+    producers
+}
+
+/// [handle_client_message_request_network_snapshot] is a function that
+/// processes the one-shot request for a [NetworkSnapshot].
+///
+/// The snapshot is created from the [DataState] under a single read lock, so
+/// that all of its parts reflect the same block height.
+pub async fn handle_client_message_request_network_snapshot(
+    sender: oneshot::Sender<NetworkSnapshot>,
+    data_state: Arc<RwLock<DataState>>,
+) {
+    let data_state_read_lock_guard = data_state.read().await;
+
+    let latest_block = data_state_read_lock_guard
+        .latest_blocks()
+        .last()
+        .map(copy_block_detail);
+    let network_snapshot = NetworkSnapshot {
+        block_height: latest_block.as_ref().map(|block| block.height),
+        node_identity: data_state_read_lock_guard
+            .node_identity()
+            .cloned()
+            .collect(),
+        latest_block,
+        top_producers: create_top_producers(&data_state_read_lock_guard),
+        histograms: create_explorer_histograms(&data_state_read_lock_guard),
+    };
+
+    // Explicitly unlock
+    drop(data_state_read_lock_guard);
+
+    // The requester may have given up on the snapshot already, in which case
+    // there is nobody left to send it to.
+    if sender.send(network_snapshot).is_err() {
+        tracing::debug!("network snapshot requester is gone");
+    }
+}
+
 /// [ProcessClientMessageError] represents the scope of errors that can be
 /// returned from the [process_client_message] function.
 #[derive(Debug)]
@@ -623,6 +1008,21 @@ where
             Ok(())
         },
 
+        InternalClientMessage::RequestNetworkSnapshot(sender) => {
+            handle_client_message_request_network_snapshot(sender, data_state).await;
+            Ok(())
+        },
+
+        InternalClientMessage::UpdateSubscription(client_id, subscription) => {
+            handle_client_message_update_subscription(
+                client_id,
+                subscription,
+                data_state,
+                client_thread_state,
+            )
+            .await
+        },
+
         InternalClientMessage::Request(client_id, ClientMessage::SubscribeLatestBlock) => {
             handle_client_message_subscribe_latest_block(client_id, client_thread_state).await;
             Ok(())
@@ -638,6 +1038,23 @@ where
             Ok(())
         },
 
+        InternalClientMessage::Request(client_id, ClientMessage::SubscribeDecided) => {
+            handle_client_message_subscribe_decided(client_id, client_thread_state).await;
+            Ok(())
+        },
+
+        InternalClientMessage::Request(
+            client_id,
+            ClientMessage::SubscribeLatestBlockWithPayload,
+        ) => {
+            handle_client_message_subscribe_latest_block_with_payload(
+                client_id,
+                client_thread_state,
+            )
+            .await;
+            Ok(())
+        },
+
         InternalClientMessage::Request(client_id, ClientMessage::RequestBlocksSnapshot) => {
             handle_client_message_request_blocks_snapshot(
                 client_id,
@@ -677,6 +1094,19 @@ where
             .await?;
             Ok(())
         },
+
+        InternalClientMessage::Request(
+            client_id,
+            ClientMessage::UpdateSubscription(subscription),
+        ) => {
+            handle_client_message_update_subscription(
+                client_id,
+                subscription,
+                data_state,
+                client_thread_state,
+            )
+            .await
+        },
     }
 }
 
@@ -699,6 +1129,56 @@ async fn drop_failed_client_sends<K>(
     }
 }
 
+/// [send_broadcast_message] sends the given message to a single subscribed
+/// client according to the given [BackpressureStrategy].
+///
+/// The returned boolean indicates whether the client should be retained.  A
+/// return value of false means that the client should be dropped from the
+/// [ClientThreadState].
+async fn send_broadcast_message<K>(
+    client_id: &ClientId,
+    sender: &Mutex<K>,
+    message: ServerMessage,
+    backpressure_strategy: BackpressureStrategy,
+) -> bool
+where
+    K: Sink<ServerMessage, Error = SendError> + Unpin,
+{
+    let mut sender = sender.lock().await;
+    if let BackpressureStrategy::Block = backpressure_strategy {
+        return sender.send(message).await.is_ok();
+    }
+
+    // We only give the client a single chance to accept the message.  If the
+    // client's sender is not ready immediately, the client is lagging behind.
+    //
+    // This has to be checked on the client's own sender: every clone of a
+    // channel sender is guaranteed a slot of its own, so a fresh clone would
+    // always appear to be ready.
+    match futures::future::poll_fn(|cx| sender.poll_ready_unpin(cx)).now_or_never() {
+        Some(Ok(())) => {},
+        Some(Err(_)) => return false,
+        None if backpressure_strategy == BackpressureStrategy::DropSlow => {
+            tracing::debug!(
+                "dropping broadcast message for lagging client {:?}",
+                client_id
+            );
+            return true;
+        },
+        None => {
+            tracing::info!("disconnecting lagging client {:?}", client_id);
+            return false;
+        },
+    }
+
+    if sender.start_send_unpin(message).is_err() {
+        return false;
+    }
+
+    // A pending flush only means that the message is still queued.
+    !matches!(sender.flush().now_or_never(), Some(Err(_)))
+}
+
 /// [handle_received_block_detail] is a function that processes received Block
 /// details and will attempt to distribute the message to all of the clients
 /// that are subscribed to the latest block stream.
@@ -723,6 +1203,7 @@ async fn handle_received_block_detail<K>(
         })
         .filter(|(_, client)| client.is_some());
 
+    let backpressure_strategy = client_thread_state_read_lock_guard.backpressure_strategy;
     let arc_block_detail = Arc::new(block_detail);
     // We collect the results of sending the latest block to the clients.
     let client_send_result_future = latest_block_subscribers.map(|(client_id, client)| {
@@ -730,12 +1211,15 @@ async fn handle_received_block_detail<K>(
         async move {
             // This is guaranteed to be a some now
             let client = client.unwrap();
-            let mut sender = client.sender.clone();
-            let send_result = sender
-                .send(ServerMessage::LatestBlock(arc_block_detail))
-                .await;
+            let retain_client = send_broadcast_message(
+                client_id,
+                &client.sender,
+                ServerMessage::LatestBlock(arc_block_detail),
+                backpressure_strategy,
+            )
+            .await;
 
-            (client_id, send_result)
+            (client_id, retain_client)
         }
     });
 
@@ -745,7 +1229,7 @@ async fn handle_received_block_detail<K>(
     // here so we can drop our read lock.
     let failed_client_sends = client_send_results
         .into_iter()
-        .filter(|(_, send_result)| send_result.is_err())
+        .filter(|(_, retain_client)| !retain_client)
         .map(|(client_id, _)| *client_id)
         .collect::<Vec<_>>();
 
@@ -759,21 +1243,22 @@ async fn handle_received_block_detail<K>(
     drop_failed_client_sends(client_thread_state, failed_client_sends).await;
 }
 
-/// [handle_received_node_identity] is a function that processes received
-/// NodeIdentity and will attempt to distribute the message to all of the
-/// clients that are subscribed to the node identity stream.
-async fn handle_received_node_identity<K>(
+/// [handle_received_block_detail_with_payload] is a function that processes
+/// received Block details along with their payload bytes, and will attempt to
+/// distribute the message to all of the clients that are subscribed to the
+/// latest block stream with payloads.
+async fn handle_received_block_detail_with_payload<K>(
     client_thread_state: Arc<RwLock<ClientThreadState<K>>>,
-    node_identity: NodeIdentity,
+    block_detail_with_payload: BlockDetailWithPayload,
 ) where
     K: Sink<ServerMessage, Error = SendError> + Clone + Unpin,
 {
     let client_thread_state_read_lock_guard = client_thread_state.read().await;
 
-    // These are the clients who are subscribed to the node identities, that
-    // have an active ClientState within the system.
-    let node_identity_subscribers = client_thread_state_read_lock_guard
-        .subscribed_node_identity
+    // These are the clients who are subscribed to the latest blocks with
+    // payloads, that have an active ClientState within the system.
+    let latest_block_with_payload_subscribers = client_thread_state_read_lock_guard
+        .subscribed_latest_block_with_payload
         .iter()
         .map(|client_id| {
             (
@@ -783,21 +1268,29 @@ async fn handle_received_node_identity<K>(
         })
         .filter(|(_, client)| client.is_some());
 
-    let arc_node_identity = Arc::new(node_identity);
+    let backpressure_strategy = client_thread_state_read_lock_guard.backpressure_strategy;
+    let (block_detail, payload) = block_detail_with_payload;
+    let arc_block_detail = Arc::new(block_detail);
+    let arc_payload = Arc::new(payload);
     // We collect the results of sending the latest block to the clients.
-    let client_send_result_future = node_identity_subscribers.map(|(client_id, client)| {
-        let arc_node_identity = arc_node_identity.clone();
-        async move {
-            // This is guaranteed to be a some now
-            let client = client.unwrap();
-            let mut sender = client.sender.clone();
-            let send_result = sender
-                .send(ServerMessage::LatestNodeIdentity(arc_node_identity.clone()))
+    let client_send_result_future =
+        latest_block_with_payload_subscribers.map(|(client_id, client)| {
+            let arc_block_detail = arc_block_detail.clone();
+            let arc_payload = arc_payload.clone();
+            async move {
+                // This is guaranteed to be a some now
+                let client = client.unwrap();
+                let retain_client = send_broadcast_message(
+                    client_id,
+                    &client.sender,
+                    ServerMessage::LatestBlockWithPayload(arc_block_detail, arc_payload),
+                    backpressure_strategy,
+                )
                 .await;
 
-            (client_id, send_result)
-        }
-    });
+                (client_id, retain_client)
+            }
+        });
 
     let client_send_results = futures::future::join_all(client_send_result_future).await;
 
@@ -805,7 +1298,7 @@ async fn handle_received_node_identity<K>(
     // here so we can drop our read lock.
     let failed_client_sends = client_send_results
         .into_iter()
-        .filter(|(_, send_result)| send_result.is_err())
+        .filter(|(_, retain_client)| !retain_client)
         .map(|(client_id, _)| *client_id)
         .collect::<Vec<_>>();
 
@@ -819,21 +1312,21 @@ async fn handle_received_node_identity<K>(
     drop_failed_client_sends(client_thread_state, failed_client_sends).await;
 }
 
-/// [handle_received_voters] is a function that processes received voters and
-/// will attempt to distribute the message to all of the clients that are
-/// subscribed to the voters stream.
-async fn handle_received_voters<K>(
+/// [handle_received_decided_block_detail] is a function that processes
+/// received decided Block details and will attempt to distribute the message
+/// to all of the clients that are subscribed to the decided block stream.
+async fn handle_received_decided_block_detail<K>(
     client_thread_state: Arc<RwLock<ClientThreadState<K>>>,
-    voters: BitVec<u16>,
+    block_detail: BlockDetail<SeqTypes>,
 ) where
     K: Sink<ServerMessage, Error = SendError> + Clone + Unpin,
 {
     let client_thread_state_read_lock_guard = client_thread_state.read().await;
 
-    // These are the clients who are subscribed to the node identities, that
+    // These are the clients who are subscribed to the decided blocks, that
     // have an active ClientState within the system.
-    let node_identity_subscribers = client_thread_state_read_lock_guard
-        .subscribed_voters
+    let decided_block_subscribers = client_thread_state_read_lock_guard
+        .subscribed_decided
         .iter()
         .map(|client_id| {
             (
@@ -843,16 +1336,23 @@ async fn handle_received_voters<K>(
         })
         .filter(|(_, client)| client.is_some());
 
-    // We collect the results of sending the latest block to the clients.
-    let client_send_result_future = node_identity_subscribers.map(|(client_id, client)| {
-        let voters = voters.clone();
+    let backpressure_strategy = client_thread_state_read_lock_guard.backpressure_strategy;
+    let arc_block_detail = Arc::new(block_detail);
+    // We collect the results of sending the decided block to the clients.
+    let client_send_result_future = decided_block_subscribers.map(|(client_id, client)| {
+        let arc_block_detail = arc_block_detail.clone();
         async move {
             // This is guaranteed to be a some now
             let client = client.unwrap();
-            let mut sender = client.sender.clone();
-            let send_result = sender.send(ServerMessage::LatestVoters(voters)).await;
+            let retain_client = send_broadcast_message(
+                client_id,
+                &client.sender,
+                ServerMessage::DecidedBlock(arc_block_detail),
+                backpressure_strategy,
+            )
+            .await;
 
-            (client_id, send_result)
+            (client_id, retain_client)
         }
     });
 
@@ -862,7 +1362,7 @@ async fn handle_received_voters<K>(
     // here so we can drop our read lock.
     let failed_client_sends = client_send_results
         .into_iter()
-        .filter(|(_, send_result)| send_result.is_err())
+        .filter(|(_, retain_client)| !retain_client)
         .map(|(client_id, _)| *client_id)
         .collect::<Vec<_>>();
 
@@ -876,8 +1376,143 @@ async fn handle_received_voters<K>(
     drop_failed_client_sends(client_thread_state, failed_client_sends).await;
 }
 
-/// InternalClientMessageProcessingTask represents an async task for
-/// InternalClientMessages, and making the appropriate updates to the
+/// [handle_received_node_identity] is a function that processes received
+/// NodeIdentity and will attempt to distribute the message to all of the
+/// clients that are subscribed to the node identity stream.
+async fn handle_received_node_identity<K>(
+    client_thread_state: Arc<RwLock<ClientThreadState<K>>>,
+    node_identity: NodeIdentity,
+) where
+    K: Sink<ServerMessage, Error = SendError> + Clone + Unpin,
+{
+    let client_thread_state_read_lock_guard = client_thread_state.read().await;
+    let public_key = *node_identity.public_key();
+
+    // These are the clients who are subscribed to the node identities, that
+    // have an active ClientState within the system.  Clients that only
+    // follow a single node are skipped for every other node.
+    let node_identity_subscribers = client_thread_state_read_lock_guard
+        .subscribed_node_identity
+        .iter()
+        .filter(|client_id| {
+            client_thread_state_read_lock_guard
+                .node_identity_filters
+                .get(client_id)
+                .is_none_or(|filter| *filter == public_key)
+        })
+        .map(|client_id| {
+            (
+                client_id,
+                client_thread_state_read_lock_guard.clients.get(client_id),
+            )
+        })
+        .filter(|(_, client)| client.is_some());
+
+    let backpressure_strategy = client_thread_state_read_lock_guard.backpressure_strategy;
+    let arc_node_identity = Arc::new(node_identity);
+    // We collect the results of sending the latest block to the clients.
+    let client_send_result_future = node_identity_subscribers.map(|(client_id, client)| {
+        let arc_node_identity = arc_node_identity.clone();
+        async move {
+            // This is guaranteed to be a some now
+            let client = client.unwrap();
+            let retain_client = send_broadcast_message(
+                client_id,
+                &client.sender,
+                ServerMessage::LatestNodeIdentity(arc_node_identity),
+                backpressure_strategy,
+            )
+            .await;
+
+            (client_id, retain_client)
+        }
+    });
+
+    let client_send_results = futures::future::join_all(client_send_result_future).await;
+
+    // These are the clients we failed to send the message to.  We copy these
+    // here so we can drop our read lock.
+    let failed_client_sends = client_send_results
+        .into_iter()
+        .filter(|(_, retain_client)| !retain_client)
+        .map(|(client_id, _)| *client_id)
+        .collect::<Vec<_>>();
+
+    // Explicitly Drop the read lock.
+    drop(client_thread_state_read_lock_guard);
+
+    if failed_client_sends.is_empty() {
+        return;
+    }
+
+    drop_failed_client_sends(client_thread_state, failed_client_sends).await;
+}
+
+/// [handle_received_voters] is a function that processes received voters and
+/// will attempt to distribute the message to all of the clients that are
+/// subscribed to the voters stream.
+async fn handle_received_voters<K>(
+    client_thread_state: Arc<RwLock<ClientThreadState<K>>>,
+    voters: BitVec<u16>,
+) where
+    K: Sink<ServerMessage, Error = SendError> + Clone + Unpin,
+{
+    let client_thread_state_read_lock_guard = client_thread_state.read().await;
+
+    // These are the clients who are subscribed to the node identities, that
+    // have an active ClientState within the system.
+    let node_identity_subscribers = client_thread_state_read_lock_guard
+        .subscribed_voters
+        .iter()
+        .map(|client_id| {
+            (
+                client_id,
+                client_thread_state_read_lock_guard.clients.get(client_id),
+            )
+        })
+        .filter(|(_, client)| client.is_some());
+
+    let backpressure_strategy = client_thread_state_read_lock_guard.backpressure_strategy;
+    // We collect the results of sending the latest block to the clients.
+    let client_send_result_future = node_identity_subscribers.map(|(client_id, client)| {
+        let voters = voters.clone();
+        async move {
+            // This is guaranteed to be a some now
+            let client = client.unwrap();
+            let retain_client = send_broadcast_message(
+                client_id,
+                &client.sender,
+                ServerMessage::LatestVoters(voters),
+                backpressure_strategy,
+            )
+            .await;
+
+            (client_id, retain_client)
+        }
+    });
+
+    let client_send_results = futures::future::join_all(client_send_result_future).await;
+
+    // These are the clients we failed to send the message to.  We copy these
+    // here so we can drop our read lock.
+    let failed_client_sends = client_send_results
+        .into_iter()
+        .filter(|(_, retain_client)| !retain_client)
+        .map(|(client_id, _)| *client_id)
+        .collect::<Vec<_>>();
+
+    // Explicitly Drop the read lock.
+    drop(client_thread_state_read_lock_guard);
+
+    if failed_client_sends.is_empty() {
+        return;
+    }
+
+    drop_failed_client_sends(client_thread_state, failed_client_sends).await;
+}
+
+/// InternalClientMessageProcessingTask represents an async task for
+/// InternalClientMessages, and making the appropriate updates to the
 /// [ClientThreadState] and [DataState].
 pub struct InternalClientMessageProcessingTask {
     pub task_handle: Option<JoinHandle<()>>,
@@ -927,8 +1562,10 @@ impl InternalClientMessageProcessingTask {
             let message = if let Some(message) = message_result {
                 message
             } else {
-                tracing::error!("internal client message handler closed.");
-                panic!("InternalClientMessageProcessingTask stream closed, unable to process new requests from clients.");
+                // The stream is only closed once the service is shutting
+                // down, after every queued request has been processed.
+                tracing::info!("internal client message stream closed, stopping.");
+                return;
             };
 
             if let Err(err) =
@@ -1027,6 +1664,158 @@ impl Drop for ProcessDistributeBlockDetailHandlingTask {
     }
 }
 
+/// [ProcessDistributeBlockDetailWithPayloadHandlingTask] represents an async
+/// task for processing the incoming [BlockDetail]s along with their payload
+/// bytes, and distributing them to all subscribed clients.
+pub struct ProcessDistributeBlockDetailWithPayloadHandlingTask {
+    pub task_handle: Option<JoinHandle<()>>,
+}
+
+impl ProcessDistributeBlockDetailWithPayloadHandlingTask {
+    /// [new] creates a new [ProcessDistributeBlockDetailWithPayloadHandlingTask]
+    /// with the given client_thread_state and
+    /// block_detail_with_payload_receiver.
+    ///
+    /// Calling this function will start an async task that will start
+    /// processing.  The handle for the async task is stored within the
+    /// returned state.
+    pub fn new<S, K>(
+        client_thread_state: Arc<RwLock<ClientThreadState<K>>>,
+        block_detail_with_payload_receiver: S,
+    ) -> Self
+    where
+        S: Stream<Item = BlockDetailWithPayload> + Send + Sync + Unpin + 'static,
+        K: Sink<ServerMessage, Error = SendError> + Clone + Send + Sync + Unpin + 'static,
+    {
+        let task_handle = spawn(
+            Self::process_distribute_block_detail_with_payload_handling_stream(
+                client_thread_state.clone(),
+                block_detail_with_payload_receiver,
+            ),
+        );
+
+        Self {
+            task_handle: Some(task_handle),
+        }
+    }
+
+    /// [process_distribute_block_detail_with_payload_handling_stream] is a
+    /// function that processes the [Stream] of incoming [BlockDetail]s along
+    /// with their payload bytes, and distributes them to all subscribed
+    /// clients.
+    async fn process_distribute_block_detail_with_payload_handling_stream<S, K>(
+        client_thread_state: Arc<RwLock<ClientThreadState<K>>>,
+        mut stream: S,
+    ) where
+        S: Stream<Item = BlockDetailWithPayload> + Unpin,
+        K: Sink<ServerMessage, Error = SendError> + Clone + Unpin,
+    {
+        loop {
+            let block_detail_with_payload_result = stream.next().await;
+
+            let block_detail_with_payload =
+                if let Some(block_detail_with_payload) = block_detail_with_payload_result {
+                    block_detail_with_payload
+                } else {
+                    tracing::error!(
+                        "block payload stream closed.  shutting down client handling stream.",
+                    );
+                    return;
+                };
+
+            handle_received_block_detail_with_payload(
+                client_thread_state.clone(),
+                block_detail_with_payload,
+            )
+            .await
+        }
+    }
+}
+
+/// [drop] implementation for
+/// [ProcessDistributeBlockDetailWithPayloadHandlingTask] that will cancel the
+/// task if it is still running.
+impl Drop for ProcessDistributeBlockDetailWithPayloadHandlingTask {
+    fn drop(&mut self) {
+        let task_handle = self.task_handle.take();
+        if let Some(task_handle) = task_handle {
+            task_handle.abort();
+        }
+    }
+}
+
+/// [ProcessDistributeDecidedBlockDetailHandlingTask] represents an async task
+/// for processing the incoming decided [BlockDetail] and distributing them to
+/// all subscribed clients.
+pub struct ProcessDistributeDecidedBlockDetailHandlingTask {
+    pub task_handle: Option<JoinHandle<()>>,
+}
+
+impl ProcessDistributeDecidedBlockDetailHandlingTask {
+    /// [new] creates a new [ProcessDistributeDecidedBlockDetailHandlingTask]
+    /// with the given client_thread_state and decided_block_detail_receiver.
+    ///
+    /// Calling this function will start an async task that will start
+    /// processing.  The handle for the async task is stored within the
+    /// returned state.
+    pub fn new<S, K>(
+        client_thread_state: Arc<RwLock<ClientThreadState<K>>>,
+        decided_block_detail_receiver: S,
+    ) -> Self
+    where
+        S: Stream<Item = BlockDetail<SeqTypes>> + Send + Sync + Unpin + 'static,
+        K: Sink<ServerMessage, Error = SendError> + Clone + Send + Sync + Unpin + 'static,
+    {
+        let task_handle = spawn(
+            Self::process_distribute_decided_block_detail_handling_stream(
+                client_thread_state.clone(),
+                decided_block_detail_receiver,
+            ),
+        );
+
+        Self {
+            task_handle: Some(task_handle),
+        }
+    }
+
+    /// [process_distribute_decided_block_detail_handling_stream] is a function
+    /// that processes the [Stream] of incoming decided [BlockDetail] and
+    /// distributes them to all subscribed clients.
+    async fn process_distribute_decided_block_detail_handling_stream<S, K>(
+        client_thread_state: Arc<RwLock<ClientThreadState<K>>>,
+        mut stream: S,
+    ) where
+        S: Stream<Item = BlockDetail<SeqTypes>> + Unpin,
+        K: Sink<ServerMessage, Error = SendError> + Clone + Unpin,
+    {
+        loop {
+            let block_detail_result = stream.next().await;
+
+            let block_detail = if let Some(block_detail) = block_detail_result {
+                block_detail
+            } else {
+                tracing::error!(
+                    "decided block detail stream closed.  shutting down client handling stream.",
+                );
+                return;
+            };
+
+            handle_received_decided_block_detail(client_thread_state.clone(), block_detail).await
+        }
+    }
+}
+
+/// [drop] implementation for [ProcessDistributeDecidedBlockDetailHandlingTask]
+/// that will cancel the task if it is still running.
+impl Drop for ProcessDistributeDecidedBlockDetailHandlingTask {
+    fn drop(&mut self) {
+        let task_handle = self.task_handle.take();
+        if let Some(task_handle) = task_handle {
+            task_handle.abort();
+        }
+    }
+}
+
 /// [ProcessDistributeNodeIdentityHandlingTask] represents an async task for
 /// processing the incoming [NodeIdentity] and distributing them to all
 /// subscribed clients.
@@ -1173,35 +1962,52 @@ pub mod tests {
     use bitvec::vec::BitVec;
     use espresso_types::{NodeState, ValidatedState};
     use futures::{
-        channel::mpsc::{self, Sender},
+        channel::{
+            mpsc::{self, Sender},
+            oneshot,
+        },
         SinkExt, StreamExt,
     };
     use hotshot_example_types::node_types::TestVersions;
     use hotshot_query_service::{
         availability::{BlockQueryData, Leaf1QueryData},
+        metrics::PrometheusMetrics,
         testing::mocks::MockVersions,
     };
     use hotshot_types::{
-        data::Leaf2, signature_key::BLSPubKey, traits::signature_key::SignatureKey,
+        data::Leaf2,
+        signature_key::BLSPubKey,
+        traits::{signature_key::SignatureKey, EncodeBytes},
     };
     use tokio::{
         spawn,
         time::{sleep, timeout},
     };
 
-    use super::{ClientThreadState, InternalClientMessageProcessingTask};
-    use crate::service::{
-        client_id::ClientId,
-        client_message::{ClientMessage, InternalClientMessage},
-        client_state::{
-            ProcessDistributeBlockDetailHandlingTask, ProcessDistributeNodeIdentityHandlingTask,
-            ProcessDistributeVotersHandlingTask,
-        },
-        data_state::{
-            create_block_detail_from_block, DataState, LocationDetails, NodeIdentity,
-            ProcessLeafAndBlockPairStreamTask,
+    use super::{
+        handle_client_message_connected, handle_client_message_disconnected,
+        handle_client_message_subscribe_decided, handle_client_message_subscribe_latest_block,
+        handle_client_message_subscribe_latest_block_with_payload,
+        handle_client_message_subscribe_node_identity, handle_client_message_subscribe_voters,
+        BackpressureStrategy, ClientThreadState, InternalClientMessageProcessingTask,
+    };
+    use crate::{
+        api::node_validator::v0::LeafBlockPair,
+        service::{
+            client_id::ClientId,
+            client_message::{ClientMessage, InternalClientMessage, SubscriptionSpec},
+            client_state::{
+                ProcessDistributeBlockDetailHandlingTask,
+                ProcessDistributeBlockDetailWithPayloadHandlingTask,
+                ProcessDistributeDecidedBlockDetailHandlingTask,
+                ProcessDistributeNodeIdentityHandlingTask, ProcessDistributeVotersHandlingTask,
+            },
+            data_state::{
+                create_block_detail_from_block, BlockDetailWithPayload, DataState, LocationDetails,
+                NodeIdentity, ProcessLeafAndBlockPairStreamTask,
+            },
+            server_message::{BlockProducer, ServerMessage},
         },
-        server_message::ServerMessage,
     };
 
     pub fn create_test_client_thread_state() -> ClientThreadState<Sender<ServerMessage>> {
@@ -1210,7 +2016,13 @@ pub mod tests {
             subscribed_latest_block: Default::default(),
             subscribed_node_identity: Default::default(),
             subscribed_voters: Default::default(),
+            subscribed_decided: Default::default(),
+            subscribed_latest_block_with_payload: Default::default(),
+            node_identity_filters: Default::default(),
             connection_id_counter: ClientId::from_count(1),
+            backpressure_strategy: Default::default(),
+            include_block_payload: false,
+            metrics: Default::default(),
         }
     }
 
@@ -1510,6 +2322,65 @@ pub mod tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_process_client_handling_stream_request_network_snapshot() {
+        let (node_1, node_2, node_3, mut data_state) = create_test_data_state();
+        let client_thread_state = Arc::new(RwLock::new(create_test_client_thread_state()));
+        let validated_state = ValidatedState::default();
+        let instance_state = NodeState::mock();
+        let block_1 =
+            BlockQueryData::genesis::<MockVersions>(&validated_state, &instance_state).await;
+
+        let (mut internal_client_message_sender, internal_client_message_receiver) =
+            mpsc::channel(1);
+        let data_state = Arc::new(RwLock::new({
+            let mut block_2 = create_block_detail_from_block(&block_1);
+            block_2.height = 1;
+            data_state.add_latest_block(create_block_detail_from_block(&block_1));
+            data_state.add_latest_block(block_2);
+            data_state
+        }));
+        let mut process_internal_client_message_handle = InternalClientMessageProcessingTask::new(
+            internal_client_message_receiver,
+            data_state,
+            client_thread_state,
+        );
+
+        // A snapshot does not require the requester to be connected.
+        let (network_snapshot_sender, network_snapshot_receiver) = oneshot::channel();
+        assert_eq!(
+            internal_client_message_sender
+                .send(InternalClientMessage::RequestNetworkSnapshot(
+                    network_snapshot_sender
+                ))
+                .await,
+            Ok(()),
+        );
+
+        let network_snapshot = network_snapshot_receiver.await.unwrap();
+        let expected_block = create_block_detail_from_block(&block_1);
+        assert_eq!(network_snapshot.block_height, Some(1));
+        assert_eq!(network_snapshot.node_identity, vec![node_1, node_2, node_3]);
+        assert_eq!(
+            network_snapshot.latest_block.map(|block| block.height),
+            Some(1)
+        );
+        assert_eq!(
+            network_snapshot.top_producers,
+            vec![BlockProducer {
+                proposer_id: expected_block.proposer_id,
+                num_blocks: 2,
+            }]
+        );
+        assert_eq!(network_snapshot.histograms.block_heights, vec![1]);
+
+        if let Some(process_internal_client_message_handle) =
+            process_internal_client_message_handle.task_handle.take()
+        {
+            process_internal_client_message_handle.abort();
+        }
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_process_client_handling_stream_subscribe_latest_block() {
         let (_, _, _, data_state) = create_test_data_state();
@@ -1519,6 +2390,7 @@ pub mod tests {
         let (mut leaf_sender, leaf_receiver) = mpsc::channel(1);
         let (block_detail_sender, block_detail_receiver) = mpsc::channel(1);
         let (voters_sender, voters_receiver) = mpsc::channel(1);
+        let (decided_block_detail_sender, _decided_block_detail_receiver) = mpsc::channel(1);
         let (internal_client_message_sender, internal_client_message_receiver) = mpsc::channel(1);
         let (server_message_sender_1, mut server_message_receiver_1) = mpsc::channel(1);
         let (server_message_sender_2, mut server_message_receiver_2) = mpsc::channel(1);
@@ -1543,6 +2415,8 @@ pub mod tests {
             data_state,
             block_detail_sender,
             voters_sender,
+            decided_block_detail_sender,
+            None::<Sender<BlockDetailWithPayload>>,
         );
 
         // Send a Connected Message to the server
@@ -1625,10 +2499,16 @@ pub mod tests {
 
         assert_eq!(
             leaf_sender
-                .send((
-                    Leaf1QueryData::new(leaf.clone().to_leaf_unsafe(), leaf.justify_qc().to_qc()),
-                    block_query_data.clone()
-                ))
+                .send(
+                    LeafBlockPair::try_new(
+                        Leaf1QueryData::new(
+                            leaf.clone().to_leaf_unsafe(),
+                            leaf.justify_qc().to_qc()
+                        ),
+                        block_query_data.clone()
+                    )
+                    .unwrap()
+                )
                 .await,
             Ok(())
         );
@@ -1792,6 +2672,144 @@ pub mod tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_process_client_handling_stream_update_subscription() {
+        let (node_1, node_2, node_3, mut data_state) = create_test_data_state();
+        let client_thread_state = Arc::new(RwLock::new(create_test_client_thread_state()));
+        let validated_state = ValidatedState::default();
+        let instance_state = NodeState::mock();
+        let block_1 =
+            BlockQueryData::genesis::<MockVersions>(&validated_state, &instance_state).await;
+        data_state.add_latest_block(create_block_detail_from_block(&block_1));
+        let data_state = Arc::new(RwLock::new(data_state));
+
+        let (mut node_identity_sender, node_identity_receiver) = mpsc::channel(1);
+        let (mut internal_client_message_sender, internal_client_message_receiver) =
+            mpsc::channel(1);
+        let (server_message_sender_1, mut server_message_receiver_1) = mpsc::channel(1);
+        let mut process_internal_client_message_handle = InternalClientMessageProcessingTask::new(
+            internal_client_message_receiver,
+            data_state,
+            client_thread_state.clone(),
+        );
+
+        let mut process_distribute_node_identity_handle =
+            ProcessDistributeNodeIdentityHandlingTask::new(
+                client_thread_state,
+                node_identity_receiver,
+            );
+
+        assert_eq!(
+            internal_client_message_sender
+                .send(InternalClientMessage::Connected(server_message_sender_1))
+                .await,
+            Ok(())
+        );
+
+        assert_eq!(
+            server_message_receiver_1.next().await,
+            Some(ServerMessage::YouAre(ClientId::from_count(2))),
+        );
+
+        let client_1_id = ClientId::from_count(2);
+
+        // Following a single node starts with a snapshot of only that node.
+        assert_eq!(
+            internal_client_message_sender
+                .send(
+                    ClientMessage::UpdateSubscription(SubscriptionSpec::node_only(
+                        *node_2.public_key()
+                    ))
+                    .to_internal_with_client_id(client_1_id)
+                )
+                .await,
+            Ok(()),
+        );
+
+        assert_eq!(
+            server_message_receiver_1.next().await,
+            Some(ServerMessage::NodeIdentitySnapshot(Arc::new(vec![
+                node_2.clone()
+            ]))),
+        );
+
+        // Updates for the other nodes are not relayed to the client.
+        assert_eq!(node_identity_sender.send(node_1.clone()).await, Ok(()));
+        assert_eq!(node_identity_sender.send(node_2.clone()).await, Ok(()));
+
+        assert_eq!(
+            server_message_receiver_1.next().await,
+            Some(ServerMessage::LatestNodeIdentity(Arc::new(node_2.clone())))
+        );
+
+        // Adding the latest block stream mid-session starts it with a fresh
+        // blocks snapshot, without repeating the node identity snapshot.
+        let mut subscription = SubscriptionSpec::node_only(*node_2.public_key());
+        subscription.latest_block = true;
+        assert_eq!(
+            internal_client_message_sender
+                .send(InternalClientMessage::UpdateSubscription(
+                    client_1_id,
+                    subscription
+                ))
+                .await,
+            Ok(()),
+        );
+
+        assert_eq!(
+            server_message_receiver_1.next().await,
+            Some(ServerMessage::BlocksSnapshot(Arc::new(vec![
+                create_block_detail_from_block(&block_1)
+            ]))),
+        );
+
+        if timeout(Duration::from_millis(10), server_message_receiver_1.next())
+            .await
+            .is_ok()
+        {
+            panic!("receiver 1 should not have received another snapshot.");
+        }
+
+        // Widening the node identity subscription sends a snapshot of every
+        // node.
+        assert_eq!(
+            internal_client_message_sender
+                .send(InternalClientMessage::UpdateSubscription(
+                    client_1_id,
+                    SubscriptionSpec::node_identity_only()
+                ))
+                .await,
+            Ok(()),
+        );
+
+        assert_eq!(
+            server_message_receiver_1.next().await,
+            Some(ServerMessage::NodeIdentitySnapshot(Arc::new(vec![
+                node_1.clone(),
+                node_2.clone(),
+                node_3.clone()
+            ]))),
+        );
+
+        assert_eq!(node_identity_sender.send(node_1.clone()).await, Ok(()));
+        assert_eq!(
+            server_message_receiver_1.next().await,
+            Some(ServerMessage::LatestNodeIdentity(Arc::new(node_1)))
+        );
+
+        if let Some(process_internal_client_message_handle) =
+            process_internal_client_message_handle.task_handle.take()
+        {
+            process_internal_client_message_handle.abort();
+        }
+
+        if let Some(process_distribute_node_identity_handle) =
+            process_distribute_node_identity_handle.task_handle.take()
+        {
+            process_distribute_node_identity_handle.abort();
+        }
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_process_client_handling_stream_subscribe_voters() {
         let (_, _, _, data_state) = create_test_data_state();
@@ -1905,6 +2923,391 @@ pub mod tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_process_client_handling_stream_subscribe_decided() {
+        let client_thread_state = Arc::new(RwLock::new(create_test_client_thread_state()));
+
+        let (mut decided_block_detail_sender, decided_block_detail_receiver) = mpsc::channel(1);
+        let (server_message_sender_1, mut server_message_receiver_1) = mpsc::channel(1);
+        let (server_message_sender_2, mut server_message_receiver_2) = mpsc::channel(1);
+
+        let mut process_distribute_decided_block_detail_handle =
+            ProcessDistributeDecidedBlockDetailHandlingTask::new(
+                client_thread_state.clone(),
+                decided_block_detail_receiver,
+            );
+
+        let client_1_id =
+            handle_client_message_connected(server_message_sender_1, client_thread_state.clone())
+                .await
+                .unwrap();
+        let client_2_id =
+            handle_client_message_connected(server_message_sender_2, client_thread_state.clone())
+                .await
+                .unwrap();
+        assert_eq!(
+            server_message_receiver_1.next().await,
+            Some(ServerMessage::YouAre(client_1_id)),
+        );
+        assert_eq!(
+            server_message_receiver_2.next().await,
+            Some(ServerMessage::YouAre(client_2_id)),
+        );
+
+        // Only the first client is interested in decided blocks.
+        handle_client_message_subscribe_decided(client_1_id, client_thread_state.clone()).await;
+        handle_client_message_subscribe_latest_block(client_2_id, client_thread_state.clone())
+            .await;
+
+        let validated_state = ValidatedState::default();
+        let instance_state = NodeState::mock();
+        let sample_block_query_data =
+            BlockQueryData::genesis::<MockVersions>(&validated_state, &instance_state).await;
+        let block_detail = create_block_detail_from_block(&sample_block_query_data);
+
+        assert_eq!(
+            decided_block_detail_sender.send(block_detail.clone()).await,
+            Ok(()),
+        );
+
+        assert_eq!(
+            timeout(Duration::from_secs(1), server_message_receiver_1.next()).await,
+            Ok(Some(ServerMessage::DecidedBlock(Arc::new(block_detail)))),
+        );
+        assert!(
+            timeout(Duration::from_millis(200), server_message_receiver_2.next())
+                .await
+                .is_err()
+        );
+
+        if let Some(process_distribute_decided_block_detail_handle) =
+            process_distribute_decided_block_detail_handle
+                .task_handle
+                .take()
+        {
+            process_distribute_decided_block_detail_handle.abort();
+        }
+    }
+
+    /// Subscribes a client to the latest block stream with payloads using a
+    /// [ClientThreadState] configured with the given include_block_payload
+    /// setting, and returns the first block update that the client receives
+    /// after a block has been distributed.
+    async fn receive_block_update_with_include_block_payload(
+        include_block_payload: bool,
+    ) -> ServerMessage {
+        let client_thread_state = Arc::new(RwLock::new(
+            create_test_client_thread_state().with_include_block_payload(include_block_payload),
+        ));
+
+        let (mut block_detail_sender, block_detail_receiver) = mpsc::channel(1);
+        let (mut block_detail_with_payload_sender, block_detail_with_payload_receiver) =
+            mpsc::channel(1);
+        let (server_message_sender, mut server_message_receiver) = mpsc::channel(1);
+
+        let _process_distribute_block_detail_handle = ProcessDistributeBlockDetailHandlingTask::new(
+            client_thread_state.clone(),
+            block_detail_receiver,
+        );
+        let _process_distribute_block_detail_with_payload_handle =
+            ProcessDistributeBlockDetailWithPayloadHandlingTask::new(
+                client_thread_state.clone(),
+                block_detail_with_payload_receiver,
+            );
+
+        let client_id =
+            handle_client_message_connected(server_message_sender, client_thread_state.clone())
+                .await
+                .unwrap();
+        assert_eq!(
+            server_message_receiver.next().await,
+            Some(ServerMessage::YouAre(client_id)),
+        );
+
+        handle_client_message_subscribe_latest_block_with_payload(
+            client_id,
+            client_thread_state.clone(),
+        )
+        .await;
+
+        let validated_state = ValidatedState::default();
+        let instance_state = NodeState::mock();
+        let sample_block_query_data =
+            BlockQueryData::genesis::<MockVersions>(&validated_state, &instance_state).await;
+        let block_detail = create_block_detail_from_block(&sample_block_query_data);
+        let payload = sample_block_query_data.payload().encode().to_vec();
+
+        assert_eq!(block_detail_sender.send(block_detail.clone()).await, Ok(()));
+        assert_eq!(
+            block_detail_with_payload_sender
+                .send((block_detail, payload))
+                .await,
+            Ok(()),
+        );
+
+        let block_update = timeout(Duration::from_secs(1), server_message_receiver.next())
+            .await
+            .unwrap()
+            .unwrap();
+
+        // The client should only receive a single update for the block.
+        assert!(
+            timeout(Duration::from_millis(200), server_message_receiver.next())
+                .await
+                .is_err()
+        );
+
+        block_update
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_subscribe_latest_block_with_payload_included() {
+        let block_update = receive_block_update_with_include_block_payload(true).await;
+
+        match block_update {
+            ServerMessage::LatestBlockWithPayload(block_detail, payload) => {
+                assert_eq!(block_detail.size, payload.len() as u64);
+            },
+            message => panic!("expected a block update with a payload, got {:?}", message),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_subscribe_latest_block_with_payload_excluded() {
+        let block_update = receive_block_update_with_include_block_payload(false).await;
+
+        assert!(
+            matches!(block_update, ServerMessage::LatestBlock(_)),
+            "expected a block update without a payload, got {:?}",
+            block_update
+        );
+    }
+
+    /// Connects a slow client, which does not read any of its messages, and a
+    /// fast client to a [ClientThreadState] using the given
+    /// [BackpressureStrategy].  Both clients are subscribed to the voters
+    /// stream.
+    #[allow(clippy::type_complexity)]
+    async fn create_slow_and_fast_voters_clients(
+        backpressure_strategy: BackpressureStrategy,
+    ) -> (
+        Arc<RwLock<ClientThreadState<Sender<ServerMessage>>>>,
+        ClientId,
+        mpsc::Receiver<ServerMessage>,
+        mpsc::Receiver<ServerMessage>,
+    ) {
+        let client_thread_state = Arc::new(RwLock::new(
+            create_test_client_thread_state().with_backpressure_strategy(backpressure_strategy),
+        ));
+
+        let (slow_sender, slow_receiver) = mpsc::channel(1);
+        let (fast_sender, mut fast_receiver) = mpsc::channel(1);
+
+        let slow_client_id =
+            handle_client_message_connected(slow_sender, client_thread_state.clone())
+                .await
+                .unwrap();
+        let fast_client_id =
+            handle_client_message_connected(fast_sender, client_thread_state.clone())
+                .await
+                .unwrap();
+
+        assert_eq!(
+            fast_receiver.next().await,
+            Some(ServerMessage::YouAre(fast_client_id)),
+        );
+
+        handle_client_message_subscribe_voters(slow_client_id, client_thread_state.clone()).await;
+        handle_client_message_subscribe_voters(fast_client_id, client_thread_state.clone()).await;
+
+        (
+            client_thread_state,
+            slow_client_id,
+            slow_receiver,
+            fast_receiver,
+        )
+    }
+
+    /// The number of voters updates sent by
+    /// [assert_fast_client_receives_all_voters].
+    const BACKPRESSURE_TEST_UPDATES: usize = 5;
+
+    /// Sends a number of voters updates to the distribution task, and ensures
+    /// that the fast client receives every one of them.
+    async fn assert_fast_client_receives_all_voters(
+        voters_sender: &mut Sender<BitVec<u16>>,
+        fast_receiver: &mut mpsc::Receiver<ServerMessage>,
+    ) {
+        for i in 0..BACKPRESSURE_TEST_UPDATES as u16 {
+            let voters = BitVec::from_vec(vec![i]);
+            assert_eq!(voters_sender.send(voters.clone()).await, Ok(()));
+
+            assert_eq!(
+                timeout(Duration::from_secs(1), fast_receiver.next()).await,
+                Ok(Some(ServerMessage::LatestVoters(voters))),
+            );
+        }
+    }
+
+    /// Drains the messages that have been queued for the slow client, and
+    /// returns how many of them are voters updates.
+    fn count_queued_voters(slow_receiver: &mut mpsc::Receiver<ServerMessage>) -> usize {
+        let mut voters = 0;
+        while let Ok(Some(message)) = slow_receiver.try_next() {
+            if let ServerMessage::LatestVoters(_) = message {
+                voters += 1;
+            }
+        }
+        voters
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_backpressure_strategy_drop_slow() {
+        let (client_thread_state, slow_client_id, mut slow_receiver, mut fast_receiver) =
+            create_slow_and_fast_voters_clients(BackpressureStrategy::DropSlow).await;
+
+        let (mut voters_sender, voters_receiver) = mpsc::channel(1);
+        let mut process_distribute_voters_handle =
+            ProcessDistributeVotersHandlingTask::new(client_thread_state.clone(), voters_receiver);
+
+        assert_fast_client_receives_all_voters(&mut voters_sender, &mut fast_receiver).await;
+
+        // The slow client should still be connected.
+        assert!(client_thread_state
+            .read()
+            .await
+            .clients
+            .contains_key(&slow_client_id));
+
+        // But the updates that it was not ready for should have been dropped
+        // rather than queued.
+        let queued_voters = count_queued_voters(&mut slow_receiver);
+        assert!(queued_voters > 0);
+        assert!(queued_voters < BACKPRESSURE_TEST_UPDATES);
+
+        if let Some(process_distribute_voters_handle) =
+            process_distribute_voters_handle.task_handle.take()
+        {
+            process_distribute_voters_handle.abort();
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_backpressure_strategy_disconnect() {
+        let (client_thread_state, slow_client_id, mut slow_receiver, mut fast_receiver) =
+            create_slow_and_fast_voters_clients(BackpressureStrategy::Disconnect).await;
+
+        let (mut voters_sender, voters_receiver) = mpsc::channel(1);
+        let mut process_distribute_voters_handle =
+            ProcessDistributeVotersHandlingTask::new(client_thread_state.clone(), voters_receiver);
+
+        assert_fast_client_receives_all_voters(&mut voters_sender, &mut fast_receiver).await;
+
+        // The slow client should have been dropped.
+        let client_thread_state_read_lock_guard = client_thread_state.read().await;
+        assert!(!client_thread_state_read_lock_guard
+            .clients
+            .contains_key(&slow_client_id));
+        assert!(!client_thread_state_read_lock_guard
+            .subscribed_voters
+            .contains(&slow_client_id));
+        drop(client_thread_state_read_lock_guard);
+
+        // Only the updates queued before it was dropped should have reached
+        // the slow client, after which its stream is closed.
+        assert!(count_queued_voters(&mut slow_receiver) < BACKPRESSURE_TEST_UPDATES);
+        assert!(matches!(slow_receiver.try_next(), Ok(None)));
+
+        if let Some(process_distribute_voters_handle) =
+            process_distribute_voters_handle.task_handle.take()
+        {
+            process_distribute_voters_handle.abort();
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_backpressure_strategy_block() {
+        let (client_thread_state, slow_client_id, mut slow_receiver, mut fast_receiver) =
+            create_slow_and_fast_voters_clients(BackpressureStrategy::Block).await;
+
+        let (mut voters_sender, voters_receiver) = mpsc::channel(1);
+        let mut process_distribute_voters_handle =
+            ProcessDistributeVotersHandlingTask::new(client_thread_state.clone(), voters_receiver);
+
+        // With the Block strategy the slow client must eventually consume its
+        // messages, otherwise the broadcast will never make progress.
+        let slow_reader_handle = spawn(async move {
+            while slow_receiver.next().await.is_some() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        });
+
+        assert_fast_client_receives_all_voters(&mut voters_sender, &mut fast_receiver).await;
+
+        // The slow client should still be connected.
+        assert!(client_thread_state
+            .read()
+            .await
+            .clients
+            .contains_key(&slow_client_id));
+
+        slow_reader_handle.abort();
+        if let Some(process_distribute_voters_handle) =
+            process_distribute_voters_handle.task_handle.take()
+        {
+            process_distribute_voters_handle.abort();
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_client_thread_state_metrics() {
+        let metrics = PrometheusMetrics::default();
+        let client_thread_state = Arc::new(RwLock::new(
+            create_test_client_thread_state().with_metrics(&metrics),
+        ));
+
+        let connected_clients = metrics
+            .get_gauge("node_validator_connected_clients")
+            .unwrap();
+        let active_subscriptions = metrics
+            .get_gauge("node_validator_active_subscriptions")
+            .unwrap();
+        assert_eq!(connected_clients.get(), 0);
+        assert_eq!(active_subscriptions.get(), 0);
+
+        let (sender_1, _receiver_1) = mpsc::channel(1);
+        let (sender_2, _receiver_2) = mpsc::channel(1);
+
+        let client_id_1 = handle_client_message_connected(sender_1, client_thread_state.clone())
+            .await
+            .unwrap();
+        let client_id_2 = handle_client_message_connected(sender_2, client_thread_state.clone())
+            .await
+            .unwrap();
+        assert_eq!(connected_clients.get(), 2);
+        assert_eq!(active_subscriptions.get(), 0);
+
+        handle_client_message_subscribe_latest_block(client_id_1, client_thread_state.clone())
+            .await;
+        handle_client_message_subscribe_node_identity(client_id_1, client_thread_state.clone())
+            .await;
+        handle_client_message_subscribe_voters(client_id_1, client_thread_state.clone()).await;
+        handle_client_message_subscribe_voters(client_id_2, client_thread_state.clone()).await;
+        assert_eq!(active_subscriptions.get(), 4);
+
+        // Subscribing to the same stream twice should not be counted twice.
+        handle_client_message_subscribe_voters(client_id_2, client_thread_state.clone()).await;
+        assert_eq!(active_subscriptions.get(), 4);
+
+        handle_client_message_disconnected(client_id_1, client_thread_state.clone()).await;
+        assert_eq!(connected_clients.get(), 1);
+        assert_eq!(active_subscriptions.get(), 1);
+
+        handle_client_message_disconnected(client_id_2, client_thread_state.clone()).await;
+        assert_eq!(connected_clients.get(), 0);
+        assert_eq!(active_subscriptions.get(), 0);
+    }
+
     // The following tests codify assumptions being bad on behalf of the Sink
     // and Receivers provided by the async_std library.  The purpose of these
     // tests are to document these assumptions, and add a test to ensure that
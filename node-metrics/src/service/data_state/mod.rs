@@ -1,7 +1,11 @@
 pub mod location_details;
 pub mod node_identity;
 
-use std::{collections::HashSet, iter::zip, sync::Arc};
+use std::{
+    collections::{HashSet, VecDeque},
+    iter::zip,
+    sync::Arc,
+};
 
 use async_lock::RwLock;
 use bitvec::vec::BitVec;
@@ -25,10 +29,14 @@ use hotshot_types::{
 };
 pub use location_details::LocationDetails;
 pub use node_identity::NodeIdentity;
+use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use tokio::{spawn, task::JoinHandle};
 
-use crate::api::node_validator::v0::LeafAndBlock;
+use crate::{
+    api::node_validator::v0::LeafBlockPair,
+    service::storage::{InMemoryStorage, Storage, StorageError},
+};
 
 /// MAX_HISTORY represents the last N records that are stored within the
 /// DataState structure for the various different sample types.
@@ -38,15 +46,41 @@ pub const MAX_HISTORY: usize = 50;
 /// the DataState structure for the voters.
 pub const MAX_VOTERS_HISTORY: usize = 100;
 
+/// [DecideEvent] records a block that has been decided, along with the view
+/// of the leaf that contains it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecideEvent {
+    /// The height of the decided block.
+    pub height: u64,
+    /// The view of the decided leaf.
+    pub view: u64,
+    /// The size of the decided block's payload in bytes.
+    pub size: u64,
+}
+
+/// [BlockDetailWithPayload] pairs the [BlockDetail] of a block with the
+/// encoded bytes of that block's payload.
+pub type BlockDetailWithPayload = (BlockDetail<SeqTypes>, Vec<u8>);
+
 /// [DataState] represents the state of the data that is being stored within
 /// the service.
-#[cfg_attr(test, derive(Default))]
 pub struct DataState {
     latest_blocks: CircularBuffer<MAX_HISTORY, BlockDetail<SeqTypes>>,
     latest_voters: CircularBuffer<MAX_VOTERS_HISTORY, BitVec<u16>>,
     stake_table: StakeTable<BLSPubKey, StateVerKey, CircuitField>,
     // Do we need any other data at the moment?
     node_identity: Vec<NodeIdentity>,
+    recent_decides: VecDeque<DecideEvent>,
+    decide_history: usize,
+    storage: Arc<dyn Storage>,
+    restored_block_height: Option<u64>,
+}
+
+#[cfg(test)]
+impl Default for DataState {
+    fn default() -> Self {
+        Self::new(Default::default(), Default::default(), Default::default())
+    }
 }
 
 impl DataState {
@@ -70,7 +104,60 @@ impl DataState {
             latest_voters,
             stake_table,
             node_identity,
+            recent_decides: VecDeque::new(),
+            decide_history: MAX_HISTORY,
+            storage: Arc::new(InMemoryStorage),
+            restored_block_height: None,
+        }
+    }
+
+    /// [with_decide_history] sets the number of recent decide events that
+    /// are retained.  By default, the last [MAX_HISTORY] decide events are
+    /// retained.
+    pub fn with_decide_history(mut self, decide_history: usize) -> Self {
+        self.decide_history = decide_history;
+        self.recent_decides.truncate(decide_history);
+        self
+    }
+
+    /// [with_storage] sets the [Storage] that the node identities and the
+    /// latest blocks are written through to.  By default, they are only kept
+    /// in memory.
+    pub fn with_storage(mut self, storage: Arc<dyn Storage>) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// [restore_from_storage] loads the node identities and the latest
+    /// blocks that have been persisted in the [Storage] into this
+    /// [DataState].
+    ///
+    /// The voters are not persisted, so the leaves of the restored blocks
+    /// still need to be replayed in order to rebuild them.  Only their voters
+    /// are recorded when they come back around, see
+    /// [restored_block_height](Self::restored_block_height).
+    pub async fn restore_from_storage(&mut self) -> Result<(), StorageError> {
+        for node_identity in self.storage.load_node_identities().await? {
+            self.add_node_identity(node_identity);
+        }
+
+        for block in self.storage.load_latest_blocks().await? {
+            self.latest_blocks.push_back(block);
         }
+        self.restored_block_height = self.storage.latest_block_height().await?;
+
+        Ok(())
+    }
+
+    /// [restored_block_height] is the height of the most recent block that
+    /// has been restored from the [Storage], if any.  Blocks up to and
+    /// including this height have already been processed by a previous run.
+    pub fn restored_block_height(&self) -> Option<u64> {
+        self.restored_block_height
+    }
+
+    pub fn storage(&self) -> Arc<dyn Storage> {
+        self.storage.clone()
     }
 
     pub fn latest_blocks(&self) -> impl Iterator<Item = &BlockDetail<SeqTypes>> {
@@ -89,6 +176,17 @@ impl DataState {
         self.node_identity.iter()
     }
 
+    /// [recent_decides] returns up to `window` of the most recently retained
+    /// decide events, newest first.  Without a window, all retained decide
+    /// events are returned.
+    pub fn recent_decides(&self, window: Option<usize>) -> Vec<DecideEvent> {
+        self.recent_decides
+            .iter()
+            .take(window.unwrap_or(self.decide_history))
+            .cloned()
+            .collect()
+    }
+
     pub fn replace_stake_table(
         &mut self,
         stake_table: StakeTable<BLSPubKey, StateVerKey, CircuitField>,
@@ -128,6 +226,11 @@ impl DataState {
         self.latest_voters.push_back(voters);
     }
 
+    pub fn add_decide_event(&mut self, decide_event: DecideEvent) {
+        self.recent_decides.push_front(decide_event);
+        self.recent_decides.truncate(self.decide_history);
+    }
+
     pub fn add_node_identity(&mut self, identity: NodeIdentity) {
         // We need to check to see if this identity is already in the list,
         // if it is, we will want to replace it.
@@ -162,6 +265,8 @@ impl DataState {
 pub enum ProcessLeafError {
     BlockSendError(SendError),
     VotersSendError(SendError),
+    DecidedBlockSendError(SendError),
+    BlockPayloadSendError(SendError),
 }
 
 impl std::fmt::Display for ProcessLeafError {
@@ -173,6 +278,12 @@ impl std::fmt::Display for ProcessLeafError {
             ProcessLeafError::VotersSendError(err) => {
                 write!(f, "error sending voters to sender: {}", err)
             },
+            ProcessLeafError::DecidedBlockSendError(err) => {
+                write!(f, "error sending decided block detail to sender: {}", err)
+            },
+            ProcessLeafError::BlockPayloadSendError(err) => {
+                write!(f, "error sending block payload to sender: {}", err)
+            },
         }
     }
 }
@@ -182,6 +293,8 @@ impl std::error::Error for ProcessLeafError {
         match self {
             ProcessLeafError::BlockSendError(err) => Some(err),
             ProcessLeafError::VotersSendError(err) => Some(err),
+            ProcessLeafError::DecidedBlockSendError(err) => Some(err),
+            ProcessLeafError::BlockPayloadSendError(err) => Some(err),
         }
     }
 }
@@ -214,22 +327,33 @@ pub fn create_block_detail_from_block(block: &BlockQueryData<SeqTypes>) -> Block
 /// an incoming [Leaf] and update the [DataState] with the new information.
 /// Additionally, the block that is contained within the [Leaf] will be
 /// computed into a [BlockDetail] and sent to the [Sink] so that it can be
-/// processed for real-time considerations.
-async fn process_incoming_leaf_and_block<BDSink, BVSink>(
+/// processed for real-time considerations.  The leaves that we receive from
+/// the availability stream have all been decided already, so the block is
+/// also recorded as a decide event, and sent to the decided block [Sink].
+/// If a block payload [Sink] is provided, the [BlockDetail] is also sent to
+/// it alongside the encoded bytes of the block's payload.
+///
+/// Blocks that have already been restored from the [Storage] only contribute
+/// their voters, since everything else about them has been recorded before.
+async fn process_incoming_leaf_and_block<BDSink, BVSink, BPSink>(
     leaf: Leaf1QueryData<SeqTypes>,
     block: BlockQueryData<SeqTypes>,
     data_state: Arc<RwLock<DataState>>,
     mut block_sender: BDSink,
     mut voters_sender: BVSink,
+    mut decided_block_sender: BDSink,
+    block_payload_sender: Option<BPSink>,
 ) -> Result<(), ProcessLeafError>
 where
     Header: BlockHeader<SeqTypes> + QueryableHeader<SeqTypes> + ExplorerHeader<SeqTypes>,
     Payload: BlockPayload<SeqTypes>,
     BDSink: Sink<BlockDetail<SeqTypes>, Error = SendError> + Unpin,
     BVSink: Sink<BitVec<u16>, Error = SendError> + Unpin,
+    BPSink: Sink<BlockDetailWithPayload, Error = SendError> + Unpin,
 {
     let block_detail = create_block_detail_from_block(&block);
     let block_detail_copy = create_block_detail_from_block(&block);
+    let decided_block_detail = create_block_detail_from_block(&block);
 
     let certificate = leaf.leaf().justify_qc();
     let signatures = &certificate.signatures;
@@ -291,25 +415,69 @@ where
         },
     );
 
-    data_state_write_lock_guard
-        .latest_blocks
-        .push_back(block_detail);
     data_state_write_lock_guard
         .latest_voters
         .push_back(voters_bitvec.clone());
 
+    if data_state_write_lock_guard
+        .restored_block_height
+        .is_some_and(|restored_block_height| block_detail.height <= restored_block_height)
+    {
+        drop(data_state_write_lock_guard);
+
+        if let Err(err) = voters_sender.send(voters_bitvec).await {
+            // We have an error that prevents us from continuing
+            return Err(ProcessLeafError::VotersSendError(err));
+        }
+
+        return Ok(());
+    }
+
+    data_state_write_lock_guard
+        .latest_blocks
+        .push_back(block_detail);
+
+    data_state_write_lock_guard.add_decide_event(DecideEvent {
+        height: decided_block_detail.height,
+        view: *leaf.leaf().view_number(),
+        size: decided_block_detail.size,
+    });
+
+    let storage = data_state_write_lock_guard.storage();
     drop(data_state_write_lock_guard);
 
+    // Failing to persist the block only affects what can be restored after
+    // a restart, so processing carries on regardless.
+    if let Err(err) = storage.store_block(&block_detail_copy).await {
+        tracing::warn!("failed to persist block detail: {}", err);
+    }
+
     if let Err(err) = block_sender.send(block_detail_copy).await {
         // We have an error that prevents us from continuing
         return Err(ProcessLeafError::BlockSendError(err));
     }
 
+    if let Some(mut block_payload_sender) = block_payload_sender {
+        let block_detail_with_payload = (
+            create_block_detail_from_block(&block),
+            block.payload().encode().to_vec(),
+        );
+        if let Err(err) = block_payload_sender.send(block_detail_with_payload).await {
+            // We have an error that prevents us from continuing
+            return Err(ProcessLeafError::BlockPayloadSendError(err));
+        }
+    }
+
     if let Err(err) = voters_sender.send(voters_bitvec).await {
         // We have an error that prevents us from continuing
         return Err(ProcessLeafError::VotersSendError(err));
     }
 
+    if let Err(err) = decided_block_sender.send(decided_block_detail).await {
+        // We have an error that prevents us from continuing
+        return Err(ProcessLeafError::DecidedBlockSendError(err));
+    }
+
     Ok(())
 }
 
@@ -326,22 +494,31 @@ impl ProcessLeafAndBlockPairStreamTask {
     /// Calling this function will create an asynchronous task that will start
     /// processing immediately. The handle for the task will be stored within
     /// the returned structure.
-    pub fn new<S, K1, K2>(
+    ///
+    /// The block payload sender is optional, as encoding and forwarding the
+    /// payload of every block is only worthwhile when clients are able to
+    /// receive it.
+    pub fn new<S, K1, K2, K3>(
         leaf_receiver: S,
         data_state: Arc<RwLock<DataState>>,
         block_detail_sender: K1,
         voters_sender: K2,
+        decided_block_detail_sender: K1,
+        block_payload_sender: Option<K3>,
     ) -> Self
     where
-        S: Stream<Item = LeafAndBlock<SeqTypes>> + Send + Sync + Unpin + 'static,
+        S: Stream<Item = LeafBlockPair<SeqTypes>> + Send + Sync + Unpin + 'static,
         K1: Sink<BlockDetail<SeqTypes>, Error = SendError> + Clone + Send + Sync + Unpin + 'static,
         K2: Sink<BitVec<u16>, Error = SendError> + Clone + Send + Sync + Unpin + 'static,
+        K3: Sink<BlockDetailWithPayload, Error = SendError> + Clone + Send + Sync + Unpin + 'static,
     {
         let task_handle = spawn(Self::process_leaf_stream(
             leaf_receiver,
             data_state.clone(),
             block_detail_sender,
             voters_sender,
+            decided_block_detail_sender,
+            block_payload_sender,
         ));
 
         Self {
@@ -351,21 +528,24 @@ impl ProcessLeafAndBlockPairStreamTask {
 
     /// [process_leaf_stream] allows for the consumption of a [Stream] when
     /// attempting to process new incoming [Leaf]s.
-    async fn process_leaf_stream<S, BDSink, BVSink>(
+    async fn process_leaf_stream<S, BDSink, BVSink, BPSink>(
         mut stream: S,
         data_state: Arc<RwLock<DataState>>,
         block_sender: BDSink,
         voters_senders: BVSink,
+        decided_block_sender: BDSink,
+        block_payload_sender: Option<BPSink>,
     ) where
-        S: Stream<Item = LeafAndBlock<SeqTypes>> + Unpin,
+        S: Stream<Item = LeafBlockPair<SeqTypes>> + Unpin,
         Header: BlockHeader<SeqTypes> + QueryableHeader<SeqTypes> + ExplorerHeader<SeqTypes>,
         Payload: BlockPayload<SeqTypes>,
         BDSink: Sink<BlockDetail<SeqTypes>, Error = SendError> + Clone + Unpin,
         BVSink: Sink<BitVec<u16>, Error = SendError> + Clone + Unpin,
+        BPSink: Sink<BlockDetailWithPayload, Error = SendError> + Clone + Unpin,
     {
         loop {
             let leaf_result = stream.next().await;
-            let (leaf, block) = if let Some(pair) = leaf_result {
+            let LeafBlockPair { leaf, block, .. } = if let Some(pair) = leaf_result {
                 pair
             } else {
                 // We have reached the end of the stream
@@ -379,6 +559,8 @@ impl ProcessLeafAndBlockPairStreamTask {
                 data_state.clone(),
                 block_sender.clone(),
                 voters_senders.clone(),
+                decided_block_sender.clone(),
+                block_payload_sender.clone(),
             )
             .await
             {
@@ -395,6 +577,12 @@ impl ProcessLeafAndBlockPairStreamTask {
                     ProcessLeafError::VotersSendError(_) => {
                         panic!("ProcessLeafStreamTask: process_incoming_leaf failed, underlying sink is closed, voters will stagnate: {}", err)
                     },
+                    ProcessLeafError::DecidedBlockSendError(_) => {
+                        panic!("ProcessLeafStreamTask: process_incoming_leaf failed, underlying sink is closed, decided blocks will stagnate: {}", err)
+                    },
+                    ProcessLeafError::BlockPayloadSendError(_) => {
+                        panic!("ProcessLeafStreamTask: process_incoming_leaf failed, underlying sink is closed, block payloads will stagnate: {}", err)
+                    },
                 }
             }
         }
@@ -457,6 +645,13 @@ where
 {
     let mut data_state_write_lock_guard = data_state.write().await;
     data_state_write_lock_guard.add_node_identity(node_identity.clone());
+    let storage = data_state_write_lock_guard.storage();
+    drop(data_state_write_lock_guard);
+
+    if let Err(err) = storage.store_node_identity(&node_identity).await {
+        tracing::warn!("failed to persist node identity: {}", err);
+    }
+
     node_identity_sender.send(node_identity).await?;
 
     Ok(())
@@ -572,14 +767,22 @@ mod tests {
         testing::mocks::MockVersions,
     };
     use hotshot_types::{
-        data::Leaf2, signature_key::BLSPubKey, traits::signature_key::SignatureKey,
+        data::{Leaf, Leaf2, QuorumProposal, ViewNumber},
+        signature_key::BLSPubKey,
+        traits::{node_implementation::ConsensusTime, signature_key::SignatureKey},
     };
     use tokio::time::timeout;
     use url::Url;
 
-    use super::{DataState, ProcessLeafAndBlockPairStreamTask};
-    use crate::service::data_state::{
-        LocationDetails, NodeIdentity, ProcessNodeIdentityStreamTask,
+    use super::{
+        BlockDetailWithPayload, DataState, DecideEvent, ProcessLeafAndBlockPairStreamTask,
+    };
+    use crate::{
+        api::node_validator::v0::LeafBlockPair,
+        service::{
+            data_state::{LocationDetails, NodeIdentity, ProcessNodeIdentityStreamTask},
+            storage::SqliteStorage,
+        },
     };
 
     #[tokio::test(flavor = "multi_thread")]
@@ -608,6 +811,7 @@ mod tests {
         let data_state = Arc::new(RwLock::new(data_state));
         let (block_sender, block_receiver) = futures::channel::mpsc::channel(1);
         let (voters_sender, voters_receiver) = futures::channel::mpsc::channel(1);
+        let (decided_block_sender, _decided_block_receiver) = futures::channel::mpsc::channel(1);
         let (leaf_sender, leaf_receiver) = futures::channel::mpsc::channel(1);
 
         let mut process_leaf_stream_task_handle = ProcessLeafAndBlockPairStreamTask::new(
@@ -615,6 +819,8 @@ mod tests {
             data_state.clone(),
             block_sender,
             voters_sender,
+            decided_block_sender,
+            None::<mpsc::Sender<BlockDetailWithPayload>>,
         );
 
         {
@@ -641,13 +847,16 @@ mod tests {
         // We should be able to send a leaf without issue
         assert_eq!(
             leaf_sender
-                .send((
-                    Leaf1QueryData::new(
-                        sample_leaf.clone().to_leaf_unsafe(),
-                        sample_leaf.justify_qc().to_qc()
-                    ),
-                    sample_block_query_data
-                ))
+                .send(
+                    LeafBlockPair::try_new(
+                        Leaf1QueryData::new(
+                            sample_leaf.clone().to_leaf_unsafe(),
+                            sample_leaf.justify_qc().to_qc()
+                        ),
+                        sample_block_query_data
+                    )
+                    .unwrap()
+                )
                 .await,
             Ok(()),
         );
@@ -683,6 +892,197 @@ mod tests {
         .is_ok());
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_process_leaf_stream_decided_blocks() {
+        let data_state: DataState = Default::default();
+        let data_state = Arc::new(RwLock::new(data_state));
+        let (block_sender, mut block_receiver) = futures::channel::mpsc::channel(10);
+        let (voters_sender, _voters_receiver) = futures::channel::mpsc::channel(10);
+        let (decided_block_sender, mut decided_block_receiver) =
+            futures::channel::mpsc::channel(10);
+        let (mut leaf_sender, leaf_receiver) = futures::channel::mpsc::channel(10);
+
+        let _process_leaf_stream_task_handle = ProcessLeafAndBlockPairStreamTask::new(
+            leaf_receiver,
+            data_state.clone(),
+            block_sender,
+            voters_sender,
+            decided_block_sender,
+            None::<mpsc::Sender<BlockDetailWithPayload>>,
+        );
+
+        let validated_state = ValidatedState {
+            block_merkle_tree: BlockMerkleTree::new(32),
+            fee_merkle_tree: FeeMerkleTree::new(32),
+            reward_merkle_tree: RewardMerkleTree::new(32),
+            chain_config: ChainConfig::default().into(),
+        };
+        let instance_state = NodeState::mock();
+
+        let genesis_leaf = Leaf2::genesis::<TestVersions>(&validated_state, &instance_state).await;
+        let genesis_block =
+            BlockQueryData::genesis::<MockVersions>(&validated_state, &instance_state).await;
+
+        // View 3 fails, so the leaf in view 4 extends the leaf in view 2.
+        let views_and_parent_views = [(1, 0), (2, 1), (4, 2)];
+        for (view, parent_view) in views_and_parent_views {
+            let mut justify_qc = genesis_leaf.justify_qc().to_qc();
+            justify_qc.view_number = ViewNumber::new(parent_view);
+
+            // We use the block height to identify the leaf that the block
+            // belongs to.
+            let mut header = genesis_block.header().clone();
+            *header.height_mut() = view;
+
+            let leaf = Leaf::from_quorum_proposal(&QuorumProposal {
+                block_header: header.clone(),
+                view_number: ViewNumber::new(view),
+                justify_qc: justify_qc.clone(),
+                upgrade_certificate: None,
+                proposal_certificate: None,
+            });
+
+            let block = BlockQueryData::new(header, genesis_block.payload().clone());
+
+            assert_eq!(
+                leaf_sender
+                    .send(
+                        LeafBlockPair::try_new(Leaf1QueryData::new(leaf, justify_qc), block)
+                            .unwrap()
+                    )
+                    .await,
+                Ok(())
+            );
+
+            // The leaves of the availability stream are decided, so every
+            // block is delivered on both the latest and the decided block
+            // streams, without waiting for later leaves.
+            let next_block = timeout(Duration::from_secs(1), block_receiver.next()).await;
+            assert_eq!(next_block.unwrap().unwrap().height, view);
+            let decided_block =
+                timeout(Duration::from_secs(1), decided_block_receiver.next()).await;
+            assert_eq!(decided_block.unwrap().unwrap().height, view);
+        }
+
+        // The decide events are retained, newest first.
+        let data_state = data_state.read().await;
+        let decides = data_state.recent_decides(None);
+        assert_eq!(
+            decides
+                .iter()
+                .map(|decide| (decide.height, decide.view))
+                .collect::<Vec<_>>(),
+            vec![(4, 4), (2, 2), (1, 1)]
+        );
+        assert_eq!(decides[0].size, decides[2].size);
+        assert_eq!(data_state.recent_decides(Some(2)), decides[..2].to_vec());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_process_leaf_stream_skips_restored_blocks() {
+        let mut data_state: DataState = Default::default();
+        // The block at height 1 has been restored from a previous run.
+        data_state.restored_block_height = Some(1);
+        let data_state = Arc::new(RwLock::new(data_state));
+        let (block_sender, mut block_receiver) = futures::channel::mpsc::channel(10);
+        let (voters_sender, mut voters_receiver) = futures::channel::mpsc::channel(10);
+        let (decided_block_sender, mut decided_block_receiver) =
+            futures::channel::mpsc::channel(10);
+        let (mut leaf_sender, leaf_receiver) = futures::channel::mpsc::channel(10);
+
+        let _process_leaf_stream_task_handle = ProcessLeafAndBlockPairStreamTask::new(
+            leaf_receiver,
+            data_state.clone(),
+            block_sender,
+            voters_sender,
+            decided_block_sender,
+            None::<mpsc::Sender<BlockDetailWithPayload>>,
+        );
+
+        let validated_state = ValidatedState {
+            block_merkle_tree: BlockMerkleTree::new(32),
+            fee_merkle_tree: FeeMerkleTree::new(32),
+            reward_merkle_tree: RewardMerkleTree::new(32),
+            chain_config: ChainConfig::default().into(),
+        };
+        let instance_state = NodeState::mock();
+
+        let genesis_leaf = Leaf2::genesis::<TestVersions>(&validated_state, &instance_state).await;
+        let genesis_block =
+            BlockQueryData::genesis::<MockVersions>(&validated_state, &instance_state).await;
+
+        for height in [1, 2] {
+            let justify_qc = genesis_leaf.justify_qc().to_qc();
+            let mut header = genesis_block.header().clone();
+            *header.height_mut() = height;
+
+            let leaf = Leaf::from_quorum_proposal(&QuorumProposal {
+                block_header: header.clone(),
+                view_number: ViewNumber::new(height),
+                justify_qc: justify_qc.clone(),
+                upgrade_certificate: None,
+                proposal_certificate: None,
+            });
+            let block = BlockQueryData::new(header, genesis_block.payload().clone());
+
+            assert_eq!(
+                leaf_sender
+                    .send(
+                        LeafBlockPair::try_new(Leaf1QueryData::new(leaf, justify_qc), block)
+                            .unwrap()
+                    )
+                    .await,
+                Ok(())
+            );
+
+            // Every replayed leaf contributes its voters.
+            let next_voters = timeout(Duration::from_secs(1), voters_receiver.next()).await;
+            assert!(next_voters.unwrap().is_some());
+        }
+
+        // Only the block that has not been restored is forwarded.
+        let next_block = timeout(Duration::from_secs(1), block_receiver.next()).await;
+        assert_eq!(next_block.unwrap().unwrap().height, 2);
+        let decided_block = timeout(Duration::from_secs(1), decided_block_receiver.next()).await;
+        assert_eq!(decided_block.unwrap().unwrap().height, 2);
+
+        let data_state = data_state.read().await;
+        assert_eq!(data_state.restored_block_height(), Some(1));
+        assert_eq!(
+            data_state
+                .latest_blocks()
+                .map(|block| block.height)
+                .collect::<Vec<_>>(),
+            vec![2]
+        );
+        assert_eq!(data_state.latest_voters().count(), 2);
+        assert_eq!(data_state.recent_decides(None).len(), 1);
+    }
+
+    #[test]
+    fn test_recent_decides_retention() {
+        let mut data_state = DataState::default().with_decide_history(3);
+        for height in 0..5 {
+            data_state.add_decide_event(DecideEvent {
+                height,
+                view: height + 10,
+                size: 0,
+            });
+        }
+
+        // Only the newest decide events are retained, and a window cannot
+        // extend beyond them.
+        let heights = |decides: Vec<DecideEvent>| {
+            decides
+                .into_iter()
+                .map(|decide| decide.height)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(heights(data_state.recent_decides(None)), vec![4, 3, 2]);
+        assert_eq!(heights(data_state.recent_decides(Some(1))), vec![4]);
+        assert_eq!(heights(data_state.recent_decides(Some(10))), vec![4, 3, 2]);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_process_node_identity_stream() {
         let data_state: DataState = Default::default();
@@ -807,4 +1207,62 @@ mod tests {
             process_node_identity_task_handle.abort();
         }
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_node_identity_restored_from_storage() {
+        let tmp_dir = tempfile::TempDir::new().unwrap();
+        let storage = Arc::new(
+            SqliteStorage::connect(tmp_dir.path().join("node-metrics.sqlite"))
+                .await
+                .unwrap(),
+        );
+
+        let data_state = DataState::default().with_storage(storage.clone());
+        let data_state = Arc::new(RwLock::new(data_state));
+        let (mut node_identity_sender_1, node_identity_receiver_1) = mpsc::channel(1);
+        let (node_identity_sender_2, mut node_identity_receiver_2) = mpsc::channel(1);
+
+        let mut process_node_identity_task_handle = ProcessNodeIdentityStreamTask::new(
+            node_identity_receiver_1,
+            data_state.clone(),
+            node_identity_sender_2,
+        );
+
+        let public_key = BLSPubKey::generated_from_seed_indexed([0; 32], 0).0;
+        let node_identity = NodeIdentity::new(
+            public_key,
+            Some("name".to_string()),
+            Some(Url::parse("https://example.com/").unwrap()),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(
+            node_identity_sender_1.send(node_identity.clone()).await,
+            Ok(())
+        );
+        assert_eq!(
+            node_identity_receiver_2.next().await,
+            Some(node_identity.clone())
+        );
+
+        // A fresh data state, as created on restart, picks the node identity
+        // back up from the storage.
+        let mut restored_data_state = DataState::default().with_storage(storage);
+        assert_eq!(restored_data_state.node_identity().count(), 0);
+        restored_data_state.restore_from_storage().await.unwrap();
+        assert_eq!(
+            restored_data_state.node_identity().collect::<Vec<_>>(),
+            vec![&node_identity]
+        );
+
+        if let Some(process_node_identity_task_handle) =
+            process_node_identity_task_handle.task_handle.take()
+        {
+            process_node_identity_task_handle.abort();
+        }
+    }
 }
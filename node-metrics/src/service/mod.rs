@@ -4,3 +4,4 @@ pub mod client_state;
 pub mod data_state;
 pub mod node_type;
 pub mod server_message;
+pub mod storage;
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use bitvec::vec::BitVec;
 use espresso_types::SeqTypes;
-use hotshot_query_service::explorer::{BlockDetail, ExplorerHistograms};
+use hotshot_query_service::explorer::{BlockDetail, ExplorerHistograms, ProposerId};
 use serde::{Deserialize, Serialize};
 
 use super::{client_id::ClientId, data_state::NodeIdentity};
@@ -26,6 +26,15 @@ pub enum ServerMessage {
     /// voters that have arrived.
     LatestVoters(BitVec<u16>),
 
+    /// LatestBlockWithPayload is a message that is meant to show the most
+    /// recent block that has arrived, along with the encoded bytes of its
+    /// payload.
+    LatestBlockWithPayload(Arc<BlockDetail<SeqTypes>>, Arc<Vec<u8>>),
+
+    /// DecidedBlock is a message that is meant to show a block once it has
+    /// been decided.
+    DecidedBlock(Arc<BlockDetail<SeqTypes>>),
+
     /// BlocksSnapshot is a message that is sent in response to a request for
     /// the snapshot of block information that is available.
     BlocksSnapshot(Arc<Vec<BlockDetail<SeqTypes>>>),
@@ -50,6 +59,11 @@ impl PartialEq for ServerMessage {
             (Self::LatestBlock(lhs), Self::LatestBlock(rhs)) => lhs == rhs,
             (Self::LatestNodeIdentity(lhs), Self::LatestNodeIdentity(rhs)) => lhs == rhs,
             (Self::LatestVoters(lhs), Self::LatestVoters(rhs)) => lhs == rhs,
+            (
+                Self::LatestBlockWithPayload(lhs_block, lhs_payload),
+                Self::LatestBlockWithPayload(rhs_block, rhs_payload),
+            ) => lhs_block == rhs_block && lhs_payload == rhs_payload,
+            (Self::DecidedBlock(lhs), Self::DecidedBlock(rhs)) => lhs == rhs,
             (Self::BlocksSnapshot(lhs), Self::BlocksSnapshot(rhs)) => lhs == rhs,
             (Self::NodeIdentitySnapshot(lhs), Self::NodeIdentitySnapshot(rhs)) => lhs == rhs,
             (Self::HistogramSnapshot(_), Self::HistogramSnapshot(_)) => false,
@@ -59,5 +73,84 @@ impl PartialEq for ServerMessage {
     }
 }
 
+/// [BlockProducer] represents the number of blocks that a single proposer
+/// has produced within the latest blocks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockProducer {
+    pub proposer_id: ProposerId<SeqTypes>,
+    pub num_blocks: u64,
+}
+
+/// [NetworkSnapshot] is a point-in-time view of the full network map, as
+/// held by the service.  It is sent in response to a one-shot snapshot
+/// request, for clients that do not want to follow the streams.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NetworkSnapshot {
+    /// The height of the latest block that this snapshot reflects, if any
+    /// block has been received yet.  Clients can use this to detect a stale
+    /// snapshot.
+    pub block_height: Option<u64>,
+
+    /// The current node identity table.
+    pub node_identity: Vec<NodeIdentity>,
+
+    /// The latest block that has arrived.
+    pub latest_block: Option<BlockDetail<SeqTypes>>,
+
+    /// The proposers that have produced the most of the latest blocks, most
+    /// productive first.
+    pub top_producers: Vec<BlockProducer>,
+
+    /// The histogram summaries over the latest blocks.
+    pub histograms: ExplorerHistograms,
+}
+
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use std::sync::Arc;
+
+    use bitvec::vec::BitVec;
+    use hotshot_types::{signature_key::BLSPubKey, traits::signature_key::SignatureKey};
+    use vbs::{bincode_serializer::BincodeSerializer, BinarySerializer};
+
+    use super::ServerMessage;
+    use crate::{
+        api::node_validator::v0::Version01,
+        service::{client_id::ClientId, data_state::NodeIdentity},
+    };
+
+    #[test]
+    fn test_server_message_encodings() {
+        let public_key = BLSPubKey::generated_from_seed_indexed([0; 32], 0).0;
+        let voters = BitVec::<u16>::repeat(true, 100);
+        let messages = [
+            ServerMessage::YouAre(ClientId::from_count(1)),
+            ServerMessage::LatestVoters(voters.clone()),
+            ServerMessage::VotersSnapshot(Arc::new(vec![voters; 10])),
+            ServerMessage::NodeIdentitySnapshot(Arc::new(vec![NodeIdentity::from_public_key(
+                public_key,
+            )])),
+        ];
+
+        for message in messages.iter() {
+            // A client accepting JSON decodes the original encoding.
+            let json = serde_json::to_vec(message).unwrap();
+            let from_json: ServerMessage = serde_json::from_slice(&json).unwrap();
+            assert_eq!(*message, from_json);
+
+            // Any other client decodes the compact binary encoding.
+            let binary = BincodeSerializer::<Version01>::serialize(message).unwrap();
+            let from_binary: ServerMessage =
+                BincodeSerializer::<Version01>::deserialize(&binary).unwrap();
+            assert_eq!(*message, from_binary);
+        }
+
+        let voters_snapshot = &messages[2];
+        assert!(
+            BincodeSerializer::<Version01>::serialize(voters_snapshot)
+                .unwrap()
+                .len()
+                < serde_json::to_vec(voters_snapshot).unwrap().len()
+        );
+    }
+}
@@ -0,0 +1,104 @@
+pub mod sqlite;
+
+use async_trait::async_trait;
+use espresso_types::SeqTypes;
+use hotshot_query_service::explorer::BlockDetail;
+pub use sqlite::SqliteStorage;
+
+use super::data_state::NodeIdentity;
+
+/// [StorageError] represents the errors that can occur when loading or
+/// storing the state of the service.
+#[derive(Debug)]
+pub enum StorageError {
+    Sqlite(sqlx::Error),
+    Encoding(serde_json::Error),
+}
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageError::Sqlite(err) => write!(f, "sqlite error: {}", err),
+            StorageError::Encoding(err) => write!(f, "encoding error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StorageError::Sqlite(err) => Some(err),
+            StorageError::Encoding(err) => Some(err),
+        }
+    }
+}
+
+impl From<sqlx::Error> for StorageError {
+    fn from(err: sqlx::Error) -> Self {
+        StorageError::Sqlite(err)
+    }
+}
+
+impl From<serde_json::Error> for StorageError {
+    fn from(err: serde_json::Error) -> Self {
+        StorageError::Encoding(err)
+    }
+}
+
+/// [Storage] persists the parts of the network map that cannot be cheaply
+/// recovered on restart: the node identity table, and the most recent
+/// [MAX_HISTORY](super::data_state::MAX_HISTORY) blocks.
+///
+/// The service keeps its working state in the
+/// [DataState](super::data_state::DataState), and only writes through to the
+/// [Storage] so that it can restore that state when it is restarted.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// [load_node_identities] returns every persisted [NodeIdentity].
+    async fn load_node_identities(&self) -> Result<Vec<NodeIdentity>, StorageError>;
+
+    /// [load_latest_blocks] returns the persisted blocks, oldest first.
+    async fn load_latest_blocks(&self) -> Result<Vec<BlockDetail<SeqTypes>>, StorageError>;
+
+    /// [latest_block_height] returns the height of the most recent persisted
+    /// block, if any block has been persisted.
+    async fn latest_block_height(&self) -> Result<Option<u64>, StorageError>;
+
+    /// [store_node_identity] persists the given [NodeIdentity], replacing
+    /// any identity previously persisted for the same public key.
+    async fn store_node_identity(&self, node_identity: &NodeIdentity) -> Result<(), StorageError>;
+
+    /// [store_block] persists the given block, evicting any block that no
+    /// longer belongs to the most recent
+    /// [MAX_HISTORY](super::data_state::MAX_HISTORY) blocks.
+    async fn store_block(&self, block: &BlockDetail<SeqTypes>) -> Result<(), StorageError>;
+}
+
+/// [InMemoryStorage] is the [Storage] that keeps the state of the service in
+/// memory only.  As the [DataState](super::data_state::DataState) already
+/// holds that state, nothing is written, and nothing survives a restart.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InMemoryStorage;
+
+#[async_trait]
+impl Storage for InMemoryStorage {
+    async fn load_node_identities(&self) -> Result<Vec<NodeIdentity>, StorageError> {
+        Ok(vec![])
+    }
+
+    async fn load_latest_blocks(&self) -> Result<Vec<BlockDetail<SeqTypes>>, StorageError> {
+        Ok(vec![])
+    }
+
+    async fn latest_block_height(&self) -> Result<Option<u64>, StorageError> {
+        Ok(None)
+    }
+
+    async fn store_node_identity(&self, _node_identity: &NodeIdentity) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    async fn store_block(&self, _block: &BlockDetail<SeqTypes>) -> Result<(), StorageError> {
+        Ok(())
+    }
+}
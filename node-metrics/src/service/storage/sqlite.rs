@@ -0,0 +1,224 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+use espresso_types::SeqTypes;
+use hotshot_query_service::explorer::BlockDetail;
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    SqlitePool,
+};
+
+use super::{Storage, StorageError};
+use crate::service::data_state::{NodeIdentity, MAX_HISTORY};
+
+/// [SqliteStorage] is a [Storage] that persists the state of the service in
+/// a SQLite database, so that it survives a restart.
+///
+/// The database holds a row per node identity, keyed by public key, and a
+/// bounded ring of the most recent [MAX_HISTORY] blocks, keyed by height.
+/// Both are stored as JSON.
+#[derive(Debug, Clone)]
+pub struct SqliteStorage {
+    pool: SqlitePool,
+}
+
+impl SqliteStorage {
+    /// [connect] opens the SQLite database at the given path, creating it
+    /// and its tables if they do not exist yet.
+    pub async fn connect(path: impl AsRef<Path>) -> Result<Self, StorageError> {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true);
+        let pool = SqlitePoolOptions::new().connect_with(options).await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS node_identity (
+                public_key TEXT PRIMARY KEY,
+                data TEXT NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await?;
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS block_detail (
+                height INTEGER PRIMARY KEY,
+                data TEXT NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl Storage for SqliteStorage {
+    async fn load_node_identities(&self) -> Result<Vec<NodeIdentity>, StorageError> {
+        let rows = sqlx::query_scalar::<_, String>("SELECT data FROM node_identity ORDER BY rowid")
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter()
+            .map(|data| serde_json::from_str(data).map_err(StorageError::from))
+            .collect()
+    }
+
+    async fn load_latest_blocks(&self) -> Result<Vec<BlockDetail<SeqTypes>>, StorageError> {
+        let rows = sqlx::query_scalar::<_, String>(
+            "SELECT data FROM block_detail ORDER BY height DESC LIMIT $1",
+        )
+        .bind(MAX_HISTORY as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .rev()
+            .map(|data| serde_json::from_str(data).map_err(StorageError::from))
+            .collect()
+    }
+
+    async fn latest_block_height(&self) -> Result<Option<u64>, StorageError> {
+        let height = sqlx::query_scalar::<_, Option<i64>>("SELECT MAX(height) FROM block_detail")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(height.map(|height| height as u64))
+    }
+
+    async fn store_node_identity(&self, node_identity: &NodeIdentity) -> Result<(), StorageError> {
+        let public_key = serde_json::to_string(node_identity.public_key())?;
+        let data = serde_json::to_string(node_identity)?;
+
+        sqlx::query(
+            "INSERT INTO node_identity (public_key, data) VALUES ($1, $2)
+                ON CONFLICT (public_key) DO UPDATE SET data = excluded.data",
+        )
+        .bind(public_key)
+        .bind(data)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn store_block(&self, block: &BlockDetail<SeqTypes>) -> Result<(), StorageError> {
+        let data = serde_json::to_string(block)?;
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("INSERT OR REPLACE INTO block_detail (height, data) VALUES ($1, $2)")
+            .bind(block.height as i64)
+            .bind(data)
+            .execute(&mut *tx)
+            .await?;
+
+        // Only the most recent blocks are kept around.
+        sqlx::query(
+            "DELETE FROM block_detail WHERE height NOT IN (
+                SELECT height FROM block_detail ORDER BY height DESC LIMIT $1
+            )",
+        )
+        .bind(MAX_HISTORY as i64)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use espresso_types::{
+        v0_1::RewardMerkleTree, v0_99::ChainConfig, BlockMerkleTree, FeeMerkleTree, NodeState,
+        ValidatedState,
+    };
+    use hotshot_query_service::{availability::BlockQueryData, testing::mocks::MockVersions};
+    use hotshot_types::{signature_key::BLSPubKey, traits::signature_key::SignatureKey};
+    use tempfile::TempDir;
+
+    use super::SqliteStorage;
+    use crate::service::{
+        data_state::{create_block_detail_from_block, NodeIdentity, MAX_HISTORY},
+        storage::Storage,
+    };
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_sqlite_storage_survives_reconnect() {
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.path().join("node-metrics.sqlite");
+
+        let validated_state = ValidatedState {
+            block_merkle_tree: BlockMerkleTree::new(32),
+            fee_merkle_tree: FeeMerkleTree::new(32),
+            reward_merkle_tree: RewardMerkleTree::new(32),
+            chain_config: ChainConfig::default().into(),
+        };
+        let instance_state = NodeState::mock();
+        let genesis_block =
+            BlockQueryData::genesis::<MockVersions>(&validated_state, &instance_state).await;
+        let make_block = |height: u64| {
+            let mut header = genesis_block.header().clone();
+            *header.height_mut() = height;
+            create_block_detail_from_block(&BlockQueryData::new(
+                header,
+                genesis_block.payload().clone(),
+            ))
+        };
+
+        let public_key_1 = BLSPubKey::generated_from_seed_indexed([0; 32], 0).0;
+        let public_key_2 = BLSPubKey::generated_from_seed_indexed([0; 32], 1).0;
+        let node_identity_1 = NodeIdentity::from_public_key(public_key_1);
+        let node_identity_2 = NodeIdentity::from_public_key(public_key_2);
+        let node_identity_1_named = NodeIdentity::new(
+            public_key_1,
+            Some("sequencer0".to_string()),
+            Some("https://example.com/".parse().unwrap()),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+
+        {
+            let storage = SqliteStorage::connect(&path).await.unwrap();
+            assert_eq!(storage.load_node_identities().await.unwrap(), vec![]);
+            assert!(storage.load_latest_blocks().await.unwrap().is_empty());
+            assert_eq!(storage.latest_block_height().await.unwrap(), None);
+
+            storage.store_node_identity(&node_identity_1).await.unwrap();
+            storage.store_node_identity(&node_identity_2).await.unwrap();
+            // Storing an identity again replaces it, without reordering.
+            storage
+                .store_node_identity(&node_identity_1_named)
+                .await
+                .unwrap();
+
+            for height in 0..(MAX_HISTORY as u64 + 10) {
+                storage.store_block(&make_block(height)).await.unwrap();
+            }
+        }
+
+        let storage = SqliteStorage::connect(&path).await.unwrap();
+        assert_eq!(
+            storage.load_node_identities().await.unwrap(),
+            vec![node_identity_1_named, node_identity_2]
+        );
+
+        // Only the most recent blocks are kept, oldest first.
+        let heights: Vec<_> = storage
+            .load_latest_blocks()
+            .await
+            .unwrap()
+            .iter()
+            .map(|block| block.height)
+            .collect();
+        assert_eq!(heights, (10..(MAX_HISTORY as u64 + 10)).collect::<Vec<_>>());
+        assert_eq!(
+            storage.latest_block_height().await.unwrap(),
+            Some(MAX_HISTORY as u64 + 9)
+        );
+    }
+}
@@ -20,6 +20,7 @@ use futures::{
     future::{BoxFuture, Future, FutureExt},
     stream::BoxStream,
 };
+use hotshot::helpers::TaskLogLevels;
 use hotshot_events_service::events_source::{
     EventFilterSet, EventsSource, EventsStreamer, StartupInfo,
 };
@@ -643,6 +644,10 @@ impl<N: ConnectedNetwork<PubKey>, D: Sync, V: Versions, P: SequencerPersistence>
     async fn get_config(&self) -> PublicNetworkConfig {
         self.as_ref().network_config().await.into()
     }
+
+    async fn task_log_levels(&self) -> TaskLogLevels {
+        self.as_ref().task_log_levels().await
+    }
 }
 
 impl<N: ConnectedNetwork<PubKey>, V: Versions, P: SequencerPersistence> HotShotConfigDataSource
@@ -651,6 +656,15 @@ impl<N: ConnectedNetwork<PubKey>, V: Versions, P: SequencerPersistence> HotShotC
     async fn get_config(&self) -> PublicNetworkConfig {
         self.network_config().await.into()
     }
+
+    async fn task_log_levels(&self) -> TaskLogLevels {
+        self.consensus()
+            .await
+            .read()
+            .await
+            .task_log_levels()
+            .clone()
+    }
 }
 
 #[async_trait]
@@ -2826,6 +2840,61 @@ mod test {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_log_level_requires_opt_in() {
+        setup_test();
+
+        for enable_log_level in [false, true] {
+            let port = pick_unused_port().expect("No ports free");
+            let url: surf_disco::Url = format!("http://localhost:{port}").parse().unwrap();
+            let client: Client<ServerError, StaticVersion<0, 1>> = Client::new(url);
+
+            let options = Options::with_port(port).config(options::Config { enable_log_level });
+            let anvil = Anvil::new().spawn();
+            let l1 = anvil.endpoint().parse().unwrap();
+            let network_config = TestConfigBuilder::default().l1_url(l1).build();
+            let config = TestNetworkConfigBuilder::default()
+                .api_config(options)
+                .network_config(network_config)
+                .build();
+            let network = TestNetwork::new(config, MockSequencerVersions::new()).await;
+            client.connect(None).await;
+
+            let res = client
+                .post::<Option<String>>("config/log-level/quorum-proposal/debug")
+                .send()
+                .await;
+            let log_level = network
+                .server
+                .consensus()
+                .read()
+                .await
+                .task_log_levels()
+                .quorum_proposal
+                .clone();
+
+            if enable_log_level {
+                assert_eq!(res.unwrap(), Some("debug".to_string()));
+                assert_eq!(
+                    log_level.level(),
+                    Some(tracing::level_filters::LevelFilter::DEBUG)
+                );
+
+                // Without a level, the override is removed.
+                let res = client
+                    .post::<Option<String>>("config/log-level/quorum-proposal")
+                    .send()
+                    .await
+                    .unwrap();
+                assert_eq!(res, None);
+                assert_eq!(log_level.level(), None);
+            } else {
+                res.unwrap_err();
+                assert_eq!(log_level.level(), None);
+            }
+        }
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_hotshot_event_streaming() {
         setup_test();
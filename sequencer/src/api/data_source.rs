@@ -9,6 +9,7 @@ use espresso_types::{
     FeeAccount, FeeAccountProof, FeeMerkleTree, Leaf2, NodeState, PubKey, Transaction,
 };
 use futures::future::Future;
+use hotshot::helpers::TaskLogLevels;
 use hotshot_query_service::{
     availability::AvailabilityDataSource,
     data_source::{UpdateDataSource, VersionedDataSource},
@@ -100,6 +101,9 @@ pub(crate) trait SubmitDataSource<N: ConnectedNetwork<PubKey>, P: SequencerPersi
 
 pub(crate) trait HotShotConfigDataSource {
     fn get_config(&self) -> impl Send + Future<Output = PublicNetworkConfig>;
+
+    /// Handles to the log levels of the consensus tasks of this node
+    fn task_log_levels(&self) -> impl Send + Future<Output = TaskLogLevels>;
 }
 
 #[async_trait]
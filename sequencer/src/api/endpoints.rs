@@ -33,6 +33,7 @@ use serde::{de::Error as _, Deserialize, Serialize};
 use snafu::OptionExt;
 use tagged_base64::TaggedBase64;
 use tide_disco::{method::ReadState, Api, Error as _, StatusCode};
+use tracing::level_filters::LevelFilter;
 use vbs::version::{StaticVersion, StaticVersionType};
 
 use super::{
@@ -40,7 +41,7 @@ use super::{
         CatchupDataSource, HotShotConfigDataSource, NodeStateDataSource, SequencerDataSource,
         StakeTableDataSource, StateSignatureDataSource, SubmitDataSource,
     },
-    StorageState,
+    options, StorageState,
 };
 use crate::{SeqTypes, SequencerApiVersion, SequencerPersistence};
 
@@ -512,6 +513,9 @@ where
         }
         .boxed()
     })?
+    .get("reward_config", |_, state| {
+        async move { Ok(state.node_state().await.reward_config()) }.boxed()
+    })?
     .get("leafchain", |req, state| {
         async move {
             let height = req
@@ -554,6 +558,7 @@ where
 }
 
 pub(super) fn config<S, ApiVer: StaticVersionType + 'static>(
+    opt: options::Config,
     _: ApiVer,
 ) -> Result<Api<S, Error, ApiVer>>
 where
@@ -577,6 +582,42 @@ where
         .boxed()
     })?;
 
+    // Changing log levels is not authenticated, so the route is only served when the operator
+    // opted into it.
+    if !opt.enable_log_level {
+        return Ok(api);
+    }
+
+    api.at("log_level", |req, state| {
+        async move {
+            let task = req
+                .string_param("task")
+                .map_err(Error::from_request_error)?;
+            let task_log_levels = state.task_log_levels().await;
+            let log_level = task_log_levels.task(&task).ok_or_else(|| {
+                Error::catch_all(StatusCode::NOT_FOUND, format!("unknown task {task}"))
+            })?;
+            match req
+                .opt_string_param("level")
+                .map_err(Error::from_request_error)?
+            {
+                Some(level) => {
+                    let level = level.parse::<LevelFilter>().map_err(|err| {
+                        Error::catch_all(StatusCode::BAD_REQUEST, format!("{err:#}"))
+                    })?;
+                    tracing::info!(%task, %level, "changing task log level");
+                    log_level.set_level(level);
+                },
+                None => {
+                    tracing::info!(%task, "resetting task log level");
+                    log_level.reset();
+                },
+            }
+            Ok(log_level.level().map(|level| level.to_string()))
+        }
+        .boxed()
+    })?;
+
     Ok(api)
 }
 
@@ -310,8 +310,8 @@ impl Options {
 
         app.register_module("state-signature", endpoints::state_signature(bind_version)?)?;
 
-        if self.config.is_some() {
-            app.register_module("config", endpoints::config(bind_version)?)?;
+        if let Some(config) = self.config {
+            app.register_module("config", endpoints::config(config, bind_version)?)?;
         }
         Ok((metrics, ds, app))
     }
@@ -449,8 +449,8 @@ impl Options {
         let state_signature_api = endpoints::state_signature(bind_version)?;
         app.register_module("state-signature", state_signature_api)?;
 
-        if self.config.is_some() {
-            app.register_module("config", endpoints::config(bind_version)?)?;
+        if let Some(config) = self.config {
+            app.register_module("config", endpoints::config(config, bind_version)?)?;
         }
 
         Ok(())
@@ -563,7 +563,14 @@ pub struct Catchup;
 
 /// Options for the config API module.
 #[derive(Parser, Clone, Copy, Debug, Default)]
-pub struct Config;
+pub struct Config {
+    /// Allow changing the log levels of the consensus tasks through the config API.
+    ///
+    /// The endpoint is not authenticated, so this should only be enabled on nodes whose API is
+    /// not publicly reachable.
+    #[clap(long, env = "ESPRESSO_SEQUENCER_API_ENABLE_LOG_LEVEL")]
+    pub enable_log_level: bool,
+}
 
 /// Options for the query API module.
 #[derive(Parser, Clone, Debug, Default)]
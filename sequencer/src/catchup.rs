@@ -11,7 +11,7 @@ use espresso_types::{
     v0_1::{RewardAccount, RewardAccountProof, RewardMerkleCommitment, RewardMerkleTree},
     v0_99::ChainConfig,
     BackoffParams, BlockMerkleTree, FeeAccount, FeeAccountProof, FeeMerkleCommitment,
-    FeeMerkleTree, Leaf2, NodeState, SeqTypes,
+    FeeMerkleTree, Leaf2, NodeState, RewardConfig, SeqTypes,
 };
 use futures::future::{Future, FutureExt, TryFuture, TryFutureExt};
 use hotshot_types::{
@@ -243,6 +243,35 @@ impl<ApiVer: StaticVersionType> StatePeers<ApiVer> {
             })
             .await
     }
+
+    /// Check that our reward distribution parameters match those of our peers.
+    ///
+    /// The reward parameters are not committed to in the chain config, so a node configured with
+    /// different ones would compute different reward roots and fail to validate any header with
+    /// rewards. It is better to refuse to start. If no peer can be reached (e.g. the whole network
+    /// is starting at once), there is nothing to compare against and the check passes.
+    #[tracing::instrument(skip(self))]
+    pub async fn check_reward_config(&self, reward_config: RewardConfig) -> anyhow::Result<()> {
+        match self
+            .fetch(0, |client| {
+                client.get::<RewardConfig>("catchup/reward-config").send()
+            })
+            .await
+        {
+            Ok(peer_config) => {
+                ensure!(
+                    peer_config == reward_config,
+                    "reward config {reward_config:?} does not match peer reward config \
+                     {peer_config:?}"
+                );
+                Ok(())
+            },
+            Err(err) => {
+                tracing::warn!("unable to check reward config against peers: {err:#}");
+                Ok(())
+            },
+        }
+    }
 }
 
 #[async_trait]
@@ -360,13 +389,15 @@ impl<ApiVer: StaticVersionType> StateCatchup for StatePeers<ApiVer> {
                 .await?;
 
             // Verify proofs.
-            for account in accounts {
-                let (proof, _) = RewardAccountProof::prove(&snapshot, (*account).into())
-                    .context(format!("response missing account {account}"))?;
-                proof
-                    .verify(&reward_merkle_tree_root)
-                    .context(format!("invalid proof for account {account}"))?;
-            }
+            let proofs = accounts
+                .iter()
+                .map(|account| {
+                    let (proof, _) = RewardAccountProof::prove(&snapshot, (*account).into())
+                        .context(format!("response missing account {account}"))?;
+                    Ok(proof)
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            RewardAccountProof::verify_batch(&proofs, &reward_merkle_tree_root)?;
 
             anyhow::Ok(snapshot)
         })
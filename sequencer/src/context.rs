@@ -6,6 +6,7 @@ use std::{
 
 use anyhow::Context;
 use async_lock::RwLock;
+use committable::{Commitment, Committable};
 use derivative::Derivative;
 use espresso_types::{
     v0::traits::{EventConsumer as PersistenceEventConsumer, SequencerPersistence},
@@ -316,9 +317,16 @@ impl<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Versions> Sequence
         self.handle.read().await.event_stream()
     }
 
-    pub async fn submit_transaction(&self, tx: Transaction) -> anyhow::Result<()> {
+    /// Submit a transaction to consensus.
+    ///
+    /// Returns the commitment of the transaction, which clients can use to poll for its inclusion.
+    pub async fn submit_transaction(
+        &self,
+        tx: Transaction,
+    ) -> anyhow::Result<Commitment<Transaction>> {
+        let commit = tx.commit();
         self.handle.read().await.submit_transaction(tx).await?;
-        Ok(())
+        Ok(commit)
     }
 
     /// get event streamer
@@ -5,8 +5,10 @@ use std::{
 
 use anyhow::{Context, Ok};
 use espresso_types::{
-    v0_99::ChainConfig, FeeAccount, FeeAmount, GenesisHeader, L1BlockInfo, L1Client, Timestamp,
-    Upgrade,
+    v0_1::{block_reward, RewardAmount},
+    v0_99::ChainConfig,
+    FeeAccount, FeeAmount, GenesisHeader, L1BlockInfo, L1Client, RemainderPolicy, RewardConfig,
+    Timestamp, Upgrade,
 };
 use ethers::types::H160;
 use ethers_conv::ToAlloy;
@@ -62,6 +64,16 @@ pub struct Genesis {
     #[serde(rename = "upgrade", with = "upgrade_ser")]
     #[serde(default)]
     pub upgrades: BTreeMap<Version, Upgrade>,
+    /// Upper bound on the byte length of block payloads, on top of the chain config's
+    /// `max_block_size`, so that we never build a block too large for VID dispersal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_block_payload_bytes: Option<u64>,
+    /// Reward for each block once rewards are enabled, overriding the default block reward.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_reward: Option<RewardAmount>,
+    /// What happens to the rounding remainder of the reward distribution of each block.
+    #[serde(default)]
+    pub reward_remainder_policy: RemainderPolicy,
 }
 
 impl Genesis {
@@ -80,6 +92,14 @@ impl Genesis {
 
         base_fee
     }
+
+    /// The parameters of the reward distribution, which every node must agree on.
+    pub fn reward_config(&self) -> RewardConfig {
+        RewardConfig {
+            block_reward: self.block_reward.unwrap_or_else(block_reward),
+            remainder_policy: self.reward_remainder_policy,
+        }
+    }
 }
 
 impl Genesis {
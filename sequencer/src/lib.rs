@@ -21,6 +21,7 @@ use catchup::StatePeers;
 use context::SequencerContext;
 use espresso_types::{
     traits::{EventConsumer, MembershipPersistence},
+    v0_1::block_reward,
     BackoffParams, EpochCommittees, L1ClientOptions, NodeState, PubKey, SeqTypes,
     SolverAuctionResultsProvider, ValidatedState,
 };
@@ -469,15 +470,12 @@ pub async fn init_node<P: SequencerPersistence + MembershipPersistence, V: Versi
         genesis_state.prefund_account(address, amount);
     }
 
-    let peers = catchup::local_and_remote(
-        persistence.clone(),
-        StatePeers::<SequencerApiVersion>::from_urls(
-            network_params.state_peers,
-            network_params.catchup_backoff,
-            metrics,
-        ),
-    )
-    .await;
+    let state_peers = StatePeers::<SequencerApiVersion>::from_urls(
+        network_params.state_peers,
+        network_params.catchup_backoff,
+        metrics,
+    );
+    let peers = catchup::local_and_remote(persistence.clone(), state_peers.clone()).await;
     // Create the HotShot membership
     let membership = EpochCommittees::new_stake(
         network_config.config.known_nodes_with_stake.clone(),
@@ -489,7 +487,8 @@ pub async fn init_node<P: SequencerPersistence + MembershipPersistence, V: Versi
             .map(|a| a.to_alloy()),
         peers.clone(),
         persistence.clone(),
-    );
+    )
+    .with_metrics(metrics);
 
     let membership: Arc<RwLock<EpochCommittees>> = Arc::new(RwLock::new(membership));
     let coordinator =
@@ -507,7 +506,17 @@ pub async fn init_node<P: SequencerPersistence + MembershipPersistence, V: Versi
         epoch_height: Some(epoch_height),
         peers,
         coordinator: coordinator.clone(),
+        max_block_payload_bytes: genesis.max_block_payload_bytes,
+        block_reward: genesis.block_reward.unwrap_or_else(block_reward),
+        reward_remainder_policy: genesis.reward_remainder_policy,
     };
+    instance_state
+        .validate_epoch_height()
+        .context("invalid genesis")?;
+    state_peers
+        .check_reward_config(instance_state.reward_config())
+        .await
+        .context("invalid genesis")?;
 
     // Initialize the Libp2p network
     let network = {
@@ -838,6 +847,11 @@ pub mod testing {
                 stop_voting_time: 0,
                 epoch_height: 300,
                 epoch_start_block: 0,
+                allow_leader_vid_dispersal: true,
+                max_future_view_skew: None,
+                views_per_decide_alert_threshold: None,
+                max_proposal_storage_attempts: 3,
+                high_qc_wait_fraction: (1, 2),
             };
 
             Self {
@@ -1098,6 +1112,7 @@ pub mod testing {
 #[cfg(test)]
 mod test {
 
+    use committable::Committable;
     use espresso_types::{Header, MockSequencerVersions, NamespaceId, Payload, Transaction};
     use futures::StreamExt;
     use hotshot::types::EventType::Decide;
@@ -1145,10 +1160,11 @@ mod test {
 
         // Submit target transaction to handle
         let txn = Transaction::new(NamespaceId::from(1_u32), vec![1, 2, 3]);
-        handles[0]
+        let commit = handles[0]
             .submit_transaction(txn.clone())
             .await
             .expect("Failed to submit transaction");
+        assert_eq!(commit, txn.commit());
         tracing::info!("Submitted transaction to handle: {txn:?}");
 
         wait_for_decide_on_handle(&mut events, &txn).await;
@@ -545,6 +545,9 @@ impl TestNetwork {
             base_version: Version { major: 0, minor: 1 },
             upgrade_version: Version { major: 0, minor: 2 },
             epoch_height: None,
+            max_block_payload_bytes: None,
+            block_reward: None,
+            reward_remainder_policy: Default::default(),
 
             // Start with a funded account, so we can test catchup after restart.
             accounts: [(builder_account(), 1000000000.into())]
@@ -295,6 +295,9 @@ mod test {
             base_version: Version { major: 0, minor: 1 },
             upgrade_version: Version { major: 0, minor: 2 },
             epoch_height: None,
+            max_block_payload_bytes: None,
+            block_reward: None,
+            reward_remainder_policy: Default::default(),
         };
         genesis.to_file(&genesis_file).unwrap();
 
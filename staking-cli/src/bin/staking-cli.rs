@@ -1,20 +1,28 @@
 use std::path::PathBuf;
 
-use alloy::{
-    network::EthereumWallet,
-    providers::ProviderBuilder,
-    signers::local::{coins_bip39::English, MnemonicBuilder},
-};
+use alloy::providers::ProviderBuilder;
 use anyhow::Result;
 use clap::Parser;
 use clap_serde_derive::ClapSerde;
-use contract_bindings_alloy::staketable::StakeTable::StakeTableInstance;
+use contract_bindings_alloy::{
+    esptoken::EspToken::EspTokenInstance, staketable::StakeTable::StakeTableInstance,
+};
 use staking_cli::{
-    claim::{claim_validator_exit, claim_withdrawal},
-    delegation::{delegate, undelegate},
+    claim::{
+        claim_validator_exit, claim_withdrawal, preview_claim_validator_exit,
+        preview_claim_withdrawal, wait_for_validator_exit, wait_for_withdrawal,
+    },
+    connect_wallet,
+    delegation::{
+        approve_if_needed, delegate, fetch_delegators, preview_approve_if_needed, preview_delegate,
+        preview_undelegate, undelegate, ApprovalAmount,
+    },
     demo::stake_for_demo,
-    registration::{deregister_validator, register_validator},
-    Commands, Config,
+    registration::{
+        deregister_validator, fetch_validators, preview_deregister_validator,
+        preview_register_validator, register_validator, verify_keys,
+    },
+    Commands, Config, OutputFormat,
 };
 use sysinfo::System;
 
@@ -158,21 +166,106 @@ pub async fn main() -> Result<()> {
             println!("Arch: {}", System::cpu_arch());
             return Ok(());
         },
+        Commands::VerifyKeys {
+            consensus_private_key,
+            state_private_key,
+        } => {
+            let keys = verify_keys(consensus_private_key.into(), (&state_private_key).into())
+                .unwrap_or_else(|err| exit_err("Key verification failed", err));
+            println!("Keys round trip through the on-chain format");
+            println!("BLS key: {:?}", keys.bls_vk);
+            println!("State key: {:?}", keys.schnorr_vk);
+            return Ok(());
+        },
         _ => {}, // Other commands handled after shared setup.
     }
 
-    let signer = MnemonicBuilder::<English>::default()
-        .phrase(config.mnemonic.as_str())
-        .index(config.account_index)?
-        .build()?;
-    let account = signer.address();
-    let wallet = EthereumWallet::from(signer);
+    let (account, wallet) = connect_wallet(&config).await?;
     let provider = ProviderBuilder::new()
         .with_recommended_fillers()
         .wallet(wallet)
         .on_http(config.rpc_url.clone());
     let stake_table = StakeTableInstance::new(config.stake_table_address, provider.clone());
 
+    if config.dry_run {
+        let previews = match &config.commands {
+            Commands::RegisterValidator {
+                consensus_private_key,
+                state_private_key,
+                commission,
+            } => vec![
+                preview_register_validator(
+                    stake_table,
+                    *commission,
+                    account,
+                    consensus_private_key.clone().into(),
+                    state_private_key.into(),
+                )
+                .await,
+            ],
+            Commands::DeregisterValidator {} => {
+                vec![preview_deregister_validator(stake_table, account).await]
+            },
+            Commands::Delegate {
+                validator_address,
+                amount,
+                auto_approve,
+                approve_max,
+            } => {
+                let mut previews = Vec::new();
+                if *auto_approve {
+                    let token = EspTokenInstance::new(config.token_address, provider.clone());
+                    let approval = if *approve_max {
+                        ApprovalAmount::Max
+                    } else {
+                        ApprovalAmount::Exact
+                    };
+                    previews.extend(
+                        preview_approve_if_needed(
+                            token,
+                            account,
+                            config.stake_table_address,
+                            *amount,
+                            approval,
+                        )
+                        .await?,
+                    );
+                }
+                // Without a prior approval, the delegation is expected to fail gas estimation.
+                previews.push(
+                    preview_delegate(stake_table, account, *validator_address, *amount).await,
+                );
+                previews
+            },
+            Commands::Undelegate {
+                validator_address,
+                amount,
+            } => vec![preview_undelegate(stake_table, account, *validator_address, *amount).await],
+            Commands::ClaimWithdrawal {
+                validator_address, ..
+            } => {
+                vec![preview_claim_withdrawal(stake_table, account, *validator_address).await]
+            },
+            Commands::ClaimValidatorExit {
+                validator_address, ..
+            } => {
+                vec![preview_claim_validator_exit(stake_table, account, *validator_address).await]
+            },
+            Commands::StakeForDemo { .. } => exit_err(
+                "Invalid arguments",
+                "stake-for-demo does not support --dry-run",
+            ),
+            // The remaining commands do not send any transaction.
+            _ => vec![],
+        };
+        if !previews.is_empty() {
+            for preview in previews {
+                println!("{preview}\n");
+            }
+            return Ok(());
+        }
+    }
+
     let result = match config.commands {
         // TODO: The info command is not implemented yet. It's not very useful for local testing or
         // the demo and requires code that is not yet merged into main, so it's left for later.
@@ -195,15 +288,70 @@ pub async fn main() -> Result<()> {
         Commands::Delegate {
             validator_address,
             amount,
-        } => delegate(stake_table, validator_address, amount).await,
+            auto_approve,
+            approve_max,
+        } => {
+            if auto_approve {
+                let token = EspTokenInstance::new(config.token_address, provider.clone());
+                let approval = if approve_max {
+                    ApprovalAmount::Max
+                } else {
+                    ApprovalAmount::Exact
+                };
+                if let Some(receipt) =
+                    approve_if_needed(token, account, config.stake_table_address, amount, approval)
+                        .await?
+                {
+                    tracing::info!("Approval: {:?}", receipt);
+                }
+            }
+            delegate(stake_table, validator_address, amount).await
+        },
         Commands::Undelegate {
             validator_address,
             amount,
         } => undelegate(stake_table, validator_address, amount).await,
-        Commands::ClaimWithdrawal { validator_address } => {
+        Commands::ListValidators { blocks_per_query } => {
+            let validators = fetch_validators(stake_table, blocks_per_query).await?;
+            match config.format {
+                OutputFormat::Human => println!("{validators}"),
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&validators)?),
+            }
+            return Ok(());
+        },
+        Commands::ListDelegators { validator_address } => {
+            let delegators = fetch_delegators(stake_table, validator_address).await?;
+            match config.format {
+                OutputFormat::Human => println!("{delegators}"),
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&delegators)?),
+            }
+            return Ok(());
+        },
+        Commands::ClaimWithdrawal {
+            validator_address,
+            wait,
+            poll_interval,
+        } => {
+            wait_for_withdrawal(
+                &stake_table,
+                account,
+                validator_address,
+                wait.then_some(poll_interval),
+            )
+            .await?;
             claim_withdrawal(stake_table, validator_address).await
         },
-        Commands::ClaimValidatorExit { validator_address } => {
+        Commands::ClaimValidatorExit {
+            validator_address,
+            wait,
+            poll_interval,
+        } => {
+            wait_for_validator_exit(
+                &stake_table,
+                validator_address,
+                wait.then_some(poll_interval),
+            )
+            .await?;
             claim_validator_exit(stake_table, validator_address).await
         },
         Commands::StakeForDemo { num_validators } => {
@@ -1,9 +1,140 @@
+use std::time::Duration;
+
 use alloy::{
-    primitives::Address, providers::Provider, rpc::types::TransactionReceipt, transports::Transport,
+    eips::{BlockId, BlockNumberOrTag},
+    primitives::Address,
+    providers::Provider,
+    rpc::types::{BlockTransactionsKind, TransactionReceipt},
+    transports::Transport,
 };
-use anyhow::Result;
+use anyhow::{Context, Result};
 use contract_bindings_alloy::staketable::StakeTable::StakeTableInstance;
 
+use crate::TransactionPreview;
+
+/// The timestamp of the L1 block `block`, in seconds since the unix epoch.
+async fn block_timestamp<P: Provider<T>, T: Transport + Clone>(
+    provider: &P,
+    block: BlockNumberOrTag,
+) -> Result<u64> {
+    let block = provider
+        .get_block_by_number(block, BlockTransactionsKind::Hashes)
+        .await?
+        .with_context(|| format!("L1 block {block} not found"))?;
+    Ok(block.header.timestamp)
+}
+
+/// The time at which the pending withdrawal of `delegator` from `validator_address` can be
+/// claimed, in seconds since the unix epoch, or `None` if the delegator never undelegated.
+///
+/// The contract does not expose pending undelegations, so the unlock time is derived from the
+/// block of the latest undelegation and the exit escrow period in effect at that block, like the
+/// contract does when the undelegation is made. Reading the escrow period of a past block needs an
+/// L1 node that still serves the state of that block.
+///
+/// Only the blocks since the stake table was initialized are searched for undelegations.
+pub async fn withdrawal_unlock_time<P: Provider<T>, T: Transport + Clone>(
+    stake_table: &StakeTableInstance<T, P>,
+    delegator: Address,
+    validator_address: Address,
+) -> Result<Option<u64>> {
+    let initialized_at = stake_table.initializedAtBlock().call().await?._0;
+    let undelegations = stake_table
+        .Undelegated_filter()
+        .topic1(delegator.into_word())
+        .topic2(validator_address.into_word())
+        .from_block(u64::try_from(initialized_at)?)
+        .query()
+        .await?;
+    let Some((_, log)) = undelegations.last() else {
+        return Ok(None);
+    };
+    let block = log
+        .block_number
+        .context("undelegation log without block number")?;
+    let undelegated_at = block_timestamp(stake_table.provider(), block.into()).await?;
+    let escrow_period = stake_table
+        .exitEscrowPeriod()
+        .block(BlockId::number(block))
+        .call()
+        .await?
+        ._0;
+
+    Ok(Some(undelegated_at.saturating_add(
+        escrow_period.try_into().unwrap_or(u64::MAX),
+    )))
+}
+
+/// The time at which the delegators of the exited validator `validator_address` can claim their
+/// funds, in seconds since the unix epoch, or `None` if the validator has not exited.
+pub async fn validator_exit_unlock_time<P: Provider<T>, T: Transport + Clone>(
+    stake_table: &StakeTableInstance<T, P>,
+    validator_address: Address,
+) -> Result<Option<u64>> {
+    let unlocks_at = stake_table
+        .validatorExits(validator_address)
+        .call()
+        .await?
+        ._0;
+    if unlocks_at.is_zero() {
+        return Ok(None);
+    }
+    Ok(Some(unlocks_at.try_into().unwrap_or(u64::MAX)))
+}
+
+/// Make sure funds unlocking at `unlocks_at` can be claimed in the next L1 block.
+///
+/// Without a `poll_interval`, fails with the remaining lock time if the funds are still locked, so
+/// that no transaction doomed to revert is sent. Otherwise polls the L1 every `poll_interval`
+/// until the funds are unlocked.
+pub async fn wait_until_unlocked<P: Provider<T>, T: Transport + Clone>(
+    provider: &P,
+    unlocks_at: u64,
+    poll_interval: Option<Duration>,
+) -> Result<()> {
+    loop {
+        // The contract compares against the timestamp of the block including the claim, which is
+        // always later than the one of the latest block.
+        let now = block_timestamp(provider, BlockNumberOrTag::Latest).await?;
+        if now >= unlocks_at {
+            return Ok(());
+        }
+        let remaining = Duration::from_secs(unlocks_at - now);
+        let Some(poll_interval) = poll_interval else {
+            anyhow::bail!("funds are locked for another {remaining:?}, retry later or use --wait");
+        };
+        tracing::info!("Funds are locked for another {remaining:?}, waiting");
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+/// Make sure the withdrawal of `delegator` from `validator_address` can be claimed, see
+/// [`wait_until_unlocked`].
+pub async fn wait_for_withdrawal<P: Provider<T>, T: Transport + Clone>(
+    stake_table: &StakeTableInstance<T, P>,
+    delegator: Address,
+    validator_address: Address,
+    poll_interval: Option<Duration>,
+) -> Result<()> {
+    let unlocks_at = withdrawal_unlock_time(stake_table, delegator, validator_address)
+        .await?
+        .with_context(|| format!("no pending withdrawal from validator {validator_address}"))?;
+    wait_until_unlocked(stake_table.provider(), unlocks_at, poll_interval).await
+}
+
+/// Make sure the funds delegated to the exited validator `validator_address` can be claimed, see
+/// [`wait_until_unlocked`].
+pub async fn wait_for_validator_exit<P: Provider<T>, T: Transport + Clone>(
+    stake_table: &StakeTableInstance<T, P>,
+    validator_address: Address,
+    poll_interval: Option<Duration>,
+) -> Result<()> {
+    let unlocks_at = validator_exit_unlock_time(stake_table, validator_address)
+        .await?
+        .with_context(|| format!("validator {validator_address} has not exited"))?;
+    wait_until_unlocked(stake_table.provider(), unlocks_at, poll_interval).await
+}
+
 pub async fn claim_withdrawal<P: Provider<T>, T: Transport + Clone>(
     stake_table: StakeTableInstance<T, P>,
     validator_address: Address,
@@ -29,6 +160,36 @@ pub async fn claim_validator_exit<P: Provider<T>, T: Transport + Clone>(
         .await?)
 }
 
+/// Preview the transaction [`claim_withdrawal`] would send on behalf of `delegator`.
+pub async fn preview_claim_withdrawal<P: Provider<T>, T: Transport + Clone>(
+    stake_table: StakeTableInstance<T, P>,
+    delegator: Address,
+    validator_address: Address,
+) -> TransactionPreview {
+    TransactionPreview::new(
+        *stake_table.address(),
+        delegator,
+        stake_table.claimWithdrawal(validator_address),
+        vec![("validator", validator_address.to_string())],
+    )
+    .await
+}
+
+/// Preview the transaction [`claim_validator_exit`] would send on behalf of `delegator`.
+pub async fn preview_claim_validator_exit<P: Provider<T>, T: Transport + Clone>(
+    stake_table: StakeTableInstance<T, P>,
+    delegator: Address,
+    validator_address: Address,
+) -> TransactionPreview {
+    TransactionPreview::new(
+        *stake_table.address(),
+        delegator,
+        stake_table.claimValidatorExit(validator_address),
+        vec![("validator", validator_address.to_string())],
+    )
+    .await
+}
+
 #[cfg(test)]
 mod test {
     use alloy::primitives::U256;
@@ -74,4 +235,80 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_wait_for_withdrawal() -> Result<()> {
+        let system = TestSystem::deploy().await?;
+        let amount = U256::from(123);
+        let delegator = system.deployer_address;
+        let validator_address = system.deployer_address;
+        system.register_validator().await?;
+        system.delegate(amount).await?;
+
+        // Nothing to claim before undelegating.
+        assert!(
+            withdrawal_unlock_time(&system.stake_table, delegator, validator_address)
+                .await?
+                .is_none()
+        );
+        system.undelegate(amount).await?;
+        assert!(
+            withdrawal_unlock_time(&system.stake_table, delegator, validator_address)
+                .await?
+                .is_some()
+        );
+
+        // Without waiting, fail while the funds are locked.
+        let err = wait_for_withdrawal(&system.stake_table, delegator, validator_address, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("locked"), "{err:#}");
+
+        // Otherwise wait until they are unlocked.
+        let stake_table = system.stake_table.clone();
+        let wait = tokio::spawn(async move {
+            wait_for_withdrawal(
+                &stake_table,
+                delegator,
+                validator_address,
+                Some(Duration::from_millis(100)),
+            )
+            .await
+        });
+        system.warp_to_unlock_time().await?;
+        wait.await??;
+
+        let receipt = claim_withdrawal(system.stake_table, validator_address).await?;
+        assert!(receipt.status());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_validator_exit() -> Result<()> {
+        let system = TestSystem::deploy().await?;
+        let amount = U256::from(123);
+        let validator_address = system.deployer_address;
+        system.register_validator().await?;
+        system.delegate(amount).await?;
+
+        let err = wait_for_validator_exit(&system.stake_table, validator_address, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("has not exited"), "{err:#}");
+
+        system.deregister_validator().await?;
+        let err = wait_for_validator_exit(&system.stake_table, validator_address, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("locked"), "{err:#}");
+
+        system.warp_to_unlock_time().await?;
+        wait_for_validator_exit(&system.stake_table, validator_address, None).await?;
+
+        let receipt = claim_validator_exit(system.stake_table, validator_address).await?;
+        assert!(receipt.status());
+
+        Ok(())
+    }
 }
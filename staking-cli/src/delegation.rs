@@ -1,11 +1,103 @@
+use std::{collections::HashMap, fmt};
+
 use alloy::{
     primitives::{Address, U256},
     providers::Provider,
     rpc::types::TransactionReceipt,
     transports::Transport,
 };
-use anyhow::Result;
-use contract_bindings_alloy::staketable::StakeTable::StakeTableInstance;
+use anyhow::{Context, Result};
+use contract_bindings_alloy::{
+    esptoken::EspToken::EspTokenInstance, staketable::StakeTable::StakeTableInstance,
+};
+use serde::{Serialize, Serializer};
+
+use crate::TransactionPreview;
+
+/// How much to approve when the allowance of the stake table does not cover a delegation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalAmount {
+    /// Set the allowance to exactly the amount being delegated.
+    Exact,
+    /// Approve the maximum amount, so that later delegations need no further approval.
+    Max,
+}
+
+/// Approve `spender` to transfer `amount` tokens of `owner`, unless the current allowance already
+/// covers it.
+///
+/// An ERC20 approval replaces the previous allowance instead of adding to it, so with
+/// [`ApprovalAmount::Exact`] the allowance is set to `amount`, enough for this delegation only.
+/// Returns the receipt of the approval, if one was sent.
+pub async fn approve_if_needed<P: Provider<T>, T: Transport + Clone>(
+    token: EspTokenInstance<T, P>,
+    owner: Address,
+    spender: Address,
+    amount: U256,
+    approval: ApprovalAmount,
+) -> Result<Option<TransactionReceipt>> {
+    let Some(approve_amount) = approval_needed(&token, owner, spender, amount, approval).await?
+    else {
+        return Ok(None);
+    };
+    let receipt = token
+        .approve(spender, approve_amount)
+        .send()
+        .await?
+        .get_receipt()
+        .await?;
+    anyhow::ensure!(receipt.status(), "approval transaction failed: {receipt:?}");
+    Ok(Some(receipt))
+}
+
+/// Like [`approve_if_needed`], but only preview the approval instead of sending it.
+pub async fn preview_approve_if_needed<P: Provider<T>, T: Transport + Clone>(
+    token: EspTokenInstance<T, P>,
+    owner: Address,
+    spender: Address,
+    amount: U256,
+    approval: ApprovalAmount,
+) -> Result<Option<TransactionPreview>> {
+    let Some(approve_amount) = approval_needed(&token, owner, spender, amount, approval).await?
+    else {
+        return Ok(None);
+    };
+    Ok(Some(
+        TransactionPreview::new(
+            *token.address(),
+            owner,
+            token.approve(spender, approve_amount),
+            vec![
+                ("spender", spender.to_string()),
+                ("value", approve_amount.to_string()),
+            ],
+        )
+        .await,
+    ))
+}
+
+/// The amount `owner` has to approve for `spender` to transfer `amount` tokens, if the current
+/// allowance does not cover it.
+async fn approval_needed<P: Provider<T>, T: Transport + Clone>(
+    token: &EspTokenInstance<T, P>,
+    owner: Address,
+    spender: Address,
+    amount: U256,
+    approval: ApprovalAmount,
+) -> Result<Option<U256>> {
+    let allowance = token.allowance(owner, spender).call().await?._0;
+    if allowance >= amount {
+        tracing::info!("allowance of {allowance} for {spender} is sufficient");
+        return Ok(None);
+    }
+
+    let approve_amount = match approval {
+        ApprovalAmount::Exact => amount,
+        ApprovalAmount::Max => U256::MAX,
+    };
+    tracing::info!("approve {approve_amount} for {spender}, current allowance {allowance}");
+    Ok(Some(approve_amount))
+}
 
 pub async fn delegate<P: Provider<T>, T: Transport + Clone>(
     stake_table: StakeTableInstance<T, P>,
@@ -35,12 +127,158 @@ pub async fn undelegate<P: Provider<T>, T: Transport + Clone>(
         .await?)
 }
 
+/// Preview the transaction [`delegate`] would send on behalf of `delegator`.
+pub async fn preview_delegate<P: Provider<T>, T: Transport + Clone>(
+    stake_table: StakeTableInstance<T, P>,
+    delegator: Address,
+    validator_address: Address,
+    amount: U256,
+) -> TransactionPreview {
+    TransactionPreview::new(
+        *stake_table.address(),
+        delegator,
+        stake_table.delegate(validator_address, amount),
+        vec![
+            ("validator", validator_address.to_string()),
+            ("amount", amount.to_string()),
+        ],
+    )
+    .await
+}
+
+/// Preview the transaction [`undelegate`] would send on behalf of `delegator`.
+pub async fn preview_undelegate<P: Provider<T>, T: Transport + Clone>(
+    stake_table: StakeTableInstance<T, P>,
+    delegator: Address,
+    validator_address: Address,
+    amount: U256,
+) -> TransactionPreview {
+    TransactionPreview::new(
+        *stake_table.address(),
+        delegator,
+        stake_table.undelegate(validator_address, amount),
+        vec![
+            ("validator", validator_address.to_string()),
+            ("amount", amount.to_string()),
+        ],
+    )
+    .await
+}
+
+/// The delegators of a validator together with the stake each of them has delegated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidatorDelegators {
+    /// The address of the validator.
+    pub validator: Address,
+    /// Delegator addresses and their stake, sorted by stake in descending order.
+    pub delegators: Vec<(Address, U256)>,
+    /// The sum of all delegated stake.
+    pub total: U256,
+}
+
+impl fmt::Display for ValidatorDelegators {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (delegator, stake) in &self.delegators {
+            writeln!(f, "{delegator}: {stake}")?;
+        }
+        write!(f, "Total: {}", self.total)
+    }
+}
+
+/// Addresses are serialized in checksummed hex, and amounts as decimal strings, so that they do
+/// not lose precision when parsed as JSON numbers.
+impl Serialize for ValidatorDelegators {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Delegator {
+            address: String,
+            stake: String,
+        }
+
+        #[derive(Serialize)]
+        struct Delegators {
+            validator: String,
+            delegators: Vec<Delegator>,
+            total: String,
+        }
+
+        Delegators {
+            validator: self.validator.to_checksum(None),
+            delegators: self
+                .delegators
+                .iter()
+                .map(|(address, stake)| Delegator {
+                    address: address.to_checksum(None),
+                    stake: stake.to_string(),
+                })
+                .collect(),
+            total: self.total.to_string(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Fetch all delegators of a validator and their current stake.
+///
+/// The stake table contract does not expose its delegations, so they are reconstructed from the
+/// `Delegated` and `Undelegated` events emitted for the validator.
+pub async fn fetch_delegators<P: Provider<T>, T: Transport + Clone>(
+    stake_table: StakeTableInstance<T, P>,
+    validator_address: Address,
+) -> Result<ValidatorDelegators> {
+    let delegated = stake_table
+        .Delegated_filter()
+        .topic2(validator_address.into_word())
+        .from_block(0)
+        .query()
+        .await?;
+    let undelegated = stake_table
+        .Undelegated_filter()
+        .topic2(validator_address.into_word())
+        .from_block(0)
+        .query()
+        .await?;
+
+    let mut stakes = HashMap::<Address, U256>::new();
+    for (event, _) in delegated {
+        *stakes.entry(event.delegator).or_default() += event.amount;
+    }
+    for (event, _) in undelegated {
+        let stake = stakes.entry(event.delegator).or_default();
+        *stake = stake.checked_sub(event.amount).with_context(|| {
+            format!(
+                "delegator {} undelegated more than it delegated",
+                event.delegator
+            )
+        })?;
+    }
+
+    let mut delegators: Vec<_> = stakes
+        .into_iter()
+        .filter(|(_, stake)| !stake.is_zero())
+        .collect();
+    delegators.sort_by(|(a, a_stake), (b, b_stake)| b_stake.cmp(a_stake).then(a.cmp(b)));
+    let total = delegators.iter().map(|(_, stake)| *stake).sum();
+
+    Ok(ValidatorDelegators {
+        validator: validator_address,
+        delegators,
+        total,
+    })
+}
+
 #[cfg(test)]
 mod test {
-    use contract_bindings_alloy::staketable::StakeTable::{self};
+    use alloy::{
+        network::EthereumWallet,
+        providers::ProviderBuilder,
+        signers::local::{coins_bip39::English, MnemonicBuilder},
+        sol_types::SolCall,
+    };
+    use contract_bindings_alloy::{esptoken::EspToken::EspTokenInstance, staketable::StakeTable};
 
     use super::*;
-    use crate::{deploy::TestSystem, l1::decode_log};
+    use crate::{deploy::TestSystem, l1::decode_log, DEV_MNEMONIC};
 
     #[tokio::test]
     async fn test_delegate() -> Result<()> {
@@ -59,6 +297,97 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_preview_delegate() -> Result<()> {
+        let system = TestSystem::deploy().await?;
+        system.register_validator().await?;
+        let validator_address = system.deployer_address;
+
+        let amount = U256::from(123);
+        let preview = preview_delegate(
+            system.stake_table.clone(),
+            system.deployer_address,
+            validator_address,
+            amount,
+        )
+        .await;
+        assert_eq!(preview.to, *system.stake_table.address());
+        assert_eq!(preview.function, "delegate(address,uint256)");
+        assert_eq!(preview.selector.0, StakeTable::delegateCall::SELECTOR);
+        assert_eq!(
+            preview.arguments,
+            vec![
+                ("validator", validator_address.to_string()),
+                ("amount", "123".to_string())
+            ]
+        );
+        assert!(preview.estimated_gas.unwrap() > 0);
+
+        // Nothing was delegated.
+        let delegators = fetch_delegators(system.stake_table, validator_address).await?;
+        assert!(delegators.delegators.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_approve_if_needed() -> Result<()> {
+        let system = TestSystem::deploy().await?;
+        let owner = system.deployer_address;
+        let spender = *system.stake_table.address();
+
+        // Deploying the test system approves a large amount, so no approval is needed.
+        let amount = U256::from(123);
+        let receipt = approve_if_needed(
+            system.token.clone(),
+            owner,
+            spender,
+            amount,
+            ApprovalAmount::Exact,
+        )
+        .await?;
+        assert!(receipt.is_none());
+
+        // Once the allowance is too low it is raised to exactly the amount needed.
+        system
+            .token
+            .approve(spender, U256::from(100))
+            .send()
+            .await?
+            .get_receipt()
+            .await?;
+        let receipt = approve_if_needed(
+            system.token.clone(),
+            owner,
+            spender,
+            amount,
+            ApprovalAmount::Exact,
+        )
+        .await?;
+        assert!(receipt.is_some());
+        assert_eq!(
+            system.token.allowance(owner, spender).call().await?._0,
+            amount
+        );
+
+        // Or to the maximum.
+        let receipt = approve_if_needed(
+            system.token.clone(),
+            owner,
+            spender,
+            amount + U256::from(1),
+            ApprovalAmount::Max,
+        )
+        .await?;
+        assert!(receipt.is_some());
+        assert_eq!(
+            system.token.allowance(owner, spender).call().await?._0,
+            U256::MAX
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_undelegate() -> Result<()> {
         let system = TestSystem::deploy().await?;
@@ -76,4 +405,81 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_validator_delegators_json() -> Result<()> {
+        let validator = Address::random();
+        let delegator = Address::random();
+        // More than fits into a JSON number without loss of precision.
+        let stake = U256::from(u64::MAX) * U256::from(1000);
+        let delegators = ValidatorDelegators {
+            validator,
+            delegators: vec![(delegator, stake)],
+            total: stake,
+        };
+
+        assert_eq!(
+            serde_json::to_value(&delegators)?,
+            serde_json::json!({
+                "validator": validator.to_checksum(None),
+                "delegators": [{
+                    "address": delegator.to_checksum(None),
+                    "stake": "18446744073709551615000",
+                }],
+                "total": "18446744073709551615000",
+            })
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_delegators() -> Result<()> {
+        let system = TestSystem::deploy().await?;
+        system.register_validator().await?;
+        let validator_address = system.deployer_address;
+
+        // The deployer delegates 100 and withdraws 50 of it again.
+        system.delegate(U256::from(100)).await?;
+        system.undelegate(U256::from(50)).await?;
+
+        // A second account delegates more than the deployer.
+        let signer = MnemonicBuilder::<English>::default()
+            .phrase(DEV_MNEMONIC)
+            .index(1)?
+            .build()?;
+        let delegator = signer.address();
+        let provider = ProviderBuilder::new()
+            .with_recommended_fillers()
+            .wallet(EthereumWallet::from(signer))
+            .on_http(system.rpc_url.clone());
+        let amount = U256::from(300);
+        system.transfer(delegator, amount).await?;
+        let token = EspTokenInstance::new(*system.token.address(), provider.clone());
+        let receipt = token
+            .approve(*system.stake_table.address(), amount)
+            .send()
+            .await?
+            .get_receipt()
+            .await?;
+        assert!(receipt.status());
+        let stake_table = StakeTableInstance::new(*system.stake_table.address(), provider);
+        let receipt = delegate(stake_table, validator_address, amount).await?;
+        assert!(receipt.status());
+
+        let delegators = fetch_delegators(system.stake_table, validator_address).await?;
+        assert_eq!(
+            delegators.delegators,
+            vec![
+                (delegator, U256::from(300)),
+                (validator_address, U256::from(50))
+            ]
+        );
+        assert_eq!(delegators.total, U256::from(350));
+        assert_eq!(
+            delegators.to_string(),
+            format!("{delegator}: 300\n{validator_address}: 50\nTotal: 350")
+        );
+
+        Ok(())
+    }
 }
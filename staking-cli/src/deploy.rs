@@ -176,6 +176,8 @@ impl TestSystem {
         self.provider
             .anvil_increase_time(U256::from(self.exit_escrow_period.as_secs()))
             .await?;
+        // Mine a block, so that the latest block is past the unlock time as well.
+        self.provider.anvil_mine(Some(U256::from(1)), None).await?;
         Ok(())
     }
 
@@ -1,4 +1,117 @@
-use alloy::{primitives::Log, rpc::types::TransactionReceipt, sol_types::SolEvent};
+use std::{fmt, marker::PhantomData};
+
+use alloy::{
+    contract::{CallBuilder, Event},
+    network::EthereumWallet,
+    primitives::{Address, Log, Selector},
+    providers::Provider,
+    rpc::types::{Log as RpcLog, TransactionReceipt},
+    signers::{
+        ledger::{HDPath, LedgerSigner},
+        local::{coins_bip39::English, MnemonicBuilder},
+        Signer,
+    },
+    sol_types::{SolCall, SolEvent},
+    transports::Transport,
+};
+use anyhow::{Context, Result};
+
+use crate::Config;
+
+/// Build the wallet that signs the transactions of the account selected in the config, and
+/// return it together with the address of the account.
+///
+/// With `ledger` set, transactions are signed on the Ledger and the mnemonic is ignored.
+pub async fn connect_wallet(config: &Config) -> Result<(Address, EthereumWallet)> {
+    if config.ledger {
+        let signer = LedgerSigner::new(HDPath::LedgerLive(config.account_index as usize), None)
+            .await
+            .context(
+                "failed to connect to the Ledger, make sure it is plugged in and unlocked, and \
+                 that the Ethereum app is open",
+            )?;
+        return Ok((signer.address(), EthereumWallet::from(signer)));
+    }
+
+    let signer = MnemonicBuilder::<English>::default()
+        .phrase(config.mnemonic.as_str())
+        .index(config.account_index)?
+        .build()?;
+    Ok((signer.address(), EthereumWallet::from(signer)))
+}
+
+/// A transaction that is shown to the user instead of being sent, with `--dry-run`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionPreview {
+    /// The contract the transaction is sent to.
+    pub to: Address,
+    /// The signature of the called function.
+    pub function: &'static str,
+    /// The selector of the called function.
+    pub selector: Selector,
+    /// The names and values of the arguments of the call.
+    pub arguments: Vec<(&'static str, String)>,
+    /// The gas estimated by the L1 for the current contract state, or why the estimate failed,
+    /// typically because the transaction would revert.
+    pub estimated_gas: Result<u64, String>,
+}
+
+impl TransactionPreview {
+    /// Preview `call` to the contract at `to`, as if sent by `from`.
+    pub async fn new<T: Transport + Clone, P: Provider<T>, C: SolCall>(
+        to: Address,
+        from: Address,
+        call: CallBuilder<T, P, PhantomData<C>>,
+        arguments: Vec<(&'static str, String)>,
+    ) -> Self {
+        let estimated_gas = call
+            .from(from)
+            .estimate_gas()
+            .await
+            .map_err(|err| err.to_string());
+        Self {
+            to,
+            function: C::SIGNATURE,
+            selector: C::SELECTOR.into(),
+            arguments,
+            estimated_gas,
+        }
+    }
+}
+
+impl fmt::Display for TransactionPreview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "to: {}", self.to)?;
+        writeln!(f, "function: {}", self.function)?;
+        writeln!(f, "selector: {}", self.selector)?;
+        for (name, value) in &self.arguments {
+            writeln!(f, "  {name}: {value}")?;
+        }
+        match &self.estimated_gas {
+            Ok(gas) => write!(f, "estimated gas: {gas}"),
+            Err(err) => write!(f, "gas estimation failed: {err}"),
+        }
+    }
+}
+
+/// Query the logs of `filter` from genesis up to `to_block`, at most `blocks_per_query` blocks at
+/// a time, so that the range of a single query stays within the limits of RPC providers.
+pub(crate) async fn query_in_chunks<T: Transport + Clone, P: Provider<T>, E: SolEvent>(
+    filter: impl Fn() -> Event<T, P, E>,
+    to_block: u64,
+    blocks_per_query: u64,
+) -> Result<Vec<(E, RpcLog)>> {
+    anyhow::ensure!(blocks_per_query > 0, "blocks per query must be positive");
+
+    let mut events = Vec::new();
+    let mut from = 0;
+    while from <= to_block {
+        let to = to_block.min(from.saturating_add(blocks_per_query - 1));
+        events.extend(filter().from_block(from).to_block(to).query().await?);
+        from = to + 1;
+    }
+    Ok(events)
+}
 
 // TODO this function can be removed once we move to alloy 0.12
 #[allow(dead_code)]
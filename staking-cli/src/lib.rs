@@ -1,6 +1,9 @@
+use std::time::Duration;
+
 use alloy::primitives::{Address, U256};
-use clap::Subcommand;
+use clap::{Subcommand, ValueEnum};
 use clap_serde_derive::ClapSerde;
+use espresso_types::parse_duration;
 pub(crate) use hotshot_types::{
     light_client::{StateSignKey, StateVerKey},
     signature_key::BLSPrivKey,
@@ -14,6 +17,7 @@ pub mod claim;
 pub mod delegation;
 pub mod demo;
 mod l1;
+pub use l1::{connect_wallet, TransactionPreview};
 pub mod parse;
 pub mod registration;
 
@@ -23,7 +27,7 @@ pub const DEV_MNEMONIC: &str = "test test test test test test test test test tes
 
 #[derive(ClapSerde, Debug, Deserialize, Serialize)]
 pub struct Config {
-    // # TODO for mainnet we should support hardware wallets. Alloy has support for this.
+    /// The mnemonic of the account, ignored when signing with a Ledger.
     #[default(DEV_MNEMONIC.to_string())]
     #[clap(long, env = "MNEMONIC")]
     #[serde(alias = "mnemonic", alias = "MNEMONIC")]
@@ -32,6 +36,38 @@ pub struct Config {
     #[clap(long, env = "ACCOUNT_INDEX", default_value = "0")]
     pub account_index: u32,
 
+    /// Sign transactions with a Ledger hardware wallet, instead of the mnemonic.
+    ///
+    /// The account is derived with the Ledger Live derivation path of the account index.
+    #[clap(
+        long,
+        env = "USE_LEDGER",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    #[default(false)]
+    #[serde(default)]
+    pub ledger: bool,
+
+    /// Print the transactions that would be sent, with their estimated gas, instead of sending
+    /// them.
+    #[clap(
+        long,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    #[default(false)]
+    #[serde(default)]
+    pub dry_run: bool,
+
+    /// The output format of the commands that read from the stake table.
+    #[clap(long, value_enum, env = "OUTPUT_FORMAT")]
+    #[default(OutputFormat::Human)]
+    #[serde(default)]
+    pub format: OutputFormat,
+
     /// L1 Ethereum RPC.
     #[clap(long, env = "L1_PROVIDER")]
     #[default(Url::parse("http://localhost:8545").unwrap())]
@@ -39,7 +75,7 @@ pub struct Config {
 
     /// Deployed ESP token contract address.
     #[clap(long, env = "ESP_TOKEN_ADDRESS")]
-    token_address: Address,
+    pub token_address: Address,
 
     /// Deployed stake table contract address.
     #[clap(long, env = "STAKE_TABLE_ADDRESS")]
@@ -50,6 +86,17 @@ pub struct Config {
     pub commands: Commands,
 }
 
+/// The output format of the commands that read from the stake table.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Human readable text.
+    #[default]
+    Human,
+    /// JSON, with addresses in checksummed hex and amounts as decimal strings.
+    Json,
+}
+
 #[derive(Default, Subcommand, Debug)]
 pub enum Commands {
     Version,
@@ -80,6 +127,16 @@ pub enum Commands {
         #[clap(long, value_parser = parse::parse_commission)]
         commission: Commission,
     },
+    /// Check that the validator keys round trip through the on-chain format.
+    VerifyKeys {
+        /// The consensus signing key.
+        #[clap(long, value_parser = parse::parse_bls_priv_key)]
+        consensus_private_key: BLSPrivKey,
+
+        /// The state signing key.
+        #[clap(long, value_parser = parse::parse_state_priv_key)]
+        state_private_key: StateSignKey,
+    },
     /// Deregister a validator.
     DeregisterValidator {},
     /// Delegate funds to a validator.
@@ -89,6 +146,14 @@ pub enum Commands {
 
         #[clap(long)]
         amount: U256,
+
+        /// Approve the stake table to spend the amount if the current allowance is insufficient.
+        #[clap(long)]
+        auto_approve: bool,
+
+        /// Approve the maximum amount instead of only the delegated amount.
+        #[clap(long, requires = "auto_approve")]
+        approve_max: bool,
     },
     /// Initiate a withdrawal of delegated funds from a validator.
     Undelegate {
@@ -98,15 +163,46 @@ pub enum Commands {
         #[clap(long)]
         amount: U256,
     },
+    /// List all registered validators, with their commission and stake.
+    ListValidators {
+        /// The maximum number of blocks to query the contract logs for at once.
+        #[clap(long, default_value_t = 10_000)]
+        blocks_per_query: u64,
+    },
+    /// List all delegators of a validator and their stake.
+    ListDelegators {
+        #[clap(long)]
+        validator_address: Address,
+    },
     /// Claim withdrawal after an undelegation.
+    ///
+    /// Fails without sending a transaction if the funds are still locked, unless `--wait` is set.
     ClaimWithdrawal {
         #[clap(long)]
         validator_address: Address,
+
+        /// Wait until the funds are unlocked, instead of failing.
+        #[clap(long)]
+        wait: bool,
+
+        /// How often to check whether the funds are unlocked, with `--wait`.
+        #[clap(long, value_parser = parse_duration, default_value = "1m", requires = "wait")]
+        poll_interval: Duration,
     },
     /// Claim withdrawal after validator exit.
+    ///
+    /// Fails without sending a transaction if the funds are still locked, unless `--wait` is set.
     ClaimValidatorExit {
         #[clap(long)]
         validator_address: Address,
+
+        /// Wait until the funds are unlocked, instead of failing.
+        #[clap(long)]
+        wait: bool,
+
+        /// How often to check whether the funds are unlocked, with `--wait`.
+        #[clap(long, value_parser = parse_duration, default_value = "1m", requires = "wait")]
+        poll_interval: Duration,
     },
     /// Register the validators and delegates for the local demo.
     StakeForDemo {
@@ -1,6 +1,7 @@
 use std::{fmt::Display, str::FromStr as _};
 
 use derive_more::From;
+use espresso_types::v0_1::COMMISSION_BASIS_POINTS;
 use hotshot_types::{light_client::StateSignKey, signature_key::BLSPrivKey};
 use rust_decimal::{prelude::ToPrimitive as _, Decimal};
 use tagged_base64::{TaggedBase64, Tb64Error};
@@ -34,13 +35,11 @@ impl TryFrom<&str> for Commission {
 impl TryFrom<u16> for Commission {
     type Error = ParseCommissionError;
 
-    fn try_from(s: u16) -> Result<Self, Self::Error> {
-        if s > 10000 {
-            return Err("Commission must be between 0 (0.00%) and 100 (100.00%)"
-                .to_string()
-                .into());
+    fn try_from(basis_points: u16) -> Result<Self, Self::Error> {
+        if basis_points > COMMISSION_BASIS_POINTS {
+            return Err(out_of_range_error(basis_points));
         }
-        Ok(Commission(s))
+        Ok(Commission(basis_points))
     }
 }
 
@@ -51,11 +50,21 @@ impl Display for Commission {
 }
 
 #[derive(Clone, Debug, From, Error)]
-#[error("failed to parse ByteSize. {msg}")]
+#[error("failed to parse commission. {msg}")]
 pub struct ParseCommissionError {
     msg: String,
 }
 
+/// The error for a commission outside of `0..=COMMISSION_BASIS_POINTS`, which would be rejected
+/// when computing rewards.
+fn out_of_range_error(basis_points: impl Display) -> ParseCommissionError {
+    format!(
+        "commission must be between 0 and {COMMISSION_BASIS_POINTS} basis points (0.00% to \
+         100.00%), got {basis_points} basis points"
+    )
+    .into()
+}
+
 /// Parse a percentage string into a `Percentage` type.
 pub fn parse_commission(s: &str) -> Result<Commission, ParseCommissionError> {
     let dec = Decimal::from_str(s).map_err(|e| ParseCommissionError { msg: e.to_string() })?;
@@ -66,20 +75,14 @@ pub fn parse_commission(s: &str) -> Result<Commission, ParseCommissionError> {
                 .into(),
         );
     }
-    let hundred = Decimal::new(100, 0);
-    if dec < Decimal::ZERO || dec > hundred {
-        return Err(
-            format!("Commission must be between 0 (0.00%) and 100 (100.00%), got {dec}")
-                .to_string()
-                .into(),
-        );
+    let basis_points = dec
+        .checked_mul(Decimal::new(100, 0))
+        .ok_or_else(|| out_of_range_error(format!("more than {COMMISSION_BASIS_POINTS}")))?
+        .normalize();
+    if basis_points < Decimal::ZERO || basis_points > Decimal::from(COMMISSION_BASIS_POINTS) {
+        return Err(out_of_range_error(basis_points));
     }
-    Ok(Commission(
-        dec.checked_mul(hundred)
-            .expect("multiplication succeeds")
-            .to_u16()
-            .expect("conversion to u64 succeeds"),
-    ))
+    Commission::try_from(basis_points.to_u16().expect("conversion to u16 succeeds"))
 }
 
 #[cfg(test)]
@@ -138,4 +141,26 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn test_commission_basis_points_range() {
+        assert_eq!(Commission::try_from(0u16).unwrap().to_evm(), 0);
+        assert_eq!(Commission::try_from(10000u16).unwrap().to_evm(), 10000);
+        let err = Commission::try_from(10001u16).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("commission must be between 0 and 10000 basis points"),
+            "unexpected error: {err}"
+        );
+
+        assert_eq!(parse_commission("0").unwrap().to_evm(), 0);
+        assert_eq!(parse_commission("100").unwrap().to_evm(), 10000);
+        let err = parse_commission("100.01").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("commission must be between 0 and 10000 basis points"),
+            "unexpected error: {err}"
+        );
+        assert!(err.to_string().contains("got 10001 basis points"));
+    }
 }
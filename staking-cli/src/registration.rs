@@ -1,22 +1,32 @@
+use std::fmt;
+
 use alloy::{
-    primitives::Address, providers::Provider, rpc::types::TransactionReceipt,
-    sol_types::SolValue as _, transports::Transport,
+    primitives::{Address, U256},
+    providers::Provider,
+    rpc::types::TransactionReceipt,
+    sol_types::SolValue as _,
+    transports::Transport,
 };
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use ark_ec::CurveGroup;
 use contract_bindings_alloy::staketable::{
     EdOnBN254::EdOnBN254Point,
     StakeTable::StakeTableInstance,
     BN254::{G1Point, G2Point},
 };
+use espresso_types::{validators_from_l1_events, StakeTableEvent, StakeTableEventMode};
 use ethers_conv::ToAlloy;
 use hotshot_contract_adapter::{
     jellyfish::ParsedG1Point,
-    stake_table::{ParsedEdOnBN254Point, ParsedG2Point},
+    stake_table::{
+        bls_alloy_to_jf2, bls_jf_to_alloy2, edward_bn254point_to_state_ver, ParsedEdOnBN254Point,
+        ParsedG2Point,
+    },
 };
 use jf_signature::constants::CS_ID_BLS_BN254;
+use serde::{Serialize, Serializer};
 
-use crate::{parse::Commission, BLSKeyPair, StateVerKey};
+use crate::{l1::query_in_chunks, parse::Commission, BLSKeyPair, StateVerKey, TransactionPreview};
 
 fn to_alloy_g1_point(p: ParsedG1Point) -> G1Point {
     G1Point {
@@ -41,13 +51,46 @@ fn to_alloy_ed_on_bn_point(p: ParsedEdOnBN254Point) -> EdOnBN254Point {
     }
 }
 
-pub async fn register_validator<P: Provider<T>, T: Transport + Clone>(
-    stake_table: StakeTableInstance<T, P>,
-    commission: Commission,
+/// The public keys of a validator in the format stored in the stake table contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OnChainKeys {
+    pub bls_vk: G2Point,
+    pub schnorr_vk: EdOnBN254Point,
+}
+
+/// Check that the public keys of a validator survive the conversion to the on-chain format and
+/// back, and return the on-chain format of the keys.
+pub fn verify_keys(bls_key_pair: BLSKeyPair, schnorr_vk: StateVerKey) -> Result<OnChainKeys> {
+    let bls_vk = bls_key_pair.ver_key();
+    let bls_vk_alloy = bls_jf_to_alloy2(bls_vk);
+    ensure!(
+        bls_vk_alloy == to_alloy_g2_point(bls_vk.to_affine().into()),
+        "BLS key conversion disagrees with the key submitted at registration"
+    );
+    ensure!(
+        bls_alloy_to_jf2(bls_vk_alloy.clone()) == bls_vk,
+        "BLS key does not round trip through the on-chain format"
+    );
+
+    let schnorr_vk_alloy = to_alloy_ed_on_bn_point(schnorr_vk.to_affine().into());
+    ensure!(
+        edward_bn254point_to_state_ver(schnorr_vk_alloy.clone()) == schnorr_vk,
+        "state key does not round trip through the on-chain format"
+    );
+
+    Ok(OnChainKeys {
+        bls_vk: bls_vk_alloy,
+        schnorr_vk: schnorr_vk_alloy,
+    })
+}
+
+/// The keys of a validator, and the signature proving ownership of its BLS key, in the format
+/// expected by `registerValidator`.
+fn registration_args(
     validator_address: Address,
     bls_key_pair: BLSKeyPair,
     schnorr_vk: StateVerKey,
-) -> Result<TransactionReceipt> {
+) -> (G2Point, EdOnBN254Point, G1Point) {
     let bls_vk = bls_key_pair.ver_key();
 
     let sig_parsed: ParsedG2Point = bls_vk.to_affine().into();
@@ -60,19 +103,50 @@ pub async fn register_validator<P: Provider<T>, T: Transport + Clone>(
     let schnorr_vk_parsed: ParsedEdOnBN254Point = schnorr_vk.to_affine().into();
     let schnorr_vk_alloy = to_alloy_ed_on_bn_point(schnorr_vk_parsed);
 
+    (bls_vk_alloy, schnorr_vk_alloy, sig_alloy)
+}
+
+pub async fn register_validator<P: Provider<T>, T: Transport + Clone>(
+    stake_table: StakeTableInstance<T, P>,
+    commission: Commission,
+    validator_address: Address,
+    bls_key_pair: BLSKeyPair,
+    schnorr_vk: StateVerKey,
+) -> Result<TransactionReceipt> {
+    let (bls_vk, schnorr_vk, sig) = registration_args(validator_address, bls_key_pair, schnorr_vk);
+
     Ok(stake_table
-        .registerValidator(
-            bls_vk_alloy,
-            schnorr_vk_alloy,
-            sig_alloy,
-            commission.to_evm(),
-        )
+        .registerValidator(bls_vk, schnorr_vk, sig, commission.to_evm())
         .send()
         .await?
         .get_receipt()
         .await?)
 }
 
+/// Preview the transaction [`register_validator`] would send.
+pub async fn preview_register_validator<P: Provider<T>, T: Transport + Clone>(
+    stake_table: StakeTableInstance<T, P>,
+    commission: Commission,
+    validator_address: Address,
+    bls_key_pair: BLSKeyPair,
+    schnorr_vk: StateVerKey,
+) -> TransactionPreview {
+    let arguments = vec![
+        ("blsVK", bls_key_pair.ver_key().to_string()),
+        ("schnorrVK", schnorr_vk.to_string()),
+        ("commission", commission.to_string()),
+    ];
+    let (bls_vk, schnorr_vk, sig) = registration_args(validator_address, bls_key_pair, schnorr_vk);
+
+    TransactionPreview::new(
+        *stake_table.address(),
+        validator_address,
+        stake_table.registerValidator(bls_vk, schnorr_vk, sig, commission.to_evm()),
+        arguments,
+    )
+    .await
+}
+
 pub async fn deregister_validator<P: Provider<T>, T: Transport + Clone>(
     stake_table: StakeTableInstance<T, P>,
 ) -> Result<TransactionReceipt> {
@@ -84,9 +158,145 @@ pub async fn deregister_validator<P: Provider<T>, T: Transport + Clone>(
         .await?)
 }
 
+/// Preview the transaction [`deregister_validator`] would send.
+pub async fn preview_deregister_validator<P: Provider<T>, T: Transport + Clone>(
+    stake_table: StakeTableInstance<T, P>,
+    validator_address: Address,
+) -> TransactionPreview {
+    TransactionPreview::new(
+        *stake_table.address(),
+        validator_address,
+        stake_table.deregisterValidator(),
+        vec![],
+    )
+    .await
+}
+
+/// A registered validator, as reconstructed from the events of the stake table contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredValidator {
+    pub address: Address,
+    /// The commission in basis points.
+    pub commission: u16,
+    /// The sum of the stake delegated to the validator.
+    pub stake: U256,
+    /// The number of delegators with stake delegated to the validator.
+    pub num_delegators: usize,
+}
+
+/// All validators currently registered in the stake table contract, in order of registration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredValidators(pub Vec<RegisteredValidator>);
+
+impl fmt::Display for RegisteredValidators {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for validator in &self.0 {
+            let commission = Commission::try_from(validator.commission).map_or_else(
+                |_| format!("{} bps", validator.commission),
+                |c| c.to_string(),
+            );
+            writeln!(
+                f,
+                "{}: commission {commission}, stake {}, {} delegators",
+                validator.address, validator.stake, validator.num_delegators
+            )?;
+        }
+        write!(f, "Validators: {}", self.0.len())
+    }
+}
+
+/// Addresses are serialized in checksummed hex, and stakes as decimal strings, so that they do
+/// not lose precision when parsed as JSON numbers.
+impl Serialize for RegisteredValidators {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Validator {
+            address: String,
+            commission: u16,
+            stake: String,
+            delegators: usize,
+        }
+
+        self.0
+            .iter()
+            .map(|validator| Validator {
+                address: validator.address.to_checksum(None),
+                commission: validator.commission,
+                stake: validator.stake.to_string(),
+                delegators: validator.num_delegators,
+            })
+            .collect::<Vec<_>>()
+            .serialize(serializer)
+    }
+}
+
+/// Fetch all validators currently registered in the stake table contract.
+///
+/// The validators are reconstructed from the events of the contract with the same logic the
+/// sequencer uses to build its stake table, querying the logs `blocks_per_query` blocks at a time.
+pub async fn fetch_validators<P: Provider<T>, T: Transport + Clone>(
+    stake_table: StakeTableInstance<T, P>,
+    blocks_per_query: u64,
+) -> Result<RegisteredValidators> {
+    let to_block = stake_table.provider().get_block_number().await?;
+
+    let registered = query_in_chunks(
+        || stake_table.ValidatorRegistered_filter(),
+        to_block,
+        blocks_per_query,
+    )
+    .await?;
+    let deregistered = query_in_chunks(
+        || stake_table.ValidatorExit_filter(),
+        to_block,
+        blocks_per_query,
+    )
+    .await?;
+    let delegated = query_in_chunks(
+        || stake_table.Delegated_filter(),
+        to_block,
+        blocks_per_query,
+    )
+    .await?;
+    let undelegated = query_in_chunks(
+        || stake_table.Undelegated_filter(),
+        to_block,
+        blocks_per_query,
+    )
+    .await?;
+    let keys_update = query_in_chunks(
+        || stake_table.ConsensusKeysUpdated_filter(),
+        to_block,
+        blocks_per_query,
+    )
+    .await?;
+
+    let events = StakeTableEvent::sort_events(
+        registered,
+        deregistered,
+        delegated,
+        undelegated,
+        keys_update,
+    )?;
+    let validators = validators_from_l1_events(events.into_values(), StakeTableEventMode::Strict)?;
+
+    Ok(RegisteredValidators(
+        validators
+            .into_values()
+            .map(|validator| RegisteredValidator {
+                address: validator.account,
+                commission: validator.commission,
+                stake: validator.stake,
+                num_delegators: validator.delegators.len(),
+            })
+            .collect(),
+    ))
+}
+
 #[cfg(test)]
 mod test {
     use contract_bindings_alloy::staketable::StakeTable;
+    use hotshot_types::light_client::StateKeyPair;
 
     use super::*;
     use crate::{deploy::TestSystem, l1::decode_log};
@@ -115,6 +325,21 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_verify_keys() -> Result<()> {
+        let bls_key_pair = BLSKeyPair::generate(&mut rand::thread_rng());
+        let schnorr_key_pair = StateKeyPair::generate();
+
+        let keys = verify_keys(bls_key_pair.clone(), schnorr_key_pair.ver_key())?;
+        assert_eq!(bls_alloy_to_jf2(keys.bls_vk), bls_key_pair.ver_key());
+        assert_eq!(
+            edward_bn254point_to_state_ver(keys.schnorr_vk),
+            schnorr_key_pair.ver_key()
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_deregister_validator() -> Result<()> {
         let system = TestSystem::deploy().await?;
@@ -128,4 +353,35 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_fetch_validators() -> Result<()> {
+        let system = TestSystem::deploy().await?;
+        system.register_validator().await?;
+        system.delegate(U256::from(100)).await?;
+        system.undelegate(U256::from(40)).await?;
+
+        // Query a single block at a time, to go through many pages of logs.
+        let validators = fetch_validators(system.stake_table.clone(), 1).await?;
+        assert_eq!(
+            validators,
+            RegisteredValidators(vec![RegisteredValidator {
+                address: system.deployer_address,
+                commission: system.commission.to_evm(),
+                stake: U256::from(60),
+                num_delegators: 1,
+            }])
+        );
+        assert_eq!(
+            validators,
+            fetch_validators(system.stake_table.clone(), 10_000).await?
+        );
+
+        // Validators are no longer listed once they exit.
+        system.deregister_validator().await?;
+        let validators = fetch_validators(system.stake_table, 1).await?;
+        assert_eq!(validators, RegisteredValidators(vec![]));
+
+        Ok(())
+    }
 }
@@ -1,6 +1,9 @@
 use std::process::{Command, Output};
 
-use alloy::primitives::U256;
+use alloy::{
+    primitives::U256,
+    signers::local::{coins_bip39::English, MnemonicBuilder},
+};
 use anyhow::Result;
 use staking_cli::*;
 
@@ -65,6 +68,19 @@ fn test_cli_created_and_remove_config_file() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_cli_ledger_not_connected() -> Result<()> {
+    let output = cmd().arg("--ledger").arg("deregister-validator").output()?;
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(
+        stderr.contains("failed to connect to the Ledger"),
+        "unexpected error: {stderr}"
+    );
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_cli_register_validator() -> Result<()> {
     let system = TestSystem::deploy().await?;
@@ -111,6 +127,125 @@ async fn test_cli_delegate() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_cli_delegate_auto_approve() -> Result<()> {
+    let system = TestSystem::deploy().await?;
+    system.register_validator().await?;
+
+    // A fresh account has tokens but has not approved the stake table.
+    let delegator = MnemonicBuilder::<English>::default()
+        .phrase(DEV_MNEMONIC)
+        .index(1)?
+        .build()?
+        .address();
+    let amount = U256::from(123);
+    system.transfer(delegator, amount).await?;
+    let stake_table = *system.stake_table.address();
+
+    // Without approval the delegation fails.
+    let output = system
+        .cmd()
+        .arg("--account-index")
+        .arg("1")
+        .arg("delegate")
+        .arg("--validator-address")
+        .arg(system.deployer_address.to_string())
+        .arg("--amount")
+        .arg(amount.to_string())
+        .output()?;
+    assert!(!output.status.success());
+
+    system
+        .cmd()
+        .arg("--account-index")
+        .arg("1")
+        .arg("delegate")
+        .arg("--validator-address")
+        .arg(system.deployer_address.to_string())
+        .arg("--amount")
+        .arg(amount.to_string())
+        .arg("--auto-approve")
+        .output()?
+        .assert_success();
+
+    // The approval covered exactly the delegated amount, which has been spent.
+    assert_eq!(
+        system
+            .token
+            .allowance(delegator, stake_table)
+            .call()
+            .await?
+            ._0,
+        U256::ZERO
+    );
+    assert_eq!(system.balance(delegator).await?, U256::ZERO);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cli_list_delegators() -> Result<()> {
+    let system = TestSystem::deploy().await?;
+    system.register_validator().await?;
+    system.delegate(U256::from(123)).await?;
+
+    let output = system
+        .cmd()
+        .arg("list-delegators")
+        .arg("--validator-address")
+        .arg(system.deployer_address.to_string())
+        .output()?;
+    output.assert_success();
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains(&format!("{}: 123", system.deployer_address)));
+    assert!(stdout.contains("Total: 123"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cli_list_validators() -> Result<()> {
+    let system = TestSystem::deploy().await?;
+    system.register_validator().await?;
+    system.delegate(U256::from(123)).await?;
+
+    let output = system.cmd().arg("list-validators").output()?;
+    output.assert_success();
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains(&format!(
+        "{}: commission {}, stake 123, 1 delegators",
+        system.deployer_address, system.commission
+    )));
+    assert!(stdout.contains("Validators: 1"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cli_list_delegators_json() -> Result<()> {
+    let system = TestSystem::deploy().await?;
+    system.register_validator().await?;
+    system.delegate(U256::from(123)).await?;
+
+    let output = system
+        .cmd()
+        .arg("--format")
+        .arg("json")
+        .arg("list-delegators")
+        .arg("--validator-address")
+        .arg(system.deployer_address.to_string())
+        .output()?;
+    output.assert_success();
+    let delegators: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let deployer_address = system.deployer_address.to_checksum(None);
+    assert_eq!(
+        delegators,
+        serde_json::json!({
+            "validator": deployer_address,
+            "delegators": [{ "address": deployer_address, "stake": "123" }],
+            "total": "123",
+        })
+    );
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_cli_deregister_validator() -> Result<()> {
     let system = TestSystem::deploy().await?;
@@ -162,6 +297,33 @@ async fn test_cli_claim_withdrawal() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_cli_claim_withdrawal_locked() -> Result<()> {
+    let system = TestSystem::deploy().await?;
+    let amount = U256::from(123);
+    system.register_validator().await?;
+    system.delegate(amount).await?;
+    system.undelegate(amount).await?;
+    let balance_before = system.balance(system.deployer_address).await?;
+
+    let output = system
+        .cmd()
+        .arg("claim-withdrawal")
+        .arg("--validator-address")
+        .arg(system.deployer_address.to_string())
+        .output()?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("locked"), "{stderr}");
+
+    // Nothing was claimed.
+    assert_eq!(
+        system.balance(system.deployer_address).await?,
+        balance_before
+    );
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_cli_claim_validator_exit() -> Result<()> {
     let system = TestSystem::deploy().await?;
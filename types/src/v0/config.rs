@@ -2,6 +2,8 @@ use std::{num::NonZeroUsize, time::Duration};
 
 use anyhow::Context;
 use hotshot_types::{
+    default_allow_leader_vid_dispersal, default_high_qc_wait_fraction,
+    default_max_proposal_storage_attempts,
     network::{
         BuilderType, CombinedNetworkConfig, Libp2pConfig, NetworkConfig, RandomBuilderConfig,
     },
@@ -74,6 +76,16 @@ pub struct PublicHotShotConfig {
     stop_voting_time: u64,
     epoch_height: u64,
     epoch_start_block: u64,
+    #[serde(default = "default_allow_leader_vid_dispersal")]
+    allow_leader_vid_dispersal: bool,
+    #[serde(default)]
+    max_future_view_skew: Option<u64>,
+    #[serde(default)]
+    views_per_decide_alert_threshold: Option<u64>,
+    #[serde(default = "default_max_proposal_storage_attempts")]
+    max_proposal_storage_attempts: u64,
+    #[serde(default = "default_high_qc_wait_fraction")]
+    high_qc_wait_fraction: (u64, u64),
 }
 
 impl From<HotShotConfig<SeqTypes>> for PublicHotShotConfig {
@@ -104,6 +116,11 @@ impl From<HotShotConfig<SeqTypes>> for PublicHotShotConfig {
             stop_voting_time,
             epoch_height,
             epoch_start_block,
+            allow_leader_vid_dispersal,
+            max_future_view_skew,
+            views_per_decide_alert_threshold,
+            max_proposal_storage_attempts,
+            high_qc_wait_fraction,
         } = v;
 
         Self {
@@ -129,6 +146,11 @@ impl From<HotShotConfig<SeqTypes>> for PublicHotShotConfig {
             stop_voting_time,
             epoch_height,
             epoch_start_block,
+            allow_leader_vid_dispersal,
+            max_future_view_skew,
+            views_per_decide_alert_threshold,
+            max_proposal_storage_attempts,
+            high_qc_wait_fraction,
         }
     }
 }
@@ -158,6 +180,11 @@ impl PublicHotShotConfig {
             stop_voting_time: self.stop_voting_time,
             epoch_height: self.epoch_height,
             epoch_start_block: self.epoch_start_block,
+            allow_leader_vid_dispersal: self.allow_leader_vid_dispersal,
+            max_future_view_skew: self.max_future_view_skew,
+            views_per_decide_alert_threshold: self.views_per_decide_alert_threshold,
+            max_proposal_storage_attempts: self.max_proposal_storage_attempts,
+            high_qc_wait_fraction: self.high_qc_wait_fraction,
         }
     }
 
@@ -1,3 +1,5 @@
 mod ns_proof;
 mod ns_table;
 mod payload;
+
+pub use ns_proof::advz::NsProofVerifierCache;
@@ -1,5 +1,7 @@
 //! This module contains the original namespace proof implementation for ADVZ scheme.
 
+use std::num::NonZeroUsize;
+
 use hotshot_types::{
     data::VidCommitment,
     traits::EncodeBytes,
@@ -9,6 +11,7 @@ use jf_vid::{
     payload_prover::{PayloadProver, Statement},
     VidScheme,
 };
+use lru::LruCache;
 
 use crate::{
     v0_1::ADVZNsProof, NamespaceId, NsIndex, NsTable, Payload, PayloadByteLen, Transaction,
@@ -136,6 +139,37 @@ impl ADVZNsProof {
         }
     }
 
+    /// Verify many [`ADVZNsProof`]s against the same payload commitment, one at
+    /// a time. Each entry of the returned [`Vec`] is the result of
+    /// [`ADVZNsProof::verify`] for the proof at the same position.
+    ///
+    /// `on_progress` is invoked after each proof with the number of proofs
+    /// verified so far and the total number of proofs. A client in a WASM
+    /// environment cannot spread this work across threads, so it can use the
+    /// callback to report progress or hand control back to its event loop in
+    /// between proofs.
+    pub fn verify_batch<F>(
+        proofs: &[ADVZNsProof],
+        ns_table: &NsTable,
+        commit: &VidCommitment,
+        common: &ADVZCommon,
+        mut on_progress: F,
+    ) -> Vec<Option<(Vec<Transaction>, NamespaceId)>>
+    where
+        F: FnMut(usize, usize),
+    {
+        let total = proofs.len();
+        proofs
+            .iter()
+            .enumerate()
+            .map(|(i, proof)| {
+                let result = proof.verify(ns_table, commit, common);
+                on_progress(i + 1, total);
+                result
+            })
+            .collect()
+    }
+
     /// Return all transactions in the namespace whose payload is proven by
     /// `self`. The namespace ID for each returned [`Transaction`] is set to
     /// `ns_id`.
@@ -163,6 +197,80 @@ impl ADVZNsProof {
     }
 }
 
+/// Cache key for [`NsProofVerifierCache`]: the payload commitment, the
+/// namespace index, and a hash of the proof together with the namespace table
+/// it was verified against.
+type NsProofCacheKey = (VidCommitment, NsIndex, [u8; 32]);
+
+/// A bounded cache of [`ADVZNsProof::verify`] results.
+///
+/// Verifying a namespace proof requires setting up the VID scheme and running
+/// `payload_verify`, which is expensive for a client that repeatedly verifies
+/// the same proof, e.g. a WASM light client re-rendering its UI. This cache
+/// memoizes the result of each verification, evicting the least recently used
+/// entry once `capacity` is reached.
+///
+/// Results are keyed on the payload commitment, so a proof is never confused
+/// with an identical proof for a different block.
+pub struct NsProofVerifierCache {
+    results: LruCache<NsProofCacheKey, Option<(Vec<Transaction>, NamespaceId)>>,
+    misses: usize,
+}
+
+impl NsProofVerifierCache {
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            results: LruCache::new(capacity),
+            misses: 0,
+        }
+    }
+
+    /// Same as [`ADVZNsProof::verify`], but returns the memoized result if
+    /// this proof has already been verified against the same namespace table
+    /// and commitment.
+    pub fn verify(
+        &mut self,
+        proof: &ADVZNsProof,
+        ns_table: &NsTable,
+        commit: &VidCommitment,
+        common: &ADVZCommon,
+    ) -> Option<(Vec<Transaction>, NamespaceId)> {
+        let Ok(bytes) = bincode::serialize(&(proof, ns_table)) else {
+            // Unable to compute a cache key, fall back to verifying directly.
+            self.misses += 1;
+            return proof.verify(ns_table, commit, common);
+        };
+        let key = (
+            *commit,
+            proof.ns_index.clone(),
+            *blake3::hash(&bytes).as_bytes(),
+        );
+
+        if let Some(result) = self.results.get(&key) {
+            return result.clone();
+        }
+
+        self.misses += 1;
+        let result = proof.verify(ns_table, commit, common);
+        self.results.put(key, result.clone());
+        result
+    }
+
+    /// The number of calls to [`Self::verify`] that were not answered from
+    /// the cache, and thus required a full verification.
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use futures::future;
@@ -174,6 +282,7 @@ mod tests {
     };
     use jf_vid::{VidDisperse, VidScheme};
 
+    use super::NsProofVerifierCache;
     use crate::{v0::impls::block::test::ValidTest, v0_1::ADVZNsProof, Payload};
 
     #[tokio::test(flavor = "multi_thread")]
@@ -327,4 +436,117 @@ mod tests {
                 .is_none());
         }
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ns_proof_verify_batch() {
+        let mut rng = jf_utils::test_rng();
+        let test =
+            ValidTest::from_tx_lengths(vec![vec![5, 8, 8], vec![7, 9, 11], vec![]], &mut rng);
+
+        let block =
+            Payload::from_transactions(test.all_txs(), &Default::default(), &Default::default())
+                .await
+                .unwrap()
+                .0;
+        let vid = advz_scheme(10).disperse(block.encode()).unwrap();
+        let ns_proofs: Vec<ADVZNsProof> = block
+            .ns_table()
+            .iter()
+            .map(|ns_index| ADVZNsProof::new(&block, &ns_index, &vid.common).unwrap())
+            .collect();
+
+        let mut progress = Vec::new();
+        let results = ADVZNsProof::verify_batch(
+            &ns_proofs,
+            block.ns_table(),
+            &VidCommitment::V0(vid.commit),
+            &vid.common,
+            |verified, total| progress.push((verified, total)),
+        );
+
+        // the callback is invoked once per proof
+        let total = ns_proofs.len();
+        assert_eq!(
+            progress,
+            (1..=total).map(|i| (i, total)).collect::<Vec<_>>()
+        );
+
+        assert_eq!(results.len(), total);
+        for (ns_proof, result) in ns_proofs.iter().zip(results) {
+            let ns_id = block.ns_table().read_ns_id(&ns_proof.ns_index).unwrap();
+            let (txs, result_ns_id) = result.expect("namespace proof verification failure");
+            assert_eq!(result_ns_id, ns_id);
+            assert_eq!(txs, test.nss[&ns_id]);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ns_proof_verifier_cache() {
+        let mut rng = jf_utils::test_rng();
+        let tests = ValidTest::many_from_tx_lengths(
+            vec![
+                vec![vec![5, 8, 8], vec![7, 9]],
+                vec![vec![5, 8, 8], vec![7, 9]],
+            ],
+            &mut rng,
+        );
+
+        let mut blocks = Vec::new();
+        for test in &tests {
+            let block = Payload::from_transactions(
+                test.all_txs(),
+                &Default::default(),
+                &Default::default(),
+            )
+            .await
+            .unwrap()
+            .0;
+            let vid = advz_scheme(10).disperse(block.encode()).unwrap();
+            let ns_proof = ADVZNsProof::new(
+                &block,
+                &block.ns_table().iter().next().unwrap(),
+                &vid.common,
+            )
+            .unwrap();
+            blocks.push((block, vid, ns_proof));
+        }
+
+        let mut cache = NsProofVerifierCache::new(2.try_into().unwrap());
+        let (block_0, vid_0, ns_proof_0) = &blocks[0];
+        let commit_0 = VidCommitment::V0(vid_0.commit);
+
+        // Verifying the same proof twice only runs the scheme once.
+        let expected = ns_proof_0.verify(block_0.ns_table(), &commit_0, &vid_0.common);
+        assert!(expected.is_some());
+        for _ in 0..2 {
+            assert_eq!(
+                cache.verify(ns_proof_0, block_0.ns_table(), &commit_0, &vid_0.common),
+                expected
+            );
+        }
+        assert_eq!(cache.misses(), 1);
+        assert_eq!(cache.len(), 1);
+
+        // The same proof against a different block's commitment is not served
+        // from the cache, and fails.
+        let (block_1, vid_1, ns_proof_1) = &blocks[1];
+        let commit_1 = VidCommitment::V0(vid_1.commit);
+        assert!(cache
+            .verify(ns_proof_0, block_0.ns_table(), &commit_1, &vid_1.common)
+            .is_none());
+        assert_eq!(cache.misses(), 2);
+
+        // The proof for the other block is cached separately.
+        assert!(cache
+            .verify(ns_proof_1, block_1.ns_table(), &commit_1, &vid_1.common)
+            .is_some());
+        assert_eq!(cache.misses(), 3);
+
+        // The cache is bounded; the least recently used entry was evicted.
+        assert_eq!(cache.len(), 2);
+        assert!(cache
+            .verify(ns_proof_0, block_0.ns_table(), &commit_0, &vid_0.common)
+            .is_some());
+        assert_eq!(cache.misses(), 4);
+    }
 }
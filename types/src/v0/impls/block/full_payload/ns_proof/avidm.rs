@@ -66,11 +66,13 @@ impl AvidMNsProof {
 mod tests {
     use futures::future;
     use hotshot::{helpers::initialize_logging, traits::BlockPayload};
+    use hotshot_query_service::VidCommon;
     use hotshot_types::{
         data::VidCommitment,
         traits::EncodeBytes,
         vid::avidm::{AvidMParam, AvidMScheme},
     };
+    use vid::avid_m::proofs::NsProof;
 
     use crate::{v0::impls::block::test::ValidTest, v0_3::AvidMNsProof, NsIndex, Payload};
 
@@ -176,5 +178,53 @@ mod tests {
                 .verify(ns_table_1, vid_commit_1, &param)
                 .is_none());
         }
+
+        // hack the proof
+        {
+            let vid_commit_0 = &blocks[0].vid_commit;
+            ns_proof_0_0
+                .verify(ns_table_0, vid_commit_0, &param)
+                .expect("sanity: correct proof should succeed");
+
+            let wrong_ns_index_ns_proof_0_0 = AvidMNsProof(NsProof {
+                ns_index: blocks[0].ns_proofs[1].0.ns_index,
+                ..ns_proof_0_0.0.clone()
+            });
+            assert!(wrong_ns_index_ns_proof_0_0
+                .verify(ns_table_0, vid_commit_0, &param)
+                .is_none());
+
+            let wrong_ns_payload_ns_proof_0_0 = AvidMNsProof(NsProof {
+                ns_payload: blocks[0].ns_proofs[1].0.ns_payload.clone(),
+                ..ns_proof_0_0.0.clone()
+            });
+            assert!(wrong_ns_payload_ns_proof_0_0
+                .verify(ns_table_0, vid_commit_0, &param)
+                .is_none());
+
+            let wrong_proof_ns_proof_0_0 = AvidMNsProof(NsProof {
+                ns_proof: blocks[0].ns_proofs[1].0.ns_proof.clone(),
+                ..ns_proof_0_0.0.clone()
+            });
+            assert!(wrong_proof_ns_proof_0_0
+                .verify(ns_table_0, vid_commit_0, &param)
+                .is_none());
+        }
+
+        // the versioned proof dispatches to the AvidM proof
+        {
+            let vid_commit_0 = &blocks[0].vid_commit;
+            let vid_common = VidCommon::V1(param.clone());
+            let ns_index = blocks[0].block.ns_table().iter().next().unwrap();
+            let ns_proof = crate::NsProof::new(&blocks[0].block, &ns_index, &vid_common).unwrap();
+            assert_eq!(ns_proof, crate::NsProof::V1(ns_proof_0_0.clone()));
+            assert_eq!(
+                ns_proof.verify(ns_table_0, vid_commit_0, &vid_common),
+                ns_proof_0_0.verify(ns_table_0, vid_commit_0, &param)
+            );
+            assert!(ns_proof
+                .verify(ns_table_0, vid_commit_1, &vid_common)
+                .is_none());
+        }
     }
 }
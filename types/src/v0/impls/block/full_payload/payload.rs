@@ -72,13 +72,12 @@ impl Payload {
     /// Need a sync version of [`BlockPayload::from_transactions`] in order to impl [`BlockPayload::empty`].
     fn from_transactions_sync(
         transactions: impl IntoIterator<Item = <Self as BlockPayload<SeqTypes>>::Transaction> + Send,
-        chain_config: ChainConfig,
+        max_block_byte_len: u64,
     ) -> Result<
         (Self, <Self as BlockPayload<SeqTypes>>::Metadata),
         <Self as BlockPayload<SeqTypes>>::Error,
     > {
         // accounting for block byte length limit
-        let max_block_byte_len = u64::from(chain_config.max_block_size);
         let mut block_byte_len = NsTableBuilder::header_byte_len() as u64;
 
         // add each tx to its namespace
@@ -156,7 +155,8 @@ impl BlockPayload<SeqTypes> for Payload {
             }
         };
 
-        Self::from_transactions_sync(transactions, ChainConfig::from(chain_config))
+        let max_block_byte_len = instance_state.max_block_byte_len(&chain_config);
+        Self::from_transactions_sync(transactions, max_block_byte_len)
     }
 
     // TODO avoid cloning the entire payload here?
@@ -168,7 +168,8 @@ impl BlockPayload<SeqTypes> for Payload {
     }
 
     fn empty() -> (Self, Self::Metadata) {
-        let payload = Self::from_transactions_sync(vec![], Default::default())
+        let max_block_byte_len = u64::from(ChainConfig::default().max_block_size);
+        let payload = Self::from_transactions_sync(vec![], max_block_byte_len)
             .unwrap()
             .0;
 
@@ -3,4 +3,5 @@ mod namespace_payload;
 mod test;
 mod uint_bytes;
 
+pub use full_payload::NsProofVerifierCache;
 pub use uint_bytes::*;
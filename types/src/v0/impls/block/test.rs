@@ -3,14 +3,18 @@ use std::collections::BTreeMap;
 
 use hotshot::traits::BlockPayload;
 use hotshot_query_service::availability::QueryablePayload;
-use hotshot_types::{data::VidCommitment, traits::EncodeBytes, vid::advz::advz_scheme};
+use hotshot_types::{
+    data::{null_block, VidCommitment},
+    traits::EncodeBytes,
+    vid::advz::advz_scheme,
+};
 use jf_vid::VidScheme;
 use rand::RngCore;
 use sequencer_utils::test_utils::setup_test;
 
 use crate::{
-    v0_1::ADVZNsProof, v0_99::ChainConfig, BlockSize, NamespaceId, NodeState, Payload, Transaction,
-    TxProof, ValidatedState,
+    v0_1::ADVZNsProof, v0_99::ChainConfig, BlockSize, NamespaceId, NodeState, Payload, SeqTypes,
+    Transaction, TxProof, ValidatedState,
 };
 
 #[tokio::test(flavor = "multi_thread")]
@@ -163,6 +167,74 @@ async fn enforce_max_block_size() {
     assert_eq!(block.len(block.ns_table()), tx_count_expected - 1);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn enforce_max_block_payload_bytes() {
+    setup_test();
+    let test_case = vec![vec![5, 8, 8], vec![7, 9, 11], vec![10, 5, 8]];
+    let payload_byte_len_expected: usize = 119;
+    let ns_table_byte_len_expected: usize = 28;
+    let max_block_byte_len = (payload_byte_len_expected + ns_table_byte_len_expected) as u64;
+
+    let mut rng = jf_utils::test_rng();
+    let test = ValidTest::from_tx_lengths(test_case, &mut rng);
+    let txs = test.all_txs();
+
+    // The chain config allows much larger blocks than the payload limit of this node.
+    let chain_config = ChainConfig {
+        max_block_size: BlockSize::from(max_block_byte_len * 10),
+        ..Default::default()
+    };
+    let validated_state = ValidatedState {
+        chain_config: chain_config.into(),
+        ..Default::default()
+    };
+
+    // test: actual block size equals the payload limit
+    let instance_state = NodeState::default()
+        .with_chain_config(chain_config)
+        .with_max_block_payload_bytes(max_block_byte_len);
+    let block = Payload::from_transactions(txs.clone(), &validated_state, &instance_state)
+        .await
+        .unwrap()
+        .0;
+    assert_eq!(block.encode().len(), payload_byte_len_expected);
+    assert_eq!(block.len(block.ns_table()), txs.len());
+
+    // test: actual block size exceeds the payload limit, so assembly stops before the last tx
+    let instance_state = NodeState::default()
+        .with_chain_config(chain_config)
+        .with_max_block_payload_bytes(max_block_byte_len - 1);
+    let block = Payload::from_transactions(txs.clone(), &validated_state, &instance_state)
+        .await
+        .unwrap()
+        .0;
+    assert!(block.encode().len() < payload_byte_len_expected);
+    assert_eq!(block.len(block.ns_table()), txs.len() - 1);
+    let included: Vec<_> = block
+        .iter(block.ns_table())
+        .map(|index| block.transaction(&index).unwrap())
+        .collect();
+    assert_eq!(included, txs[..txs.len() - 1]);
+}
+
+#[test]
+fn empty_block_commitment() {
+    let (builder_commitment, metadata) = null_block::empty_block_commitment::<SeqTypes>();
+
+    let (empty_payload, empty_metadata) = Payload::empty();
+    assert_eq!(metadata, empty_metadata);
+    assert_eq!(
+        builder_commitment,
+        empty_payload.builder_commitment(&empty_metadata)
+    );
+
+    // The memoized result must be stable across calls.
+    assert_eq!(
+        null_block::empty_block_commitment::<SeqTypes>(),
+        (builder_commitment, metadata)
+    );
+}
+
 // TODO lots of infra here that could be reused in other tests.
 pub struct ValidTest {
     pub nss: BTreeMap<NamespaceId, Vec<Transaction>>,
@@ -28,12 +28,17 @@ use time::OffsetDateTime;
 use vbs::version::{StaticVersionType, Version};
 
 use super::{
-    instance_state::NodeState, state::ValidatedState, v0_1::RewardMerkleCommitment, v0_3::Validator,
+    instance_state::NodeState,
+    state::ValidatedState,
+    v0_1::{RewardAmount, RewardMerkleCommitment},
+    v0_3::Validator,
 };
 use crate::{
     v0::{
         header::{EitherOrVersion, VersionedHeader},
-        impls::reward::{apply_rewards, catchup_missing_accounts, first_two_epochs},
+        impls::reward::{
+            apply_rewards, catchup_missing_accounts, first_two_epochs, RemainderPolicy,
+        },
         MarketplaceVersion,
     },
     v0_1, v0_2, v0_3,
@@ -416,6 +421,8 @@ impl Header {
         version: Version,
         auction_results: Option<SolverAuctionResults>,
         validator: Option<Validator<BLSPubKey>>,
+        block_reward: RewardAmount,
+        reward_remainder_policy: RemainderPolicy,
     ) -> anyhow::Result<Self> {
         ensure!(
             version.major == 0,
@@ -526,7 +533,12 @@ impl Header {
         // so that marketplace version also supports this,
         // and the marketplace integration test passes
         if let Some(validator) = validator {
-            let reward_state = apply_rewards(state.reward_merkle_tree.clone(), validator)?;
+            let reward_state = apply_rewards(
+                state.reward_merkle_tree.clone(),
+                validator,
+                block_reward,
+                reward_remainder_policy,
+            )?;
             state.reward_merkle_tree = reward_state;
         }
 
@@ -967,6 +979,8 @@ impl BlockHeader<SeqTypes> for Header {
             version,
             auction_results,
             None,
+            instance_state.block_reward,
+            instance_state.reward_remainder_policy,
         )?)
     }
 
@@ -1116,6 +1130,8 @@ impl BlockHeader<SeqTypes> for Header {
             version,
             None,
             leader_config,
+            instance_state.block_reward,
+            instance_state.reward_remainder_policy,
         )?)
     }
 
@@ -1349,6 +1365,8 @@ mod test_headers {
                 Version { major: 0, minor: 1 },
                 None,
                 None,
+                genesis.instance_state.block_reward,
+                genesis.instance_state.reward_remainder_policy,
             )
             .unwrap();
             assert_eq!(header.height(), parent.height() + 1);
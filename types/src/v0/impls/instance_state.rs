@@ -1,5 +1,6 @@
 use std::{collections::BTreeMap, sync::Arc};
 
+use anyhow::ensure;
 #[cfg(any(test, feature = "testing"))]
 use async_lock::RwLock;
 use async_trait::async_trait;
@@ -9,20 +10,19 @@ use hotshot_types::{
     HotShotConfig,
 };
 use indexmap::IndexMap;
-#[cfg(any(test, feature = "testing"))]
-use vbs::version::StaticVersionType;
-use vbs::version::Version;
+use vbs::version::{StaticVersionType, Version};
 
 use super::{
+    reward::{RemainderPolicy, RewardConfig},
     state::ValidatedState,
     traits::MembershipPersistence,
-    v0_1::NoStorage,
+    v0_1::{block_reward, NoStorage, RewardAmount},
     v0_3::{IndexedStake, Validator},
     SeqTypes,
 };
 use crate::v0::{
-    traits::StateCatchup, v0_99::ChainConfig, GenesisHeader, L1BlockInfo, L1Client, Timestamp,
-    Upgrade, UpgradeMode,
+    traits::StateCatchup, v0_99::ChainConfig, EpochVersion, GenesisHeader, L1BlockInfo, L1Client,
+    Timestamp, Upgrade, UpgradeMode,
 };
 #[cfg(any(test, feature = "testing"))]
 use crate::EpochCommittees;
@@ -60,6 +60,20 @@ pub struct NodeState {
     /// to use in functions such as genesis.
     /// (example: genesis returns V2 Header if version is 0.2)
     pub current_version: Version,
+
+    /// Upper bound on the byte length of the block payloads built by this node, on top of the
+    /// `max_block_size` of the chain config.
+    ///
+    /// This should be set to the largest payload VID dispersal is provisioned for, so that we never
+    /// build a block that cannot be dispersed.
+    pub max_block_payload_bytes: Option<u64>,
+
+    /// Reward distributed to the leader of each block and its delegators, once rewards are
+    /// enabled.
+    pub block_reward: RewardAmount,
+
+    /// What happens to the rounding remainder of the reward distribution of each block.
+    pub reward_remainder_policy: RemainderPolicy,
 }
 
 #[async_trait]
@@ -108,6 +122,9 @@ impl NodeState {
             current_version,
             epoch_height: None,
             coordinator,
+            max_block_payload_bytes: None,
+            block_reward: block_reward(),
+            reward_remainder_policy: Default::default(),
         }
     }
 
@@ -256,6 +273,55 @@ impl NodeState {
         self.epoch_height = Some(epoch_height);
         self
     }
+
+    pub fn with_max_block_payload_bytes(mut self, max_block_payload_bytes: u64) -> Self {
+        self.max_block_payload_bytes = Some(max_block_payload_bytes);
+        self
+    }
+
+    pub fn with_reward_config(mut self, reward_config: RewardConfig) -> Self {
+        self.block_reward = reward_config.block_reward;
+        self.reward_remainder_policy = reward_config.remainder_policy;
+        self
+    }
+
+    /// The parameters of the reward distribution of this node.
+    pub fn reward_config(&self) -> RewardConfig {
+        RewardConfig {
+            block_reward: self.block_reward,
+            remainder_policy: self.reward_remainder_policy,
+        }
+    }
+
+    /// Check that the epoch height is consistent with the versions this node may run.
+    ///
+    /// An epoch height of zero silently disables epochs, so a node that runs, or is scheduled to
+    /// upgrade to, a version with epochs must be configured with a nonzero epoch height.
+    pub fn validate_epoch_height(&self) -> anyhow::Result<()> {
+        let epochs_expected = self.current_version >= EpochVersion::version()
+            || self
+                .upgrades
+                .keys()
+                .any(|version| *version >= EpochVersion::version());
+        if epochs_expected {
+            ensure!(
+                self.epoch_height
+                    .is_some_and(|epoch_height| epoch_height > 0),
+                "epoch height must be nonzero for a chain with epochs (version {} and later), got \
+                 {:?}",
+                EpochVersion::version(),
+                self.epoch_height
+            );
+        }
+        Ok(())
+    }
+
+    /// The maximum byte length of a block built on top of `chain_config`.
+    pub fn max_block_byte_len(&self, chain_config: &ChainConfig) -> u64 {
+        let max_block_size = u64::from(chain_config.max_block_size);
+        self.max_block_payload_bytes
+            .map_or(max_block_size, |max| max.min(max_block_size))
+    }
 }
 
 // This allows us to turn on `Default` on InstanceState trait
@@ -433,3 +499,55 @@ pub mod mock {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::v0::{UpgradeType, ViewBasedUpgrade};
+
+    #[test]
+    fn test_validate_epoch_height() {
+        // Without epochs, any epoch height is fine.
+        NodeState::mock().validate_epoch_height().unwrap();
+        NodeState::mock()
+            .with_epoch_height(0)
+            .validate_epoch_height()
+            .unwrap();
+
+        // With epochs expected, the epoch height must be set and nonzero.
+        NodeState::mock_v3().validate_epoch_height().unwrap_err();
+        let err = NodeState::mock_v3()
+            .with_epoch_height(0)
+            .validate_epoch_height()
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("epoch height must be nonzero"),
+            "unexpected error: {err}"
+        );
+        NodeState::mock_v3()
+            .with_epoch_height(10)
+            .validate_epoch_height()
+            .unwrap();
+
+        // Epochs are also expected when an upgrade to a version with epochs is scheduled.
+        let upgrades = BTreeMap::from([(
+            EpochVersion::version(),
+            Upgrade {
+                mode: UpgradeMode::View(ViewBasedUpgrade {
+                    start_proposing_view: 1,
+                    stop_proposing_view: 10,
+                    start_voting_view: None,
+                    stop_voting_view: None,
+                }),
+                upgrade_type: UpgradeType::Epoch {
+                    chain_config: ChainConfig::default(),
+                },
+            },
+        )]);
+        NodeState::mock()
+            .with_epoch_height(0)
+            .with_upgrades(upgrades)
+            .validate_epoch_height()
+            .unwrap_err();
+    }
+}
@@ -1,5 +1,6 @@
 use std::{
     cmp::{min, Ordering},
+    collections::BTreeMap,
     num::NonZeroUsize,
     pin::Pin,
     result::Result as StdResult,
@@ -47,10 +48,11 @@ use tracing::Instrument;
 use url::Url;
 
 use super::{
-    from_l1_events,
+    from_l1_events_with_mode,
     v0_1::{SingleTransport, SingleTransportStatus, SwitchingTransport},
     v0_3::Validator,
-    L1BlockInfo, L1BlockInfoWithParent, L1ClientMetrics, L1State, L1UpdateTask, StakeTableEvent,
+    L1BlockInfo, L1BlockInfoWithParent, L1ClientMetrics, L1State, L1UpdateTask, MinStakePolicy,
+    StakeTableEvent, StakeTableEventMode,
 };
 use crate::{FeeInfo, L1Client, L1ClientOptions, L1Event, L1Snapshot};
 
@@ -876,60 +878,88 @@ impl L1Client {
             .await
     }
 
-    /// Get `StakeTable` at block height.
+    /// Get `StakeTable` at block height, selecting at most `max_validators` validators according
+    /// to `min_stake_policy`.
     pub async fn get_stake_table(
         &self,
         contract: Address,
         block: u64,
+        min_stake_policy: MinStakePolicy,
+        max_validators: usize,
     ) -> anyhow::Result<IndexMap<Address, Validator<BLSPubKey>>> {
+        let events = self.get_stake_table_events(contract, 0, block).await?;
+
+        from_l1_events_with_mode(
+            events.into_values(),
+            StakeTableEventMode::Strict,
+            min_stake_policy,
+            max_validators,
+        )
+    }
+
+    /// Get the stake table events emitted from block `from_block` to block `to_block`, both
+    /// inclusive, sorted by their `(block_number, log_index)`.
+    pub async fn get_stake_table_events(
+        &self,
+        contract: Address,
+        from_block: u64,
+        to_block: u64,
+    ) -> anyhow::Result<BTreeMap<(u64, u64), StakeTableEvent>> {
         // TODO stake_table_address needs to be passed in to L1Client
         // before update loop starts.
         let stake_table_contract = StakeTableInstance::new(contract, self.provider.clone());
 
         let registered = stake_table_contract
             .ValidatorRegistered_filter()
-            .from_block(0)
-            .to_block(block)
+            .from_block(from_block)
+            .to_block(to_block)
             .query()
             .await?;
 
         let deregistered = stake_table_contract
             .ValidatorExit_filter()
-            .from_block(0)
-            .to_block(block)
+            .from_block(from_block)
+            .to_block(to_block)
             .query()
             .await?;
 
         let delegated = stake_table_contract
             .Delegated_filter()
-            .from_block(0)
-            .to_block(block)
+            .from_block(from_block)
+            .to_block(to_block)
             .query()
             .await?;
 
         let undelegated = stake_table_contract
             .Undelegated_filter()
-            .from_block(0)
-            .to_block(block)
+            .from_block(from_block)
+            .to_block(to_block)
             .query()
             .await?;
 
         let keys_update = stake_table_contract
             .ConsensusKeysUpdated_filter()
-            .from_block(0)
-            .to_block(block)
+            .from_block(from_block)
+            .to_block(to_block)
             .query()
             .await?;
 
-        let events = StakeTableEvent::sort_events(
+        StakeTableEvent::sort_events(
             registered,
             deregistered,
             delegated,
             undelegated,
             keys_update,
-        )?;
+        )
+    }
 
-        from_l1_events(events.values().cloned())
+    /// Get the hash of the block `number`, or `None` if the L1 does not have this block yet.
+    pub async fn get_block_hash(&self, number: u64) -> anyhow::Result<Option<B256>> {
+        let block = self
+            .provider
+            .get_block(number.into(), BlockTransactionsKind::Hashes)
+            .await?;
+        Ok(block.map(|block| block.header.hash))
     }
 
     /// Check if the given address is a proxy contract.
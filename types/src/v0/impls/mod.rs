@@ -14,10 +14,16 @@ mod state;
 mod transaction;
 
 pub use auction::SolverAuctionResultsProvider;
+pub use block::NsProofVerifierCache;
 pub use fee_info::{retain_accounts, FeeError};
 #[cfg(any(test, feature = "testing"))]
 pub use instance_state::mock;
 pub use instance_state::NodeState;
+pub use reward::{
+    epochs_until_rewards, reward_history, rewards_start_epoch, verify_block_rewards,
+    RemainderPolicy, RewardConfig, RewardDistribution, RewardMerkleTreeCheckpoints,
+    REWARD_CARRY_ACCOUNT,
+};
 pub use stake_table::*;
 pub use state::{
     get_l1_deposits, BuilderValidationError, ProposalValidationError, StateValidationError,
@@ -1,4 +1,8 @@
-use std::{collections::HashSet, str::FromStr};
+use std::{
+    collections::{HashSet, VecDeque},
+    num::NonZeroUsize,
+    str::FromStr,
+};
 
 use anyhow::{bail, ensure, Context};
 use ark_serialize::{
@@ -25,12 +29,12 @@ use num_traits::CheckedSub;
 use sequencer_utils::{
     impl_serde_from_string_or_integer, impl_to_fixed_bytes, ser::FromStringOrInteger,
 };
+use serde::{Deserialize, Serialize};
 
 use super::{
     v0_1::{
-        block_reward, RewardAccount, RewardAccountProof, RewardAccountQueryData, RewardAmount,
-        RewardInfo, RewardMerkleCommitment, RewardMerkleProof, RewardMerkleTree,
-        COMMISSION_BASIS_POINTS,
+        RewardAccount, RewardAccountProof, RewardAccountQueryData, RewardAmount, RewardInfo,
+        RewardMerkleCommitment, RewardMerkleProof, RewardMerkleTree, COMMISSION_BASIS_POINTS,
     },
     v0_3::Validator,
     Leaf2, NodeState, ValidatedState,
@@ -307,6 +311,40 @@ impl RewardAccountProof {
             },
         }
     }
+
+    /// Verify each of `proofs` against the same commitment, returning the balance of each account.
+    ///
+    /// Fails on the first invalid proof, reporting its index in `proofs`.
+    pub fn verify_batch(
+        proofs: &[Self],
+        comm: &RewardMerkleCommitment,
+    ) -> anyhow::Result<Vec<U256>> {
+        proofs
+            .iter()
+            .enumerate()
+            .map(|(i, proof)| {
+                proof.verify(comm).with_context(|| {
+                    format!("invalid proof at index {i} for account {}", proof.account)
+                })
+            })
+            .collect()
+    }
+
+    /// Remember each of `proofs` in `tree`.
+    ///
+    /// Fails on the first proof that cannot be remembered, reporting its index in `proofs`. The
+    /// proofs before it are remembered regardless.
+    pub fn remember_batch(proofs: &[Self], tree: &mut RewardMerkleTree) -> anyhow::Result<()> {
+        for (i, proof) in proofs.iter().enumerate() {
+            proof.remember(tree).with_context(|| {
+                format!(
+                    "failed to remember proof at index {i} for account {}",
+                    proof.account
+                )
+            })?;
+        }
+        Ok(())
+    }
 }
 
 impl From<(RewardAccountProof, U256)> for RewardAccountQueryData {
@@ -315,9 +353,122 @@ impl From<(RewardAccountProof, U256)> for RewardAccountQueryData {
     }
 }
 
+/// Snapshots of a [`RewardMerkleTree`] keyed by their commitment, used to restore the tree to a
+/// known-good root after a crash.
+///
+/// Only the most recent `capacity` checkpoints are kept. The checkpoints are serializable so they
+/// can be persisted alongside the rest of the node state.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RewardMerkleTreeCheckpoints {
+    capacity: NonZeroUsize,
+    checkpoints: VecDeque<(RewardMerkleCommitment, RewardMerkleTree)>,
+}
+
+impl RewardMerkleTreeCheckpoints {
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            capacity,
+            checkpoints: VecDeque::new(),
+        }
+    }
+
+    /// Checkpoint `tree`, evicting the oldest checkpoint if we are at capacity.
+    ///
+    /// Returns the commitment the checkpoint can be restored by.
+    pub fn checkpoint(&mut self, tree: &RewardMerkleTree) -> RewardMerkleCommitment {
+        let commitment = tree.commitment();
+        if self.contains(&commitment) {
+            return commitment;
+        }
+        if self.checkpoints.len() == self.capacity.get() {
+            self.checkpoints.pop_front();
+        }
+        self.checkpoints.push_back((commitment, tree.clone()));
+        commitment
+    }
+
+    /// Restore the tree with the given commitment, if it has been checkpointed.
+    pub fn restore(&self, commitment: &RewardMerkleCommitment) -> Option<RewardMerkleTree> {
+        self.checkpoints
+            .iter()
+            .find(|(c, _)| c == commitment)
+            .map(|(_, tree)| tree.clone())
+    }
+
+    /// Whether a tree with the given commitment has been checkpointed.
+    pub fn contains(&self, commitment: &RewardMerkleCommitment) -> bool {
+        self.checkpoints.iter().any(|(c, _)| c == commitment)
+    }
+
+    /// The commitment of the most recent checkpoint.
+    pub fn latest(&self) -> Option<RewardMerkleCommitment> {
+        self.checkpoints.back().map(|(c, _)| *c)
+    }
+
+    pub fn len(&self) -> usize {
+        self.checkpoints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.checkpoints.is_empty()
+    }
+}
+
+/// The parameters of the reward distribution.
+///
+/// These are not committed to in the chain config, yet every node must use the same ones to agree
+/// on the reward root of each header. Nodes therefore compare them with their peers on startup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RewardConfig {
+    /// Reward for each block, shared by the leader and its delegators.
+    pub block_reward: RewardAmount,
+    /// What happens to the rounding remainder of the reward distribution of each block.
+    pub remainder_policy: RemainderPolicy,
+}
+
+/// What happens to the rounding remainder of the reward distribution of a block.
+///
+/// Delegator rewards are rounded down, so a little of the block reward is left over once the
+/// delegators and the commission of the leader have been paid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RemainderPolicy {
+    /// The remainder is paid to the leader on top of its commission.
+    #[default]
+    ToLeader,
+    /// The remainder is not paid to anyone.
+    Burn,
+    /// The remainder is stashed in [`REWARD_CARRY_ACCOUNT`] and added to the reward of the next
+    /// block.
+    CarryForward,
+}
+
+/// The account in which the remainder of the reward distribution is carried forward under
+/// [`RemainderPolicy::CarryForward`].
+///
+/// Nobody holds the key to the zero address, so it can never be a validator or delegator. Being
+/// part of the reward tree, the carried amount is committed to by the reward root of each header.
+pub const REWARD_CARRY_ACCOUNT: RewardAccount = RewardAccount(Address::zero());
+
+/// The rewards of a block, as computed by [`compute_rewards`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RewardDistribution {
+    /// The reward paid to each account.
+    pub rewards: Vec<(alloy::primitives::Address, RewardAmount)>,
+    /// The part of the block reward that is not paid to any account. This is always zero under
+    /// [`RemainderPolicy::ToLeader`].
+    pub remainder: RewardAmount,
+}
+
+/// Distribute `block_reward` for a block led by `validator` to the accounts in `reward_state`.
+///
+/// Under [`RemainderPolicy::CarryForward`], the remainder carried forward from the previous block
+/// is added to `block_reward`, and the new remainder replaces it in [`REWARD_CARRY_ACCOUNT`].
 pub fn apply_rewards(
     mut reward_state: RewardMerkleTree,
     validator: Validator<BLSPubKey>,
+    block_reward: RewardAmount,
+    policy: RemainderPolicy,
 ) -> anyhow::Result<RewardMerkleTree> {
     let mut update_balance = |account: &RewardAccount, amount: RewardAmount| {
         let mut err = None;
@@ -339,16 +490,60 @@ pub fn apply_rewards(
         Ok::<(), anyhow::Error>(())
     };
 
-    let computed_rewards = compute_rewards(validator)?;
-    for (address, reward) in computed_rewards {
+    let mut block_reward = block_reward;
+    if policy == RemainderPolicy::CarryForward {
+        let carried = match reward_state.lookup(REWARD_CARRY_ACCOUNT) {
+            LookupResult::Ok(balance, _) => *balance,
+            LookupResult::NotFound(_) => RewardAmount::default(),
+            LookupResult::NotInMemory => bail!("carried reward remainder not in memory"),
+        };
+        block_reward = block_reward
+            .0
+            .checked_add(carried.0)
+            .context("overflow")?
+            .into();
+    }
+
+    let distribution = compute_rewards(validator, block_reward, policy)?;
+    for (address, reward) in distribution.rewards {
         update_balance(&RewardAccount(address.to_ethers()), reward)?;
     }
+    if policy == RemainderPolicy::CarryForward {
+        reward_state = reward_state
+            .persistent_update_with(REWARD_CARRY_ACCOUNT, |_| Some(distribution.remainder))?;
+    }
     Ok(reward_state)
 }
 
+/// Check that `new_reward_root` is the result of distributing `block_reward` for a block led by
+/// `validator` under `policy` on top of `parent_reward_tree`.
+///
+/// This recomputes the transition with [`apply_rewards`], so the reward distribution of a decided
+/// block can be audited independently of the node that proposed it.
+pub fn verify_block_rewards(
+    parent_reward_tree: &RewardMerkleTree,
+    validator: Validator<BLSPubKey>,
+    block_reward: RewardAmount,
+    policy: RemainderPolicy,
+    new_reward_root: RewardMerkleCommitment,
+) -> anyhow::Result<()> {
+    let expected =
+        apply_rewards(parent_reward_tree.clone(), validator, block_reward, policy)?.commitment();
+    ensure!(
+        expected == new_reward_root,
+        "reward root mismatch: expected {expected:?}, got {new_reward_root:?}"
+    );
+    Ok(())
+}
+
+/// Split `block_reward` between the delegators of `validator`, in proportion to their stake, and the
+/// validator itself, which receives its commission. The rounding remainder is handled according to
+/// `policy`.
 pub fn compute_rewards(
     validator: Validator<BLSPubKey>,
-) -> anyhow::Result<Vec<(alloy::primitives::Address, RewardAmount)>> {
+    block_reward: RewardAmount,
+    policy: RemainderPolicy,
+) -> anyhow::Result<RewardDistribution> {
     ensure!(
         validator.commission <= COMMISSION_BASIS_POINTS,
         "commission must not exceed {COMMISSION_BASIS_POINTS}"
@@ -356,7 +551,7 @@ pub fn compute_rewards(
 
     let mut rewards = Vec::new();
 
-    let total_reward = block_reward().0;
+    let total_reward = block_reward.0;
     let delegators_ratio_basis_points = U256::from(COMMISSION_BASIS_POINTS)
         .checked_sub(U256::from(validator.commission))
         .context("overflow")?;
@@ -384,13 +579,89 @@ pub fn compute_rewards(
         rewards.push((*delegator_address, delegator_reward));
     }
 
-    let leader_reward = total_reward
+    let undistributed = total_reward
         .checked_sub(delegators_rewards_distributed)
         .context("overflow")?;
+    let (leader_reward, remainder) = match policy {
+        RemainderPolicy::ToLeader => (undistributed, U256::zero()),
+        RemainderPolicy::Burn | RemainderPolicy::CarryForward => {
+            let leader_commission = U256::from(validator.commission)
+                .checked_mul(total_reward)
+                .context("overflow")?
+                .checked_div(COMMISSION_BASIS_POINTS.into())
+                .context("overflow")?;
+            let remainder = undistributed
+                .checked_sub(leader_commission)
+                .context("overflow")?;
+            (leader_commission, remainder)
+        },
+    };
     rewards.push((validator.account, leader_reward.into()));
 
-    Ok(rewards)
+    Ok(RewardDistribution {
+        rewards,
+        remainder: remainder.into(),
+    })
+}
+
+/// The reward accrued by `account` in each of a sequence of decided blocks.
+///
+/// `blocks` holds the height of each block along with the validator that led it, in increasing
+/// order of height. The rewards are recomputed with [`compute_rewards`], exactly as they were
+/// distributed by [`apply_rewards`], so the result is consistent with the reward state. Blocks in
+/// the first two epochs after `first_epoch` accrue no rewards, see [`first_two_epochs`].
+///
+/// `carried` is the remainder carried forward into the first block, and is only used under
+/// [`RemainderPolicy::CarryForward`].
+pub fn reward_history(
+    account: RewardAccount,
+    blocks: impl IntoIterator<Item = (u64, Validator<BLSPubKey>)>,
+    epoch_height: u64,
+    first_epoch: EpochNumber,
+    block_reward: RewardAmount,
+    policy: RemainderPolicy,
+    mut carried: RewardAmount,
+) -> anyhow::Result<Vec<(u64, RewardAmount)>> {
+    let mut history = Vec::new();
+    for (height, validator) in blocks {
+        if in_first_two_epochs(height, epoch_height, first_epoch) {
+            history.push((height, RewardAmount::default()));
+            continue;
+        }
+
+        let mut reward = block_reward;
+        if policy == RemainderPolicy::CarryForward {
+            reward = reward.0.checked_add(carried.0).context("overflow")?.into();
+        }
+        let distribution = compute_rewards(validator, reward, policy)?;
+        carried = distribution.remainder;
+
+        // An account may be rewarded both as the leader and as one of its delegators.
+        let accrued = distribution
+            .rewards
+            .iter()
+            .filter(|(address, _)| RewardAccount(address.to_ethers()) == account)
+            .try_fold(U256::zero(), |acc, (_, reward)| acc.checked_add(reward.0))
+            .context("overflow")?;
+        history.push((height, accrued.into()));
+    }
+    Ok(history)
+}
+
+/// The first epoch in which rewards are distributed.
+///
+/// No rewards are distributed for the first two epochs, see [`first_two_epochs`].
+pub fn rewards_start_epoch(first_epoch: EpochNumber) -> EpochNumber {
+    first_epoch + 2
+}
+
+/// The number of epochs from `current_epoch` until rewards are first distributed.
+///
+/// Returns 0 once rewards are being distributed.
+pub fn epochs_until_rewards(current_epoch: EpochNumber, first_epoch: EpochNumber) -> u64 {
+    rewards_start_epoch(first_epoch).saturating_sub(*current_epoch)
 }
+
 /// Checks whether the given height belongs to the first or second epoch.
 ///
 /// Rewards are not distributed for these epochs because the stake table
@@ -400,7 +671,6 @@ pub async fn first_two_epochs(height: u64, instance_state: &NodeState) -> anyhow
     let epoch_height = instance_state
         .epoch_height
         .context("epoch height not found")?;
-    let epoch = EpochNumber::new(epoch_from_block_number(height, epoch_height));
     let coordinator = instance_state.coordinator.clone();
     let first_epoch = coordinator
         .membership()
@@ -409,7 +679,12 @@ pub async fn first_two_epochs(height: u64, instance_state: &NodeState) -> anyhow
         .first_epoch()
         .context("The first epoch was not set.")?;
 
-    Ok(epoch == first_epoch || epoch == first_epoch + 1)
+    Ok(in_first_two_epochs(height, epoch_height, first_epoch))
+}
+
+fn in_first_two_epochs(height: u64, epoch_height: u64, first_epoch: EpochNumber) -> bool {
+    let epoch = EpochNumber::new(epoch_from_block_number(height, epoch_height));
+    epoch == first_epoch || epoch == first_epoch + 1
 }
 
 pub async fn catchup_missing_accounts(
@@ -428,8 +703,10 @@ pub async fn catchup_missing_accounts(
     let epoch_membership = coordinator.membership_for_epoch(Some(epoch)).await?;
     let membership = epoch_membership.coordinator.membership().read().await;
 
+    // Keep the `LeaderLookupError` in the error chain so that callers can tell a transient miss
+    // during catchup, which they should retry, from a fatal one.
     let leader: BLSPubKey = membership
-        .leader(view, Some(epoch))
+        .lookup_leader(view, Some(epoch))
         .context(format!("leader for epoch {epoch:?} not found"))?;
 
     let validator = membership
@@ -437,6 +714,9 @@ pub async fn catchup_missing_accounts(
         .context("validator not found")?;
     let mut reward_accounts = HashSet::new();
     reward_accounts.insert(validator.account.to_ethers().into());
+    if instance_state.reward_remainder_policy == RemainderPolicy::CarryForward {
+        reward_accounts.insert(REWARD_CARRY_ACCOUNT);
+    }
     let delegators = validator
         .delegators
         .keys()
@@ -466,11 +746,11 @@ pub async fn catchup_missing_accounts(
             )
             .await?;
 
-        for proof in missing_account_proofs.iter() {
-            proof
-                .remember(&mut validated_state.reward_merkle_tree)
-                .expect("proof previously verified");
-        }
+        RewardAccountProof::remember_batch(
+            &missing_account_proofs,
+            &mut validated_state.reward_merkle_tree,
+        )
+        .expect("proofs previously verified");
     }
     Ok(validator)
 }
@@ -479,6 +759,7 @@ pub async fn catchup_missing_accounts(
 pub mod tests {
 
     use super::*;
+    use crate::v0_1::block_reward;
 
     // TODO: current tests are just sanity checks, we need more.
 
@@ -487,36 +768,409 @@ pub mod tests {
         // This test verifies that the total rewards distributed match the block reward.
         // Due to rounding effects in distribution, the validator may receive a slightly higher amount
         // because the remainder after delegator distribution is sent to the validator.
+        let total = |distribution: RewardDistribution| {
+            assert_eq!(distribution.remainder, RewardAmount::default());
+            distribution
+                .rewards
+                .iter()
+                .fold(U256::zero(), |acc, (_, r)| acc + r.0)
+        };
+
+        for reward in [
+            block_reward(),
+            RewardAmount::from(1u64),
+            RewardAmount::from(U256::exp10(24) + 7),
+        ] {
+            let validator = Validator::mock();
+            let rewards = compute_rewards(validator, reward, RemainderPolicy::ToLeader).unwrap();
+            assert_eq!(total(rewards), reward.into());
 
+            let mut validator = Validator::mock();
+            validator.commission = 0;
+            let rewards =
+                compute_rewards(validator.clone(), reward, RemainderPolicy::ToLeader).unwrap();
+            assert_eq!(total(rewards.clone()), reward.into());
+
+            let mut validator = Validator::mock();
+            validator.commission = 10000;
+            let rewards =
+                compute_rewards(validator.clone(), reward, RemainderPolicy::ToLeader).unwrap();
+            assert_eq!(total(rewards.clone()), reward.into());
+            let validator_reward = rewards
+                .rewards
+                .iter()
+                .find(|(a, _)| *a == validator.account)
+                .unwrap()
+                .1;
+            assert_eq!(validator_reward, reward);
+
+            let mut validator = Validator::mock();
+            validator.commission = 10001;
+            assert!(
+                compute_rewards(validator.clone(), reward, RemainderPolicy::ToLeader)
+                    .err()
+                    .unwrap()
+                    .to_string()
+                    .contains("must not exceed")
+            );
+        }
+    }
+
+    #[test]
+    fn test_epochs_until_rewards() {
+        let first_epoch = EpochNumber::new(5);
+        let start = rewards_start_epoch(first_epoch);
+        assert_eq!(start, EpochNumber::new(7));
+
+        // Before the rewards start epoch.
+        assert_eq!(epochs_until_rewards(EpochNumber::new(0), first_epoch), 7);
+        assert_eq!(epochs_until_rewards(first_epoch, first_epoch), 2);
+        assert_eq!(epochs_until_rewards(first_epoch + 1, first_epoch), 1);
+
+        // At and after the rewards start epoch.
+        assert_eq!(epochs_until_rewards(start, first_epoch), 0);
+        assert_eq!(epochs_until_rewards(start + 10, first_epoch), 0);
+    }
+
+    #[test]
+    fn test_reward_merkle_tree_checkpoints() {
+        let mut checkpoints = RewardMerkleTreeCheckpoints::new(NonZeroUsize::new(2).unwrap());
+        assert!(checkpoints.is_empty());
+
+        let tree = RewardMerkleTree::new(crate::v0_1::REWARD_MERKLE_TREE_HEIGHT);
+        let tree = apply_rewards(
+            tree,
+            Validator::mock(),
+            block_reward(),
+            RemainderPolicy::ToLeader,
+        )
+        .unwrap();
+        let checkpoint = checkpoints.checkpoint(&tree);
+        assert_eq!(checkpoint, tree.commitment());
+        assert_eq!(checkpoints.latest(), Some(checkpoint));
+
+        // Further updates do not affect the checkpoint.
+        let updated = apply_rewards(
+            tree.clone(),
+            Validator::mock(),
+            block_reward(),
+            RemainderPolicy::ToLeader,
+        )
+        .unwrap();
+        assert_ne!(updated.commitment(), checkpoint);
+
+        let restored = checkpoints.restore(&checkpoint).unwrap();
+        assert_eq!(restored.commitment(), checkpoint);
+        assert_eq!(restored.num_leaves(), tree.num_leaves());
+        assert!(checkpoints.restore(&updated.commitment()).is_none());
+
+        // Checkpointing the same tree twice keeps a single checkpoint.
+        checkpoints.checkpoint(&tree);
+        assert_eq!(checkpoints.len(), 1);
+
+        // The oldest checkpoint is evicted once we are at capacity.
+        checkpoints.checkpoint(&updated);
+        let newest = apply_rewards(
+            updated,
+            Validator::mock(),
+            block_reward(),
+            RemainderPolicy::ToLeader,
+        )
+        .unwrap();
+        checkpoints.checkpoint(&newest);
+        assert_eq!(checkpoints.len(), 2);
+        assert!(!checkpoints.contains(&checkpoint));
+        assert_eq!(checkpoints.latest(), Some(newest.commitment()));
+    }
+
+    #[test]
+    fn test_verify_block_rewards() {
+        let parent = RewardMerkleTree::new(crate::v0_1::REWARD_MERKLE_TREE_HEIGHT);
         let validator = Validator::mock();
-        let rewards = compute_rewards(validator).unwrap();
-        let total = |rewards: Vec<(_, RewardAmount)>| {
-            rewards.iter().fold(U256::zero(), |acc, (_, r)| acc + r.0)
-        };
-        assert_eq!(total(rewards), block_reward().into());
+        let new_root = apply_rewards(
+            parent.clone(),
+            validator.clone(),
+            block_reward(),
+            RemainderPolicy::ToLeader,
+        )
+        .unwrap()
+        .commitment();
 
-        let mut validator = Validator::mock();
-        validator.commission = 0;
-        let rewards = compute_rewards(validator.clone()).unwrap();
-        assert_eq!(total(rewards.clone()), block_reward().into());
+        verify_block_rewards(
+            &parent,
+            validator.clone(),
+            block_reward(),
+            RemainderPolicy::ToLeader,
+            new_root,
+        )
+        .unwrap();
 
-        let mut validator = Validator::mock();
-        validator.commission = 10000;
-        let rewards = compute_rewards(validator.clone()).unwrap();
-        assert_eq!(total(rewards.clone()), block_reward().into());
-        let validator_reward = rewards
+        // A root that does not follow from the parent tree is rejected.
+        assert!(verify_block_rewards(
+            &parent,
+            validator.clone(),
+            block_reward(),
+            RemainderPolicy::ToLeader,
+            parent.commitment()
+        )
+        .unwrap_err()
+        .to_string()
+        .contains("reward root mismatch"));
+
+        // So is the correct root for a different validator.
+        assert!(verify_block_rewards(
+            &parent,
+            Validator::mock(),
+            block_reward(),
+            RemainderPolicy::ToLeader,
+            new_root
+        )
+        .is_err());
+
+        // And the correct root for a different block reward.
+        assert!(verify_block_rewards(
+            &parent,
+            validator.clone(),
+            1u64.into(),
+            RemainderPolicy::ToLeader,
+            new_root
+        )
+        .is_err());
+
+        // Or a different remainder policy.
+        assert!(verify_block_rewards(
+            &parent,
+            validator,
+            block_reward(),
+            RemainderPolicy::Burn,
+            new_root
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_reward_account_proof_batch() {
+        let mut tree = RewardMerkleTree::new(crate::v0_1::REWARD_MERKLE_TREE_HEIGHT);
+        let present = [Address::random(), Address::random()];
+        for (i, account) in present.iter().enumerate() {
+            tree.update(RewardAccount(*account), RewardAmount::from(i as u64 + 1))
+                .unwrap();
+        }
+        let absent = Address::random();
+
+        let proofs = [present[0], absent, present[1]]
+            .into_iter()
+            .map(|account| RewardAccountProof::prove(&tree, account).unwrap().0)
+            .collect::<Vec<_>>();
+        let balances = RewardAccountProof::verify_batch(&proofs, &tree.commitment()).unwrap();
+        assert_eq!(balances, vec![U256::from(1), U256::zero(), U256::from(2)]);
+
+        // The proofs can be remembered in a tree that only knows the commitment.
+        let mut forgotten = RewardMerkleTree::from_commitment(tree.commitment());
+        RewardAccountProof::remember_batch(&proofs, &mut forgotten).unwrap();
+        for (account, balance) in [present[0], absent, present[1]].into_iter().zip(balances) {
+            assert_eq!(
+                RewardAccountProof::prove(&forgotten, account).unwrap().1,
+                balance
+            );
+        }
+
+        // A tampered proof is rejected, reporting its index.
+        let mut tampered = proofs.clone();
+        tampered[2].account = absent;
+        let err = RewardAccountProof::verify_batch(&tampered, &tree.commitment()).unwrap_err();
+        assert!(
+            format!("{err:#}").contains(&format!("invalid proof at index 2 for account {absent}")),
+            "{err:#}"
+        );
+
+        // As is a batch checked against another commitment, from its first proof.
+        let empty = RewardMerkleTree::new(crate::v0_1::REWARD_MERKLE_TREE_HEIGHT);
+        let err = RewardAccountProof::verify_batch(&proofs, &empty.commitment()).unwrap_err();
+        assert!(format!("{err:#}").contains("index 0"), "{err:#}");
+    }
+
+    #[test]
+    fn test_reward_history() {
+        let epoch_height = 10;
+        let first_epoch = EpochNumber::new(1);
+        let validators = [validator_with_dust(1234), validator_with_dust(500)];
+        let delegator = RewardAccount(validators[0].delegators.keys().next().unwrap().to_ethers());
+        let leader = RewardAccount(validators[1].account.to_ethers());
+
+        // Blocks in the first two epochs accrue nothing; the others are led in turn.
+        let heights = [5, 20, 21, 22, 35];
+        let blocks = heights
             .iter()
-            .find(|(a, _)| *a == validator.account)
-            .unwrap()
-            .1;
-        assert_eq!(validator_reward, block_reward());
+            .enumerate()
+            .map(|(i, height)| (*height, validators[i % 2].clone()))
+            .collect::<Vec<_>>();
+
+        for policy in [
+            RemainderPolicy::ToLeader,
+            RemainderPolicy::Burn,
+            RemainderPolicy::CarryForward,
+        ] {
+            let mut tree = RewardMerkleTree::new(crate::v0_1::REWARD_MERKLE_TREE_HEIGHT);
+            for (height, validator) in &blocks {
+                if !in_first_two_epochs(*height, epoch_height, first_epoch) {
+                    tree = apply_rewards(tree, validator.clone(), 100u64.into(), policy).unwrap();
+                }
+            }
+
+            for account in [delegator, leader] {
+                let history = reward_history(
+                    account,
+                    blocks.clone(),
+                    epoch_height,
+                    first_epoch,
+                    100u64.into(),
+                    policy,
+                    RewardAmount::default(),
+                )
+                .unwrap();
+                assert_eq!(
+                    history
+                        .iter()
+                        .map(|(height, _)| *height)
+                        .collect::<Vec<_>>(),
+                    heights
+                );
+                assert_eq!(history[0].1, RewardAmount::default());
+                assert_eq!(history[1].1, RewardAmount::default());
+
+                // The history adds up to the balance in the reward state.
+                let total = history
+                    .iter()
+                    .fold(U256::zero(), |acc, (_, reward)| acc + reward.0);
+                let LookupResult::Ok(balance, _) = tree.lookup(account) else {
+                    panic!("account not found");
+                };
+                assert_eq!(total, balance.0);
+            }
+        }
+    }
 
+    /// A validator with three delegators of equal stake, so that the delegator rewards of most
+    /// block rewards do not divide evenly.
+    fn validator_with_dust(commission: u16) -> Validator<BLSPubKey> {
         let mut validator = Validator::mock();
-        validator.commission = 10001;
-        assert!(compute_rewards(validator.clone())
-            .err()
-            .unwrap()
-            .to_string()
-            .contains("must not exceed"));
+        validator.commission = commission;
+        validator.delegators = (0..3)
+            .map(|_| {
+                (
+                    alloy::primitives::Address::random(),
+                    alloy::primitives::U256::from(1),
+                )
+            })
+            .collect();
+        validator.stake = alloy::primitives::U256::from(3);
+        validator
+    }
+
+    #[test]
+    fn test_reward_remainder_policies() {
+        let total = |distribution: &RewardDistribution| {
+            distribution
+                .rewards
+                .iter()
+                .fold(U256::zero(), |acc, (_, r)| acc + r.0)
+        };
+
+        for commission in [0, 1234, 10000] {
+            for reward in [
+                block_reward(),
+                RewardAmount::from(100u64),
+                RewardAmount::from(U256::exp10(24) + 7),
+            ] {
+                let validator = validator_with_dust(commission);
+
+                // The whole reward is paid out to the leader and delegators.
+                let distribution =
+                    compute_rewards(validator.clone(), reward, RemainderPolicy::ToLeader).unwrap();
+                assert_eq!(distribution.remainder, RewardAmount::default());
+                assert_eq!(total(&distribution), reward.0);
+
+                // Whatever is not paid out is the remainder.
+                for policy in [RemainderPolicy::Burn, RemainderPolicy::CarryForward] {
+                    let distribution = compute_rewards(validator.clone(), reward, policy).unwrap();
+                    assert_eq!(total(&distribution) + distribution.remainder.0, reward.0);
+
+                    // The leader only receives its commission.
+                    let leader_reward = distribution
+                        .rewards
+                        .iter()
+                        .find(|(a, _)| *a == validator.account)
+                        .unwrap()
+                        .1;
+                    assert_eq!(
+                        leader_reward.0,
+                        U256::from(commission) * reward.0 / U256::from(COMMISSION_BASIS_POINTS)
+                    );
+                }
+            }
+        }
+
+        // 100 does not split evenly between three delegators, so some of it is burned.
+        let distribution = compute_rewards(
+            validator_with_dust(0),
+            RewardAmount::from(100u64),
+            RemainderPolicy::Burn,
+        )
+        .unwrap();
+        assert!(total(&distribution) < U256::from(100));
+        assert_eq!(distribution.remainder, RewardAmount::from(1u64));
+    }
+
+    #[test]
+    fn test_apply_rewards_carry_forward() {
+        let carried = |tree: &RewardMerkleTree| match tree.lookup(REWARD_CARRY_ACCOUNT) {
+            LookupResult::Ok(balance, _) => *balance,
+            _ => panic!("carried remainder not found"),
+        };
+        let validator = validator_with_dust(0);
+        let tree = RewardMerkleTree::new(crate::v0_1::REWARD_MERKLE_TREE_HEIGHT);
+
+        // The remainder of the first block is stashed.
+        let tree = apply_rewards(
+            tree,
+            validator.clone(),
+            100u64.into(),
+            RemainderPolicy::CarryForward,
+        )
+        .unwrap();
+        assert_eq!(carried(&tree), RewardAmount::from(1u64));
+
+        // And added to the reward of the next, 101 splitting into 3 * 33 with a remainder of 2.
+        let tree = apply_rewards(
+            tree,
+            validator.clone(),
+            100u64.into(),
+            RemainderPolicy::CarryForward,
+        )
+        .unwrap();
+        assert_eq!(carried(&tree), RewardAmount::from(2u64));
+        for delegator in validator.delegators.keys() {
+            let LookupResult::Ok(balance, _) = tree.lookup(RewardAccount(delegator.to_ethers()))
+            else {
+                panic!("delegator reward not found");
+            };
+            assert_eq!(*balance, RewardAmount::from(66u64));
+        }
+
+        // No reward is lost.
+        let total = validator
+            .delegators
+            .keys()
+            .map(|a| RewardAccount(a.to_ethers()))
+            .chain([
+                RewardAccount(validator.account.to_ethers()),
+                REWARD_CARRY_ACCOUNT,
+            ])
+            .fold(U256::zero(), |acc, account| match tree.lookup(account) {
+                LookupResult::Ok(balance, _) => acc + balance.0,
+                _ => acc,
+            });
+        assert_eq!(total, U256::from(200));
     }
 }
@@ -1,15 +1,16 @@
 use std::{
     cmp::max,
-    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    collections::{btree_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet},
     sync::Arc,
 };
 
 use alloy::{
-    primitives::{Address, U256},
+    primitives::{Address, B256, U256},
     rpc::types::Log,
 };
-use anyhow::{bail, Context};
+use anyhow::{bail, ensure, Context};
 use async_lock::RwLock;
+use bimap::BiHashMap;
 use contract_bindings_alloy::staketable::StakeTable::{
     ConsensusKeysUpdated, Delegated, Undelegated, ValidatorExit, ValidatorRegistered,
 };
@@ -17,14 +18,16 @@ use ethers_conv::ToEthers;
 use hotshot::types::{BLSPubKey, SignatureKey as _};
 use hotshot_contract_adapter::stake_table::{bls_alloy_to_jf2, edward_bn254point_to_state_ver};
 use hotshot_types::{
-    data::{vid_disperse::VID_TARGET_TOTAL_STAKE, EpochNumber},
+    data::EpochNumber,
     drb::{
         election::{generate_stake_cdf, select_randomized_leader, RandomizedCommittee},
         DrbResult,
     },
+    light_client::StateVerKey,
     stake_table::StakeTableEntry,
     traits::{
         election::Membership,
+        metrics::{Counter, Metrics, NoMetrics},
         node_implementation::{ConsensusTime, NodeType},
         signature_key::StakeTableEntryType,
     },
@@ -40,6 +43,7 @@ use super::{
 };
 
 type Epoch = <SeqTypes as NodeType>::Epoch;
+type View = <SeqTypes as NodeType>::View;
 
 /// Create the consensus and DA stake tables from L1 events
 ///
@@ -52,11 +56,134 @@ type Epoch = <SeqTypes as NodeType>::Epoch;
 pub fn from_l1_events<I: Iterator<Item = StakeTableEvent>>(
     events: I,
 ) -> anyhow::Result<IndexMap<Address, Validator<BLSPubKey>>> {
-    let mut validators = IndexMap::new();
-    let mut bls_keys = HashSet::new();
-    let mut schnorr_keys = HashSet::new();
+    from_l1_events_with_mode(
+        events,
+        StakeTableEventMode::Strict,
+        MinStakePolicy::default(),
+        DEFAULT_MAX_VALIDATORS,
+    )
+}
+
+/// How [`from_l1_events_with_mode`] treats events that cannot be applied to the stake table.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StakeTableEventMode {
+    /// Fail on any event that cannot be applied.
+    #[default]
+    Strict,
+    /// Skip, with a warning, key updates for validators that are not registered, clamp
+    /// undelegations exceeding the stake of the delegator to that stake, and accept, with a
+    /// warning, registrations reusing the schnorr key of another validator.
+    Lenient,
+}
+
+/// Default divisor of the maximum stake giving the minimum stake required to be selected.
+///
+/// A validator needs at least a thousandth of the stake of the largest validator.
+pub const DEFAULT_MIN_STAKE_DIVISOR: u64 = 1000;
+
+/// How the minimum stake required to be selected into an epoch's committee is derived.
+///
+/// The divisors must be positive. If the divided stake rounds down to zero, every validator with
+/// some stake qualifies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MinStakePolicy {
+    /// The minimum stake is the maximum stake of any validator divided by the given divisor.
+    FractionOfMax(u64),
+    /// The minimum stake is the total stake of all validators divided by the given divisor.
+    FractionOfTotal(u64),
+    /// The minimum stake is a fixed amount.
+    Absolute(U256),
+}
+
+impl Default for MinStakePolicy {
+    fn default() -> Self {
+        Self::FractionOfMax(DEFAULT_MIN_STAKE_DIVISOR)
+    }
+}
+
+impl MinStakePolicy {
+    /// Compute the minimum stake for a set of validators under this policy.
+    fn minimum_stake(
+        &self,
+        validators: &IndexMap<Address, Validator<BLSPubKey>>,
+    ) -> anyhow::Result<U256> {
+        match self {
+            Self::FractionOfMax(0) | Self::FractionOfTotal(0) => {
+                bail!("minimum stake divisor must be positive")
+            },
+            Self::FractionOfMax(divisor) => {
+                let maximum_stake = validators
+                    .values()
+                    .map(|v| v.stake)
+                    .max()
+                    .context("Failed to determine max stake")?;
+                Ok(maximum_stake / U256::from(*divisor))
+            },
+            Self::FractionOfTotal(divisor) => {
+                let total_stake = validators
+                    .values()
+                    .try_fold(U256::ZERO, |acc, v| acc.checked_add(v.stake))
+                    .context("total stake overflow")?;
+                Ok(total_stake / U256::from(*divisor))
+            },
+            Self::Absolute(minimum_stake) => Ok(*minimum_stake),
+        }
+    }
+}
+
+/// Like [`from_l1_events`], but with configurable handling of invalid events, of the minimum
+/// stake required for selection and of the maximum number of selected validators.
+pub fn from_l1_events_with_mode<I: Iterator<Item = StakeTableEvent>>(
+    events: I,
+    mode: StakeTableEventMode,
+    min_stake_policy: MinStakePolicy,
+    max_validators: usize,
+) -> anyhow::Result<IndexMap<Address, Validator<BLSPubKey>>> {
+    let mut validators = validators_from_l1_events(events, mode)?;
+    select_validators(&mut validators, min_stake_policy, max_validators)?;
+
+    Ok(validators)
+}
+
+/// Reconstruct every registered validator, with its stake and delegators, from L1 events.
+///
+/// Unlike [`from_l1_events`], this does not select the validators of the stake table of an
+/// epoch, so validators without stake are kept.
+pub fn validators_from_l1_events<I: Iterator<Item = StakeTableEvent>>(
+    events: I,
+    mode: StakeTableEventMode,
+) -> anyhow::Result<IndexMap<Address, Validator<BLSPubKey>>> {
+    let mut state = StakeTableState::default();
     for event in events {
-        tracing::debug!("Processing stake table event: {:?}", event);
+        state.apply_event(event, mode)?;
+    }
+
+    Ok(state.validators)
+}
+
+/// Every registered validator, reconstructed from a prefix of the stake table events, together
+/// with the keys used so far, so that later events can be applied on top.
+#[derive(Clone, Debug, Default)]
+pub struct StakeTableState {
+    validators: IndexMap<Address, Validator<BLSPubKey>>,
+    /// BLS keys of every validator ever registered, including the ones that exited
+    bls_keys: HashSet<BLSPubKey>,
+    /// Schnorr keys of every validator ever registered, including the ones that exited
+    schnorr_keys: HashSet<StateVerKey>,
+}
+
+impl StakeTableState {
+    /// The validators registered so far, before selection.
+    pub fn validators(&self) -> &IndexMap<Address, Validator<BLSPubKey>> {
+        &self.validators
+    }
+
+    /// Apply the next stake table event.
+    pub fn apply_event(
+        &mut self,
+        event: StakeTableEvent,
+        mode: StakeTableEventMode,
+    ) -> anyhow::Result<()> {
         match event {
             StakeTableEvent::Register(ValidatorRegistered {
                 account,
@@ -70,19 +197,22 @@ pub fn from_l1_events<I: Iterator<Item = StakeTableEvent>>(
                 // TODO(MA): The stake table contract currently enforces that each bls key is only used once. We will
                 // move this check to the confirmation layer and remove it from the contract. Once we have the signature
                 // check in this functions we can skip if a BLS key, or Schnorr key was previously used.
-                if bls_keys.contains(&stake_table_key) {
+                if self.bls_keys.contains(&stake_table_key) {
                     bail!("bls key {} already used", stake_table_key.to_string());
                 };
 
                 // The contract does *not* enforce that each schnorr key is only used once.
-                if schnorr_keys.contains(&state_ver_key) {
+                if self.schnorr_keys.contains(&state_ver_key) {
+                    if mode != StakeTableEventMode::Lenient {
+                        bail!("schnorr key {} already used", state_ver_key.to_string());
+                    }
                     tracing::warn!("schnorr key {} already used", state_ver_key.to_string());
                 };
 
-                bls_keys.insert(stake_table_key);
-                schnorr_keys.insert(state_ver_key.clone());
+                self.bls_keys.insert(stake_table_key);
+                self.schnorr_keys.insert(state_ver_key.clone());
 
-                match validators.entry(account) {
+                match self.validators.entry(account) {
                     indexmap::map::Entry::Occupied(_occupied_entry) => {
                         bail!("validator {:#x} already registered", *account)
                     },
@@ -97,7 +227,7 @@ pub fn from_l1_events<I: Iterator<Item = StakeTableEvent>>(
                 };
             },
             StakeTableEvent::Deregister(exit) => {
-                validators
+                self.validators
                     .shift_remove(&exit.validator)
                     .with_context(|| format!("validator {:#x} not found", exit.validator))?;
             },
@@ -107,13 +237,14 @@ pub fn from_l1_events<I: Iterator<Item = StakeTableEvent>>(
                     validator,
                     amount,
                 } = delegated;
-                let validator_entry = validators
+                let validator_entry = self
+                    .validators
                     .get_mut(&validator)
                     .with_context(|| format!("validator {validator:#x} not found"))?;
 
                 if amount.is_zero() {
                     tracing::warn!("delegator {delegator:?} has 0 stake");
-                    continue;
+                    return Ok(());
                 }
                 // Increase stake
                 validator_entry.stake += amount;
@@ -126,27 +257,38 @@ pub fn from_l1_events<I: Iterator<Item = StakeTableEvent>>(
                     validator,
                     amount,
                 } = undelegated;
-                let validator_entry = validators
+                let validator_entry = self
+                    .validators
                     .get_mut(&validator)
                     .with_context(|| format!("validator {validator:#x} not found"))?;
 
-                validator_entry.stake = validator_entry
-                    .stake
-                    .checked_sub(amount)
-                    .with_context(|| "stake is less than undelegated amount")?;
-
                 let delegator_stake = validator_entry
                     .delegators
                     .get_mut(&delegator)
                     .with_context(|| format!("delegator {delegator:#x} not found"))?;
-                *delegator_stake = delegator_stake
-                    .checked_sub(amount)
-                    .with_context(|| "delegator_stake is less than undelegated amount")?;
+                let amount = if amount > *delegator_stake {
+                    if mode != StakeTableEventMode::Lenient {
+                        bail!("delegator_stake is less than undelegated amount");
+                    }
+                    tracing::warn!(
+                        "undelegation of {amount} exceeds the stake {delegator_stake} of delegator \
+                         {delegator:#x}, clamping it"
+                    );
+                    *delegator_stake
+                } else {
+                    amount
+                };
+                *delegator_stake -= amount;
 
                 if delegator_stake.is_zero() {
                     // if delegator stake is 0, remove from set
                     validator_entry.delegators.remove(&delegator);
                 }
+
+                validator_entry.stake = validator_entry
+                    .stake
+                    .checked_sub(amount)
+                    .with_context(|| "stake is less than undelegated amount")?;
             },
             StakeTableEvent::KeyUpdate(update) => {
                 let ConsensusKeysUpdated {
@@ -154,9 +296,13 @@ pub fn from_l1_events<I: Iterator<Item = StakeTableEvent>>(
                     blsVK,
                     schnorrVK,
                 } = update;
-                let validator = validators
-                    .get_mut(&account)
-                    .with_context(|| "validator {account:#x} not found")?;
+                let Some(validator) = self.validators.get_mut(&account) else {
+                    if mode == StakeTableEventMode::Lenient {
+                        tracing::warn!("skipping key update for unknown validator {account:#x}");
+                        return Ok(());
+                    }
+                    bail!("validator {account:#x} not found");
+                };
                 let bls = bls_alloy_to_jf2(blsVK);
                 let state_ver_key = edward_bn254point_to_state_ver(schnorrVK);
 
@@ -164,45 +310,321 @@ pub fn from_l1_events<I: Iterator<Item = StakeTableEvent>>(
                 validator.state_ver_key = state_ver_key;
             },
         }
-    }
 
-    select_validators(&mut validators)?;
+        Ok(())
+    }
+}
 
-    Ok(validators)
+/// A [`StakeTableState`] with every event up to an L1 block applied, so that the stake table of a
+/// later L1 block can be computed from the newer events only.
+#[derive(Clone, Debug)]
+pub struct StakeTableSnapshot {
+    state: StakeTableState,
+    /// The L1 block up to which every event was applied
+    l1_block: u64,
+    /// The hash of `l1_block`, to detect L1 reorgs
+    l1_block_hash: B256,
+    /// The `(block_number, log_index)` of the last applied event, if any
+    last_log: Option<(u64, u64)>,
 }
 
-fn select_validators(
-    validators: &mut IndexMap<Address, Validator<BLSPubKey>>,
-) -> anyhow::Result<()> {
-    // Remove invalid validators first
-    validators.retain(|address, validator| {
-        if validator.delegators.is_empty() {
-            tracing::info!("Validator {address:?} does not have any delegator");
-            return false;
+impl StakeTableSnapshot {
+    /// Apply the `events` emitted up to `l1_block`, sorted by position, to `state`.
+    ///
+    /// Events at or before `last_log` are assumed to be applied already and skipped, so that the
+    /// events may be fetched starting at the block of the last applied event.
+    fn apply(
+        mut state: StakeTableState,
+        mut last_log: Option<(u64, u64)>,
+        events: BTreeMap<(u64, u64), StakeTableEvent>,
+        l1_block: u64,
+        l1_block_hash: B256,
+        mode: StakeTableEventMode,
+    ) -> anyhow::Result<Self> {
+        for (position, event) in events {
+            if Some(position) <= last_log {
+                continue;
+            }
+            state.apply_event(event, mode)?;
+            last_log = Some(position);
         }
 
-        if validator.stake.is_zero() {
-            tracing::info!("Validator {address:?} does not have any stake");
-            return false;
+        Ok(Self {
+            state,
+            l1_block,
+            l1_block_hash,
+            last_log,
+        })
+    }
+
+    /// Build a snapshot from every event emitted up to `l1_block`.
+    pub fn from_events(
+        events: BTreeMap<(u64, u64), StakeTableEvent>,
+        l1_block: u64,
+        l1_block_hash: B256,
+        mode: StakeTableEventMode,
+    ) -> anyhow::Result<Self> {
+        Self::apply(
+            StakeTableState::default(),
+            None,
+            events,
+            l1_block,
+            l1_block_hash,
+            mode,
+        )
+    }
+
+    /// Apply the newer `events` emitted up to `l1_block` to a copy of this snapshot.
+    ///
+    /// `events` may include events that were already applied, as long as they are at or before the
+    /// last applied event.
+    pub fn update(
+        &self,
+        events: BTreeMap<(u64, u64), StakeTableEvent>,
+        l1_block: u64,
+        l1_block_hash: B256,
+        mode: StakeTableEventMode,
+    ) -> anyhow::Result<Self> {
+        ensure!(
+            l1_block >= self.l1_block,
+            "cannot update a stake table snapshot at L1 block {} to the earlier L1 block {l1_block}",
+            self.l1_block
+        );
+        Self::apply(
+            self.state.clone(),
+            self.last_log,
+            events,
+            l1_block,
+            l1_block_hash,
+            mode,
+        )
+    }
+
+    /// The first L1 block that may contain events newer than the snapshot.
+    pub fn next_block(&self) -> u64 {
+        self.last_log.map_or(0, |(block, _)| block)
+    }
+
+    /// The L1 block up to which every event was applied.
+    pub fn l1_block(&self) -> u64 {
+        self.l1_block
+    }
+
+    /// The hash of [`Self::l1_block`].
+    pub fn l1_block_hash(&self) -> B256 {
+        self.l1_block_hash
+    }
+
+    /// The state with every event applied.
+    pub fn state(&self) -> &StakeTableState {
+        &self.state
+    }
+}
+
+/// Why a sequence of stake table events is not a valid lifecycle, see
+/// [`validate_event_lifecycle`].
+///
+/// Each variant names the offending validator, and the index of the offending event in the
+/// sequence.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum LifecycleError {
+    #[error("event {index}: validator {validator:#x} is already registered")]
+    AlreadyRegistered { index: usize, validator: Address },
+    #[error("event {index}: validator {validator:#x} is not registered")]
+    NotRegistered { index: usize, validator: Address },
+    #[error("event {index}: validator {validator:#x} has already exited")]
+    AfterExit { index: usize, validator: Address },
+    #[error(
+        "event {index}: delegator {delegator:#x} undelegates {amount} from validator \
+         {validator:#x}, but only delegated {delegated}"
+    )]
+    UndelegationExceedsDelegation {
+        index: usize,
+        validator: Address,
+        delegator: Address,
+        amount: U256,
+        delegated: U256,
+    },
+}
+
+/// Check that the events of every validator follow a valid lifecycle.
+///
+/// A validator must be registered before it is delegated to, undelegated from or has its keys
+/// updated, and no event may follow its exit. An undelegation may never exceed the amount the
+/// delegator has delegated to the validator. Unlike [`from_l1_events`], which stops at the first
+/// event it cannot apply, this reports why the sequence is invalid.
+pub fn validate_event_lifecycle(
+    events: impl IntoIterator<Item = StakeTableEvent>,
+) -> Result<(), LifecycleError> {
+    // Whether each validator seen so far is still registered, or has exited.
+    let mut registered = HashMap::<Address, bool>::new();
+    let mut delegations = HashMap::<(Address, Address), U256>::new();
+
+    for (index, event) in events.into_iter().enumerate() {
+        let validator = match &event {
+            StakeTableEvent::Register(event) => event.account,
+            StakeTableEvent::Deregister(event) => event.validator,
+            StakeTableEvent::Delegate(event) => event.validator,
+            StakeTableEvent::Undelegate(event) => event.validator,
+            StakeTableEvent::KeyUpdate(event) => event.account,
+        };
+        match (registered.get(&validator).copied(), &event) {
+            (Some(false), _) => return Err(LifecycleError::AfterExit { index, validator }),
+            (Some(true), StakeTableEvent::Register(_)) => {
+                return Err(LifecycleError::AlreadyRegistered { index, validator })
+            },
+            (None, StakeTableEvent::Register(_)) => {
+                registered.insert(validator, true);
+            },
+            (None, _) => return Err(LifecycleError::NotRegistered { index, validator }),
+            (Some(true), StakeTableEvent::Deregister(_)) => {
+                registered.insert(validator, false);
+            },
+            (Some(true), StakeTableEvent::Delegate(event)) => {
+                *delegations.entry((validator, event.delegator)).or_default() += event.amount;
+            },
+            (Some(true), StakeTableEvent::Undelegate(event)) => {
+                let delegated = delegations.entry((validator, event.delegator)).or_default();
+                if event.amount > *delegated {
+                    return Err(LifecycleError::UndelegationExceedsDelegation {
+                        index,
+                        validator,
+                        delegator: event.delegator,
+                        amount: event.amount,
+                        delegated: *delegated,
+                    });
+                }
+                *delegated -= event.amount;
+            },
+            (Some(true), StakeTableEvent::KeyUpdate(_)) => {},
         }
+    }
+
+    Ok(())
+}
+
+/// Default maximum number of validators selected into the stake table of an epoch.
+pub const DEFAULT_MAX_VALIDATORS: usize = 100;
 
-        true
+/// Rank validators by descending stake.
+///
+/// Ties are broken by ascending address, so that every node ranks the same validators in the same
+/// order regardless of the order it processed them in.
+fn rank_by_stake(stakers: &mut [(Address, U256)]) {
+    stakers.sort_by(|(addr_a, stake_a), (addr_b, stake_b)| {
+        stake_b.cmp(stake_a).then_with(|| addr_a.cmp(addr_b))
     });
+}
+
+/// Whether a validator can be considered for selection at all.
+fn is_selection_candidate(address: &Address, validator: &Validator<BLSPubKey>) -> bool {
+    if validator.delegators.is_empty() {
+        tracing::info!("Validator {address:?} does not have any delegator");
+        return false;
+    }
+
+    if validator.stake.is_zero() {
+        tracing::info!("Validator {address:?} does not have any stake");
+        return false;
+    }
+
+    true
+}
+
+/// Evidence of why a validator was or was not selected into the stake table of an epoch.
+///
+/// Selection is deterministic, so anyone holding the same set of candidate validators can
+/// recompute every field of the evidence and check the decision.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SelectionEvidence {
+    /// The epoch the evidence is for
+    pub epoch: Epoch,
+    /// The validator the evidence is for
+    pub address: Address,
+    /// The minimum stake a validator needed to be selected
+    pub minimum_stake: U256,
+    /// The stake of the validator, `None` if it is not a candidate for selection
+    pub stake: Option<U256>,
+    /// The 1-based rank of the validator among the candidates, ordered by descending stake and
+    /// then by ascending address
+    pub rank: Option<usize>,
+    /// The lowest rank that is still selected
+    pub cutoff_rank: usize,
+    /// Whether the validator was selected
+    pub selected: bool,
+}
+
+impl SelectionEvidence {
+    /// Compute the selection evidence for `address` from the candidate `validators` of `epoch`,
+    /// mirroring the decision made when selecting the stake table.
+    pub fn from_candidates(
+        epoch: Epoch,
+        validators: &IndexMap<Address, Validator<BLSPubKey>>,
+        min_stake_policy: MinStakePolicy,
+        max_validators: usize,
+        address: Address,
+    ) -> anyhow::Result<Self> {
+        ensure!(
+            max_validators > 0,
+            "maximum number of validators must be positive"
+        );
+
+        let candidates: IndexMap<_, _> = validators
+            .iter()
+            .filter(|(address, validator)| is_selection_candidate(address, validator))
+            .map(|(address, validator)| (*address, validator.clone()))
+            .collect();
+
+        if candidates.is_empty() {
+            bail!("No valid validators found");
+        }
+
+        let minimum_stake = min_stake_policy.minimum_stake(&candidates)?;
+
+        // Candidates below the minimum stake always rank after those above it, so ranking all
+        // candidates gives the same order as the one used for the top-N cutoff.
+        let mut ranked: Vec<_> = candidates
+            .iter()
+            .map(|(addr, v)| (*addr, v.stake))
+            .collect();
+        rank_by_stake(&mut ranked);
+
+        let position = ranked.iter().position(|(addr, _)| *addr == address);
+        let stake = position.map(|i| ranked[i].1);
+        let rank = position.map(|i| i + 1);
+        let selected = matches!((stake, rank), (Some(stake), Some(rank))
+            if stake >= minimum_stake && rank <= max_validators);
+
+        Ok(Self {
+            epoch,
+            address,
+            minimum_stake,
+            stake,
+            rank,
+            cutoff_rank: max_validators,
+            selected,
+        })
+    }
+}
+
+fn select_validators(
+    validators: &mut IndexMap<Address, Validator<BLSPubKey>>,
+    min_stake_policy: MinStakePolicy,
+    max_validators: usize,
+) -> anyhow::Result<()> {
+    ensure!(
+        max_validators > 0,
+        "maximum number of validators must be positive"
+    );
+
+    // Remove invalid validators first
+    validators.retain(|address, validator| is_selection_candidate(address, validator));
 
     if validators.is_empty() {
         bail!("No valid validators found");
     }
 
-    // Find the maximum stake
-    let maximum_stake = validators
-        .values()
-        .map(|v| v.stake)
-        .max()
-        .context("Failed to determine max stake")?;
-
-    let minimum_stake = maximum_stake
-        .checked_div(U256::from(VID_TARGET_TOTAL_STAKE))
-        .context("div err")?;
+    let minimum_stake = min_stake_policy.minimum_stake(validators)?;
 
     // Collect validators that meet the minimum stake criteria
     let mut valid_stakers: Vec<_> = validators
@@ -212,12 +634,10 @@ fn select_validators(
         .collect();
 
     // Sort by stake (descending order)
-    valid_stakers.sort_by_key(|(_, stake)| std::cmp::Reverse(*stake));
+    rank_by_stake(&mut valid_stakers);
 
-    // Keep only the top 100 stakers
-    if valid_stakers.len() > 100 {
-        valid_stakers.truncate(100);
-    }
+    // Keep only the top `max_validators` stakers
+    valid_stakers.truncate(max_validators);
 
     // Retain only the selected validators
     let selected_addresses: HashSet<_> = valid_stakers.iter().map(|(addr, _)| *addr).collect();
@@ -226,6 +646,142 @@ fn select_validators(
     Ok(())
 }
 
+/// Changes to the validator set between two epochs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StakeTableDiff {
+    /// Validators present in the later epoch but not in the earlier one
+    pub added: Vec<Address>,
+    /// Validators present in the earlier epoch but not in the later one
+    pub removed: Vec<Address>,
+    /// Validators present in both epochs whose stake or consensus keys changed
+    pub changed: Vec<Address>,
+}
+
+impl StakeTableDiff {
+    /// Compute the changes going from the `from` validator set to the `to` validator set.
+    pub fn new(
+        from: &IndexMap<Address, Validator<BLSPubKey>>,
+        to: &IndexMap<Address, Validator<BLSPubKey>>,
+    ) -> Self {
+        let added = to
+            .keys()
+            .filter(|address| !from.contains_key(*address))
+            .copied()
+            .collect();
+        let removed = from
+            .keys()
+            .filter(|address| !to.contains_key(*address))
+            .copied()
+            .collect();
+        let changed = to
+            .iter()
+            .filter_map(|(address, new)| {
+                let old = from.get(address)?;
+                (old.stake != new.stake
+                    || old.stake_table_key != new.stake_table_key
+                    || old.state_ver_key != new.state_ver_key)
+                    .then_some(*address)
+            })
+            .collect();
+
+        Self {
+            added,
+            removed,
+            changed,
+        }
+    }
+
+    /// Returns true if the validator set did not change
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Outcome of comparing the stake table stored for an epoch against one recomputed from L1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuditResult {
+    /// The audited epoch
+    pub epoch: Epoch,
+    /// The L1 block the stake table was recomputed at
+    pub l1_block: u64,
+    /// Changes going from the stored validator set to the recomputed one
+    pub diff: StakeTableDiff,
+    /// Validators present in both sets whose records differ in any field, including the
+    /// commission and delegators which are not covered by `diff`
+    pub mismatched: Vec<Address>,
+}
+
+impl AuditResult {
+    /// Compare the `stored` validator set of `epoch` against the set `recomputed` at `l1_block`.
+    pub fn new(
+        epoch: Epoch,
+        l1_block: u64,
+        stored: &IndexMap<Address, Validator<BLSPubKey>>,
+        recomputed: &IndexMap<Address, Validator<BLSPubKey>>,
+    ) -> Self {
+        let mismatched = recomputed
+            .iter()
+            .filter_map(|(address, validator)| {
+                let stored = stored.get(address)?;
+                (stored != validator).then_some(*address)
+            })
+            .collect();
+
+        Self {
+            epoch,
+            l1_block,
+            diff: StakeTableDiff::new(stored, recomputed),
+            mismatched,
+        }
+    }
+
+    /// Returns true if the stored stake table matches the one recomputed from L1
+    pub fn is_clean(&self) -> bool {
+        self.diff.is_empty() && self.mismatched.is_empty()
+    }
+}
+
+/// Changes to the DA committee between two epochs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DaCommitteeDiff {
+    /// DA members present in the later epoch but not in the earlier one
+    pub added: Vec<PubKey>,
+    /// DA members present in the earlier epoch but not in the later one
+    pub removed: Vec<PubKey>,
+}
+
+impl DaCommitteeDiff {
+    /// Compute the changes going from the `from` DA committee to the `to` DA committee.
+    pub fn new(from: &[PeerConfig<SeqTypes>], to: &[PeerConfig<SeqTypes>]) -> Self {
+        let keys = |members: &[PeerConfig<SeqTypes>]| -> Vec<PubKey> {
+            members
+                .iter()
+                .map(|member| PubKey::public_key(&member.stake_table_entry))
+                .collect()
+        };
+        let from = keys(from);
+        let to = keys(to);
+
+        let added = to
+            .iter()
+            .filter(|key| !from.contains(key))
+            .copied()
+            .collect();
+        let removed = from
+            .iter()
+            .filter(|key| !to.contains(key))
+            .copied()
+            .collect();
+
+        Self { added, removed }
+    }
+
+    /// Returns true if the DA committee did not change
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
 #[derive(Clone, derive_more::From)]
 pub enum StakeTableEvent {
     Register(ValidatorRegistered),
@@ -248,6 +804,10 @@ impl std::fmt::Debug for StakeTableEvent {
 }
 
 impl StakeTableEvent {
+    /// Sort the stake table events by their position on L1.
+    ///
+    /// Fails if two events share the same `(block_number, log_index)`, which means the logs were
+    /// fetched incorrectly. Keeping only one of them would silently corrupt the stake table.
     pub fn sort_events(
         registrations: Vec<(ValidatorRegistered, Log)>,
         deregistrations: Vec<(ValidatorExit, Log)>,
@@ -256,51 +816,39 @@ impl StakeTableEvent {
         keys_update: Vec<(ConsensusKeysUpdated, Log)>,
     ) -> anyhow::Result<BTreeMap<(u64, u64), StakeTableEvent>> {
         let mut map = BTreeMap::new();
-        for (registration, log) in registrations {
-            map.insert(
-                (
-                    log.block_number.context("block number")?,
-                    log.log_index.context("log index")?,
-                ),
-                registration.into(),
+        let mut insert = |event: StakeTableEvent, log: Log| -> anyhow::Result<()> {
+            let key = (
+                log.block_number.context("block number")?,
+                log.log_index.context("log index")?,
             );
+            match map.entry(key) {
+                Entry::Vacant(entry) => {
+                    entry.insert(event);
+                    Ok(())
+                },
+                Entry::Occupied(entry) => bail!(
+                    "stake table events {:?} and {event:?} share block number {} and log index {}",
+                    entry.get(),
+                    key.0,
+                    key.1
+                ),
+            }
+        };
+
+        for (registration, log) in registrations {
+            insert(registration.into(), log)?;
         }
         for (dereg, log) in deregistrations {
-            map.insert(
-                (
-                    log.block_number.context("block number")?,
-                    log.log_index.context("log index")?,
-                ),
-                dereg.into(),
-            );
+            insert(dereg.into(), log)?;
         }
         for (delegation, log) in delegations {
-            map.insert(
-                (
-                    log.block_number.context("block number")?,
-                    log.log_index.context("log index")?,
-                ),
-                delegation.into(),
-            );
+            insert(delegation.into(), log)?;
         }
         for (undelegated, log) in undelegated_events {
-            map.insert(
-                (
-                    log.block_number.context("block number")?,
-                    log.log_index.context("log index")?,
-                ),
-                undelegated.into(),
-            );
+            insert(undelegated.into(), log)?;
         }
-
         for (update, log) in keys_update {
-            map.insert(
-                (
-                    log.block_number.context("block number")?,
-                    log.log_index.context("log index")?,
-                ),
-                update.into(),
-            );
+            insert(update.into(), log)?;
         }
         Ok(map)
     }
@@ -333,6 +881,21 @@ pub struct EpochCommittees {
     persistence: Arc<dyn MembershipPersistence>,
 
     first_epoch: Option<Epoch>,
+
+    /// Policy used to derive the minimum stake when selecting validators from L1
+    min_stake_policy: MinStakePolicy,
+
+    /// Maximum number of validators selected from L1 into the stake table of an epoch
+    max_validators: usize,
+
+    /// The stake table events up to the latest L1 block a stake table was fetched for, so that
+    /// the next stake table only requires fetching newer events
+    #[debug(skip)]
+    stake_table_snapshot: Option<StakeTableSnapshot>,
+
+    /// Number of leader lookups for an epoch whose randomized committee was not ready yet
+    #[debug(skip)]
+    randomized_committee_misses: Arc<dyn Counter>,
 }
 
 /// Holds Stake table and da stake
@@ -366,7 +929,48 @@ pub struct EpochCommittee {
     /// Keys for nodes participating in the network
     stake_table: IndexMap<PubKey, PeerConfig<SeqTypes>>,
     validators: IndexMap<Address, Validator<BLSPubKey>>,
-    address_mapping: HashMap<BLSPubKey, Address>,
+    /// One-to-one mapping between the BLS key and the ethereum address of each validator
+    address_mapping: BiHashMap<BLSPubKey, Address>,
+    /// The L1 block the validators were selected at, if known
+    #[serde(default)]
+    l1_block: Option<u64>,
+    /// Every validator that was a candidate for selection, if the stake table was computed from
+    /// the L1 events by this node
+    #[serde(default)]
+    candidates: Option<IndexMap<Address, Validator<BLSPubKey>>>,
+}
+
+/// Compute the cumulative stake distribution used to select the leaders of an epoch.
+///
+/// This is the distribution a node derives once it learns the DRB result of the epoch, so a client
+/// that trusts `eligible_leaders` and `drb` can pass it to [`predict_leader`] to compute the
+/// leaders of the epoch itself.
+pub fn leader_stake_cdf(
+    eligible_leaders: &[PeerConfig<SeqTypes>],
+    drb: DrbResult,
+) -> RandomizedCommittee<StakeTableEntry<PubKey>> {
+    let leaders = eligible_leaders
+        .iter()
+        .map(|peer_config| peer_config.stake_table_entry.clone())
+        .collect::<Vec<_>>();
+    generate_stake_cdf(leaders, drb)
+}
+
+/// Predict the leader of `view` from the stake distribution of its epoch.
+///
+/// This matches the leader returned by [`Membership::lookup_leader`] for a node that holds the
+/// same distribution. Returns `None` if nobody in the distribution has any stake.
+pub fn predict_leader(
+    randomized_committee: &RandomizedCommittee<StakeTableEntry<PubKey>>,
+    view: View,
+) -> Option<PubKey> {
+    if randomized_committee.total_stake().is_zero() {
+        return None;
+    }
+    Some(PubKey::public_key(&select_randomized_leader(
+        randomized_committee,
+        *view,
+    )))
 }
 
 impl EpochCommittees {
@@ -384,7 +988,7 @@ impl EpochCommittees {
         epoch: EpochNumber,
         validators: IndexMap<Address, Validator<BLSPubKey>>,
     ) {
-        let mut address_mapping = HashMap::new();
+        let mut address_mapping = BiHashMap::new();
         let stake_table = validators
             .values()
             .map(|v| {
@@ -409,10 +1013,35 @@ impl EpochCommittees {
                 stake_table,
                 validators,
                 address_mapping,
+                l1_block: None,
+                candidates: None,
             },
         );
     }
 
+    /// Record that the stake table of `epoch` was selected at `l1_block`, from `candidates` if
+    /// they are known, so that the selection can be explained later on.
+    fn record_selection(
+        &mut self,
+        epoch: EpochNumber,
+        l1_block: u64,
+        candidates: Option<IndexMap<Address, Validator<BLSPubKey>>>,
+    ) {
+        if let Some(committee) = self.state.get_mut(&epoch) {
+            committee.l1_block = Some(l1_block);
+            committee.candidates = candidates;
+        }
+    }
+
+    /// The cumulative stake distribution used to select the leaders of `epoch`, if its DRB result
+    /// is known.
+    pub fn leader_stake_cdf(
+        &self,
+        epoch: &Epoch,
+    ) -> Option<&RandomizedCommittee<StakeTableEntry<PubKey>>> {
+        self.randomized_committees.get(epoch)
+    }
+
     pub fn validators(
         &self,
         epoch: &Epoch,
@@ -425,32 +1054,216 @@ impl EpochCommittees {
             .clone())
     }
 
+    /// Explain why the validator with the given ethereum address was or was not selected into
+    /// the stake table of `epoch`.
+    ///
+    /// The evidence is computed from every candidate for selection, including the validators that
+    /// were excluded. If this node did not compute the stake table of `epoch` from the L1 events
+    /// itself, for instance because it was loaded from persistence, the candidates are recomputed
+    /// from the events up to the L1 block the stake table was selected at.
+    pub async fn selection_evidence(
+        &self,
+        epoch: &Epoch,
+        address: Address,
+    ) -> anyhow::Result<SelectionEvidence> {
+        let committee = self
+            .state
+            .get(epoch)
+            .with_context(|| format!("stake table for epoch {epoch} not loaded"))?;
+
+        let fetched;
+        let candidates = match &committee.candidates {
+            Some(candidates) => candidates,
+            None => {
+                let l1_block = committee.l1_block.with_context(|| {
+                    format!("L1 block of the stake table for epoch {epoch} is unknown")
+                })?;
+                let contract_address = self
+                    .contract_address
+                    .context("stake table contract address not configured")?;
+                let events = self
+                    .l1_client
+                    .get_stake_table_events(contract_address, 0, l1_block)
+                    .await
+                    .with_context(|| {
+                        format!("failed to fetch stake table events up to L1 block {l1_block}")
+                    })?;
+                fetched =
+                    validators_from_l1_events(events.into_values(), StakeTableEventMode::Strict)?;
+                &fetched
+            },
+        };
+
+        SelectionEvidence::from_candidates(
+            *epoch,
+            candidates,
+            self.min_stake_policy,
+            self.max_validators,
+            address,
+        )
+    }
+
     pub fn address(&self, epoch: &Epoch, bls_key: BLSPubKey) -> anyhow::Result<Address> {
-        let mapping = self
+        let mapping = &self
             .state
             .get(epoch)
             .context("state for found")?
-            .address_mapping
-            .clone();
+            .address_mapping;
 
-        Ok(*mapping.get(&bls_key).context(format!(
+        Ok(*mapping.get_by_left(&bls_key).context(format!(
             "failed to get ethereum address for bls key {bls_key:?}"
         ))?)
     }
 
-    pub fn get_validator_config(
+    /// Look up the BLS key of the validator with the given ethereum address in `epoch`.
+    ///
+    /// This is the inverse of [`Self::address`].
+    pub fn bls_key_for_address(
         &self,
         epoch: &Epoch,
-        key: BLSPubKey,
-    ) -> anyhow::Result<Validator<BLSPubKey>> {
-        let address = self.address(epoch, key)?;
-        let validators = self.validators(epoch)?;
-        Ok(validators.get(&address).unwrap().clone())
+        address: Address,
+    ) -> anyhow::Result<BLSPubKey> {
+        let mapping = &self
+            .state
+            .get(epoch)
+            .context("state for found")?
+            .address_mapping;
+
+        Ok(*mapping.get_by_right(&address).context(format!(
+            "failed to get bls key for ethereum address {address:#x}"
+        ))?)
     }
 
-    // We need a constructor to match our concrete type.
-    pub fn new_stake(
-        // TODO remove `new` from trait and rename this to `new`.
+    /// Compute the changes to the validator set going from epoch `a` to epoch `b`.
+    ///
+    /// Both epochs must already be loaded.
+    pub fn diff_epochs(&self, a: Epoch, b: Epoch) -> anyhow::Result<StakeTableDiff> {
+        let from = &self
+            .state
+            .get(&a)
+            .with_context(|| format!("stake table for epoch {a} not loaded"))?
+            .validators;
+        let to = &self
+            .state
+            .get(&b)
+            .with_context(|| format!("stake table for epoch {b} not loaded"))?
+            .validators;
+
+        Ok(StakeTableDiff::new(from, to))
+    }
+
+    /// Recompute the stake table of `epoch` from the stake table contract at `l1_block` and
+    /// compare it against the stake table loaded for `epoch`.
+    ///
+    /// This bypasses persistence, so a discrepancy points to drift between the stored stake table
+    /// and the events emitted by the contract.
+    pub async fn audit_epoch(&self, epoch: Epoch, l1_block: u64) -> anyhow::Result<AuditResult> {
+        let contract_address = self
+            .contract_address
+            .context("stake table contract address not configured")?;
+        // Fail early, before querying L1, if there is nothing to compare against.
+        self.state
+            .get(&epoch)
+            .with_context(|| format!("stake table for epoch {epoch} not loaded"))?;
+
+        let recomputed = self
+            .l1_client
+            .get_stake_table(
+                contract_address,
+                l1_block,
+                self.min_stake_policy,
+                self.max_validators,
+            )
+            .await
+            .with_context(|| format!("failed to fetch stake table at L1 block {l1_block}"))?;
+
+        self.audit_validators(epoch, l1_block, &recomputed)
+    }
+
+    /// Compare the stake table loaded for `epoch` against `recomputed`, the validator set
+    /// computed from the stake table contract at `l1_block`.
+    fn audit_validators(
+        &self,
+        epoch: Epoch,
+        l1_block: u64,
+        recomputed: &IndexMap<Address, Validator<BLSPubKey>>,
+    ) -> anyhow::Result<AuditResult> {
+        let stored = &self
+            .state
+            .get(&epoch)
+            .with_context(|| format!("stake table for epoch {epoch} not loaded"))?
+            .validators;
+
+        let result = AuditResult::new(epoch, l1_block, stored, recomputed);
+        if !result.is_clean() {
+            tracing::warn!(
+                "stored stake table for epoch {epoch} does not match the stake table contract at L1 block {l1_block}: {result:?}"
+            );
+        }
+
+        Ok(result)
+    }
+
+    /// Compute the changes to the DA committee going from epoch `a` to epoch `b`.
+    ///
+    /// Both epochs must already be loaded. The DA committee is not read from the stake table
+    /// contract yet, so every epoch uses the DA members the node was configured with and the
+    /// diff is currently always empty.
+    pub fn da_diff_epochs(&self, a: Epoch, b: Epoch) -> anyhow::Result<DaCommitteeDiff> {
+        for epoch in [a, b] {
+            self.state
+                .get(&epoch)
+                .with_context(|| format!("stake table for epoch {epoch} not loaded"))?;
+        }
+
+        Ok(DaCommitteeDiff::new(
+            &self.da_stake_table(Some(a)),
+            &self.da_stake_table(Some(b)),
+        ))
+    }
+
+    pub fn get_validator_config(
+        &self,
+        epoch: &Epoch,
+        key: BLSPubKey,
+    ) -> anyhow::Result<Validator<BLSPubKey>> {
+        let address = self.address(epoch, key)?;
+        let validators = self.validators(epoch)?;
+        Ok(validators.get(&address).unwrap().clone())
+    }
+
+    /// Look up the validator with the given ethereum address in the stake table of `epoch`.
+    pub fn validator_by_address(
+        &self,
+        epoch: &Epoch,
+        address: Address,
+    ) -> anyhow::Result<Validator<BLSPubKey>> {
+        let validators = &self
+            .state
+            .get(epoch)
+            .with_context(|| format!("stake table for epoch {epoch} not loaded"))?
+            .validators;
+
+        validators.get(&address).cloned().with_context(|| {
+            format!("validator {address:#x} is not in the stake table of epoch {epoch}")
+        })
+    }
+
+    /// The ethereum addresses of the validators in the stake table of `epoch`, in stake table
+    /// order.
+    pub fn validator_addresses(&self, epoch: &Epoch) -> anyhow::Result<Vec<Address>> {
+        let validators = &self
+            .state
+            .get(epoch)
+            .with_context(|| format!("stake table for epoch {epoch} not loaded"))?
+            .validators;
+
+        Ok(validators.keys().copied().collect())
+    }
+
+    // We need a constructor to match our concrete type.
+    pub fn new_stake(
+        // TODO remove `new` from trait and rename this to `new`.
         // https://github.com/EspressoSystems/HotShot/commit/fcb7d54a4443e29d643b3bbc53761856aef4de8b
         committee_members: Vec<PeerConfig<SeqTypes>>,
         da_members: Vec<PeerConfig<SeqTypes>>,
@@ -525,7 +1338,9 @@ impl EpochCommittees {
                 .map(|x| (PubKey::public_key(&x.stake_table_entry), x.clone()))
                 .collect(),
             validators: Default::default(),
-            address_mapping: HashMap::new(),
+            address_mapping: BiHashMap::new(),
+            l1_block: None,
+            candidates: None,
         };
         map.insert(Epoch::genesis(), epoch_committee.clone());
         // TODO: remove this, workaround for hotshot asking for stake tables from epoch 1
@@ -540,8 +1355,33 @@ impl EpochCommittees {
             peers,
             persistence: Arc::new(persistence),
             first_epoch: None,
+            min_stake_policy: MinStakePolicy::default(),
+            max_validators: DEFAULT_MAX_VALIDATORS,
+            stake_table_snapshot: None,
+            randomized_committee_misses: Arc::new(NoMetrics),
         }
     }
+
+    /// Use `min_stake_policy` when selecting validators from stake tables fetched from L1.
+    pub fn with_min_stake_policy(mut self, min_stake_policy: MinStakePolicy) -> Self {
+        self.min_stake_policy = min_stake_policy;
+        self
+    }
+
+    /// Select at most `max_validators` validators from stake tables fetched from L1.
+    pub fn with_max_validators(mut self, max_validators: usize) -> Self {
+        self.max_validators = max_validators;
+        self
+    }
+
+    /// Report metrics about leader lookups to `metrics`.
+    pub fn with_metrics(mut self, metrics: &(impl Metrics + ?Sized)) -> Self {
+        self.randomized_committee_misses = metrics
+            .create_counter("randomized_committee_misses".into(), None)
+            .into();
+        self
+    }
+
     fn get_stake_table(&self, epoch: &Option<Epoch>) -> Option<Vec<PeerConfig<SeqTypes>>> {
         if let Some(epoch) = epoch {
             self.state
@@ -553,27 +1393,119 @@ impl EpochCommittees {
     }
 
     /// Get the stake table by epoch. Try to load from DB and fall back to fetching from l1.
+    ///
+    /// When fetching from l1, also returns the snapshot of the stake table events at `l1_block`,
+    /// if one could be taken.
     async fn get_stake_table_by_epoch(
         &self,
         epoch: Epoch,
         contract_address: Address,
         l1_block: u64,
-    ) -> Result<IndexMap<alloy::primitives::Address, Validator<BLSPubKey>>, GetStakeTablesError>
-    {
+    ) -> Result<
+        (
+            IndexMap<alloy::primitives::Address, Validator<BLSPubKey>>,
+            Option<StakeTableSnapshot>,
+        ),
+        GetStakeTablesError,
+    > {
         if let Some(stake_tables) = self
             .persistence
             .load_stake(epoch)
             .await
             .map_err(GetStakeTablesError::PersistenceLoadError)?
         {
-            Ok(stake_tables)
+            Ok((stake_tables, None))
         } else {
-            self.l1_client
-                .get_stake_table(contract_address, l1_block)
+            self.fetch_stake_table(contract_address, l1_block)
                 .await
                 .map_err(GetStakeTablesError::L1ClientFetchError)
         }
     }
+
+    /// Compute the stake table at `l1_block` from the stake table events.
+    ///
+    /// If the latest snapshot is still on the L1 chain, only the events emitted after its last
+    /// event are fetched and applied to it. Otherwise, after a reorg or when no snapshot was taken
+    /// yet, the stake table is recomputed from every event. The snapshot at `l1_block` is returned
+    /// along with the stake table, unless the L1 does not have this block yet.
+    async fn fetch_stake_table(
+        &self,
+        contract_address: Address,
+        l1_block: u64,
+    ) -> anyhow::Result<(
+        IndexMap<Address, Validator<BLSPubKey>>,
+        Option<StakeTableSnapshot>,
+    )> {
+        let Some(l1_block_hash) = self.l1_client.get_block_hash(l1_block).await? else {
+            let validators = self
+                .l1_client
+                .get_stake_table(
+                    contract_address,
+                    l1_block,
+                    self.min_stake_policy,
+                    self.max_validators,
+                )
+                .await?;
+            return Ok((validators, None));
+        };
+
+        let mut base = None;
+        if let Some(snapshot) = self
+            .stake_table_snapshot
+            .as_ref()
+            .filter(|snapshot| snapshot.l1_block() <= l1_block)
+        {
+            let hash = self.l1_client.get_block_hash(snapshot.l1_block()).await?;
+            if hash == Some(snapshot.l1_block_hash()) {
+                base = Some(snapshot);
+            } else {
+                tracing::warn!(
+                    "L1 block {} of the stake table snapshot was reorged, recomputing the stake \
+                     table from every event",
+                    snapshot.l1_block()
+                );
+            }
+        }
+
+        let snapshot = match base {
+            Some(base) => {
+                let events = self
+                    .l1_client
+                    .get_stake_table_events(contract_address, base.next_block(), l1_block)
+                    .await?;
+                base.update(events, l1_block, l1_block_hash, StakeTableEventMode::Strict)?
+            },
+            None => {
+                let events = self
+                    .l1_client
+                    .get_stake_table_events(contract_address, 0, l1_block)
+                    .await?;
+                StakeTableSnapshot::from_events(
+                    events,
+                    l1_block,
+                    l1_block_hash,
+                    StakeTableEventMode::Strict,
+                )?
+            },
+        };
+
+        let mut validators = snapshot.state().validators().clone();
+        select_validators(&mut validators, self.min_stake_policy, self.max_validators)?;
+        Ok((validators, Some(snapshot)))
+    }
+
+    /// Keep `snapshot` to compute later stake tables incrementally, unless the current snapshot
+    /// is more recent.
+    fn update_stake_table_snapshot(&mut self, snapshot: StakeTableSnapshot) {
+        if self
+            .stake_table_snapshot
+            .as_ref()
+            .is_some_and(|current| current.l1_block() > snapshot.l1_block())
+        {
+            return;
+        }
+        self.stake_table_snapshot = Some(snapshot);
+    }
 }
 
 #[derive(Error, Debug)]
@@ -585,9 +1517,21 @@ enum GetStakeTablesError {
     L1ClientFetchError(anyhow::Error),
 }
 
-#[derive(Error, Debug)]
-#[error("Could not lookup leader")] // TODO error variants? message?
-pub struct LeaderLookupError;
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum LeaderLookupError {
+    /// The DRB result for `epoch` has not been computed or fetched yet, so its randomized
+    /// committee is not available. This is expected while catching up, and the lookup should be
+    /// retried later.
+    #[error("Randomized committee for epoch {epoch} is not ready")]
+    RandomizedCommitteeNotReady { epoch: Epoch },
+}
+
+impl LeaderLookupError {
+    /// Whether the lookup may succeed if retried later.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::RandomizedCommitteeNotReady { .. })
+    }
+}
 
 // #[async_trait]
 impl Membership<SeqTypes> for EpochCommittees {
@@ -684,11 +1628,12 @@ impl Membership<SeqTypes> for EpochCommittees {
     ) -> Result<PubKey, Self::Error> {
         if let Some(epoch) = epoch {
             let Some(randomized_committee) = self.randomized_committees.get(&epoch) else {
-                tracing::error!(
+                tracing::warn!(
                     "We are missing the randomized committee for epoch {}",
                     epoch
                 );
-                return Err(LeaderLookupError);
+                self.randomized_committee_misses.add(1);
+                return Err(LeaderLookupError::RandomizedCommitteeNotReady { epoch });
             };
 
             Ok(PubKey::public_key(&select_randomized_leader(
@@ -766,8 +1711,9 @@ impl Membership<SeqTypes> for EpochCommittees {
             return None;
         };
 
-        let stake_tables = self
-            .get_stake_table_by_epoch(epoch, address, block_header.height())
+        let l1_block = block_header.height();
+        let (stake_tables, snapshot) = self
+            .get_stake_table_by_epoch(epoch, address, l1_block)
             .await
             .inspect_err(|e| {
                 tracing::error!(?e, "`add_epoch_root`, error retrieving stake table");
@@ -782,8 +1728,17 @@ impl Membership<SeqTypes> for EpochCommittees {
             tracing::error!(?e, "`add_epoch_root`, error storing stake table");
         }
 
+        // The snapshot holds every validator before selection.
+        let candidates = snapshot
+            .as_ref()
+            .map(|snapshot| snapshot.state().validators().clone());
+
         Some(Box::new(move |committee: &mut Self| {
+            if let Some(snapshot) = snapshot {
+                committee.update_stake_table_snapshot(snapshot);
+            }
             committee.update_stake_table(epoch, stake_tables);
+            committee.record_selection(epoch, l1_block, candidates);
         }))
     }
 
@@ -856,22 +1811,21 @@ impl Membership<SeqTypes> for EpochCommittees {
             return;
         };
 
-        let leaders = raw_stake_table
-            .eligible_leaders
-            .clone()
-            .into_iter()
-            .map(|peer_config| peer_config.stake_table_entry)
-            .collect::<Vec<_>>();
-        let randomized_committee = generate_stake_cdf(leaders, drb);
+        let randomized_committee = leader_stake_cdf(&raw_stake_table.eligible_leaders, drb);
 
         self.randomized_committees
             .insert(epoch, randomized_committee);
     }
 
     fn set_first_epoch(&mut self, epoch: Epoch, initial_drb_result: DrbResult) {
+        let Some(epoch_committee) = self.state.get(&Epoch::genesis()).cloned() else {
+            tracing::error!(
+                "set_first_epoch({epoch}) was called, but we do not have the genesis stake table"
+            );
+            return;
+        };
         self.first_epoch = Some(epoch);
 
-        let epoch_committee = self.state.get(&Epoch::genesis()).unwrap().clone();
         self.state.insert(epoch, epoch_committee.clone());
         self.state.insert(epoch + 1, epoch_committee);
         self.add_drb_result(epoch, initial_drb_result);
@@ -979,10 +1933,17 @@ pub mod testing {
 #[cfg(test)]
 mod tests {
     use alloy::primitives::Address;
+    use hotshot_types::{
+        data::{
+            vid_disperse::{verify_vid_dispersal_coverage, vid_total_weight, vid_weights},
+            ViewNumber,
+        },
+        vid::avidm::{init_avidm_param, AvidMScheme},
+    };
     use sequencer_utils::test_utils::setup_test;
 
     use super::*;
-    use crate::v0::impls::testing::*;
+    use crate::{mock::MockStateCatchup, v0::impls::testing::*, v0_1::NoStorage};
 
     #[test]
     fn test_from_l1_events() -> anyhow::Result<()> {
@@ -1096,6 +2057,848 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_validate_event_lifecycle() {
+        setup_test();
+        let val = TestValidator::random();
+        let delegator = Address::random();
+        let register: StakeTableEvent = ValidatorRegistered {
+            account: val.account,
+            blsVk: val.bls_vk.clone(),
+            schnorrVk: val.schnorr_vk.clone(),
+            commission: val.commission,
+        }
+        .into();
+        let delegate = |amount: u64| -> StakeTableEvent {
+            Delegated {
+                delegator,
+                validator: val.account,
+                amount: U256::from(amount),
+            }
+            .into()
+        };
+        let undelegate = |amount: u64| -> StakeTableEvent {
+            Undelegated {
+                delegator,
+                validator: val.account,
+                amount: U256::from(amount),
+            }
+            .into()
+        };
+        let key_update: StakeTableEvent = ConsensusKeysUpdated {
+            account: val.account,
+            blsVK: val.bls_vk.clone(),
+            schnorrVK: val.schnorr_vk.clone(),
+        }
+        .into();
+        let exit: StakeTableEvent = ValidatorExit {
+            validator: val.account,
+        }
+        .into();
+
+        validate_event_lifecycle(vec![
+            register.clone(),
+            delegate(10),
+            delegate(5),
+            undelegate(15),
+            key_update.clone(),
+            exit.clone(),
+        ])
+        .unwrap();
+
+        let cases = [
+            (
+                vec![delegate(10), register.clone()],
+                LifecycleError::NotRegistered {
+                    index: 0,
+                    validator: val.account,
+                },
+            ),
+            (
+                vec![register.clone(), key_update.clone(), register.clone()],
+                LifecycleError::AlreadyRegistered {
+                    index: 2,
+                    validator: val.account,
+                },
+            ),
+            (
+                vec![register.clone(), exit.clone(), delegate(10)],
+                LifecycleError::AfterExit {
+                    index: 2,
+                    validator: val.account,
+                },
+            ),
+            (
+                vec![register.clone(), exit.clone(), register.clone()],
+                LifecycleError::AfterExit {
+                    index: 2,
+                    validator: val.account,
+                },
+            ),
+            (
+                vec![register.clone(), delegate(10), undelegate(7), undelegate(7)],
+                LifecycleError::UndelegationExceedsDelegation {
+                    index: 3,
+                    validator: val.account,
+                    delegator,
+                    amount: U256::from(7),
+                    delegated: U256::from(3),
+                },
+            ),
+        ];
+        for (events, expected) in cases {
+            assert_eq!(
+                validate_event_lifecycle(events.clone()),
+                Err(expected),
+                "events {events:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_l1_events_key_update_unknown_validator() -> anyhow::Result<()> {
+        setup_test();
+        let val = TestValidator::random();
+        let unknown = TestValidator::random();
+        let delegator = Address::random();
+        let events: Vec<StakeTableEvent> = vec![
+            ValidatorRegistered {
+                account: val.account,
+                blsVk: val.bls_vk.clone(),
+                schnorrVk: val.schnorr_vk.clone(),
+                commission: val.commission,
+            }
+            .into(),
+            Delegated {
+                delegator,
+                validator: val.account,
+                amount: U256::from(10),
+            }
+            .into(),
+            ConsensusKeysUpdated {
+                account: unknown.account,
+                blsVK: unknown.bls_vk.clone(),
+                schnorrVK: unknown.schnorr_vk.clone(),
+            }
+            .into(),
+        ];
+
+        let err = from_l1_events_with_mode(
+            events.iter().cloned(),
+            StakeTableEventMode::Strict,
+            MinStakePolicy::default(),
+            DEFAULT_MAX_VALIDATORS,
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().contains(&format!("{:#x}", unknown.account)),
+            "unexpected error: {err}"
+        );
+
+        let st = from_l1_events_with_mode(
+            events.iter().cloned(),
+            StakeTableEventMode::Lenient,
+            MinStakePolicy::default(),
+            DEFAULT_MAX_VALIDATORS,
+        )?;
+        assert_eq!(st.len(), 1);
+        assert_eq!(
+            st.get(&val.account).unwrap().stake_table_key,
+            bls_alloy_to_jf2(val.bls_vk.clone())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_l1_events_over_undelegation() -> anyhow::Result<()> {
+        setup_test();
+        let val = TestValidator::random();
+        let delegator = Address::random();
+        let other_delegator = Address::random();
+        let events: Vec<StakeTableEvent> = vec![
+            ValidatorRegistered {
+                account: val.account,
+                blsVk: val.bls_vk.clone(),
+                schnorrVk: val.schnorr_vk.clone(),
+                commission: val.commission,
+            }
+            .into(),
+            Delegated {
+                delegator,
+                validator: val.account,
+                amount: U256::from(10),
+            }
+            .into(),
+            Delegated {
+                delegator: other_delegator,
+                validator: val.account,
+                amount: U256::from(5),
+            }
+            .into(),
+            Undelegated {
+                delegator,
+                validator: val.account,
+                amount: U256::from(12),
+            }
+            .into(),
+        ];
+
+        let err = from_l1_events_with_mode(
+            events.iter().cloned(),
+            StakeTableEventMode::Strict,
+            MinStakePolicy::default(),
+            DEFAULT_MAX_VALIDATORS,
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("delegator_stake is less than undelegated amount"),
+            "unexpected error: {err}"
+        );
+
+        // The undelegation is clamped to the stake of the delegator, which is then removed.
+        let st = from_l1_events_with_mode(
+            events.iter().cloned(),
+            StakeTableEventMode::Lenient,
+            MinStakePolicy::default(),
+            DEFAULT_MAX_VALIDATORS,
+        )?;
+        let validator = st.get(&val.account).unwrap();
+        assert_eq!(validator.stake, U256::from(5));
+        assert!(!validator.delegators.contains_key(&delegator));
+        assert_eq!(
+            validator.delegators.get(&other_delegator),
+            Some(&U256::from(5))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stake_table_snapshot_update() -> anyhow::Result<()> {
+        setup_test();
+        let val = TestValidator::random();
+        let other_val = TestValidator::random();
+        let delegator = Address::random();
+        let register = |val: &TestValidator| -> StakeTableEvent {
+            ValidatorRegistered {
+                account: val.account,
+                blsVk: val.bls_vk.clone(),
+                schnorrVk: val.schnorr_vk.clone(),
+                commission: val.commission,
+            }
+            .into()
+        };
+        let delegate = |val: &TestValidator, amount: u64| -> StakeTableEvent {
+            Delegated {
+                delegator,
+                validator: val.account,
+                amount: U256::from(amount),
+            }
+            .into()
+        };
+        let events: BTreeMap<(u64, u64), StakeTableEvent> = [
+            ((1, 0), register(&val)),
+            ((2, 0), delegate(&val, 10)),
+            ((2, 1), register(&other_val)),
+            ((5, 3), delegate(&other_val, 20)),
+            (
+                (7, 0),
+                Undelegated {
+                    delegator,
+                    validator: val.account,
+                    amount: U256::from(4),
+                }
+                .into(),
+            ),
+            (
+                (9, 2),
+                ValidatorExit {
+                    validator: other_val.account,
+                }
+                .into(),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let split = |from: u64, to: u64| -> BTreeMap<_, _> {
+            events
+                .iter()
+                .filter(|((block, _), _)| (from..=to).contains(block))
+                .map(|(position, event)| (*position, event.clone()))
+                .collect()
+        };
+        let mode = StakeTableEventMode::Strict;
+
+        // Build a snapshot at block 2, in the middle of the events of that block.
+        let snapshot = StakeTableSnapshot::from_events(split(0, 2), 2, B256::ZERO, mode)?;
+        assert_eq!(snapshot.next_block(), 2);
+        assert_eq!(snapshot.state().validators().len(), 2);
+
+        // Newer events fetched from the block of the last applied event are applied once.
+        let updated = snapshot.update(
+            split(snapshot.next_block(), 9),
+            9,
+            B256::repeat_byte(1),
+            mode,
+        )?;
+        let full = validators_from_l1_events(events.values().cloned(), mode)?;
+        assert_eq!(updated.state().validators(), &full);
+        assert_eq!(updated.l1_block(), 9);
+        assert_eq!(updated.l1_block_hash(), B256::repeat_byte(1));
+        assert_eq!(updated.next_block(), 9);
+
+        // Applying the same events again does not change anything.
+        let again = updated.update(split(0, 9), 10, B256::ZERO, mode)?;
+        assert_eq!(again.state().validators(), &full);
+
+        // The keys of validators that exited stay used.
+        let reregister = [((11, 0), register(&other_val))].into_iter().collect();
+        assert!(updated.update(reregister, 11, B256::ZERO, mode).is_err());
+
+        // A snapshot cannot be moved back in time.
+        assert!(updated.update(split(0, 2), 2, B256::ZERO, mode).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_l1_events_duplicate_schnorr_key() -> anyhow::Result<()> {
+        setup_test();
+        let val = TestValidator::random();
+        let mut other_val = TestValidator::random();
+        other_val.schnorr_vk = val.schnorr_vk.clone();
+
+        let events: Vec<StakeTableEvent> = [&val, &other_val]
+            .into_iter()
+            .flat_map(|val| {
+                [
+                    ValidatorRegistered {
+                        account: val.account,
+                        blsVk: val.bls_vk.clone(),
+                        schnorrVk: val.schnorr_vk.clone(),
+                        commission: val.commission,
+                    }
+                    .into(),
+                    Delegated {
+                        delegator: Address::random(),
+                        validator: val.account,
+                        amount: U256::from(10),
+                    }
+                    .into(),
+                ]
+            })
+            .collect();
+
+        let err = validators_from_l1_events(events.iter().cloned(), StakeTableEventMode::Strict)
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("schnorr key"),
+            "unexpected error: {err}"
+        );
+        assert!(from_l1_events(events.iter().cloned()).is_err());
+
+        // The lenient mode accepts both registrations.
+        let st = validators_from_l1_events(events.iter().cloned(), StakeTableEventMode::Lenient)?;
+        assert_eq!(st.len(), 2);
+        assert_eq!(
+            st.get(&val.account).unwrap().state_ver_key,
+            st.get(&other_val.account).unwrap().state_ver_key
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sort_events_detects_collisions() -> anyhow::Result<()> {
+        setup_test();
+        let val = TestValidator::random();
+        let registration = ValidatorRegistered {
+            account: val.account,
+            blsVk: val.bls_vk.clone(),
+            schnorrVk: val.schnorr_vk.clone(),
+            commission: val.commission,
+        };
+        let delegation = Delegated {
+            delegator: Address::random(),
+            validator: val.account,
+            amount: U256::from(10),
+        };
+        let log = |block_number, log_index| Log {
+            block_number: Some(block_number),
+            log_index: Some(log_index),
+            ..Default::default()
+        };
+
+        // Events at distinct positions are sorted by block number and log index.
+        let events = StakeTableEvent::sort_events(
+            vec![(registration.clone(), log(1, 0))],
+            vec![],
+            vec![(delegation.clone(), log(1, 1))],
+            vec![],
+            vec![],
+        )?;
+        assert_eq!(events.keys().copied().collect::<Vec<_>>(), [(1, 0), (1, 1)]);
+
+        // Two events at the same position are rejected rather than one being dropped.
+        let err = StakeTableEvent::sort_events(
+            vec![(registration, log(1, 0))],
+            vec![],
+            vec![(delegation, log(1, 0))],
+            vec![],
+            vec![],
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("share block number 1 and log index 0"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stake_table_diff() {
+        let unchanged = Validator::mock();
+        let removed = Validator::mock();
+        let added = Validator::mock();
+        let stake_changed = Validator::mock();
+        let keys_changed = Validator::mock();
+
+        let epoch_a: IndexMap<_, _> = [&unchanged, &removed, &stake_changed, &keys_changed]
+            .into_iter()
+            .map(|v| (v.account, v.clone()))
+            .collect();
+
+        let mut epoch_b = epoch_a.clone();
+        epoch_b.shift_remove(&removed.account);
+        epoch_b.insert(added.account, added.clone());
+        epoch_b.get_mut(&stake_changed.account).unwrap().stake += U256::from(1);
+        let new_keys = Validator::mock();
+        let keys_changed_b = epoch_b.get_mut(&keys_changed.account).unwrap();
+        keys_changed_b.stake_table_key = new_keys.stake_table_key;
+        keys_changed_b.state_ver_key = new_keys.state_ver_key;
+
+        let diff = StakeTableDiff::new(&epoch_a, &epoch_b);
+        assert_eq!(diff.added, vec![added.account]);
+        assert_eq!(diff.removed, vec![removed.account]);
+        assert_eq!(
+            diff.changed,
+            vec![stake_changed.account, keys_changed.account]
+        );
+
+        // the reverse diff swaps added and removed
+        let diff = StakeTableDiff::new(&epoch_b, &epoch_a);
+        assert_eq!(diff.added, vec![removed.account]);
+        assert_eq!(diff.removed, vec![added.account]);
+
+        assert!(StakeTableDiff::new(&epoch_a, &epoch_a).is_empty());
+    }
+
+    #[test]
+    fn test_audit_epoch() -> anyhow::Result<()> {
+        setup_test();
+        let l1 = L1Client::new(vec!["http://localhost:3331".parse().unwrap()])
+            .expect("Failed to create L1 client");
+        let mut committees = EpochCommittees::new_stake(
+            vec![],
+            vec![],
+            l1,
+            None,
+            Arc::new(MockStateCatchup::default()),
+            NoStorage,
+        );
+
+        let validators = [TestValidator::random(), TestValidator::random()];
+        let delegator = Address::random();
+        let mut events: Vec<StakeTableEvent> = validators
+            .iter()
+            .flat_map(|val| {
+                [
+                    ValidatorRegistered {
+                        account: val.account,
+                        blsVk: val.bls_vk.clone(),
+                        schnorrVk: val.schnorr_vk.clone(),
+                        commission: val.commission,
+                    }
+                    .into(),
+                    Delegated {
+                        delegator,
+                        validator: val.account,
+                        amount: U256::from(10),
+                    }
+                    .into(),
+                ]
+            })
+            .collect();
+
+        let epoch = EpochNumber::new(2);
+        let l1_block = 100;
+        let stored = from_l1_events(events.iter().cloned())?;
+        committees.update_stake_table(epoch, stored.clone());
+
+        // The stake table recomputed from the same events matches the stored one.
+        let recomputed = from_l1_events(events.iter().cloned())?;
+        let result = committees.audit_validators(epoch, l1_block, &recomputed)?;
+        assert!(result.is_clean());
+        assert_eq!(result.epoch, epoch);
+        assert_eq!(result.l1_block, l1_block);
+
+        // Events missed by the stored stake table are reported.
+        let new_val = TestValidator::random();
+        events.extend([
+            Undelegated {
+                delegator,
+                validator: validators[0].account,
+                amount: U256::from(5),
+            }
+            .into(),
+            ValidatorRegistered {
+                account: new_val.account,
+                blsVk: new_val.bls_vk.clone(),
+                schnorrVk: new_val.schnorr_vk.clone(),
+                commission: new_val.commission,
+            }
+            .into(),
+            Delegated {
+                delegator,
+                validator: new_val.account,
+                amount: U256::from(10),
+            }
+            .into(),
+        ]);
+        let recomputed = from_l1_events(events.iter().cloned())?;
+        let result = committees.audit_validators(epoch, l1_block, &recomputed)?;
+        assert!(!result.is_clean());
+        assert_eq!(result.diff.added, vec![new_val.account]);
+        assert!(result.diff.removed.is_empty());
+        assert_eq!(result.diff.changed, vec![validators[0].account]);
+        assert_eq!(result.mismatched, vec![validators[0].account]);
+
+        // A change to the commission is not covered by the diff, but is still reported.
+        let mut recomputed = stored.clone();
+        recomputed
+            .get_mut(&validators[1].account)
+            .unwrap()
+            .commission += 1;
+        let result = committees.audit_validators(epoch, l1_block, &recomputed)?;
+        assert!(result.diff.is_empty());
+        assert_eq!(result.mismatched, vec![validators[1].account]);
+        assert!(!result.is_clean());
+
+        // Only loaded epochs can be audited.
+        assert!(committees
+            .audit_validators(EpochNumber::new(3), l1_block, &recomputed)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_da_committee_diff() {
+        let l1 = L1Client::new(vec!["http://localhost:3331".parse().unwrap()])
+            .expect("Failed to create L1 client");
+        let da_members: Vec<_> = (0..3)
+            .map(|_| {
+                let validator = Validator::mock();
+                PeerConfig::<SeqTypes> {
+                    stake_table_entry: BLSPubKey::stake_table_entry(
+                        &validator.stake_table_key,
+                        validator.stake.to_ethers(),
+                    ),
+                    state_ver_key: validator.state_ver_key,
+                }
+            })
+            .collect();
+        let mut committees = EpochCommittees::new_stake(
+            vec![],
+            da_members.clone(),
+            l1,
+            None,
+            Arc::new(MockStateCatchup::default()),
+            NoStorage,
+        );
+
+        let validators: IndexMap<_, _> = (0..3)
+            .map(|_| {
+                let validator = Validator::mock();
+                (validator.account, validator)
+            })
+            .collect();
+        let epoch_a = EpochNumber::new(2);
+        let epoch_b = EpochNumber::new(3);
+        committees.update_stake_table(epoch_a, validators.clone());
+        let mut validators_b = validators;
+        let added = Validator::mock();
+        validators_b.insert(added.account, added);
+        committees.update_stake_table(epoch_b, validators_b);
+
+        // The validator set changed, but the DA committee is the same in every epoch.
+        assert!(!committees.diff_epochs(epoch_a, epoch_b).unwrap().is_empty());
+        assert!(committees
+            .da_diff_epochs(epoch_a, epoch_b)
+            .unwrap()
+            .is_empty());
+
+        // Both epochs must be loaded.
+        assert!(committees
+            .da_diff_epochs(epoch_a, EpochNumber::new(4))
+            .is_err());
+
+        // Changes to the DA members are reported as added and removed keys.
+        let key = |member: &PeerConfig<SeqTypes>| PubKey::public_key(&member.stake_table_entry);
+        let diff = DaCommitteeDiff::new(&da_members[..2], &da_members[1..]);
+        assert_eq!(diff.added, vec![key(&da_members[2])]);
+        assert_eq!(diff.removed, vec![key(&da_members[0])]);
+    }
+
+    #[test]
+    fn test_lookup_leader_without_drb_result_is_retryable() {
+        let l1 = L1Client::new(vec!["http://localhost:3331".parse().unwrap()])
+            .expect("Failed to create L1 client");
+        let members: Vec<_> = (0..3)
+            .map(|_| {
+                let validator = Validator::mock();
+                PeerConfig::<SeqTypes> {
+                    stake_table_entry: BLSPubKey::stake_table_entry(
+                        &validator.stake_table_key,
+                        validator.stake.to_ethers(),
+                    ),
+                    state_ver_key: validator.state_ver_key,
+                }
+            })
+            .collect();
+        let mut committees = EpochCommittees::new_stake(
+            members.clone(),
+            members,
+            l1,
+            None,
+            Arc::new(MockStateCatchup::default()),
+            NoStorage,
+        );
+
+        let validators: IndexMap<_, _> = (0..3)
+            .map(|_| {
+                let validator = Validator::mock();
+                (validator.account, validator)
+            })
+            .collect();
+        let epoch = EpochNumber::new(2);
+        committees.update_stake_table(epoch, validators);
+
+        // Without a DRB result, the randomized committee is not ready yet.
+        let err = committees
+            .lookup_leader(ViewNumber::new(1), Some(epoch))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            LeaderLookupError::RandomizedCommitteeNotReady { epoch }
+        );
+        assert!(err.is_retryable());
+
+        // Once the DRB result is known, the lookup succeeds.
+        committees.add_drb_result(epoch, [0u8; 32]);
+        assert!(committees
+            .lookup_leader(ViewNumber::new(1), Some(epoch))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_predict_leader_matches_lookup_leader() {
+        let l1 = L1Client::new(vec!["http://localhost:3331".parse().unwrap()])
+            .expect("Failed to create L1 client");
+        let members: Vec<_> = (0..5)
+            .map(|_| {
+                let validator = Validator::mock();
+                PeerConfig::<SeqTypes> {
+                    stake_table_entry: BLSPubKey::stake_table_entry(
+                        &validator.stake_table_key,
+                        validator.stake.to_ethers(),
+                    ),
+                    state_ver_key: validator.state_ver_key,
+                }
+            })
+            .collect();
+        let mut committees = EpochCommittees::new_stake(
+            members.clone(),
+            members.clone(),
+            l1,
+            None,
+            Arc::new(MockStateCatchup::default()),
+            NoStorage,
+        );
+
+        let validators: IndexMap<_, _> = (0..5)
+            .map(|_| {
+                let validator = Validator::mock();
+                (validator.account, validator)
+            })
+            .collect();
+        let epoch = EpochNumber::new(2);
+        let drb = [42u8; 32];
+        committees.update_stake_table(epoch, validators);
+        committees.add_drb_result(epoch, drb);
+
+        // A client holding the eligible leaders and the DRB result derives the same distribution
+        // as the node.
+        let cdf = leader_stake_cdf(&members, drb);
+        let node_cdf = committees.leader_stake_cdf(&epoch).unwrap();
+        assert_eq!(cdf.drb(), drb);
+        assert_eq!(cdf.total_stake(), node_cdf.total_stake());
+        assert_eq!(cdf.cdf().len(), members.len());
+        assert!(cdf.cdf().windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        assert_eq!(
+            cdf.cdf().iter().map(|(entry, _)| entry).collect::<Vec<_>>(),
+            node_cdf
+                .cdf()
+                .iter()
+                .map(|(entry, _)| entry)
+                .collect::<Vec<_>>()
+        );
+
+        // And predicts the same leaders.
+        for view in 0..50 {
+            let view = ViewNumber::new(view);
+            assert_eq!(
+                predict_leader(&cdf, view).unwrap(),
+                committees.lookup_leader(view, Some(epoch)).unwrap()
+            );
+        }
+
+        // Without any stake there is no leader to predict.
+        assert_eq!(
+            predict_leader(&leader_stake_cdf(&[], drb), ViewNumber::new(1)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_set_first_epoch_without_genesis_state() {
+        let l1 = L1Client::new(vec!["http://localhost:3331".parse().unwrap()])
+            .expect("Failed to create L1 client");
+        let mut committees = EpochCommittees::new_stake(
+            vec![],
+            vec![],
+            l1,
+            None,
+            Arc::new(MockStateCatchup::default()),
+            NoStorage,
+        );
+        committees.state.remove(&Epoch::genesis());
+
+        // Without the genesis stake table there is nothing to copy, so the committees are left
+        // untouched instead of panicking.
+        let epoch = EpochNumber::new(5);
+        committees.set_first_epoch(epoch, [0u8; 32]);
+        assert_eq!(committees.first_epoch(), None);
+        assert!(!committees.state.contains_key(&epoch));
+        assert!(!committees.state.contains_key(&(epoch + 1)));
+        assert!(committees.randomized_committees.is_empty());
+    }
+
+    #[test]
+    fn test_address_mapping_is_consistent() {
+        let l1 = L1Client::new(vec!["http://localhost:3331".parse().unwrap()])
+            .expect("Failed to create L1 client");
+        let mut committees = EpochCommittees::new_stake(
+            vec![],
+            vec![],
+            l1,
+            None,
+            Arc::new(MockStateCatchup::default()),
+            NoStorage,
+        );
+
+        let validators: IndexMap<_, _> = (0..10)
+            .map(|_| {
+                let validator = Validator::mock();
+                (validator.account, validator)
+            })
+            .collect();
+        let epoch = EpochNumber::new(2);
+        committees.update_stake_table(epoch, validators.clone());
+
+        for validator in validators.values() {
+            assert_eq!(
+                committees
+                    .address(&epoch, validator.stake_table_key)
+                    .unwrap(),
+                validator.account
+            );
+            assert_eq!(
+                committees
+                    .bls_key_for_address(&epoch, validator.account)
+                    .unwrap(),
+                validator.stake_table_key
+            );
+        }
+
+        // Unknown keys and addresses, and unknown epochs, are errors in both directions.
+        let unknown = Validator::mock();
+        assert!(committees.address(&epoch, unknown.stake_table_key).is_err());
+        assert!(committees
+            .bls_key_for_address(&epoch, unknown.account)
+            .is_err());
+        let missing_epoch = EpochNumber::new(3);
+        let validator = validators.values().next().unwrap();
+        assert!(committees
+            .address(&missing_epoch, validator.stake_table_key)
+            .is_err());
+        assert!(committees
+            .bls_key_for_address(&missing_epoch, validator.account)
+            .is_err());
+    }
+
+    #[test]
+    fn test_validator_by_address() {
+        let l1 = L1Client::new(vec!["http://localhost:3331".parse().unwrap()])
+            .expect("Failed to create L1 client");
+        let mut committees = EpochCommittees::new_stake(
+            vec![],
+            vec![],
+            l1,
+            None,
+            Arc::new(MockStateCatchup::default()),
+            NoStorage,
+        );
+
+        let validators: IndexMap<_, _> = (0..5)
+            .map(|_| {
+                let validator = Validator::mock();
+                (validator.account, validator)
+            })
+            .collect();
+        let epoch = EpochNumber::new(2);
+        committees.update_stake_table(epoch, validators.clone());
+
+        assert_eq!(
+            committees.validator_addresses(&epoch).unwrap(),
+            validators.keys().copied().collect::<Vec<_>>()
+        );
+        for validator in validators.values() {
+            assert_eq!(
+                &committees
+                    .validator_by_address(&epoch, validator.account)
+                    .unwrap(),
+                validator
+            );
+        }
+
+        let unknown = Validator::mock();
+        let err = committees
+            .validator_by_address(&epoch, unknown.account)
+            .unwrap_err();
+        assert!(
+            err.to_string().contains(&format!("{:#x}", unknown.account)),
+            "unexpected error: {err}"
+        );
+        let missing_epoch = EpochNumber::new(3);
+        assert!(committees.validator_addresses(&missing_epoch).is_err());
+        assert!(committees
+            .validator_by_address(&missing_epoch, validators[0].account)
+            .is_err());
+    }
+
     #[test]
     fn test_validators_selection() {
         let mut validators = IndexMap::new();
@@ -1110,9 +2913,14 @@ mod tests {
             }
         }
 
-        let minimum_stake = highest_stake / U256::from(VID_TARGET_TOTAL_STAKE);
+        let minimum_stake = highest_stake / U256::from(DEFAULT_MIN_STAKE_DIVISOR);
 
-        select_validators(&mut validators).expect("Failed to select validators");
+        select_validators(
+            &mut validators,
+            MinStakePolicy::default(),
+            DEFAULT_MAX_VALIDATORS,
+        )
+        .expect("Failed to select validators");
         assert!(
             validators.len() <= 100,
             "validators len is {}, expected at most 100",
@@ -1134,4 +2942,269 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_validators_selection_min_stake_policy() {
+        // Total stake is 1611.
+        let stakes = [1000u64, 500, 100, 10, 1];
+        let mut validators = IndexMap::new();
+        let mut addresses = Vec::new();
+        for stake in stakes {
+            let mut validator = Validator::mock();
+            validator.stake = U256::from(stake);
+            addresses.push(validator.account);
+            validators.insert(validator.account, validator);
+        }
+
+        let select = |policy: MinStakePolicy| {
+            let mut validators = validators.clone();
+            select_validators(&mut validators, policy, DEFAULT_MAX_VALIDATORS)
+                .expect("Failed to select validators");
+            validators.keys().copied().collect::<HashSet<_>>()
+        };
+        let expected = |n: usize| addresses[..n].iter().copied().collect::<HashSet<_>>();
+
+        // The default divides the maximum stake by `DEFAULT_MIN_STAKE_DIVISOR`, so every validator
+        // with at least 1 stake is selected.
+        assert_eq!(select(MinStakePolicy::default()), expected(5));
+        assert_eq!(select(MinStakePolicy::FractionOfMax(100)), expected(4));
+        assert_eq!(select(MinStakePolicy::FractionOfTotal(10)), expected(2));
+        assert_eq!(
+            select(MinStakePolicy::Absolute(U256::from(100))),
+            expected(3)
+        );
+        assert_eq!(
+            select(MinStakePolicy::Absolute(U256::from(1001))),
+            expected(0)
+        );
+
+        // A zero divisor is rejected rather than dividing by zero.
+        for policy in [
+            MinStakePolicy::FractionOfMax(0),
+            MinStakePolicy::FractionOfTotal(0),
+        ] {
+            let mut validators = validators.clone();
+            assert!(select_validators(&mut validators, policy, DEFAULT_MAX_VALIDATORS).is_err());
+        }
+    }
+
+    #[test]
+    fn test_validators_selection_min_stake_rounds_to_zero() {
+        // The maximum stake is below the divisor, so the minimum stake rounds down to zero.
+        let stakes = [999u64, 1, 0];
+        let validators: IndexMap<_, _> = stakes
+            .into_iter()
+            .map(|stake| {
+                let mut validator = Validator::mock();
+                validator.stake = U256::from(stake);
+                (validator.account, validator)
+            })
+            .collect();
+        let addresses: Vec<_> = validators.keys().copied().collect();
+        assert!(MinStakePolicy::default()
+            .minimum_stake(&validators)
+            .unwrap()
+            .is_zero());
+
+        // Every validator with some stake qualifies, but not the one without stake.
+        let mut selected = validators;
+        select_validators(
+            &mut selected,
+            MinStakePolicy::default(),
+            DEFAULT_MAX_VALIDATORS,
+        )
+        .unwrap();
+        assert_eq!(selected.keys().copied().collect::<Vec<_>>(), addresses[..2]);
+    }
+
+    #[test]
+    fn test_validators_selection_max_validators() {
+        let stakes = [1000u64, 500, 500, 500, 10];
+        let validators: IndexMap<_, _> = stakes
+            .into_iter()
+            .map(|stake| {
+                let mut validator = Validator::mock();
+                validator.stake = U256::from(stake);
+                (validator.account, validator)
+            })
+            .collect();
+        let addresses: Vec<_> = validators.keys().copied().collect();
+
+        let select = |validators: &IndexMap<Address, Validator<BLSPubKey>>, max_validators| {
+            let mut validators = validators.clone();
+            select_validators(&mut validators, MinStakePolicy::default(), max_validators)
+                .expect("Failed to select validators");
+            validators.keys().copied().collect::<HashSet<_>>()
+        };
+
+        assert_eq!(select(&validators, 1), HashSet::from([addresses[0]]));
+        assert_eq!(select(&validators, 5).len(), 5);
+        assert_eq!(select(&validators, 10).len(), 5);
+
+        // Ties are broken by address, independently of the order of the validators.
+        let mut tied = addresses[1..4].to_vec();
+        tied.sort();
+        let expected = HashSet::from([addresses[0], tied[0], tied[1]]);
+        assert_eq!(select(&validators, 3), expected);
+        let mut reversed = validators.clone();
+        reversed.reverse();
+        assert_eq!(select(&reversed, 3), expected);
+
+        // The cap must be positive.
+        let mut validators = validators;
+        assert!(select_validators(&mut validators, MinStakePolicy::default(), 0).is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_selection_evidence() {
+        let l1 = L1Client::new(vec!["http://localhost:3331".parse().unwrap()])
+            .expect("Failed to create L1 client");
+        let mut committees = EpochCommittees::new_stake(
+            vec![],
+            vec![],
+            l1,
+            None,
+            Arc::new(MockStateCatchup::default()),
+            NoStorage,
+        )
+        .with_min_stake_policy(MinStakePolicy::FractionOfMax(100));
+
+        // With a minimum of 1% of the maximum stake, the last validator is not selected.
+        let stakes = [1000u64, 500, 100, 10, 9];
+        let mut candidates = IndexMap::new();
+        let mut addresses = Vec::new();
+        for stake in stakes {
+            let mut validator = Validator::mock();
+            validator.stake = U256::from(stake);
+            addresses.push(validator.account);
+            candidates.insert(validator.account, validator);
+        }
+        let included = addresses[1];
+        let excluded = addresses[4];
+
+        let mut selected = candidates.clone();
+        select_validators(
+            &mut selected,
+            MinStakePolicy::FractionOfMax(100),
+            DEFAULT_MAX_VALIDATORS,
+        )
+        .expect("Failed to select validators");
+        assert!(selected.contains_key(&included));
+        assert!(!selected.contains_key(&excluded));
+
+        let epoch = EpochNumber::new(2);
+        committees.update_stake_table(epoch, selected.clone());
+        committees.record_selection(epoch, 10, Some(candidates.clone()));
+
+        let evidence = committees
+            .selection_evidence(&epoch, included)
+            .await
+            .unwrap();
+        assert_eq!(
+            evidence,
+            SelectionEvidence {
+                epoch,
+                address: included,
+                minimum_stake: U256::from(10),
+                stake: Some(U256::from(500)),
+                rank: Some(2),
+                cutoff_rank: DEFAULT_MAX_VALIDATORS,
+                selected: true,
+            }
+        );
+
+        // The excluded validator is ranked among every candidate, which explains the exclusion.
+        let evidence = committees
+            .selection_evidence(&epoch, excluded)
+            .await
+            .unwrap();
+        assert_eq!(
+            evidence,
+            SelectionEvidence {
+                epoch,
+                address: excluded,
+                minimum_stake: U256::from(10),
+                stake: Some(U256::from(9)),
+                rank: Some(5),
+                cutoff_rank: DEFAULT_MAX_VALIDATORS,
+                selected: false,
+            }
+        );
+
+        // The evidence matches the actual selection for every candidate.
+        for address in addresses {
+            let evidence = committees
+                .selection_evidence(&epoch, address)
+                .await
+                .unwrap();
+            assert_eq!(
+                evidence.selected,
+                committees
+                    .validators(&epoch)
+                    .unwrap()
+                    .contains_key(&address)
+            );
+        }
+
+        // Without the candidates, they are recomputed from L1, which requires knowing the L1 block
+        // and the stake table contract.
+        let other_epoch = EpochNumber::new(3);
+        committees.update_stake_table(other_epoch, selected);
+        assert!(committees
+            .selection_evidence(&other_epoch, included)
+            .await
+            .is_err());
+
+        assert!(committees
+            .selection_evidence(&EpochNumber::new(4), included)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_vid_dispersal_covers_committee() {
+        let l1 = L1Client::new(vec!["http://localhost:3331".parse().unwrap()])
+            .expect("Failed to create L1 client");
+        let mut committees = EpochCommittees::new_stake(
+            vec![],
+            vec![],
+            l1,
+            None,
+            Arc::new(MockStateCatchup::default()),
+            NoStorage,
+        );
+        let validators: IndexMap<_, _> = (0..4)
+            .map(|_| {
+                let validator = Validator::mock();
+                (validator.account, validator)
+            })
+            .collect();
+        let epoch = EpochNumber::new(2);
+        committees.update_stake_table(epoch, validators);
+
+        let stake_table = committees.stake_table(Some(epoch));
+        let param = init_avidm_param(vid_total_weight(stake_table.clone(), Some(epoch))).unwrap();
+        let weights = vid_weights(stake_table.clone());
+        let payload = vec![7u8; 100];
+        let (_, shares) =
+            AvidMScheme::ns_disperse(&param, &weights, &payload, [0..payload.len()]).unwrap();
+        assert_eq!(shares.len(), 4);
+
+        // A complete dispersal covers the full committee weight.
+        verify_vid_dispersal_coverage(stake_table.clone(), &param, &shares).unwrap();
+
+        // Omitting a member's share is detected.
+        let mut missing = shares.clone();
+        missing.remove(1);
+        verify_vid_dispersal_coverage(stake_table.clone(), &param, &missing).unwrap_err();
+
+        // As is counting a member twice.
+        let mut duplicated = shares.clone();
+        duplicated.push(shares[1].clone());
+        verify_vid_dispersal_coverage(stake_table.clone(), &param, &duplicated).unwrap_err();
+
+        // And a param that does not match the committee.
+        let other_param = init_avidm_param(param.total_weights + 1).unwrap();
+        verify_vid_dispersal_coverage(stake_table, &other_param, &shares).unwrap_err();
+    }
 }
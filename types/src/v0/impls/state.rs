@@ -30,7 +30,10 @@ use super::{
     auction::ExecutionError,
     fee_info::FeeError,
     instance_state::NodeState,
-    reward::{apply_rewards, catchup_missing_accounts, first_two_epochs},
+    reward::{
+        apply_rewards, catchup_missing_accounts, first_two_epochs, RemainderPolicy,
+        REWARD_CARRY_ACCOUNT,
+    },
     v0_1::{
         RewardAccount, RewardAmount, RewardMerkleCommitment, RewardMerkleTree,
         REWARD_MERKLE_TREE_HEIGHT,
@@ -238,6 +241,28 @@ impl ValidatedState {
             .collect()
     }
 
+    /// Check that the commitment of [`Self::reward_merkle_tree`] matches the
+    /// reward root recorded in `header`.
+    ///
+    /// Headers from versions without a reward tree have no reward root, and
+    /// always match.
+    pub fn verify_reward_root_matches(
+        &self,
+        header: &Header,
+    ) -> Result<(), ProposalValidationError> {
+        let reward_merkle_tree_root = self.reward_merkle_tree.commitment();
+        if let Some(root) = header.reward_merkle_tree_root() {
+            if root != reward_merkle_tree_root {
+                return Err(ProposalValidationError::InvalidRewardRoot {
+                    expected_root: reward_merkle_tree_root,
+                    proposal_root: root,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     /// Check if the merkle tree is available
     pub fn need_to_fetch_blocks_mt_frontier(&self) -> bool {
         let num_leaves = self.block_merkle_tree.num_leaves();
@@ -301,8 +326,15 @@ impl ValidatedState {
         &mut self,
         delta: &mut Delta,
         validator: Validator<BLSPubKey>,
+        block_reward: RewardAmount,
+        policy: RemainderPolicy,
     ) -> anyhow::Result<()> {
-        let reward_state = apply_rewards(self.reward_merkle_tree.clone(), validator.clone())?;
+        let reward_state = apply_rewards(
+            self.reward_merkle_tree.clone(),
+            validator.clone(),
+            block_reward,
+            policy,
+        )?;
         self.reward_merkle_tree = reward_state;
 
         // Update delta rewards
@@ -315,6 +347,9 @@ impl ValidatedState {
                 .keys()
                 .map(|d| RewardAccount(d.to_ethers())),
         );
+        if policy == RemainderPolicy::CarryForward {
+            delta.rewards_delta.insert(REWARD_CARRY_ACCOUNT);
+        }
 
         Ok(())
     }
@@ -655,17 +690,7 @@ impl<'a> ValidatedTransition<'a> {
     /// Validate [`RewardMerkleTree`] by comparing proposed commitment
     /// against that stored in [`ValidatedState`].
     fn validate_reward_merkle_tree(&self) -> Result<(), ProposalValidationError> {
-        let reward_merkle_tree_root = self.state.reward_merkle_tree.commitment();
-        if let Some(root) = self.proposal.header.reward_merkle_tree_root() {
-            if root != reward_merkle_tree_root {
-                return Err(ProposalValidationError::InvalidRewardRoot {
-                    expected_root: reward_merkle_tree_root,
-                    proposal_root: root,
-                });
-            }
-        }
-
-        Ok(())
+        self.state.verify_reward_root_matches(self.proposal.header)
     }
 
     /// Validate [`FeeMerkleTree`] by comparing proposed commitment
@@ -895,7 +920,12 @@ impl ValidatedState {
             // apply rewards
 
             validated_state
-                .distribute_rewards(&mut delta, validator)
+                .distribute_rewards(
+                    &mut delta,
+                    validator,
+                    instance.block_reward,
+                    instance.reward_remainder_policy,
+                )
                 .context("failed to distribute rewards")?
         }
 
@@ -1715,6 +1745,54 @@ mod test {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_verify_reward_root_matches() {
+        initialize_logging();
+        // Setup.
+        let instance = NodeState::mock_v3();
+        let (payload, metadata) = Payload::from_transactions(
+            Vec::<Transaction>::new(),
+            &instance.genesis_state,
+            &instance,
+        )
+        .await
+        .unwrap();
+        let payload_commitment = vid_commitment::<MockVersions>(
+            &payload.encode(),
+            &metadata.encode(),
+            1,
+            <MockVersions as Versions>::Base::VERSION,
+        );
+        let header = Header::genesis(
+            &instance,
+            payload_commitment,
+            payload.builder_commitment(&metadata),
+            metadata,
+        );
+        assert!(header.reward_merkle_tree_root().is_some());
+
+        // Success case.
+        let state = instance.genesis_state.clone();
+        state.verify_reward_root_matches(&header).unwrap();
+
+        // Error case.
+        let mut diverged = state.clone();
+        diverged
+            .reward_merkle_tree
+            .update(RewardAccount::default(), RewardAmount::from(100))
+            .unwrap();
+
+        let err = diverged.verify_reward_root_matches(&header).unwrap_err();
+        tracing::info!(%err, "task failed successfully");
+        assert_eq!(
+            ProposalValidationError::InvalidRewardRoot {
+                expected_root: diverged.reward_merkle_tree.commitment(),
+                proposal_root: state.reward_merkle_tree.commitment(),
+            },
+            err
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_validation_block_root() {
         initialize_logging();
@@ -20,8 +20,12 @@ pub use header::Header;
 #[cfg(any(test, feature = "testing"))]
 pub use impls::mock;
 pub use impls::{
-    get_l1_deposits, retain_accounts, BuilderValidationError, EpochCommittees, FeeError,
-    ProposalValidationError, StateValidationError,
+    epochs_until_rewards, get_l1_deposits, retain_accounts, reward_history, rewards_start_epoch,
+    validators_from_l1_events, verify_block_rewards, AuditResult, BuilderValidationError,
+    EpochCommittees, FeeError, MinStakePolicy, NsProofVerifierCache, ProposalValidationError,
+    RemainderPolicy, RewardConfig, RewardDistribution, RewardMerkleTreeCheckpoints,
+    SelectionEvidence, StakeTableEvent, StakeTableEventMode, StateValidationError,
+    REWARD_CARRY_ACCOUNT,
 };
 pub use nsproof::NsProof;
 pub use utils::*;
@@ -11,7 +11,7 @@
 //! vectors. And for dispersal, each storage node gets some vectors and their
 //! Merkle proofs according to its weight.
 
-use std::{collections::HashMap, iter, ops::Range};
+use std::{collections::HashMap, io::Write, iter, ops::Range};
 
 use ark_ff::PrimeField;
 use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
@@ -43,6 +43,14 @@ type Config = config::Sha256Config;
 #[cfg(feature = "keccak256")]
 type Config = config::Keccak256Config;
 
+/// Number of polynomials interpolated at a time by
+/// [`AvidMScheme::recover_to_writer`].
+const RECOVERY_BATCH_POLYS: usize = 256;
+
+/// Zero bytes held back by [`AvidMScheme::recover_to_writer`] are written
+/// from this buffer.
+const ZEROS: [u8; 1024] = [0; 1024];
+
 // Type alias for convenience
 type F = <Config as AvidMConfig>::BaseField;
 type MerkleTree = <Config as AvidMConfig>::MerkleTree;
@@ -112,6 +120,18 @@ pub struct AvidMShare {
     content: RawAvidMShare,
 }
 
+impl AvidMShare {
+    /// The weight of this share, i.e. the number of raw shares it contains.
+    pub fn weight(&self) -> usize {
+        self.content.range.len()
+    }
+}
+
+/// Total weight of the given shares.
+pub fn total_weight(shares: &[AvidMShare]) -> usize {
+    shares.iter().map(AvidMShare::weight).sum()
+}
+
 /// Public parameters of the AVID-M scheme.
 #[derive(Clone, Debug, Hash, Serialize, Deserialize, PartialEq, Eq)]
 pub struct AvidMParam {
@@ -132,6 +152,64 @@ impl AvidMParam {
             recovery_threshold,
         })
     }
+
+    /// Compute the range of raw shares assigned to each storage node under the
+    /// weight distribution `distribution`.
+    ///
+    /// Storage node `i` gets `distribution[i]` consecutive raw shares, so the
+    /// returned ranges tile `0..total_weights`. Returns an error if the
+    /// distribution is empty, if a weight is zero or if the weights do not sum
+    /// up to `total_weights`.
+    pub fn share_ranges(&self, distribution: &[u32]) -> VidResult<Vec<Range<usize>>> {
+        if distribution.is_empty() {
+            return Err(VidError::Argument("empty distribution".to_string()));
+        }
+        let total_weights = distribution.iter().map(|&w| w as usize).sum::<usize>();
+        if total_weights != self.total_weights {
+            return Err(VidError::Argument(
+                "Weight distribution is inconsistent with the given param".to_string(),
+            ));
+        }
+        if distribution.iter().any(|&w| w == 0) {
+            return Err(VidError::Argument("Weight cannot be zero".to_string()));
+        }
+        Ok(distribution
+            .iter()
+            .scan(0usize, |sum, &w| {
+                let prefix_sum = *sum;
+                *sum += w as usize;
+                Some(prefix_sum..*sum)
+            })
+            .collect())
+    }
+
+    /// Check that the given ranges of raw shares tile `0..total_weights`
+    /// exactly, in any order.
+    ///
+    /// This is the case for the shares of a complete dispersal. Returns an
+    /// error if a range is missing, overlaps another one or lies beyond
+    /// `total_weights`.
+    pub fn verify_coverage(&self, ranges: impl IntoIterator<Item = Range<usize>>) -> VidResult<()> {
+        let mut ranges: Vec<_> = ranges.into_iter().collect();
+        ranges.sort_by_key(|range| range.start);
+
+        let mut covered = 0usize;
+        for range in ranges {
+            if range.start != covered || range.is_empty() {
+                return Err(VidError::Argument(format!(
+                    "share range {range:?} does not continue from {covered}"
+                )));
+            }
+            covered = range.end;
+        }
+        if covered != self.total_weights {
+            return Err(VidError::Argument(format!(
+                "shares cover {covered} out of {} weights",
+                self.total_weights
+            )));
+        }
+        Ok(())
+    }
 }
 
 /// Helper: initialize a FFT domain
@@ -148,6 +226,238 @@ impl AvidMScheme {
     pub fn setup(recovery_threshold: usize, total_weights: usize) -> VidResult<AvidMParam> {
         AvidMParam::new(recovery_threshold, total_weights)
     }
+
+    /// Check that the weight distribution `distribution` is consistent with
+    /// `param`, without doing any encoding work.
+    ///
+    /// Returns an error naming the offending storage node if a weight is zero,
+    /// or, if `reject_single_node_recovery` is set, if a single node holds
+    /// enough weight to recover the payload on its own. Also returns an error if
+    /// the distribution is empty or does not sum up to `param.total_weights`.
+    pub fn validate_distribution(
+        param: &AvidMParam,
+        distribution: &[u32],
+        reject_single_node_recovery: bool,
+    ) -> VidResult<()> {
+        if distribution.is_empty() {
+            return Err(VidError::Argument("empty distribution".to_string()));
+        }
+        for (i, &w) in distribution.iter().enumerate() {
+            if w == 0 {
+                return Err(VidError::Argument(format!(
+                    "Weight of storage node {i} cannot be zero"
+                )));
+            }
+            if reject_single_node_recovery && w as usize >= param.recovery_threshold {
+                return Err(VidError::Argument(format!(
+                    "Storage node {i} has weight {w}, enough to recover the payload on its own"
+                )));
+            }
+        }
+        let total_weights = distribution.iter().map(|&w| w as usize).sum::<usize>();
+        if total_weights != param.total_weights {
+            return Err(VidError::Argument(format!(
+                "Weight distribution sums up to {total_weights}, but the param expects {}",
+                param.total_weights
+            )));
+        }
+        Ok(())
+    }
+
+    /// Verify a share against the weight distribution it was dispersed with.
+    ///
+    /// On top of [`VidScheme::verify_share`], this checks that the share holds
+    /// exactly the raw shares assigned to its index by `distribution`. Without
+    /// the distribution, the index of a share cannot be checked: a share whose
+    /// index was swapped with that of another node still passes
+    /// [`VidScheme::verify_share`].
+    pub fn verify_share_for_distribution(
+        param: &AvidMParam,
+        distribution: &[u32],
+        commit: &AvidMCommit,
+        share: &AvidMShare,
+    ) -> VidResult<crate::VerificationResult> {
+        let ranges = param.share_ranges(distribution)?;
+        let range = ranges
+            .get(share.index as usize)
+            .ok_or(VidError::IndexOutOfBound)?;
+        Self::verify_internal(param, commit, &share.content, Some(range))
+    }
+
+    /// Same as [`VidScheme::disperse`], but reads the payload as a sequence of
+    /// byte slices, and encodes it `window_chunks` chunks of
+    /// `recovery_threshold` field elements at a time.
+    ///
+    /// Only one window of the payload is held as field elements at any time, so
+    /// apart from the shares themselves, peak memory depends on the window size
+    /// rather than on the payload size. The commitment and shares are identical
+    /// to those of [`VidScheme::disperse`] for the same payload.
+    pub fn disperse_streaming<B: AsRef<[u8]>>(
+        param: &AvidMParam,
+        distribution: &[u32],
+        payload: impl IntoIterator<Item = B>,
+        window_chunks: usize,
+    ) -> VidResult<(AvidMCommit, Vec<AvidMShare>)> {
+        if window_chunks == 0 {
+            return Err(VidError::Argument(
+                "window must hold at least one chunk".to_string(),
+            ));
+        }
+        let ranges = param.share_ranges(distribution)?;
+        let domain = radix2_domain::<F>(param.total_weights)?;
+        let window_byte_len =
+            window_chunks * param.recovery_threshold * bytes_to_field::elem_byte_capacity::<F>();
+
+        let mut raw_shares = vec![vec![]; param.total_weights];
+        let mut encode_window = |window: &[F]| {
+            for (raw_share, column) in raw_shares
+                .iter_mut()
+                .zip(Self::encode_chunks(param, &domain, window))
+            {
+                raw_share.extend(column);
+            }
+        };
+
+        // Full windows never need padding, since they hold a whole number of
+        // chunks. Only the last, partial window does.
+        let mut window = Vec::with_capacity(window_byte_len);
+        let mut payload_byte_len = 0;
+        for bytes in payload {
+            let mut bytes = bytes.as_ref();
+            payload_byte_len += bytes.len();
+            while !bytes.is_empty() {
+                let len = (window_byte_len - window.len()).min(bytes.len());
+                window.extend_from_slice(&bytes[..len]);
+                bytes = &bytes[len..];
+                if window.len() == window_byte_len {
+                    encode_window(&bytes_to_field(&window).collect::<Vec<F>>());
+                    window.clear();
+                }
+            }
+        }
+        encode_window(&Self::pad_to_fields(param, &window));
+
+        let mt = Self::merklize(&raw_shares)?;
+        Ok(Self::distribute_shares_to_ranges(
+            &ranges,
+            mt,
+            raw_shares,
+            payload_byte_len,
+        ))
+    }
+
+    /// Same as [`VidScheme::recover`], but writes the payload to `writer` as it
+    /// is recovered, instead of returning it all at once.
+    ///
+    /// The padding at the end of the payload can only be told apart from the
+    /// payload once it has been fully recovered, so the trailing zero bytes
+    /// and the last non-zero byte recovered so far are held back until then.
+    /// If the payload turns out to be malformed, or writing fails, whatever
+    /// was written to `writer` until then should be discarded.
+    pub fn recover_to_writer<W: Write>(
+        param: &AvidMParam,
+        _commit: &AvidMCommit,
+        shares: &[AvidMShare],
+        mut writer: W,
+    ) -> VidResult<()> {
+        let write = |writer: &mut W, bytes: &[u8]| {
+            writer
+                .write_all(bytes)
+                .map_err(|err| VidError::Internal(err.into()))
+        };
+
+        // The last non-zero byte recovered so far, and the number of zero bytes
+        // recovered after it.
+        let mut last_non_zero = None;
+        let mut trailing_zeros = 0usize;
+        Self::recover_fields_in_batches(param, shares, RECOVERY_BATCH_POLYS, |fields| {
+            let bytes: Vec<u8> = field_to_bytes(fields).collect();
+            let Some(pos) = bytes.iter().rposition(|&b| b != 0) else {
+                trailing_zeros += bytes.len();
+                return Ok(());
+            };
+            // The bytes held back are not the padding after all.
+            if let Some(byte) = last_non_zero {
+                write(&mut writer, &[byte])?;
+            }
+            for _ in 0..trailing_zeros / ZEROS.len() {
+                write(&mut writer, &ZEROS)?;
+            }
+            write(&mut writer, &ZEROS[..trailing_zeros % ZEROS.len()])?;
+            write(&mut writer, &bytes[..pos])?;
+            last_non_zero = Some(bytes[pos]);
+            trailing_zeros = bytes.len() - pos - 1;
+            Ok(())
+        })?;
+
+        // Remove the trimming zeros and the last 1 to get the actual payload
+        // bytes. See `pad_to_fields`.
+        if last_non_zero != Some(1u8) {
+            return Err(VidError::Argument(
+                "Malformed payload, cannot find the padding position".to_string(),
+            ));
+        }
+        writer.flush().map_err(|err| VidError::Internal(err.into()))
+    }
+
+    /// Verify a batch of shares against the same commitment, in parallel.
+    ///
+    /// Each share is checked like in [`VidScheme::verify_share`]. Returns
+    /// `Ok(Err(index))` with the index in `shares` of an invalid share, as soon
+    /// as one is found. If several shares are invalid, which of them is
+    /// reported is unspecified.
+    pub fn verify_shares(
+        param: &AvidMParam,
+        commit: &AvidMCommit,
+        shares: &[AvidMShare],
+    ) -> VidResult<Result<(), usize>> {
+        let result =
+            (0..shares.len()).into_par_iter().try_for_each(|i| {
+                match Self::verify_internal(param, commit, &shares[i].content, None) {
+                    Ok(Ok(())) => Ok(()),
+                    Ok(Err(())) => Err(Ok(i)),
+                    Err(err) => Err(Err(err)),
+                }
+            });
+        match result {
+            Ok(()) => Ok(Ok(())),
+            Err(Ok(i)) => Ok(Err(i)),
+            Err(Err(err)) => Err(err),
+        }
+    }
+
+    /// Check that `commit` is the commitment to the given `payload` without
+    /// generating any shares. Returns false if the commitment cannot be computed
+    /// under `param`.
+    pub fn verify_commitment(param: &AvidMParam, commit: &AvidMCommit, payload: &[u8]) -> bool {
+        Self::commit(param, payload).is_ok_and(|expected| &expected == commit)
+    }
+
+    /// Compute the number of shares that is always enough to recover the
+    /// payload under the weight distribution `distribution`.
+    ///
+    /// This is the worst case, where the lightest shares are collected first,
+    /// until their combined weight reaches `param.recovery_threshold`. Returns
+    /// an error if `distribution` is inconsistent with `param`, see
+    /// [`AvidMParam::share_ranges`].
+    pub fn min_shares_for_recovery(param: &AvidMParam, distribution: &[u32]) -> VidResult<usize> {
+        // Validates the distribution against `param`.
+        param.share_ranges(distribution)?;
+
+        let mut weights = distribution.to_vec();
+        weights.sort_unstable();
+
+        let mut collected_weight = 0usize;
+        let mut num_shares = 0usize;
+        for w in weights {
+            if collected_weight >= param.recovery_threshold {
+                break;
+            }
+            collected_weight += w as usize;
+            num_shares += 1;
+        }
+        Ok(num_shares)
+    }
 }
 
 impl AvidMScheme {
@@ -187,9 +497,33 @@ impl AvidMScheme {
     #[inline]
     fn raw_encode(param: &AvidMParam, payload: &[F]) -> VidResult<(MerkleTree, Vec<Vec<F>>)> {
         let domain = radix2_domain::<F>(param.total_weights)?; // See docs at `domains`.
+        Self::raw_encode_with_domain(param, &domain, payload)
+    }
 
+    /// Same as `raw_encode`, but with an FFT domain that has already been set
+    /// up for `param`, so that it can be shared across several payloads.
+    #[allow(clippy::type_complexity)]
+    fn raw_encode_with_domain(
+        param: &AvidMParam,
+        domain: &Radix2EvaluationDomain<F>,
+        payload: &[F],
+    ) -> VidResult<(MerkleTree, Vec<Vec<F>>)> {
         let encoding_timer = start_timer!(|| "Encoding payload");
+        let raw_shares = Self::encode_chunks(param, domain, payload);
+        end_timer!(encoding_timer);
+
+        let mt = Self::merklize(&raw_shares)?;
+        Ok((mt, raw_shares))
+    }
 
+    /// RS-encode each chunk of `recovery_threshold` field elements of `payload`,
+    /// and return the `total_weights` raw shares, each collecting one field
+    /// element from each encoded chunk.
+    fn encode_chunks(
+        param: &AvidMParam,
+        domain: &Radix2EvaluationDomain<F>,
+        payload: &[F],
+    ) -> Vec<Vec<F>> {
         // RS-encode each chunk
         let codewords: Vec<_> = payload
             .par_chunks(param.recovery_threshold)
@@ -201,12 +535,14 @@ impl AvidMScheme {
             .collect();
         // Generate `total_weights` raw shares. Each share collects one field element
         // from each encode chunk.
-        let raw_shares: Vec<_> = (0..param.total_weights)
+        (0..param.total_weights)
             .into_par_iter()
             .map(|i| codewords.iter().map(|v| v[i]).collect::<Vec<F>>())
-            .collect();
-        end_timer!(encoding_timer);
+            .collect()
+    }
 
+    /// Commit to the raw shares with a Merkle tree over their digests.
+    fn merklize(raw_shares: &[Vec<F>]) -> VidResult<MerkleTree> {
         let hash_timer = start_timer!(|| "Compressing each raw share");
         let compressed_raw_shares = raw_shares
             .par_iter()
@@ -218,7 +554,7 @@ impl AvidMScheme {
         let mt = MerkleTree::from_elems(None, &compressed_raw_shares)?;
         end_timer!(mt_timer);
 
-        Ok((mt, raw_shares))
+        Ok(mt)
     }
 
     /// Short hand for `pad_to_field` and `raw_encode`.
@@ -235,29 +571,28 @@ impl AvidMScheme {
         raw_shares: Vec<Vec<F>>,
         payload_byte_len: usize,
     ) -> VidResult<(AvidMCommit, Vec<AvidMShare>)> {
-        // let payload_byte_len = payload.len();
-        let total_weights = distribution.iter().sum::<u32>() as usize;
-        if total_weights != param.total_weights {
-            return Err(VidError::Argument(
-                "Weight distribution is inconsistent with the given param".to_string(),
-            ));
-        }
-        if distribution.iter().any(|&w| w == 0) {
-            return Err(VidError::Argument("Weight cannot be zero".to_string()));
-        }
+        let ranges = param.share_ranges(distribution)?;
+        Ok(Self::distribute_shares_to_ranges(
+            &ranges,
+            mt,
+            raw_shares,
+            payload_byte_len,
+        ))
+    }
 
+    /// Same as `distribute_shares`, but with the share ranges of the weight
+    /// distribution already computed, so that they can be shared across several
+    /// payloads.
+    fn distribute_shares_to_ranges(
+        ranges: &[Range<usize>],
+        mt: MerkleTree,
+        raw_shares: Vec<Vec<F>>,
+        payload_byte_len: usize,
+    ) -> (AvidMCommit, Vec<AvidMShare>) {
         let distribute_timer = start_timer!(|| "Distribute codewords to the storage nodes");
         // Distribute the raw shares to each storage node according to the weight
         // distribution. For each chunk, storage `i` gets `distribution[i]`
         // consecutive raw shares ranging as `ranges[i]`.
-        let ranges: Vec<_> = distribution
-            .iter()
-            .scan(0, |sum, w| {
-                let prefix_sum = *sum;
-                *sum += w;
-                Some(prefix_sum as usize..*sum as usize)
-            })
-            .collect();
         let shares: Vec<_> = ranges
             .par_iter()
             .map(|range| {
@@ -297,17 +632,23 @@ impl AvidMScheme {
             commit: mt.commitment(),
         };
 
-        Ok((commit, shares))
+        (commit, shares)
     }
 
+    /// Verify the raw shares of `share` against `commit`, and, if given, that
+    /// they are exactly the raw shares in `expected_range`.
     pub(crate) fn verify_internal(
         param: &AvidMParam,
         commit: &AvidMCommit,
         share: &RawAvidMShare,
+        expected_range: Option<&Range<usize>>,
     ) -> VidResult<crate::VerificationResult> {
         if share.range.end > param.total_weights || share.range.len() != share.payload.len() {
             return Err(VidError::InvalidShare);
         }
+        if expected_range.is_some_and(|range| *range != share.range) {
+            return Ok(Err(()));
+        }
         for (i, index) in share.range.clone().enumerate() {
             let compressed_payload = Config::raw_share_digest(&share.payload[i])?;
             if MerkleTree::verify(
@@ -325,6 +666,23 @@ impl AvidMScheme {
     }
 
     pub(crate) fn recover_fields(param: &AvidMParam, shares: &[AvidMShare]) -> VidResult<Vec<F>> {
+        let mut fields = vec![];
+        Self::recover_fields_in_batches(param, shares, usize::MAX, |batch| {
+            fields.extend(batch);
+            Ok(())
+        })?;
+        Ok(fields)
+    }
+
+    /// Same as `recover_fields`, but interpolates `batch_polys` polynomials at
+    /// a time, and hands the recovered field elements of each batch to `sink`
+    /// in order, so that they never have to be held in memory all at once.
+    fn recover_fields_in_batches(
+        param: &AvidMParam,
+        shares: &[AvidMShare],
+        batch_polys: usize,
+        mut sink: impl FnMut(Vec<F>) -> VidResult<()>,
+    ) -> VidResult<()> {
         let recovery_threshold: usize = param.recovery_threshold;
 
         // Each share's payload contains some evaluations from `num_polys`
@@ -374,19 +732,20 @@ impl AvidMScheme {
             .map(|(i, p)| (domain.element(i), p))
             .unzip();
         // step 2: interpolate each polynomial
-        Ok((0..num_polys)
-            .into_par_iter()
-            .map(|poly_index| {
-                jf_utils::reed_solomon_code::reed_solomon_erasure_decode(
-                    x.iter().zip(raw_shares.iter().map(|p| p[poly_index])),
-                    recovery_threshold,
-                )
-                .map_err(|err| VidError::Internal(err.into()))
-            })
-            .collect::<Result<Vec<_>, _>>()?
-            .into_iter()
-            .flatten()
-            .collect())
+        for start in (0..num_polys).step_by(batch_polys) {
+            let batch = (start..start.saturating_add(batch_polys).min(num_polys))
+                .into_par_iter()
+                .map(|poly_index| {
+                    jf_utils::reed_solomon_code::reed_solomon_erasure_decode(
+                        x.iter().zip(raw_shares.iter().map(|p| p[poly_index])),
+                        recovery_threshold,
+                    )
+                    .map_err(|err| VidError::Internal(err.into()))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            sink(batch.into_iter().flatten().collect())?;
+        }
+        Ok(())
     }
 }
 
@@ -409,6 +768,8 @@ impl VidScheme for AvidMScheme {
         distribution: &[u32],
         payload: &[u8],
     ) -> VidResult<(Self::Commit, Vec<Self::Share>)> {
+        // Fail fast on a bad distribution, before the encoding work.
+        Self::validate_distribution(param, distribution, false)?;
         let (mt, raw_shares) = Self::pad_and_encode(param, payload)?;
         Self::distribute_shares(param, distribution, mt, raw_shares, payload.len())
     }
@@ -418,7 +779,7 @@ impl VidScheme for AvidMScheme {
         commit: &Self::Commit,
         share: &Self::Share,
     ) -> VidResult<crate::VerificationResult> {
-        Self::verify_internal(param, commit, &share.content)
+        Self::verify_internal(param, commit, &share.content, None)
     }
 
     /// Recover payload data from shares.
@@ -431,21 +792,12 @@ impl VidScheme for AvidMScheme {
     /// Shares beyond `recovery_threshold` are ignored.
     fn recover(
         param: &Self::Param,
-        _commit: &Self::Commit,
+        commit: &Self::Commit,
         shares: &[Self::Share],
     ) -> VidResult<Vec<u8>> {
-        let mut bytes: Vec<u8> = field_to_bytes(Self::recover_fields(param, shares)?).collect();
-        // Remove the trimming zeros and the last 1 to get the actual payload bytes.
-        // See `pad_to_fields`.
-        if let Some(pad_index) = bytes.iter().rposition(|&b| b != 0) {
-            if bytes[pad_index] == 1u8 {
-                bytes.truncate(pad_index);
-                return Ok(bytes);
-            }
-        }
-        Err(VidError::Argument(
-            "Malformed payload, cannot find the padding position".to_string(),
-        ))
+        let mut bytes = vec![];
+        Self::recover_to_writer(param, commit, shares, &mut bytes)?;
+        Ok(bytes)
     }
 }
 
@@ -455,7 +807,11 @@ pub mod tests {
     use rand::{seq::SliceRandom, RngCore};
 
     use super::F;
-    use crate::{avid_m::AvidMScheme, utils::bytes_to_field, VidScheme};
+    use crate::{
+        avid_m::{total_weight, AvidMParam, AvidMScheme},
+        utils::bytes_to_field,
+        VidError, VidScheme,
+    };
 
     #[test]
     fn test_padding() {
@@ -471,6 +827,40 @@ pub mod tests {
         assert_eq!(padded.len(), 4usize);
     }
 
+    #[test]
+    fn min_shares_for_recovery() {
+        // One heavy node, and many light ones.
+        let distribution = [1, 1, 1, 1, 1, 10];
+        let param = AvidMScheme::setup(6, 15).unwrap();
+        // All five light shares only weigh 5, so the heavy one is needed too.
+        assert_eq!(
+            AvidMScheme::min_shares_for_recovery(&param, &distribution).unwrap(),
+            6
+        );
+
+        let param = AvidMScheme::setup(5, 15).unwrap();
+        assert_eq!(
+            AvidMScheme::min_shares_for_recovery(&param, &distribution).unwrap(),
+            5
+        );
+
+        let param = AvidMScheme::setup(1, 15).unwrap();
+        assert_eq!(
+            AvidMScheme::min_shares_for_recovery(&param, &distribution).unwrap(),
+            1
+        );
+
+        // The order of the distribution does not matter.
+        let param = AvidMScheme::setup(3, 15).unwrap();
+        assert_eq!(
+            AvidMScheme::min_shares_for_recovery(&param, &[10, 1, 1, 1, 1, 1]).unwrap(),
+            3
+        );
+
+        // The distribution has to be consistent with the param.
+        assert!(AvidMScheme::min_shares_for_recovery(&param, &[1, 1]).is_err());
+    }
+
     #[test]
     fn round_trip() {
         // play with these items
@@ -518,7 +908,7 @@ pub mod tests {
                 let mut cumulated_weights = 0;
                 let mut cut_index = 0;
                 while cumulated_weights <= recovery_threshold {
-                    cumulated_weights += shares[cut_index].content.range.len();
+                    cumulated_weights += shares[cut_index].weight();
                     cut_index += 1;
                 }
                 let payload_recovered =
@@ -528,6 +918,250 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_total_weight() {
+        let mut rng = jf_utils::test_rng();
+        let weights: Vec<u32> = (0..10).map(|_| rng.next_u32() % 5 + 1).collect();
+        let total_weights: u32 = weights.iter().sum();
+        let params = AvidMScheme::setup(3usize, total_weights as usize).unwrap();
+
+        let mut payload = vec![0u8; 100];
+        rng.fill_bytes(&mut payload);
+        let (_, shares) = AvidMScheme::disperse(&params, &weights, &payload).unwrap();
+
+        for (share, weight) in shares.iter().zip(&weights) {
+            assert_eq!(share.weight(), *weight as usize);
+        }
+        assert_eq!(total_weight(&shares), params.total_weights);
+        assert_eq!(total_weight(&[]), 0);
+    }
+
+    #[test]
+    fn test_empty_distribution() {
+        let mut rng = jf_utils::test_rng();
+        let params = AvidMScheme::setup(3usize, 10usize).unwrap();
+
+        let mut payload = vec![0u8; 100];
+        rng.fill_bytes(&mut payload);
+        assert!(matches!(
+            AvidMScheme::disperse(&params, &[], &payload),
+            Err(VidError::Argument(msg)) if msg == "empty distribution"
+        ));
+    }
+
+    #[test]
+    fn test_share_ranges() {
+        let distribution = [3u32, 1, 4, 2];
+        let param = AvidMParam::new(3, 10).unwrap();
+        let ranges = param.share_ranges(&distribution).unwrap();
+        assert_eq!(ranges, vec![0..3, 3..4, 4..8, 8..10]);
+
+        // The ranges tile `0..total_weights`.
+        assert_eq!(ranges.first().unwrap().start, 0);
+        assert_eq!(ranges.last().unwrap().end, param.total_weights);
+        for (range, next) in ranges.iter().zip(ranges.iter().skip(1)) {
+            assert_eq!(range.end, next.start);
+        }
+
+        // A distribution whose sum disagrees with `total_weights` is rejected.
+        assert!(param.share_ranges(&[3, 1, 4]).is_err());
+        assert!(param.share_ranges(&[3, 1, 4, 3]).is_err());
+        // So is a zero weight.
+        assert!(param.share_ranges(&[3, 1, 0, 4, 2]).is_err());
+
+        // Dispersed shares hold the raw shares in their range.
+        let mut rng = jf_utils::test_rng();
+        let mut payload = vec![0u8; 100];
+        rng.fill_bytes(&mut payload);
+        let (commit, shares) = AvidMScheme::disperse(&param, &distribution, &payload).unwrap();
+        for share in &shares {
+            assert!(AvidMScheme::verify_share_for_distribution(
+                &param,
+                &distribution,
+                &commit,
+                share
+            )
+            .is_ok_and(|r| r.is_ok()));
+        }
+        // A share whose index was swapped with another node's still holds valid
+        // raw shares, but not those assigned to its index.
+        let mut swapped = shares[1].clone();
+        swapped.index = shares[0].index;
+        assert!(AvidMScheme::verify_share(&param, &commit, &swapped).is_ok_and(|r| r.is_ok()));
+        assert!(AvidMScheme::verify_share_for_distribution(
+            &param,
+            &distribution,
+            &commit,
+            &swapped
+        )
+        .is_ok_and(|r| r.is_err()));
+
+        // A share does not verify against a different distribution.
+        assert!(AvidMScheme::verify_share_for_distribution(
+            &param,
+            &[1, 3, 4, 2],
+            &commit,
+            &shares[0]
+        )
+        .is_ok_and(|r| r.is_err()));
+    }
+
+    #[test]
+    fn test_verify_coverage() {
+        let param = AvidMParam::new(3, 10).unwrap();
+        assert!(param.verify_coverage([0..3, 3..4, 4..8, 8..10]).is_ok());
+        // The order of the ranges does not matter.
+        assert!(param.verify_coverage([4..8, 0..3, 8..10, 3..4]).is_ok());
+
+        // A missing range is detected.
+        assert!(param.verify_coverage([0..3, 4..8, 8..10]).is_err());
+        assert!(param.verify_coverage([0..3, 3..4, 4..8]).is_err());
+        // As are overlapping or duplicated ranges.
+        assert!(param.verify_coverage([0..3, 2..4, 4..8, 8..10]).is_err());
+        assert!(param
+            .verify_coverage([0..3, 3..4, 3..4, 4..8, 8..10])
+            .is_err());
+        // And ranges beyond the total weights.
+        assert!(param.verify_coverage([0..3, 3..4, 4..8, 8..11]).is_err());
+    }
+
+    #[test]
+    fn test_disperse_streaming() {
+        let mut rng = jf_utils::test_rng();
+        let weights = [1, 2, 3, 1, 2];
+        let params = AvidMScheme::setup(4, weights.iter().sum::<u32>() as usize).unwrap();
+        // A chunk holds 4 field elements of 31 bytes each.
+        let chunk_byte_len = 4 * bytes_to_field::elem_byte_capacity::<F>();
+
+        for payload_byte_len in [
+            0,
+            1,
+            chunk_byte_len - 1,
+            chunk_byte_len,
+            2 * chunk_byte_len,
+            1000,
+        ] {
+            let mut payload = vec![0u8; payload_byte_len];
+            rng.fill_bytes(&mut payload);
+            let expected = AvidMScheme::disperse(&params, &weights, &payload).unwrap();
+
+            for window_chunks in [1, 2, 5, 100] {
+                for slice_len in [1, 7, chunk_byte_len, 1000] {
+                    let streamed = AvidMScheme::disperse_streaming(
+                        &params,
+                        &weights,
+                        payload.chunks(slice_len),
+                        window_chunks,
+                    )
+                    .unwrap();
+                    assert_eq!(streamed, expected);
+                }
+            }
+        }
+
+        assert!(AvidMScheme::disperse_streaming(&params, &weights, [[0u8; 10]], 0).is_err());
+        assert!(AvidMScheme::disperse_streaming(&params, &[1, 2], [[0u8; 10]], 1).is_err());
+    }
+
+    #[test]
+    fn test_recover_to_writer() {
+        let mut rng = jf_utils::test_rng();
+        let weights = [1, 2, 3, 1, 2];
+        let params = AvidMScheme::setup(2, weights.iter().sum::<u32>() as usize).unwrap();
+
+        // Payloads spanning several batches of polynomials, with runs of zeros
+        // across batch boundaries and at the end.
+        let mut random = |len| {
+            let mut bytes = vec![0u8; len];
+            rng.fill_bytes(&mut bytes);
+            bytes
+        };
+        let payloads = [
+            vec![],
+            vec![0u8; 100],
+            vec![1u8; 100],
+            [random(20_000), vec![0u8; 20_000], vec![5]].concat(),
+            [random(50_000), vec![1], vec![0u8; 30_000]].concat(),
+        ];
+
+        for payload in payloads {
+            let (commit, shares) = AvidMScheme::disperse(&params, &weights, &payload).unwrap();
+            let mut recovered = vec![];
+            AvidMScheme::recover_to_writer(&params, &commit, &shares, &mut recovered).unwrap();
+            assert_eq!(recovered, payload);
+            assert_eq!(
+                AvidMScheme::recover(&params, &commit, &shares).unwrap(),
+                payload
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_distribution() {
+        let params = AvidMScheme::setup(4, 9).unwrap();
+
+        AvidMScheme::validate_distribution(&params, &[1, 2, 3, 1, 2], false).unwrap();
+        AvidMScheme::validate_distribution(&params, &[1, 2, 3, 1, 2], true).unwrap();
+        // A node may hold enough weight to recover on its own, unless rejected.
+        AvidMScheme::validate_distribution(&params, &[1, 5, 3], false).unwrap();
+        let err = AvidMScheme::validate_distribution(&params, &[1, 5, 3], true).unwrap_err();
+        assert!(err.to_string().contains("Storage node 1 "), "{err}");
+
+        let err = AvidMScheme::validate_distribution(&params, &[1, 2, 0, 6], false).unwrap_err();
+        assert!(err.to_string().contains("storage node 2 "), "{err}");
+        assert!(AvidMScheme::validate_distribution(&params, &[1, 2, 3], false).is_err());
+        assert!(AvidMScheme::validate_distribution(&params, &[], false).is_err());
+
+        // Dispersal rejects the same distributions.
+        assert!(AvidMScheme::disperse(&params, &[1, 2, 0, 6], &[1, 2, 3]).is_err());
+        assert!(AvidMScheme::disperse(&params, &[1, 2, 3], &[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_verify_shares() {
+        let mut rng = jf_utils::test_rng();
+        let weights = [1, 2, 3, 1, 2];
+        let params = AvidMScheme::setup(4, weights.iter().sum::<u32>() as usize).unwrap();
+        let mut payload = vec![0u8; 100];
+        rng.fill_bytes(&mut payload);
+        let (commit, mut shares) = AvidMScheme::disperse(&params, &weights, &payload).unwrap();
+
+        assert_eq!(
+            AvidMScheme::verify_shares(&params, &commit, &shares).unwrap(),
+            Ok(())
+        );
+        assert_eq!(
+            AvidMScheme::verify_shares(&params, &commit, &[]).unwrap(),
+            Ok(())
+        );
+
+        // A batch with a single corrupted Merkle proof is rejected, reporting
+        // the corrupted share.
+        shares[2].content.mt_proofs[1] = shares[0].content.mt_proofs[0].clone();
+        assert_eq!(
+            AvidMScheme::verify_shares(&params, &commit, &shares).unwrap(),
+            Err(2)
+        );
+        assert!(AvidMScheme::verify_share(&params, &commit, &shares[2])
+            .unwrap()
+            .is_err());
+    }
+
+    #[test]
+    fn test_verify_commitment() {
+        let mut rng = jf_utils::test_rng();
+        let params = AvidMScheme::setup(3usize, 9usize).unwrap();
+
+        let mut payload = vec![0u8; 100];
+        rng.fill_bytes(&mut payload);
+        let commit = AvidMScheme::commit(&params, &payload).unwrap();
+        assert!(AvidMScheme::verify_commitment(&params, &commit, &payload));
+
+        // flip a single bit of the payload
+        payload[42] ^= 1;
+        assert!(!AvidMScheme::verify_commitment(&params, &commit, &payload));
+    }
+
     #[test]
     #[cfg(feature = "print-trace")]
     fn round_trip_breakdown() {
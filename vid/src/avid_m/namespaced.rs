@@ -1,13 +1,13 @@
 //! This file implements the namespaced AvidM scheme.
 
-use std::ops::Range;
+use std::{collections::HashSet, hash::Hash, ops::Range};
 
 use jf_merkle_tree::MerkleTreeScheme;
 use serde::{Deserialize, Serialize};
 
 use super::{AvidMCommit, AvidMShare, RawAvidMShare};
 use crate::{
-    avid_m::{AvidMScheme, MerkleTree},
+    avid_m::{radix2_domain, AvidMScheme, MerkleTree, F},
     VidError, VidResult, VidScheme,
 };
 
@@ -82,15 +82,68 @@ impl NsAvidMScheme {
         payload: &[u8],
         ns_table: impl IntoIterator<Item = Range<usize>>,
     ) -> VidResult<(NsAvidMCommit, Vec<NsAvidMShare>)> {
+        Self::disperse_ns_payloads(
+            param,
+            distribution,
+            ns_table.into_iter().map(|ns_range| &payload[ns_range]),
+        )
+    }
+
+    /// Disperse several namespaces at once according to a distribution table.
+    ///
+    /// The namespaces are encoded in the given order, as if they were laid out
+    /// back to back in a single payload, so the returned commitment is the same
+    /// as the one [`Self::commit`] computes for that payload. Each share covers
+    /// all namespaces, which can then be recovered independently with
+    /// [`Self::ns_recover`], given their position in `namespaces`.
+    ///
+    /// Unlike dispersing each namespace on its own, the FFT domain and the
+    /// share ranges are only set up once.
+    ///
+    /// Returns an error if `namespaces` is empty, or if a namespace id is
+    /// repeated.
+    pub fn disperse_namespaces<N: Eq + Hash>(
+        param: &NsAvidMParam,
+        distribution: &[u32],
+        namespaces: &[(N, Vec<u8>)],
+    ) -> VidResult<(NsAvidMCommit, Vec<NsAvidMShare>)> {
+        let mut ns_ids = HashSet::with_capacity(namespaces.len());
+        if !namespaces.iter().all(|(ns_id, _)| ns_ids.insert(ns_id)) {
+            return Err(VidError::Argument("duplicate namespace id".to_string()));
+        }
+        Self::disperse_ns_payloads(
+            param,
+            distribution,
+            namespaces.iter().map(|(_, payload)| payload.as_slice()),
+        )
+    }
+
+    /// Helper: disperse each namespace payload with the same FFT domain and
+    /// share ranges, and combine the results into namespaced shares.
+    fn disperse_ns_payloads<'a>(
+        param: &NsAvidMParam,
+        distribution: &[u32],
+        ns_payloads: impl IntoIterator<Item = &'a [u8]>,
+    ) -> VidResult<(NsAvidMCommit, Vec<NsAvidMShare>)> {
+        let domain = radix2_domain::<F>(param.total_weights)?;
+        let ranges = param.share_ranges(distribution)?;
+
         let mut ns_commits = vec![];
         let mut disperses = vec![];
         let mut ns_lens = vec![];
-        for ns_range in ns_table {
-            ns_lens.push(ns_range.len());
-            let (commit, shares) = AvidMScheme::disperse(param, distribution, &payload[ns_range])?;
+        for ns_payload in ns_payloads {
+            ns_lens.push(ns_payload.len());
+            let ns_payload_fields = AvidMScheme::pad_to_fields(param, ns_payload);
+            let (mt, raw_shares) =
+                AvidMScheme::raw_encode_with_domain(param, &domain, &ns_payload_fields)?;
+            let (commit, shares) =
+                AvidMScheme::distribute_shares_to_ranges(&ranges, mt, raw_shares, ns_payload.len());
             ns_commits.push(commit.commit);
             disperses.push(shares);
         }
+        if disperses.is_empty() {
+            return Err(VidError::Argument("no namespace to disperse".to_string()));
+        }
         let commit = NsAvidMCommit {
             commit: MerkleTree::from_elems(None, &ns_commits)
                 .map_err(|err| VidError::Internal(err.into()))?
@@ -100,9 +153,9 @@ impl NsAvidMScheme {
             .into_iter()
             .map(|comm| AvidMCommit { commit: comm })
             .collect();
-        let mut shares = vec![NsAvidMShare::default(); disperses[0].len()];
-        shares.iter_mut().for_each(|share| {
-            share.index = disperses[0][0].index;
+        let mut shares = vec![NsAvidMShare::default(); ranges.len()];
+        shares.iter_mut().enumerate().for_each(|(index, share)| {
+            share.index = index as u32;
             share.ns_commits = ns_commits.clone();
             share.ns_lens = ns_lens.clone();
         });
@@ -115,6 +168,32 @@ impl NsAvidMScheme {
         Ok((commit, shares))
     }
 
+    /// Check that `shares` are a complete dispersal under `param`, i.e. that
+    /// their ranges of raw shares tile `0..total_weights` exactly.
+    ///
+    /// This catches a dispersal that omitted or double counted a storage node.
+    /// Returns an error if a share is inconsistent across namespaces.
+    pub fn verify_dispersal_coverage(
+        param: &NsAvidMParam,
+        shares: &[NsAvidMShare],
+    ) -> VidResult<()> {
+        let ranges = shares
+            .iter()
+            .map(|share| {
+                let first = share.content.first().ok_or(VidError::InvalidShare)?;
+                if share
+                    .content
+                    .iter()
+                    .any(|content| content.range != first.range)
+                {
+                    return Err(VidError::InvalidShare);
+                }
+                Ok(first.range.clone())
+            })
+            .collect::<VidResult<Vec<_>>>()?;
+        param.verify_coverage(ranges)
+    }
+
     /// Verify a namespaced share
     pub fn verify_share(
         param: &NsAvidMParam,
@@ -128,7 +207,7 @@ impl NsAvidMScheme {
         }
         // Verify the share for each namespace
         for (commit, content) in share.ns_commits.iter().zip(share.content.iter()) {
-            if AvidMScheme::verify_internal(param, commit, content)?.is_err() {
+            if AvidMScheme::verify_internal(param, commit, content, None)?.is_err() {
                 return Ok(Err(()));
             }
         }
@@ -148,6 +227,75 @@ impl NsAvidMScheme {
         })
     }
 
+    /// Check that the namespace structure carried by a share is consistent
+    /// with the namespaced commitment and the given namespace table.
+    ///
+    /// A namespaced commitment is only the root of a Merkle tree over the
+    /// namespace commitments, so the structure is taken from the share. This
+    /// checks that
+    /// - the namespace table is well formed, i.e. its ranges are
+    ///   non-overlapping and in ascending order,
+    /// - the share contains exactly one commitment and one length for each
+    ///   namespace in the table, and each length matches its range,
+    /// - every namespace share covers the same range of raw shares, within the
+    ///   total weights of `param`,
+    /// - the namespace commitments hash to `commit`.
+    ///
+    /// It does not verify the share content against the namespace
+    /// commitments, use [`Self::verify_share`] for that.
+    pub fn verify_commit_structure(
+        param: &NsAvidMParam,
+        commit: &NsAvidMCommit,
+        share: &NsAvidMShare,
+        ns_table: impl IntoIterator<Item = Range<usize>>,
+    ) -> VidResult<crate::VerificationResult> {
+        if !(share.ns_commits.len() == share.ns_lens.len()
+            && share.ns_commits.len() == share.content.len())
+        {
+            return Err(VidError::InvalidShare);
+        }
+
+        let ns_table: Vec<_> = ns_table.into_iter().collect();
+        if ns_table.len() != share.ns_commits.len() {
+            return Ok(Err(()));
+        }
+        let mut prev_end = 0;
+        for (ns_range, ns_len) in ns_table.iter().zip(share.ns_lens.iter()) {
+            if ns_range.start < prev_end || ns_range.start > ns_range.end {
+                return Ok(Err(()));
+            }
+            if ns_range.len() != *ns_len {
+                return Ok(Err(()));
+            }
+            prev_end = ns_range.end;
+        }
+
+        if let Some(first) = share.content.first() {
+            if first.range.end > param.total_weights
+                || share
+                    .content
+                    .iter()
+                    .any(|content| content.range != first.range)
+            {
+                return Ok(Err(()));
+            }
+        }
+
+        let expected_commit = NsAvidMCommit {
+            commit: MerkleTree::from_elems(
+                None,
+                share.ns_commits.iter().map(|commit| commit.commit),
+            )
+            .map_err(|err| VidError::Internal(err.into()))?
+            .commitment(),
+        };
+        Ok(if &expected_commit == commit {
+            Ok(())
+        } else {
+            Err(())
+        })
+    }
+
     /// Recover the entire payload from enough share
     pub fn recover(param: &NsAvidMParam, shares: &[NsAvidMShare]) -> VidResult<Vec<u8>> {
         if shares.is_empty() {
@@ -249,4 +397,117 @@ pub mod tests {
         let payload_recovered = NsAvidMScheme::recover(&params, &shares[..cut_index]).unwrap();
         assert_eq!(payload_recovered, payload);
     }
+
+    #[test]
+    fn disperse_namespaces() {
+        let recovery_threshold = 3;
+        let namespaces = [(7u32, vec![1u8; 15]), (3, vec![2u8; 33]), (42, vec![])];
+
+        let mut rng = jf_utils::test_rng();
+
+        let weights: Vec<u32> = (0..9).map(|_| rng.next_u32() % 5 + 1).collect();
+        let total_weights: u32 = weights.iter().sum();
+        let params = NsAvidMScheme::setup(recovery_threshold, total_weights as usize).unwrap();
+
+        let (commit, mut shares) =
+            NsAvidMScheme::disperse_namespaces(&params, &weights, &namespaces).unwrap();
+        assert_eq!(shares.len(), weights.len());
+
+        // The combined commitment is the one of the namespaces laid out back to back.
+        let payload: Vec<u8> = namespaces
+            .iter()
+            .flat_map(|(_, ns_payload)| ns_payload.clone())
+            .collect();
+        let ns_table = [(0usize..15), (15..48), (48..48)];
+        assert_eq!(
+            commit,
+            NsAvidMScheme::commit(&params, &payload, ns_table.iter().cloned()).unwrap()
+        );
+        shares.iter().for_each(|share| {
+            assert!(NsAvidMScheme::verify_share(&params, &commit, share).is_ok_and(|r| r.is_ok()));
+            assert!(NsAvidMScheme::verify_commit_structure(
+                &params,
+                &commit,
+                share,
+                ns_table.iter().cloned()
+            )
+            .is_ok_and(|r| r.is_ok()));
+        });
+        NsAvidMScheme::verify_dispersal_coverage(&params, &shares).unwrap();
+
+        // Each namespace is recovered independently from a random subset of shares.
+        shares.shuffle(&mut rng);
+        let mut cumulated_weights = 0;
+        let mut cut_index = 0;
+        while cumulated_weights <= recovery_threshold {
+            cumulated_weights += shares[cut_index].content[0].range.len();
+            cut_index += 1;
+        }
+        for (ns_index, (_, ns_payload)) in namespaces.iter().enumerate() {
+            let ns_payload_recovered =
+                NsAvidMScheme::ns_recover(&params, ns_index, &shares[..cut_index]).unwrap();
+            assert_eq!(&ns_payload_recovered, ns_payload);
+        }
+
+        // Repeated namespace ids, and the lack of namespaces, are rejected.
+        let repeated_namespaces = [(7u32, vec![1u8; 15]), (7, vec![2u8; 33])];
+        assert!(
+            NsAvidMScheme::disperse_namespaces(&params, &weights, &repeated_namespaces).is_err()
+        );
+        assert!(NsAvidMScheme::disperse_namespaces::<u32>(&params, &weights, &[]).is_err());
+    }
+
+    #[test]
+    fn verify_commit_structure() {
+        let recovery_threshold = 3;
+        let ns_table = [(0usize..15), (15..48)];
+        let payload_byte_len = 48;
+
+        let mut rng = jf_utils::test_rng();
+
+        let weights: Vec<u32> = (0..9).map(|_| rng.next_u32() % 5 + 1).collect();
+        let total_weights: u32 = weights.iter().sum();
+        let params = NsAvidMScheme::setup(recovery_threshold, total_weights as usize).unwrap();
+
+        let payload = {
+            let mut bytes_random = vec![0u8; payload_byte_len];
+            rng.fill_bytes(&mut bytes_random);
+            bytes_random
+        };
+
+        let (commit, shares) =
+            NsAvidMScheme::ns_disperse(&params, &weights, &payload, ns_table.iter().cloned())
+                .unwrap();
+
+        // A well-formed namespaced dispersal is consistent with its table.
+        shares.iter().for_each(|share| {
+            assert!(NsAvidMScheme::verify_commit_structure(
+                &params,
+                &commit,
+                share,
+                ns_table.iter().cloned()
+            )
+            .is_ok_and(|r| r.is_ok()))
+        });
+
+        // A table with a different number of namespaces is rejected.
+        let wrong_ns_table = [(0usize..15), (15..30), (30..48)];
+        assert!(NsAvidMScheme::verify_commit_structure(
+            &params,
+            &commit,
+            &shares[0],
+            wrong_ns_table.iter().cloned()
+        )
+        .is_ok_and(|r| r.is_err()));
+
+        // So is a table with overlapping namespaces.
+        let overlapping_ns_table = [(0usize..15), (10..43)];
+        assert!(NsAvidMScheme::verify_commit_structure(
+            &params,
+            &commit,
+            &shares[0],
+            overlapping_ns_table.iter().cloned()
+        )
+        .is_ok_and(|r| r.is_err()));
+    }
 }
@@ -70,7 +70,8 @@ impl AvidMScheme {
                 .clone()
                 .zip(share.content.mt_proofs.iter())
             {
-                if index > param.total_weights {
+                // Valid indices are `0..total_weights`, matching `MalEncodingProof::verify`.
+                if index >= param.total_weights {
                     return Err(VidError::InvalidShare);
                 }
                 if visited_indices.contains(&index) {
@@ -195,7 +196,7 @@ mod tests {
             radix2_domain, AvidMScheme, Config, MerkleTree, F,
         },
         utils::bytes_to_field,
-        VidScheme,
+        VidError, VidScheme,
     };
 
     #[test]
@@ -264,6 +265,69 @@ mod tests {
         assert!(bad_proof2.verify(&param, &commit).is_err());
     }
 
+    #[test]
+    fn test_proof_of_incorrect_encoding_bad_indices() {
+        let param = AvidMScheme::setup(5usize, 10usize).unwrap();
+        let weights = [1u32; 10];
+        let payload_byte_len = bytes_to_field::elem_byte_capacity::<F>() * 4;
+        let domain = radix2_domain::<F>(param.total_weights).unwrap();
+
+        let high_degree_polynomial = vec![F::from(1u64); 10];
+        let mal_payload: Vec<_> = domain
+            .fft(&high_degree_polynomial)
+            .into_iter()
+            .take(param.total_weights)
+            .map(|v| vec![v])
+            .collect();
+
+        let mt = MerkleTree::from_elems(
+            None,
+            mal_payload
+                .iter()
+                .map(|v| Config::raw_share_digest(v).unwrap()),
+        )
+        .unwrap();
+
+        let (commit, shares) =
+            AvidMScheme::distribute_shares(&param, &weights, mt, mal_payload, payload_byte_len)
+                .unwrap();
+
+        // A share whose range reaches exactly `total_weights` is out of range.
+        let mut out_of_range_shares = shares[..5].to_vec();
+        out_of_range_shares[4].content.range = param.total_weights..param.total_weights + 1;
+        assert!(matches!(
+            AvidMScheme::proof_of_incorrect_encoding(&param, &commit, &out_of_range_shares),
+            Err(VidError::InvalidShare)
+        ));
+
+        // Each share is valid on its own, but the same index is given twice.
+        let duplicate_shares = [&shares[..1], &shares[..4]].concat();
+        assert!(matches!(
+            AvidMScheme::proof_of_incorrect_encoding(&param, &commit, &duplicate_shares),
+            Err(VidError::InvalidShare)
+        ));
+
+        // The verifier rejects duplicate indices in a proof as well.
+        let proof =
+            AvidMScheme::proof_of_incorrect_encoding(&param, &commit, &shares[..5]).unwrap();
+        assert!(proof.verify(&param, &commit).unwrap().is_ok());
+        let mut bad_proof = proof.clone();
+        bad_proof.raw_shares[1] = bad_proof.raw_shares[0].clone();
+        assert!(matches!(
+            bad_proof.verify(&param, &commit),
+            Err(VidError::InvalidShare)
+        ));
+
+        // Unlike the shares given to the prover, the indices in a proof are
+        // not verified beforehand, so the verifier checks their bound too.
+        let mut bad_proof = proof;
+        bad_proof.raw_shares[0].0 = param.total_weights;
+        assert!(matches!(
+            bad_proof.verify(&param, &commit),
+            Err(VidError::InvalidShare)
+        ));
+    }
+
     #[test]
     fn test_ns_proof() {
         let param = AvidMScheme::setup(5usize, 10usize).unwrap();